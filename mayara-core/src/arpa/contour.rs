@@ -2,6 +2,8 @@
 //!
 //! Provides contour representation and error types for target tracking.
 
+use serde::{Deserialize, Serialize};
+
 use super::polar::Polar;
 
 /// Minimum contour length to consider a valid target
@@ -10,6 +12,66 @@ pub const MIN_CONTOUR_LENGTH: usize = 6;
 /// Maximum contour length (prevents runaway on large blobs)
 pub const MAX_CONTOUR_LENGTH: usize = 2000;
 
+/// Contour length limits for a valid target blob
+///
+/// Tuned separately from the detection intensity threshold: a blob whose
+/// contour is shorter than `min_length` is noise, one longer than
+/// `max_length` is land or interference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContourLimits {
+    /// Minimum contour length (pixels)
+    pub min_length: usize,
+    /// Maximum contour length (pixels)
+    pub max_length: usize,
+}
+
+impl Default for ContourLimits {
+    fn default() -> Self {
+        ContourLimits {
+            min_length: MIN_CONTOUR_LENGTH,
+            max_length: MAX_CONTOUR_LENGTH,
+        }
+    }
+}
+
+impl ContourLimits {
+    /// Check if a contour of `length` pixels is within the limits
+    pub fn accepts(&self, length: i32) -> bool {
+        length >= self.min_length as i32 && length < self.max_length as i32 - 2
+    }
+}
+
+/// Default largest angular gap (spokes) between contours of one target
+pub const DEFAULT_MERGE_ANGLE_GAP: i32 = 3;
+
+/// Default largest radial gap (pixels) between contours of one target
+pub const DEFAULT_MERGE_RANGE_GAP: i32 = 3;
+
+/// How close two contours must be to count as one target
+///
+/// A large ship often returns as several blobs with a thin gap between
+/// them (superstructure, shadowing). Contours whose bounding boxes are
+/// within both gaps are merged before the target is associated. A gap of
+/// zero only merges touching contours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContourMerge {
+    /// Largest angular gap (spokes)
+    pub max_angle_gap: i32,
+    /// Largest radial gap (pixels)
+    pub max_range_gap: i32,
+}
+
+impl Default for ContourMerge {
+    fn default() -> Self {
+        ContourMerge {
+            max_angle_gap: DEFAULT_MERGE_ANGLE_GAP,
+            max_range_gap: DEFAULT_MERGE_RANGE_GAP,
+        }
+    }
+}
+
 /// Contour detection errors
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ContourError {
@@ -111,9 +173,72 @@ impl Contour {
         self.max_r - self.min_r
     }
 
-    /// Check if contour is within size bounds
+    /// Angular gap (spokes) between the bounding boxes of two contours,
+    /// 0 if they overlap. Handles contours that straddle angle 0.
+    pub fn angle_gap(&self, other: &Contour, spokes_per_revolution: i32) -> i32 {
+        let m = |a: i32| a.rem_euclid(spokes_per_revolution);
+        let width = m(self.max_angle - self.min_angle);
+        let other_width = m(other.max_angle - other.min_angle);
+        let to_other = m(other.min_angle - self.min_angle);
+        let from_other = m(self.min_angle - other.min_angle);
+        if to_other <= width || from_other <= other_width {
+            return 0;
+        }
+        (to_other - width).min(from_other - other_width)
+    }
+
+    /// Radial gap (pixels) between the bounding boxes of two contours,
+    /// 0 if they overlap
+    pub fn range_gap(&self, other: &Contour) -> i32 {
+        (self.min_r.max(other.min_r) - self.max_r.min(other.max_r)).max(0)
+    }
+
+    /// Check if `other` is close enough to be part of the same target
+    pub fn is_near(
+        &self,
+        other: &Contour,
+        merge: &ContourMerge,
+        spokes_per_revolution: i32,
+    ) -> bool {
+        self.angle_gap(other, spokes_per_revolution) <= merge.max_angle_gap
+            && self.range_gap(other) <= merge.max_range_gap
+    }
+
+    /// Grow this contour to cover `other` as well, and move its position
+    /// to the centre of the combined bounding box, seen at the later of
+    /// the two times
+    pub fn merge(&mut self, other: &Contour, spokes_per_revolution: i32) {
+        let m = |a: i32| a.rem_euclid(spokes_per_revolution);
+        let width = m(self.max_angle - self.min_angle);
+        let other_width = m(other.max_angle - other.min_angle);
+        // The combined arc starts at one of the two contours; take the shorter
+        let from_self = width.max(m(other.min_angle - self.min_angle) + other_width);
+        let from_other = other_width.max(m(self.min_angle - other.min_angle) + width);
+        let (start, span) = if from_self <= from_other {
+            (self.min_angle, from_self)
+        } else {
+            (other.min_angle, from_other)
+        };
+
+        self.min_angle = m(start);
+        self.max_angle = m(start + span);
+        self.min_r = self.min_r.min(other.min_r);
+        self.max_r = self.max_r.max(other.max_r);
+        self.points.extend_from_slice(&other.points);
+        self.length += other.length;
+        self.position.angle = m(start + span / 2);
+        self.position.r = (self.min_r + self.max_r) / 2;
+        self.position.time = self.position.time.max(other.position.time);
+    }
+
+    /// Check if contour is within the default size bounds
     pub fn is_valid(&self) -> bool {
-        self.length >= MIN_CONTOUR_LENGTH as i32 && self.length < MAX_CONTOUR_LENGTH as i32 - 2
+        self.is_valid_for(&ContourLimits::default())
+    }
+
+    /// Check if contour is within the given size bounds
+    pub fn is_valid_for(&self, limits: &ContourLimits) -> bool {
+        limits.accepts(self.length)
     }
 }
 
@@ -151,6 +276,38 @@ mod tests {
 
         contour.length = MAX_CONTOUR_LENGTH as i32;
         assert!(!contour.is_valid());
+
+        contour.length = 10;
+        let limits = ContourLimits {
+            min_length: 12,
+            ..Default::default()
+        };
+        assert!(!contour.is_valid_for(&limits));
+    }
+
+    #[test]
+    fn test_contour_merge_across_zero() {
+        let bounds = |min_angle, max_angle, min_r, max_r| Contour {
+            min_angle,
+            max_angle,
+            min_r,
+            max_r,
+            ..Contour::new()
+        };
+        let mut left = bounds(2040, 2046, 100, 110);
+        let mut right = bounds(1, 5, 104, 112);
+        left.position.time = 1000;
+        right.position.time = 1010;
+        assert_eq!(left.angle_gap(&right, 2048), 3);
+        assert_eq!(left.range_gap(&right), 0);
+        assert!(left.is_near(&right, &ContourMerge::default(), 2048));
+        assert!(!left.is_near(&bounds(1, 5, 120, 130), &ContourMerge::default(), 2048));
+
+        left.merge(&right, 2048);
+        assert_eq!((left.min_angle, left.max_angle), (2040, 5));
+        assert_eq!((left.min_r, left.max_r), (100, 112));
+        assert_eq!(left.position.angle, 2046);
+        assert_eq!(left.position.time, 1010);
     }
 
     #[test]
@@ -2,6 +2,7 @@
 //!
 //! Detects potential targets from radar spoke data for automatic acquisition.
 
+use super::history::Legend;
 use super::types::ArpaSettings;
 
 /// A detected target candidate from radar data
@@ -15,6 +16,10 @@ pub struct DetectedTarget {
     pub intensity: u8,
     /// Size in pixels (radial extent)
     pub size: u32,
+    /// Pixels carrying the Doppler approaching value
+    pub approaching_pix: u32,
+    /// Pixels carrying the Doppler receding value
+    pub receding_pix: u32,
 }
 
 /// Target detector for automatic ARPA acquisition
@@ -24,6 +29,8 @@ pub struct TargetDetector {
     settings: ArpaSettings,
     /// Range scale in meters (max range of current spoke data)
     range_scale: f64,
+    /// Pixel values that mark Doppler returns
+    legend: Legend,
     /// Recent detections for correlation
     recent_detections: Vec<(u64, Vec<DetectedTarget>)>,
     /// How many scans to correlate
@@ -36,6 +43,7 @@ impl TargetDetector {
         TargetDetector {
             settings,
             range_scale: 1852.0,  // Default 1nm
+            legend: Legend::default(),
             recent_detections: Vec::new(),
             correlation_scans: 3,
         }
@@ -51,6 +59,11 @@ impl TargetDetector {
         self.range_scale = range_meters;
     }
 
+    /// Set the pixel values that mark Doppler approaching/receding returns
+    pub fn set_legend(&mut self, legend: Legend) {
+        self.legend = legend;
+    }
+
     /// Detect targets in a single spoke
     ///
     /// # Arguments
@@ -80,6 +93,8 @@ impl TargetDetector {
         let mut target_start = 0;
         let mut peak_intensity: u8 = 0;
         let mut peak_index = 0;
+        let mut approaching_pix = 0;
+        let mut receding_pix = 0;
 
         for (i, &pixel) in spoke_data.iter().enumerate() {
             if pixel >= threshold {
@@ -89,11 +104,18 @@ impl TargetDetector {
                     target_start = i;
                     peak_intensity = pixel;
                     peak_index = i;
+                    approaching_pix = 0;
+                    receding_pix = 0;
                 } else if pixel > peak_intensity {
                     // Update peak
                     peak_intensity = pixel;
                     peak_index = i;
                 }
+                if pixel == self.legend.doppler_approaching {
+                    approaching_pix += 1;
+                } else if pixel == self.legend.doppler_receding {
+                    receding_pix += 1;
+                }
             } else if in_target {
                 // End of target
                 let size = i - target_start;
@@ -106,6 +128,8 @@ impl TargetDetector {
                         distance,
                         intensity: peak_intensity,
                         size: size as u32,
+                        approaching_pix,
+                        receding_pix,
                     });
                 }
                 in_target = false;
@@ -122,6 +146,8 @@ impl TargetDetector {
                     distance,
                     intensity: peak_intensity,
                     size: size as u32,
+                    approaching_pix,
+                    receding_pix,
                 });
             }
         }
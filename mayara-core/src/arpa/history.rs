@@ -4,9 +4,10 @@
 
 use bitflags::bitflags;
 
-use super::contour::{Contour, ContourError, MAX_CONTOUR_LENGTH, MIN_CONTOUR_LENGTH};
+use super::contour::{Contour, ContourError, ContourLimits, ContourMerge};
 use super::doppler::DopplerState;
 use super::polar::{Polar, FOUR_DIRECTIONS};
+use crate::raster::{rasterize_spokes, Interpolation};
 
 bitflags! {
     /// Pixel flags in history buffer for ARPA tracking
@@ -119,6 +120,8 @@ pub struct HistoryBuffer {
     pub spokes: Vec<HistorySpoke>,
     /// Number of spokes per revolution
     spokes_per_revolution: usize,
+    /// Contour lengths accepted as a target
+    contour_limits: ContourLimits,
 }
 
 impl HistoryBuffer {
@@ -131,9 +134,20 @@ impl HistoryBuffer {
         Self {
             spokes,
             spokes_per_revolution,
+            contour_limits: ContourLimits::default(),
         }
     }
 
+    /// Set the contour lengths accepted as a target
+    pub fn set_contour_limits(&mut self, limits: ContourLimits) {
+        self.contour_limits = limits;
+    }
+
+    /// Get the contour lengths accepted as a target
+    pub fn contour_limits(&self) -> ContourLimits {
+        self.contour_limits
+    }
+
     /// Reset the buffer (e.g., on range change)
     pub fn reset(&mut self) {
         for spoke in &mut self.spokes {
@@ -172,14 +186,14 @@ impl HistoryBuffer {
             .unwrap_or(false)
     }
 
-    /// Check if a blob has at least MIN_CONTOUR_LENGTH pixels
+    /// Check if a blob has a contour of at least the minimum contour length
     /// Returns true if yes, false otherwise (and clears the blob if too small)
     pub fn multi_pix(&mut self, doppler: &DopplerState, angle: i32, r: i32) -> bool {
         if !self.pix(doppler, angle, r) {
             return false;
         }
 
-        let length = MIN_CONTOUR_LENGTH;
+        let length = self.contour_limits.min_length;
         let start = Polar::new(angle, r, 0);
         let mut current = start;
 
@@ -397,7 +411,7 @@ impl HistoryBuffer {
         index = (index + 1) % 4;
 
         // Follow the contour
-        while count < MAX_CONTOUR_LENGTH {
+        while count < self.contour_limits.max_length {
             index = (index + 3) % 4; // Turn left
             found = false;
 
@@ -477,6 +491,56 @@ impl HistoryBuffer {
         self.get_contour(doppler, pol)
     }
 
+    /// Grow `contour` with the other contours within `merge` of it, so that
+    /// a ship returning as several nearby blobs becomes a single target
+    pub fn merge_nearby_contours(
+        &mut self,
+        doppler: &DopplerState,
+        mut contour: Contour,
+        merge: &ContourMerge,
+    ) -> Contour {
+        let spokes = self.spokes_per_revolution as i32;
+
+        'grow: loop {
+            let width = (contour.max_angle - contour.min_angle).rem_euclid(spokes);
+            for da in -merge.max_angle_gap..=width + merge.max_angle_gap {
+                let angle = contour.min_angle + da;
+                let inside_angle = (0..=width).contains(&da);
+                for r in
+                    (contour.min_r - merge.max_range_gap)..=(contour.max_r + merge.max_range_gap)
+                {
+                    if inside_angle && (contour.min_r..=contour.max_r).contains(&r) {
+                        continue;
+                    }
+                    if !self.pix(doppler, angle, r) {
+                        continue;
+                    }
+                    let mut pol = Polar::new(angle, r, 0);
+                    if !self.find_contour_from_inside(doppler, &mut pol) {
+                        continue;
+                    }
+                    if let Ok((other, _)) = self.get_contour(doppler, pol) {
+                        if other.is_valid_for(&self.contour_limits)
+                            && contour.is_near(&other, merge, spokes)
+                        {
+                            let mut merged = contour.clone();
+                            merged.merge(&other, spokes);
+                            // Only restart when the bounds grew, otherwise
+                            // the same blob would be found again
+                            if merged.angular_width().rem_euclid(spokes) > width
+                                || merged.radial_extent() > contour.radial_extent()
+                            {
+                                contour = merged;
+                                continue 'grow;
+                            }
+                        }
+                    }
+                }
+            }
+            return contour;
+        }
+    }
+
     /// Reset pixels of a found target to prevent re-detection
     pub fn reset_pixels(&mut self, contour: &Contour, pos: &Polar, pixels_per_meter: f64) {
         const DISTANCE_BETWEEN_TARGETS: i32 = 30;
@@ -574,11 +638,58 @@ impl HistoryBuffer {
         let idx = self.mod_spokes(angle);
         self.spokes[idx].time
     }
+
+    /// Legend value of a history pixel, or `None` when there is no echo
+    fn legend_value(pixel: HistoryPixel, legend: &Legend) -> Option<u8> {
+        if pixel.contains(HistoryPixel::CONTOUR) {
+            Some(legend.border)
+        } else if pixel.contains(HistoryPixel::APPROACHING) {
+            Some(legend.doppler_approaching)
+        } else if pixel.contains(HistoryPixel::RECEDING) {
+            Some(legend.doppler_receding)
+        } else if pixel.contains(HistoryPixel::TARGET) {
+            Some(legend.strong_return)
+        } else {
+            None
+        }
+    }
+
+    /// Render one revolution to a `size` x `size` RGBA image, angle 0 up
+    ///
+    /// Echoes take the color of their legend value in `colors` (indexed by
+    /// pixel value, see [`crate::radar::legend_colors`]), or opaque white if
+    /// the legend has no such entry. Pixels without an echo are transparent.
+    /// Rows are top to bottom, 4 bytes per pixel, ready for a PNG encoder.
+    pub fn render_rgba(&self, legend: &Legend, colors: &[[u8; 4]], size: usize) -> Vec<u8> {
+        let spokes: Vec<Vec<u8>> = self
+            .spokes
+            .iter()
+            .map(|spoke| {
+                spoke
+                    .sweep
+                    .iter()
+                    .map(|&pixel| Self::legend_value(pixel, legend).unwrap_or(0))
+                    .collect()
+            })
+            .collect();
+        // Nearest, as the cells hold legend values that must not be blended
+        let grid = rasterize_spokes(&spokes, size, Interpolation::Nearest);
+
+        let mut image = vec![0u8; size * size * 4];
+        for (pixel, &value) in image.chunks_exact_mut(4).zip(&grid) {
+            if value != 0 {
+                let color = colors.get(value as usize).copied().unwrap_or([255; 4]);
+                pixel.copy_from_slice(&color);
+            }
+        }
+        image
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::arpa::contour::MIN_CONTOUR_LENGTH;
 
     #[test]
     fn test_history_pixel_flags() {
@@ -609,6 +720,117 @@ mod tests {
         assert_eq!(buffer.mod_spokes(2049), 1);
     }
 
+    /// History with a 3x3 pixel blob at angles 10..=12, radius 50..=52
+    fn small_blob() -> HistoryBuffer {
+        let mut buffer = HistoryBuffer::new(360);
+        let legend = Legend::default();
+        for angle in 0..360 {
+            let mut data = vec![0u8; 100];
+            if (10..=12).contains(&angle) {
+                data[50..=52].fill(100);
+            }
+            buffer.update_spoke(angle, &data, 1000, 51.5, -0.1, &legend);
+        }
+        buffer
+    }
+
+    #[test]
+    fn test_contour_limits() {
+        let mut buffer = small_blob();
+        let (contour, _) = buffer
+            .get_target(&DopplerState::Any, Polar::new(11, 51, 0), 5)
+            .unwrap();
+        assert!(contour.length >= MIN_CONTOUR_LENGTH as i32);
+
+        // The same blob is too small once the minimum is raised
+        let mut buffer = small_blob();
+        buffer.set_contour_limits(ContourLimits {
+            min_length: 20,
+            ..Default::default()
+        });
+        assert_eq!(
+            buffer
+                .get_target(&DopplerState::Any, Polar::new(11, 51, 0), 5)
+                .unwrap_err(),
+            ContourError::NoContourFound
+        );
+    }
+
+    /// History with two 5x5 blobs at angles 10..=14, two pixels apart in range
+    fn split_ship() -> HistoryBuffer {
+        let mut buffer = HistoryBuffer::new(360);
+        let legend = Legend::default();
+        for angle in 0..360 {
+            let mut data = vec![0u8; 100];
+            if (10..=14).contains(&angle) {
+                data[50..=54].fill(100);
+                data[57..=61].fill(100);
+            }
+            buffer.update_spoke(angle, &data, 1000, 51.5, -0.1, &legend);
+        }
+        buffer
+    }
+
+    #[test]
+    fn test_adjacent_contours_merge_into_one_target() {
+        let doppler = DopplerState::Any;
+        let mut buffer = split_ship();
+        let (contour, _) = buffer
+            .get_target(&doppler, Polar::new(12, 52, 0), 5)
+            .unwrap();
+        assert_eq!((contour.min_r, contour.max_r), (50, 54));
+
+        let merged = buffer.merge_nearby_contours(&doppler, contour, &ContourMerge::default());
+        assert_eq!((merged.min_angle, merged.max_angle), (10, 14));
+        assert_eq!((merged.min_r, merged.max_r), (50, 61));
+        assert_eq!(merged.position.r, 55);
+
+        // Resetting the merged target leaves no second target behind
+        let pos = merged.position;
+        buffer.reset_pixels(&merged, &pos, 1.0);
+        assert!(buffer
+            .get_target(&doppler, Polar::new(12, 59, 0), 5)
+            .is_err());
+
+        // Without a gap allowance the two halves stay separate targets
+        let mut buffer = split_ship();
+        let (contour, _) = buffer
+            .get_target(&doppler, Polar::new(12, 52, 0), 5)
+            .unwrap();
+        let merge = ContourMerge {
+            max_angle_gap: 0,
+            max_range_gap: 0,
+        };
+        let single = buffer.merge_nearby_contours(&doppler, contour, &merge);
+        assert_eq!((single.min_r, single.max_r), (50, 54));
+    }
+
+    #[test]
+    fn test_render_ring() {
+        let mut buffer = HistoryBuffer::new(360);
+        let legend = Legend::default();
+        let mut data = vec![0u8; 100];
+        data[40..=42].fill(100);
+        for angle in 0..360 {
+            buffer.update_spoke(angle, &data, 1000, 51.5, -0.1, &legend);
+        }
+
+        let mut colors = vec![[0u8; 4]; 256];
+        colors[legend.strong_return as usize] = [0, 255, 0, 255];
+        let image = buffer.render_rgba(&legend, &colors, 200);
+        assert_eq!(image.len(), 200 * 200 * 4);
+
+        let pixel = |x: usize, y: usize| &image[(y * 200 + x) * 4..(y * 200 + x) * 4 + 4];
+        // On the ring (radius 41) in every direction
+        assert_eq!(pixel(100 + 41, 100), &[0, 255, 0, 255]);
+        assert_eq!(pixel(100, 100 - 41), &[0, 255, 0, 255]);
+        assert_eq!(pixel(100 - 42, 100), &[0, 255, 0, 255]);
+        // Inside and outside the ring
+        assert_eq!(pixel(100, 100), &[0, 0, 0, 0]);
+        assert_eq!(pixel(100 + 20, 100), &[0, 0, 0, 0]);
+        assert_eq!(pixel(100 + 60, 100), &[0, 0, 0, 0]);
+    }
+
     #[test]
     fn test_update_spoke() {
         let mut buffer = HistoryBuffer::new(360);
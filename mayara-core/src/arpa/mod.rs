@@ -17,6 +17,9 @@
 //! - **cpa**: CPA/TCPA calculations
 //! - **detector**: Simple target detection for auto-acquisition
 //! - **tracker**: High-level processor (simple API)
+//! - **motion**: Scripted own-ship motion for replay testing
+//! - **replay**: Deterministic replay of recorded spokes against golden tracks
+//! - **signalk**: Target deltas on `radars.{id}.targets.{target_id}`
 //! - **types**: Legacy API types (ArpaTarget, ArpaSettings, etc.)
 //!
 //! # Usage
@@ -68,20 +71,25 @@ mod types;
 mod tracker;
 mod cpa;
 mod detector;
+mod motion;
+mod replay;
+mod signalk;
 
 // Re-export new modular types
 pub use polar::{
     Polar, LocalPosition, PolarConverter, FOUR_DIRECTIONS,
     METERS_PER_DEGREE_LATITUDE, NAUTICAL_MILE, KN_TO_MS, MS_TO_KN,
-    meters_per_degree_longitude,
+    meters_per_degree_longitude, bearing_distance_to,
 };
 pub use doppler::DopplerState;
-pub use contour::{Contour, ContourError, MIN_CONTOUR_LENGTH, MAX_CONTOUR_LENGTH};
+pub use contour::{
+    Contour, ContourError, ContourLimits, ContourMerge, MIN_CONTOUR_LENGTH, MAX_CONTOUR_LENGTH,
+};
 pub use history::{HistoryPixel, HistorySpoke, HistoryBuffer, Legend};
 pub use kalman::KalmanFilter;
 pub use target::{
     TargetState, TargetStatus, RefreshState, Pass, ExtendedPosition,
-    RefreshConfig, refresh_target,
+    RefreshConfig, refresh_target, dead_reckon,
     MAX_LOST_COUNT, MAX_DETECTION_SPEED_KN,
 };
 
@@ -90,3 +98,9 @@ pub use types::*;
 pub use tracker::ArpaProcessor;
 pub use cpa::CpaResult;
 pub use detector::TargetDetector;
+pub use motion::{relative_to_true_motion, OwnShipMotionRecord, OwnShipMotionScript};
+pub use replay::{
+    compare_golden, parse_golden, replay, spoke_data, Echo, GoldenTolerance, GoldenTrack,
+    ReplayEvent, ReplayRecord, ReplayRecording,
+};
+pub use signalk::{target_signalk_value, TargetChangeTracker, TargetDelta};
@@ -0,0 +1,200 @@
+//! Scripted Own-Ship Motion
+//!
+//! Replayed spokes carry no own-ship movement, so true-motion and CPA/TCPA
+//! cannot be tested against a static own ship. A motion script supplies the
+//! own-ship state (position, heading, COG, SOG) over time instead.
+//!
+//! # Format
+//!
+//! Like the frames of a replay recording, a script is a sequence of records,
+//! each stamped with the time in milliseconds since the start of the replay.
+//! The text form is one JSON object per line; empty lines and lines starting
+//! with `#` are ignored:
+//!
+//! ```text
+//! # Steaming east at 10 knots, then turning north
+//! {"timeMs": 0, "latitude": 52.0, "longitude": 4.0, "heading": 90, "course": 90, "speed": 10}
+//! {"timeMs": 60000, "latitude": 52.0, "longitude": 4.0044, "heading": 0, "course": 0, "speed": 10}
+//! ```
+//!
+//! Between records the own ship is dead-reckoned from the most recent record
+//! using its course and speed.
+
+use serde::{Deserialize, Serialize};
+
+use super::polar::{meters_per_degree_longitude, KN_TO_MS, METERS_PER_DEGREE_LATITUDE};
+use super::types::{OwnShip, TargetMotion};
+use crate::error::ParseError;
+
+/// One own-ship state in a motion script
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnShipMotionRecord {
+    /// Milliseconds since the start of the replay
+    pub time_ms: u64,
+    /// Latitude in degrees
+    pub latitude: f64,
+    /// Longitude in degrees
+    pub longitude: f64,
+    /// True heading in degrees (0-360)
+    pub heading: f64,
+    /// Course over ground in degrees (0-360)
+    pub course: f64,
+    /// Speed over ground in knots
+    pub speed: f64,
+}
+
+/// Own-ship motion over time, for replay testing
+#[derive(Debug, Clone, Default)]
+pub struct OwnShipMotionScript {
+    records: Vec<OwnShipMotionRecord>,
+}
+
+impl OwnShipMotionScript {
+    /// Create a script from records (sorted by time)
+    pub fn new(mut records: Vec<OwnShipMotionRecord>) -> Self {
+        records.sort_by_key(|r| r.time_ms);
+        OwnShipMotionScript { records }
+    }
+
+    /// Parse a script in JSON-lines format (see module docs)
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut records = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let record: OwnShipMotionRecord = serde_json::from_str(line)
+                .map_err(|e| ParseError::DeserializationFailed(format!("line {}: {}", n + 1, e)))?;
+            records.push(record);
+        }
+        Ok(Self::new(records))
+    }
+
+    /// Get the records in time order
+    pub fn records(&self) -> &[OwnShipMotionRecord] {
+        &self.records
+    }
+
+    /// Check if the script has no records
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Own-ship state at `time_ms` since the start of the replay
+    ///
+    /// Before the first record the first record is returned unchanged.
+    pub fn own_ship_at(&self, time_ms: u64) -> Option<OwnShip> {
+        let idx = self.records.partition_point(|r| r.time_ms <= time_ms);
+        let record = self.records.get(idx.saturating_sub(1))?;
+
+        let dt = time_ms.saturating_sub(record.time_ms) as f64 / 1000.0;
+        let distance = record.speed * KN_TO_MS * dt;
+        let course = record.course.to_radians();
+        let north = distance * course.cos();
+        let east = distance * course.sin();
+
+        Some(OwnShip {
+            latitude: record.latitude + north / METERS_PER_DEGREE_LATITUDE,
+            longitude: record.longitude + east / meters_per_degree_longitude(record.latitude),
+            heading: record.heading,
+            course: record.course,
+            speed: record.speed,
+        })
+    }
+}
+
+/// Convert target motion relative to own ship into true motion
+///
+/// True velocity is the relative velocity plus the own-ship velocity.
+pub fn relative_to_true_motion(relative: &TargetMotion, own_ship: &OwnShip) -> TargetMotion {
+    let (rel_sin, rel_cos) = relative.course.to_radians().sin_cos();
+    let (own_sin, own_cos) = own_ship.course.to_radians().sin_cos();
+
+    let east = relative.speed * rel_sin + own_ship.speed * own_sin;
+    let north = relative.speed * rel_cos + own_ship.speed * own_cos;
+
+    let mut course = east.atan2(north).to_degrees();
+    if course < 0.0 {
+        course += 360.0;
+    }
+
+    TargetMotion {
+        course,
+        speed: (east * east + north * north).sqrt(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "\
+# East at 10 knots
+{\"timeMs\": 0, \"latitude\": 52.0, \"longitude\": 4.0, \"heading\": 90, \"course\": 90, \"speed\": 10}
+
+{\"timeMs\": 60000, \"latitude\": 52.0, \"longitude\": 4.01, \"heading\": 0, \"course\": 0, \"speed\": 0}
+";
+
+    #[test]
+    fn test_parse_script() {
+        let script = OwnShipMotionScript::parse(SCRIPT).unwrap();
+        assert_eq!(script.records().len(), 2);
+        assert_eq!(script.records()[1].time_ms, 60000);
+
+        let err = OwnShipMotionScript::parse("{\"timeMs\": 0}").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+
+    #[test]
+    fn test_own_ship_dead_reckoning() {
+        let script = OwnShipMotionScript::parse(SCRIPT).unwrap();
+
+        // 30 s at 10 knots east = 154.3 m
+        let own = script.own_ship_at(30_000).unwrap();
+        let east = (own.longitude - 4.0) * meters_per_degree_longitude(52.0);
+        assert!((east - 154.33).abs() < 0.1, "east = {}", east);
+        assert_eq!(own.latitude, 52.0);
+
+        // Next record takes over
+        let own = script.own_ship_at(90_000).unwrap();
+        assert_eq!(own.longitude, 4.01);
+        assert_eq!(own.speed, 0.0);
+
+        assert!(OwnShipMotionScript::default().own_ship_at(0).is_none());
+    }
+
+    #[test]
+    fn test_stationary_target_has_no_true_motion() {
+        let script = OwnShipMotionScript::parse(SCRIPT).unwrap();
+        let target = (52.01, 4.02); // Fixed buoy
+
+        // Relative position of the target as the radar sees it
+        let relative_at = |t: u64| {
+            let own = script.own_ship_at(t).unwrap();
+            let north = (target.0 - own.latitude) * METERS_PER_DEGREE_LATITUDE;
+            let east = (target.1 - own.longitude) * meters_per_degree_longitude(own.latitude);
+            (north, east)
+        };
+        let (n0, e0) = relative_at(0);
+        let (n1, e1) = relative_at(10_000);
+
+        // Relative motion: the buoy drifts west at own-ship speed
+        let (vn, ve) = ((n1 - n0) / 10.0, (e1 - e0) / 10.0);
+        let mut course = ve.atan2(vn).to_degrees();
+        if course < 0.0 {
+            course += 360.0;
+        }
+        let relative = TargetMotion {
+            course,
+            speed: (vn * vn + ve * ve).sqrt() / KN_TO_MS,
+        };
+        assert!((relative.speed - 10.0).abs() < 0.01);
+        assert!((relative.course - 270.0).abs() < 0.1);
+
+        let own = script.own_ship_at(10_000).unwrap();
+        let true_motion = relative_to_true_motion(&relative, &own);
+        assert!(true_motion.speed < 0.01, "speed = {}", true_motion.speed);
+    }
+}
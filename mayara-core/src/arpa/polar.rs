@@ -113,6 +113,18 @@ pub fn meters_per_degree_longitude(lat_deg: f64) -> f64 {
     METERS_PER_DEGREE_LATITUDE * lat_deg.to_radians().cos()
 }
 
+/// Bearing and distance from own ship to a position
+///
+/// Returns `(bearing, distance)`: true bearing in degrees (0-360) and
+/// distance in meters. Uses a flat earth around own ship, which is
+/// accurate enough at radar ranges.
+pub fn bearing_distance_to(own_lat: f64, own_lon: f64, lat: f64, lon: f64) -> (f64, f64) {
+    let north = (lat - own_lat) * METERS_PER_DEGREE_LATITUDE;
+    let east = (lon - own_lon) * meters_per_degree_longitude(own_lat);
+    let bearing = east.atan2(north).to_degrees().rem_euclid(360.0);
+    (bearing, north.hypot(east))
+}
+
 /// Polar coordinate converter with radar setup parameters
 #[derive(Debug, Clone)]
 pub struct PolarConverter {
@@ -172,6 +184,18 @@ impl PolarConverter {
         Polar::new(angle as i32, r, time)
     }
 
+    /// Convert local position (meters) to fractional polar coordinates
+    ///
+    /// Unlike [`Self::local_to_polar`] nothing is rounded: returns the angle
+    /// in spokes within [0, spokes_per_revolution) and the radius in pixels,
+    /// for callers that interpolate between spokes and samples.
+    pub fn local_to_polar_f64(&self, lat_m: f64, lon_m: f64) -> (f64, f64) {
+        let r = (lat_m * lat_m + lon_m * lon_m).sqrt() * self.pixels_per_meter;
+        let angle = (lon_m.atan2(lat_m) * self.spokes_per_revolution_f64 / (2.0 * PI))
+            .rem_euclid(self.spokes_per_revolution_f64);
+        (angle, r)
+    }
+
     /// Convert polar to geographic position offset
     ///
     /// Returns (delta_lat_deg, delta_lon_deg) to add to own ship position
@@ -222,6 +246,41 @@ mod tests {
         assert!((pol.angle_in_rad(360.0) - PI / 2.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_bearing_distance_to() {
+        let close = |(b, d): (f64, f64), bearing: f64, distance: f64| {
+            (b - bearing).abs() < 1e-6 && (d - distance).abs() < 1e-6
+        };
+
+        // One arc minute north is one nautical mile
+        assert!(close(
+            bearing_distance_to(52.0, 4.0, 52.0 + 1.0 / 60.0, 4.0),
+            0.0,
+            1852.0
+        ));
+        // One arc minute east on the equator, and west of it
+        assert!(close(
+            bearing_distance_to(0.0, 0.0, 0.0, 1.0 / 60.0),
+            90.0,
+            1852.0
+        ));
+        assert!(close(
+            bearing_distance_to(0.0, 0.0, 0.0, -1.0 / 60.0),
+            270.0,
+            1852.0
+        ));
+        // At 60° N a degree of longitude is half as long
+        assert!(close(
+            bearing_distance_to(60.0, 4.0, 60.0, 4.0 + 1.0 / 30.0),
+            90.0,
+            1852.0
+        ));
+        // South west
+        let (bearing, distance) = bearing_distance_to(0.0, 0.0, -0.01, -0.01);
+        assert!((bearing - 225.0).abs() < 1e-6);
+        assert!((distance - 0.01 * METERS_PER_DEGREE_LATITUDE * 2f64.sqrt()).abs() < 1e-6);
+    }
+
     #[test]
     fn test_mod_spokes() {
         let conv = PolarConverter::new(2048, 1.0);
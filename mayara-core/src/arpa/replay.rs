@@ -0,0 +1,353 @@
+//! Deterministic ARPA Replay and Golden Comparison
+//!
+//! Guards the tracking pipeline against regressions: a recorded spoke
+//! sequence is fed through an [`ArpaProcessor`] with a scripted own ship,
+//! and the resulting tracks are compared with stored ("golden") tracks
+//! within a tolerance.
+//!
+//! # Recording format
+//!
+//! Like an [`OwnShipMotionScript`], a recording is one JSON object per
+//! line, stamped with the time in milliseconds since the start of the
+//! replay; empty lines and lines starting with `#` are ignored. A line
+//! either acquires a target manually or carries one spoke. Spokes are
+//! stored sparsely: all samples are zero except the echoes, each given as
+//! its first sample index and the pixel values from there on:
+//!
+//! ```text
+//! {"timeMs": 0, "type": "acquire", "bearing": 45.0, "distance": 1000.0}
+//! {"timeMs": 2812, "type": "spoke", "bearing": 45.0, "len": 512, "echoes": [{"start": 275, "values": [150, 220, 150]}]}
+//! ```
+//!
+//! # Golden format
+//!
+//! One [`GoldenTrack`] per line, in the same JSON-lines form. A golden file
+//! can be produced from a reviewed replay by serializing
+//! `GoldenTrack::from(&target)` for each replayed target.
+
+use serde::{Deserialize, Serialize};
+
+use super::motion::OwnShipMotionScript;
+use super::tracker::ArpaProcessor;
+use super::types::ArpaTarget;
+use crate::error::ParseError;
+
+/// A run of non-zero samples in a recorded spoke
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Echo {
+    /// Index of the first sample
+    pub start: usize,
+    /// Pixel values from `start` on
+    pub values: Vec<u8>,
+}
+
+/// What happens at one point of a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ReplayEvent {
+    /// Manually acquire a target
+    Acquire { bearing: f64, distance: f64 },
+    /// A spoke of `len` samples, zero outside the echoes
+    Spoke {
+        bearing: f64,
+        len: usize,
+        echoes: Vec<Echo>,
+    },
+}
+
+/// One timestamped line of a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayRecord {
+    /// Milliseconds since the start of the replay
+    pub time_ms: u64,
+    #[serde(flatten)]
+    pub event: ReplayEvent,
+}
+
+/// A recorded spoke sequence (see module docs)
+#[derive(Debug, Clone, Default)]
+pub struct ReplayRecording {
+    records: Vec<ReplayRecord>,
+}
+
+/// Parse JSON lines, skipping blank lines and `#` comments
+fn parse_lines<T: serde::de::DeserializeOwned>(text: &str) -> Result<Vec<T>, ParseError> {
+    let mut items = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let item = serde_json::from_str(line)
+            .map_err(|e| ParseError::DeserializationFailed(format!("line {}: {}", n + 1, e)))?;
+        items.push(item);
+    }
+    Ok(items)
+}
+
+impl ReplayRecording {
+    /// Create a recording from records (stably sorted by time)
+    pub fn new(mut records: Vec<ReplayRecord>) -> Self {
+        records.sort_by_key(|r| r.time_ms);
+        ReplayRecording { records }
+    }
+
+    /// Parse a recording in JSON-lines format (see module docs)
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        Ok(Self::new(parse_lines(text)?))
+    }
+
+    /// Get the records in time order
+    pub fn records(&self) -> &[ReplayRecord] {
+        &self.records
+    }
+}
+
+/// Expand a sparse recorded spoke into its pixel data
+pub fn spoke_data(len: usize, echoes: &[Echo]) -> Vec<u8> {
+    let mut data = vec![0u8; len];
+    for echo in echoes {
+        let start = echo.start.min(len);
+        let end = (echo.start + echo.values.len()).min(len);
+        data[start..end].copy_from_slice(&echo.values[..end - start]);
+    }
+    data
+}
+
+/// Feed a recording through `processor`, in time order
+///
+/// Before each record the own ship is set from `motion` (if it has a
+/// state for that time), so CPA/TCPA and target positions follow the
+/// script. Returns the tracked targets at the end, sorted by ID.
+pub fn replay(
+    processor: &mut ArpaProcessor,
+    recording: &ReplayRecording,
+    motion: &OwnShipMotionScript,
+) -> Vec<ArpaTarget> {
+    for record in recording.records() {
+        if let Some(own_ship) = motion.own_ship_at(record.time_ms) {
+            processor.update_own_ship(own_ship);
+        }
+        match &record.event {
+            ReplayEvent::Acquire { bearing, distance } => {
+                processor.acquire_target(*bearing, *distance, record.time_ms);
+            }
+            ReplayEvent::Spoke {
+                bearing,
+                len,
+                echoes,
+            } => {
+                processor.process_spoke(&spoke_data(*len, echoes), *bearing, record.time_ms);
+            }
+        }
+    }
+
+    let mut targets = processor.get_targets();
+    targets.sort_by_key(|t| t.id);
+    targets
+}
+
+/// Expected state of one track at the end of a replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoldenTrack {
+    pub id: u32,
+    /// Bearing from own ship in degrees
+    pub bearing: f64,
+    /// Distance from own ship in meters
+    pub distance: f64,
+    /// Course in degrees
+    pub course: f64,
+    /// Speed in knots
+    pub speed: f64,
+}
+
+impl From<&ArpaTarget> for GoldenTrack {
+    fn from(target: &ArpaTarget) -> Self {
+        GoldenTrack {
+            id: target.id,
+            bearing: target.position.bearing,
+            distance: target.position.distance,
+            course: target.motion.course,
+            speed: target.motion.speed,
+        }
+    }
+}
+
+/// Parse golden tracks in JSON-lines format
+pub fn parse_golden(text: &str) -> Result<Vec<GoldenTrack>, ParseError> {
+    parse_lines(text)
+}
+
+/// Allowed deviation from the golden tracks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenTolerance {
+    /// Distance between actual and expected position, in meters
+    pub position_m: f64,
+    /// Course difference in degrees; not checked for targets expected to
+    /// move slower than `min_speed_for_course_kn`
+    pub course_deg: f64,
+    /// Speed difference in knots
+    pub speed_kn: f64,
+    /// Below this expected speed the course is noise and is not compared
+    pub min_speed_for_course_kn: f64,
+}
+
+impl Default for GoldenTolerance {
+    fn default() -> Self {
+        GoldenTolerance {
+            position_m: 10.0,
+            course_deg: 5.0,
+            speed_kn: 1.0,
+            min_speed_for_course_kn: 1.0,
+        }
+    }
+}
+
+/// Compare replayed targets with golden tracks
+///
+/// Returns a description of every mismatch: missing or unexpected
+/// targets, and positions, courses or speeds outside `tolerance`. An
+/// empty result means the replay matches.
+pub fn compare_golden(
+    actual: &[ArpaTarget],
+    golden: &[GoldenTrack],
+    tolerance: &GoldenTolerance,
+) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for expected in golden {
+        let Some(target) = actual.iter().find(|t| t.id == expected.id) else {
+            mismatches.push(format!("target {}: missing", expected.id));
+            continue;
+        };
+        let found = GoldenTrack::from(target);
+
+        let position_error = position_distance(&found, expected);
+        if position_error > tolerance.position_m {
+            mismatches.push(format!(
+                "target {}: position off by {:.1} m ({:.1}° {:.1} m, expected {:.1}° {:.1} m)",
+                expected.id,
+                position_error,
+                found.bearing,
+                found.distance,
+                expected.bearing,
+                expected.distance
+            ));
+        }
+        if expected.speed >= tolerance.min_speed_for_course_kn {
+            let course_error = angle_difference(found.course, expected.course);
+            if course_error > tolerance.course_deg {
+                mismatches.push(format!(
+                    "target {}: course {:.1}°, expected {:.1}°",
+                    expected.id, found.course, expected.course
+                ));
+            }
+        }
+        if (found.speed - expected.speed).abs() > tolerance.speed_kn {
+            mismatches.push(format!(
+                "target {}: speed {:.2} kn, expected {:.2} kn",
+                expected.id, found.speed, expected.speed
+            ));
+        }
+    }
+
+    for target in actual {
+        if !golden.iter().any(|g| g.id == target.id) {
+            mismatches.push(format!("target {}: unexpected", target.id));
+        }
+    }
+
+    mismatches
+}
+
+/// Straight-line distance in meters between two tracks' positions
+fn position_distance(a: &GoldenTrack, b: &GoldenTrack) -> f64 {
+    let (a_sin, a_cos) = a.bearing.to_radians().sin_cos();
+    let (b_sin, b_cos) = b.bearing.to_radians().sin_cos();
+    let dx = a.distance * a_sin - b.distance * b_sin;
+    let dy = a.distance * a_cos - b.distance * b_cos;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Absolute difference between two angles in degrees (0-180)
+fn angle_difference(a: f64, b: f64) -> f64 {
+    let diff = (a - b).rem_euclid(360.0);
+    diff.min(360.0 - diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arpa::types::ArpaSettings;
+
+    const CROSSING: &str = include_str!("testdata/crossing.jsonl");
+    const CROSSING_MOTION: &str = include_str!("testdata/crossing_motion.jsonl");
+    const CROSSING_GOLDEN: &str = include_str!("testdata/crossing_golden.jsonl");
+
+    fn replay_crossing() -> Vec<ArpaTarget> {
+        let settings = ArpaSettings {
+            auto_acquisition: true,
+            ..Default::default()
+        };
+        let mut processor = ArpaProcessor::new(settings);
+        let recording = ReplayRecording::parse(CROSSING).unwrap();
+        let motion = OwnShipMotionScript::parse(CROSSING_MOTION).unwrap();
+        replay(&mut processor, &recording, &motion)
+    }
+
+    #[test]
+    fn test_crossing_matches_golden() {
+        let golden = parse_golden(CROSSING_GOLDEN).unwrap();
+        let targets = replay_crossing();
+        let mismatches = compare_golden(&targets, &golden, &GoldenTolerance::default());
+        assert!(mismatches.is_empty(), "{:#?}", mismatches);
+
+        // The replay is deterministic
+        let again: Vec<GoldenTrack> = replay_crossing().iter().map(GoldenTrack::from).collect();
+        let first: Vec<GoldenTrack> = targets.iter().map(GoldenTrack::from).collect();
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn test_golden_mismatches_reported() {
+        let golden = parse_golden(CROSSING_GOLDEN).unwrap();
+        let targets = replay_crossing();
+
+        let mut moved = golden.clone();
+        moved[0].distance += 50.0;
+        moved[1].course += 90.0;
+        moved[1].speed += 3.0;
+        moved.push(GoldenTrack {
+            id: 7,
+            ..golden[0].clone()
+        });
+        let mismatches = compare_golden(&targets, &moved, &GoldenTolerance::default());
+        assert_eq!(mismatches.len(), 4, "{:#?}", mismatches);
+        assert!(mismatches[0].starts_with("target 1: position"));
+        assert!(mismatches[3].starts_with("target 7: missing"));
+
+        let mismatches = compare_golden(&targets, &golden[..1], &GoldenTolerance::default());
+        assert_eq!(mismatches, vec!["target 2: unexpected".to_string()]);
+    }
+
+    #[test]
+    fn test_spoke_data() {
+        let echoes = vec![
+            Echo {
+                start: 2,
+                values: vec![5, 6],
+            },
+            Echo {
+                start: 7,
+                values: vec![9, 9, 9],
+            },
+        ];
+        assert_eq!(spoke_data(8, &echoes), vec![0, 0, 5, 6, 0, 0, 0, 9]);
+
+        let err = ReplayRecording::parse("{\"timeMs\": 0, \"type\": \"jump\"}").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+}
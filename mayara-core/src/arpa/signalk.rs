@@ -0,0 +1,165 @@
+//! Publishing tracked targets on SignalK paths
+//!
+//! Each target is emitted at `radars.{id}.targets.{target_id}` with fields
+//! in SignalK units (radians, m/s, meters, seconds), analogous to the
+//! per-control deltas in [`crate::state::ControlChangeTracker`].
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::polar::KN_TO_MS;
+use super::types::ArpaTarget;
+
+/// A target that changed since the last emit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetDelta {
+    /// ARPA target ID
+    pub target_id: u32,
+    /// Target value (see [`target_signalk_value`]), or null once the
+    /// target is no longer tracked
+    pub value: serde_json::Value,
+}
+
+impl TargetDelta {
+    /// SignalK path for this target, e.g. `radars.{id}.targets.3`
+    pub fn path(&self, radar_id: &str) -> String {
+        format!("radars.{}.targets.{}", radar_id, self.target_id)
+    }
+}
+
+/// SignalK value for a tracked target
+///
+/// ```json
+/// {
+///   "position": {"latitude": 52.1, "longitude": 4.2},
+///   "bearing": 0.785, "distance": 1852.0,
+///   "courseOverGroundTrue": 1.571, "speedOverGround": 5.14,
+///   "cpa": 120.0, "tcpa": 300.0,
+///   "status": "tracking", "dopplerState": "approaching"
+/// }
+/// ```
+///
+/// `position` is only present when own ship position is known.
+pub fn target_signalk_value(target: &ArpaTarget) -> serde_json::Value {
+    let mut value = serde_json::json!({
+        "bearing": target.position.bearing.to_radians(),
+        "distance": target.position.distance,
+        "courseOverGroundTrue": target.motion.course.to_radians(),
+        "speedOverGround": target.motion.speed * KN_TO_MS,
+        "cpa": target.danger.cpa,
+        "tcpa": target.danger.tcpa,
+        "status": target.status,
+        "dopplerState": target.doppler_state,
+    });
+    if let (Some(latitude), Some(longitude)) = (target.position.latitude, target.position.longitude)
+    {
+        value["position"] = serde_json::json!({
+            "latitude": latitude,
+            "longitude": longitude,
+        });
+    }
+    value
+}
+
+/// Tracks the last emitted target values so only changed targets are sent
+#[derive(Debug, Clone, Default)]
+pub struct TargetChangeTracker {
+    last: HashMap<u32, serde_json::Value>,
+}
+
+impl TargetChangeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return new and changed targets, plus a null delta for each target
+    /// that disappeared, and remember the new values.
+    ///
+    /// Deltas are sorted by target ID so output is deterministic.
+    pub fn changes(&mut self, targets: &[ArpaTarget]) -> Vec<TargetDelta> {
+        let current: HashMap<u32, serde_json::Value> = targets
+            .iter()
+            .map(|t| (t.id, target_signalk_value(t)))
+            .collect();
+
+        let mut deltas: Vec<TargetDelta> = current
+            .iter()
+            .filter(|(id, value)| self.last.get(*id) != Some(*value))
+            .map(|(id, value)| TargetDelta {
+                target_id: *id,
+                value: value.clone(),
+            })
+            .chain(
+                self.last
+                    .keys()
+                    .filter(|id| !current.contains_key(*id))
+                    .map(|id| TargetDelta {
+                        target_id: *id,
+                        value: serde_json::Value::Null,
+                    }),
+            )
+            .collect();
+        deltas.sort_by_key(|d| d.target_id);
+
+        self.last = current;
+        deltas
+    }
+
+    /// Forget all previous values, so the next call emits every target
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arpa::types::{AcquisitionMethod, TargetStatus};
+
+    fn target() -> ArpaTarget {
+        let mut target = ArpaTarget::new(3, 90.0, 1852.0, 1000, AcquisitionMethod::Manual);
+        target.status = TargetStatus::Tracking;
+        target.position.latitude = Some(52.0);
+        target.position.longitude = Some(4.0);
+        target.motion.course = 180.0;
+        target.motion.speed = 10.0;
+        target.danger.cpa = 150.0;
+        target.danger.tcpa = 600.0;
+        target
+    }
+
+    #[test]
+    fn test_target_signalk_json() {
+        let mut tracker = TargetChangeTracker::new();
+        let deltas = tracker.changes(&[target()]);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].path("radar-1"), "radars.radar-1.targets.3");
+
+        let value = &deltas[0].value;
+        assert_eq!(value["position"]["latitude"], 52.0);
+        assert_eq!(value["position"]["longitude"], 4.0);
+        assert!(
+            (value["courseOverGroundTrue"].as_f64().unwrap() - std::f64::consts::PI).abs() < 1e-9
+        );
+        assert!((value["speedOverGround"].as_f64().unwrap() - 5.1444).abs() < 1e-3);
+        assert_eq!(value["cpa"], 150.0);
+        assert_eq!(value["tcpa"], 600.0);
+        assert_eq!(value["status"], "tracking");
+    }
+
+    #[test]
+    fn test_only_changed_targets_emitted() {
+        let mut tracker = TargetChangeTracker::new();
+        tracker.changes(&[target()]);
+        assert!(tracker.changes(&[target()]).is_empty());
+
+        let mut moved = target();
+        moved.position.distance = 1800.0;
+        assert_eq!(tracker.changes(&[moved.clone()]).len(), 1);
+
+        let deltas = tracker.changes(&[]);
+        assert_eq!(deltas.len(), 1);
+        assert!(deltas[0].value.is_null());
+    }
+}
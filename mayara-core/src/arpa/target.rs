@@ -4,11 +4,12 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::contour::{Contour, ContourError, MAX_CONTOUR_LENGTH};
+use super::contour::{Contour, ContourError, ContourLimits, ContourMerge};
 use super::doppler::DopplerState;
 use super::history::HistoryBuffer;
 use super::kalman::KalmanFilter;
 use super::polar::{LocalPosition, Polar, PolarConverter, METERS_PER_DEGREE_LATITUDE, MS_TO_KN, meters_per_degree_longitude};
+use super::types::{track_confidence, update_hit_rate};
 
 /// Maximum number of sweeps a target can be missed before being marked lost
 pub const MAX_LOST_COUNT: i32 = 12;
@@ -149,6 +150,12 @@ pub struct TargetState {
     pub age_rotations: u32,
     /// Small and fast target flag
     pub small_fast: bool,
+    /// Position is dead-reckoned, the target was missed in recent scans
+    pub dead_reckoning: bool,
+    /// Distance in meters between the last echo and where it was predicted
+    pub residual: Option<f64>,
+    /// Moving average of scans in which the target was found (1 = every scan)
+    pub hit_rate: f64,
 }
 
 impl TargetState {
@@ -187,9 +194,25 @@ impl TargetState {
             have_doppler,
             age_rotations: 0,
             small_fast: false,
+            dead_reckoning: false,
+            residual: None,
+            hit_rate: 0.0,
         }
     }
 
+    /// Track quality from 0 (unreliable) to 1 (solid)
+    ///
+    /// The same score as [`ArpaTarget::confidence`](super::ArpaTarget::confidence),
+    /// from this target's age, Kalman covariance, residual and hit rate.
+    pub fn confidence(&self) -> f64 {
+        track_confidence(
+            self.age_rotations,
+            self.kalman.position_variance(),
+            self.residual,
+            self.hit_rate,
+        )
+    }
+
     /// Reset target to lost state
     pub fn set_lost(&mut self) {
         self.contour = Contour::new();
@@ -265,13 +288,21 @@ pub struct RefreshConfig {
     pub pixels_per_meter: f64,
     pub rotation_period_ms: u64,
     pub have_doppler: bool,
+    /// Keep moving missed targets along their last velocity until they
+    /// are dropped at `MAX_LOST_COUNT`
+    pub dead_reckoning: bool,
+    /// Contour lengths accepted as a target
+    pub contour_limits: ContourLimits,
+    /// Gaps within which nearby contours are merged into one target
+    pub contour_merge: ContourMerge,
 }
 
 /// Refresh a target - the core ARPA algorithm
 ///
 /// This is the main target tracking function. It:
 /// 1. Predicts where the target should be
-/// 2. Searches for a matching contour
+/// 2. Searches for a matching contour, merged with nearby contours of the
+///    same target
 /// 3. Updates the Kalman filter with the measurement
 ///
 /// # Arguments
@@ -303,6 +334,7 @@ pub fn refresh_target(
     }
 
     let converter = PolarConverter::new(config.spokes_per_revolution, config.pixels_per_meter);
+    history.set_contour_limits(config.contour_limits);
 
     // Calculate expected polar position
     let mut pol = converter.geo_to_polar(
@@ -388,8 +420,10 @@ pub fn refresh_target(
     let found = history.get_target(&doppler, pol, dist);
 
     match found {
-        Ok((contour, pos)) => {
-            // Target found!
+        Ok((contour, _)) => {
+            // Target found! Combine it with nearby blobs of the same ship
+            let contour = history.merge_nearby_contours(&doppler, contour, &config.contour_merge);
+            let pos = contour.position;
             target.contour = contour.clone();
 
             // Count pixels and update Doppler state
@@ -419,13 +453,15 @@ pub fn refresh_target(
             history.reset_pixels(&contour, &pos, config.pixels_per_meter);
 
             // Check for oversized contour (interference)
-            if target.contour.length >= MAX_CONTOUR_LENGTH as i32 - 2 {
+            if target.contour.length >= config.contour_limits.max_length as i32 - 2 {
                 return Err(ContourError::ContourTooLong);
             }
 
             // Update target state
             target.lost_count = 0;
+            target.dead_reckoning = false;
             target.age_rotations += 1;
+            target.hit_rate = update_hit_rate(target.hit_rate, true);
 
             // Status progression
             target.status = match target.status {
@@ -451,6 +487,10 @@ pub fn refresh_target(
                 target.age_rotations = 0;
             }
 
+            if target.status != TargetStatus::Acquire1 {
+                target.residual = Some(polar_distance(&pos, &target.expected, config));
+            }
+
             // Kalman update for status >= Acquire2
             if target.status == TargetStatus::Acquire2 || target.status == TargetStatus::Acquire3 {
                 target.kalman.update_covariance();
@@ -542,13 +582,18 @@ pub fn refresh_target(
         }
         Err(_) => {
             // Target not found
-            handle_target_not_found(target, pol, pass)
+            handle_target_not_found(target, pol, pass, config)
         }
     }
 }
 
 /// Handle the case when target is not found
-fn handle_target_not_found(target: &mut TargetState, _pol: Polar, pass: Pass) -> Result<(), ContourError> {
+fn handle_target_not_found(
+    target: &mut TargetState,
+    _pol: Polar,
+    pass: Pass,
+    config: &RefreshConfig,
+) -> Result<(), ContourError> {
     // Small-fast targets must be found quickly
     if target.small_fast && pass == Pass::Second && target.status == TargetStatus::Acquire2 {
         return Err(ContourError::Lost);
@@ -563,6 +608,7 @@ fn handle_target_not_found(target: &mut TargetState, _pol: Polar, pass: Pass) ->
 
     if pass == Pass::Third {
         target.lost_count += 1;
+        target.hit_rate = update_hit_rate(target.hit_rate, false);
     }
 
     // Delete if not found too often
@@ -570,12 +616,45 @@ fn handle_target_not_found(target: &mut TargetState, _pol: Polar, pass: Pass) ->
         return Err(ContourError::Lost);
     }
 
+    // Once per scan, move the target along its last velocity so the
+    // displayed track doesn't freeze during a brief dropout
+    if pass == Pass::Third && config.dead_reckoning {
+        dead_reckon(target, target.refresh_time);
+    }
+
     target.refreshed = RefreshState::NotFound;
     target.transferred = false;
 
     Ok(())
 }
 
+/// Distance in meters between two polar positions close to each other
+fn polar_distance(a: &Polar, b: &Polar, config: &RefreshConfig) -> f64 {
+    let spokes = config.spokes_per_revolution;
+    let mut angle = (a.angle - b.angle).rem_euclid(spokes);
+    if angle > spokes / 2 {
+        angle -= spokes;
+    }
+    let along = (a.r - b.r) as f64 / config.pixels_per_meter;
+    let across = angle as f64 * 2.0 * std::f64::consts::PI / spokes as f64 * a.r as f64
+        / config.pixels_per_meter;
+    along.hypot(across)
+}
+
+/// Project the target position forward to `time` (ms) using its velocity,
+/// and flag it as dead-reckoned
+pub fn dead_reckon(target: &mut TargetState, time: u64) {
+    if time <= target.position.time {
+        return;
+    }
+    let dt = (time - target.position.time) as f64 / 1000.0;
+    target.position.lat += target.position.dlat_dt * dt / METERS_PER_DEGREE_LATITUDE;
+    target.position.lon +=
+        target.position.dlon_dt * dt / meters_per_degree_longitude(target.position.lat);
+    target.position.time = time;
+    target.dead_reckoning = true;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -590,6 +669,44 @@ mod tests {
         assert!(!target.have_doppler);
     }
 
+    #[test]
+    fn test_dead_reckoning_until_dropped() {
+        let config = RefreshConfig {
+            spokes_per_revolution: 2048,
+            spoke_len: 1024,
+            pixels_per_meter: 0.5,
+            rotation_period_ms: 2500,
+            have_doppler: false,
+            dead_reckoning: true,
+            contour_limits: ContourLimits::default(),
+            contour_merge: ContourMerge::default(),
+        };
+        // Heading north at 5 m/s
+        let pos = ExtendedPosition::new(51.5, -0.1, 5.0, 0.0, 1000, 9.7, 0.0);
+        let mut target = TargetState::new(1, pos, 51.5, -0.1, 2048, TargetStatus::Active, false);
+
+        let mut last_lat = target.position.lat;
+        for scan in 1..=MAX_LOST_COUNT {
+            target.refresh_time = 1000 + scan as u64 * 2500;
+            assert!(
+                handle_target_not_found(&mut target, Polar::default(), Pass::Third, &config)
+                    .is_ok()
+            );
+            assert!(target.dead_reckoning);
+            assert!(target.position.lat > last_lat);
+            last_lat = target.position.lat;
+        }
+        // 12 scans * 2.5 s * 5 m/s = 150 m north
+        let moved = (target.position.lat - 51.5) * METERS_PER_DEGREE_LATITUDE;
+        assert!((moved - 150.0).abs() < 0.01);
+
+        target.refresh_time += 2500;
+        assert!(matches!(
+            handle_target_not_found(&mut target, Polar::default(), Pass::Third, &config),
+            Err(ContourError::Lost)
+        ));
+    }
+
     #[test]
     fn test_target_set_lost() {
         let pos = ExtendedPosition::new(51.5, -0.1, 5.0, 3.0, 1000, 10.0, 1.0);
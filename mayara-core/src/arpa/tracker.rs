@@ -6,6 +6,7 @@ use std::collections::HashMap;
 
 use super::cpa::calculate_danger;
 use super::detector::{DetectedTarget, TargetDetector};
+use super::history::Legend;
 use super::types::*;
 
 /// Main ARPA processor
@@ -67,6 +68,11 @@ impl ArpaProcessor {
         self.detector.set_range_scale(range_meters);
     }
 
+    /// Set the pixel values that mark Doppler returns in spoke data
+    pub fn set_legend(&mut self, legend: Legend) {
+        self.detector.set_legend(legend);
+    }
+
     /// Manually acquire a target at the specified position
     ///
     /// # Returns
@@ -81,10 +87,32 @@ impl ArpaProcessor {
             return None;
         }
 
-        let id = self.next_id;
-        self.next_id += 1;
-        if self.next_id > 99 {
-            self.next_id = 1;  // Wrap around
+        let id = self.next_free_id();
+        let track = TrackingState::new(id, bearing, distance, timestamp, AcquisitionMethod::Manual);
+        self.tracks.insert(id, track);
+        Some(id)
+    }
+
+    /// Manually acquire a target under a caller-chosen ID
+    ///
+    /// # Returns
+    ///
+    /// The target ID, or None if the ID is 0, reserved (see
+    /// [`RESERVED_TARGET_ID_START`]) or in use, or max targets reached
+    pub fn acquire_target_with_id(
+        &mut self,
+        id: u32,
+        bearing: f64,
+        distance: f64,
+        timestamp: u64,
+    ) -> Option<u32> {
+        if !self.settings.enabled
+            || id == 0
+            || id >= RESERVED_TARGET_ID_START
+            || self.tracks.contains_key(&id)
+            || self.tracks.len() >= self.settings.max_targets as usize
+        {
+            return None;
         }
 
         let track = TrackingState::new(id, bearing, distance, timestamp, AcquisitionMethod::Manual);
@@ -92,6 +120,22 @@ impl ArpaProcessor {
         Some(id)
     }
 
+    /// Next manual target ID (1-99, wrapping), skipping IDs already in use
+    fn next_free_id(&mut self) -> u32 {
+        let mut id = self.next_id;
+        for _ in 0..99 {
+            id = self.next_id;
+            self.next_id += 1;
+            if self.next_id > 99 {
+                self.next_id = 1; // Wrap around
+            }
+            if !self.tracks.contains_key(&id) {
+                break;
+            }
+        }
+        id
+    }
+
     /// Cancel tracking of a target
     pub fn cancel_target(&mut self, target_id: u32) -> bool {
         self.tracks.remove(&target_id).is_some()
@@ -195,9 +239,33 @@ impl ArpaProcessor {
             })
             .collect();
 
+        // A track the sweep has just passed without a matching echo was
+        // missed in this scan: move it along its last velocity so it
+        // doesn't freeze until it is marked lost
+        for track in self.tracks.values_mut() {
+            if !track.in_window || matching_ids.contains(&track.id) {
+                continue;
+            }
+            track.in_window = false;
+            if std::mem::take(&mut track.hit_in_window) || !self.settings.dead_reckoning {
+                continue;
+            }
+            let dt = (timestamp - track.position_time) as f64 / 1000.0;
+            Self::kalman_predict_track(track, dt, self.process_noise);
+            track.position_time = timestamp;
+            track.dead_reckoning = true;
+
+            let status = Self::get_status_for_track(track);
+            let danger = Self::calculate_danger_for_track(track, self.own_ship.as_ref());
+            let target = track.to_arpa_target(status, danger, self.own_ship.as_ref());
+            events.push(ArpaEvent::TargetUpdate { target });
+        }
+
         // Process each matching track
         for id in matching_ids {
             if let Some(track) = self.tracks.get_mut(&id) {
+                track.in_window = true;
+
                 // Find best matching detection
                 let expected_distance = track.distance();
                 let best_detection = detections.iter().min_by(|a, b| {
@@ -206,44 +274,53 @@ impl ArpaProcessor {
                     dist_a.partial_cmp(&dist_b).unwrap()
                 });
 
-                if let Some(det) = best_detection {
-                    // Check if detection is close enough
-                    let distance_tolerance = expected_distance * 0.2; // 20% tolerance
-                    if (det.distance - expected_distance).abs() < distance_tolerance {
-                        // Update track with measurement
-                        let dt = (timestamp - track.last_seen) as f64 / 1000.0;
-                        Self::kalman_update_track(
-                            track,
-                            det.bearing,
-                            det.distance,
-                            dt,
-                            self.process_noise,
-                            self.measurement_noise,
-                        );
-                        track.last_seen = timestamp;
-                        track.update_count += 1;
-
-                        // Calculate danger and emit event
-                        let status = Self::get_status_for_track(track);
-                        let danger = Self::calculate_danger_for_track(track, self.own_ship.as_ref());
-                        let target = track.to_arpa_target(status, danger, self.own_ship.as_ref());
-
-                        // Check for collision warning state change
-                        let alert_state = target.alert_state(&self.settings);
-                        if alert_state != track.prev_alert_state {
-                            track.prev_alert_state = alert_state;
-                            if alert_state != AlertState::Normal {
-                                events.push(ArpaEvent::CollisionWarning {
-                                    target_id: track.id,
-                                    state: alert_state,
-                                    cpa: danger.cpa,
-                                    tcpa: danger.tcpa,
-                                });
-                            }
+                // Check if detection is close enough
+                let distance_tolerance = expected_distance * 0.2; // 20% tolerance
+                let matched = best_detection
+                    .filter(|det| (det.distance - expected_distance).abs() < distance_tolerance);
+                track.record_hit(matched.is_some());
+
+                if let Some(det) = matched {
+                    // Update track with measurement
+                    let dt = (timestamp - track.position_time) as f64 / 1000.0;
+                    Self::kalman_update_track(
+                        track,
+                        det.bearing,
+                        det.distance,
+                        dt,
+                        self.process_noise,
+                        self.measurement_noise,
+                    );
+                    track.last_seen = timestamp;
+                    track.position_time = timestamp;
+                    track.hit_in_window = true;
+                    track.dead_reckoning = false;
+                    track.update_count += 1;
+                    track.doppler =
+                        track
+                            .doppler
+                            .transition(det.size, det.approaching_pix, det.receding_pix);
+
+                    // Calculate danger and emit event
+                    let status = Self::get_status_for_track(track);
+                    let danger = Self::calculate_danger_for_track(track, self.own_ship.as_ref());
+                    let target = track.to_arpa_target(status, danger, self.own_ship.as_ref());
+
+                    // Check for collision warning state change
+                    let alert_state = target.alert_state(&self.settings);
+                    if alert_state != track.prev_alert_state {
+                        track.prev_alert_state = alert_state;
+                        if alert_state != AlertState::Normal {
+                            events.push(ArpaEvent::CollisionWarning {
+                                target_id: track.id,
+                                state: alert_state,
+                                cpa: danger.cpa,
+                                tcpa: danger.tcpa,
+                            });
                         }
-
-                        events.push(ArpaEvent::TargetUpdate { target });
                     }
+
+                    events.push(ArpaEvent::TargetUpdate { target });
                 }
             }
         }
@@ -308,6 +385,7 @@ impl ArpaProcessor {
         // Innovation (measurement residual)
         let y_x = z_x - track.x;
         let y_y = z_y - track.y;
+        track.residual = Some((y_x * y_x + y_y * y_y).sqrt());
 
         // Innovation covariance: S = H*P*H' + R
         // H = [[1, 0, 0, 0], [0, 1, 0, 0]]
@@ -443,6 +521,7 @@ impl ArpaProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::arpa::doppler::DopplerState;
 
     fn test_settings() -> ArpaSettings {
         ArpaSettings {
@@ -470,6 +549,27 @@ mod tests {
         assert!((targets[0].position.distance - 1000.0).abs() < 1.0);
     }
 
+    #[test]
+    fn test_acquire_target_with_id() {
+        let mut processor = ArpaProcessor::new(test_settings());
+
+        assert_eq!(
+            processor.acquire_target_with_id(1, 45.0, 1000.0, 0),
+            Some(1)
+        );
+        // In use, zero and reserved IDs are refused
+        assert_eq!(processor.acquire_target_with_id(1, 90.0, 1000.0, 0), None);
+        assert_eq!(processor.acquire_target_with_id(0, 90.0, 1000.0, 0), None);
+        assert_eq!(
+            processor.acquire_target_with_id(RESERVED_TARGET_ID_START, 90.0, 1000.0, 0),
+            None
+        );
+
+        // Assigned IDs skip the user's
+        assert_eq!(processor.acquire_target(90.0, 1000.0, 0), Some(2));
+        assert_eq!(processor.target_count(), 2);
+    }
+
     #[test]
     fn test_cancel_target() {
         let mut processor = ArpaProcessor::new(test_settings());
@@ -549,4 +649,99 @@ mod tests {
         assert_eq!(targets[0].status, TargetStatus::Acquiring);
         // After 3 updates it would transition to Tracking
     }
+
+    #[test]
+    fn test_confidence_rises_with_consistent_updates() {
+        let mut settings = test_settings();
+        settings.auto_acquisition = true;
+        let mut processor = ArpaProcessor::new(settings);
+        let id = processor.acquire_target(45.0, 1000.0, 0).unwrap();
+        let fresh = processor.get_target(id).unwrap().confidence;
+        assert!(fresh < 0.3, "fresh confidence {}", fresh);
+
+        let mut spoke = vec![0u8; 512];
+        for pixel in &mut spoke[272..282] {
+            *pixel = 200;
+        }
+        let mut previous = fresh;
+        for revolution in 1..=12 {
+            processor.process_spoke(&spoke, 45.0, revolution * 2500);
+            let confidence = processor.get_target(id).unwrap().confidence;
+            assert!(
+                confidence > previous,
+                "revolution {}: {} <= {}",
+                revolution,
+                confidence,
+                previous
+            );
+            previous = confidence;
+        }
+        assert!(previous > 0.8, "settled confidence {}", previous);
+
+        // Spokes at the target's bearing without its echo lower it again
+        processor.process_spoke(&vec![0u8; 512], 45.0, 32_500);
+        assert!(processor.get_target(id).unwrap().confidence < previous);
+    }
+
+    #[test]
+    fn test_missed_target_is_dead_reckoned() {
+        let mut settings = test_settings();
+        settings.auto_acquisition = true;
+        let mut processor = ArpaProcessor::new(settings.clone());
+        let id = processor.acquire_target(45.0, 1000.0, 0).unwrap();
+        processor.tracks.get_mut(&id).unwrap().vy = 5.0; // North at 5 m/s
+        let y = processor.tracks[&id].y;
+
+        // The sweep passes the target without its echo
+        processor.process_spoke(&vec![0u8; 512], 45.0, 2500);
+        assert!(!processor.get_target(id).unwrap().dead_reckoning);
+        let events = processor.process_spoke(&vec![0u8; 512], 90.0, 2600);
+        let target = match &events[..] {
+            [ArpaEvent::TargetUpdate { target }] => target.clone(),
+            _ => panic!("Expected TargetUpdate event, got {:?}", events),
+        };
+        assert!(target.dead_reckoning);
+        assert!((processor.tracks[&id].y - y - 13.0).abs() < 1e-9);
+
+        // Seen again, the flag clears
+        let mut spoke = vec![0u8; 512];
+        for pixel in &mut spoke[272..282] {
+            *pixel = 200;
+        }
+        processor.process_spoke(&spoke, 45.0, 5000);
+        assert!(!processor.get_target(id).unwrap().dead_reckoning);
+
+        // Not with dead reckoning switched off
+        settings.dead_reckoning = false;
+        let mut processor = ArpaProcessor::new(settings);
+        let id = processor.acquire_target(45.0, 1000.0, 0).unwrap();
+        processor.process_spoke(&vec![0u8; 512], 45.0, 2500);
+        assert!(processor
+            .process_spoke(&vec![0u8; 512], 90.0, 2600)
+            .is_empty());
+        assert!(!processor.get_target(id).unwrap().dead_reckoning);
+    }
+
+    #[test]
+    fn test_doppler_approaching_target_flagged() {
+        let mut settings = test_settings();
+        settings.auto_acquisition = true;
+        let mut processor = ArpaProcessor::new(settings);
+        let id = processor.acquire_target(45.0, 1000.0, 0).unwrap();
+        assert_eq!(
+            processor.get_target(id).unwrap().doppler_state,
+            DopplerState::Any
+        );
+
+        // Echo around 1000m made up entirely of Doppler approaching pixels
+        let legend = Legend::default();
+        let mut spoke = vec![0u8; 512];
+        for pixel in &mut spoke[272..282] {
+            *pixel = legend.doppler_approaching;
+        }
+        processor.process_spoke(&spoke, 45.0, 1000);
+
+        let target = processor.get_target(id).unwrap();
+        assert_eq!(target.doppler_state, DopplerState::Approaching);
+    }
 }
@@ -4,6 +4,15 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::contour::{ContourLimits, ContourMerge};
+use super::doppler::DopplerState;
+
+/// First target ID reserved for automatically acquired targets.
+///
+/// Manually acquired targets get IDs below this; user-supplied IDs must be
+/// in `1..RESERVED_TARGET_ID_START`.
+pub const RESERVED_TARGET_ID_START: u32 = 1000;
+
 /// Target acquisition method
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -94,6 +103,16 @@ pub struct ArpaTarget {
     pub first_seen: u64,
     /// Unix timestamp (ms) of last radar return
     pub last_seen: u64,
+    /// Doppler classification of the target's echo (approaching/receding)
+    #[serde(default)]
+    pub doppler_state: DopplerState,
+    /// Track quality from 0 (unreliable) to 1 (solid), see
+    /// [`TrackingState::confidence`]; clients may hide low-confidence tracks
+    #[serde(default)]
+    pub confidence: f64,
+    /// Position is dead-reckoned, the target was missed in recent scans
+    #[serde(default)]
+    pub dead_reckoning: bool,
 }
 
 impl ArpaTarget {
@@ -113,6 +132,9 @@ impl ArpaTarget {
             acquisition: method,
             first_seen: timestamp,
             last_seen: timestamp,
+            doppler_state: DopplerState::Any,
+            confidence: 0.0,
+            dead_reckoning: false,
         }
     }
 
@@ -148,6 +170,28 @@ impl ArpaTarget {
     }
 }
 
+/// Output filter for target lists
+///
+/// Only hides targets from clients; filtered targets stay tracked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetFilter {
+    /// Hide targets with a TCPA below this, in seconds. Receding targets
+    /// have a negative TCPA, so 0 hides every target moving away.
+    #[serde(default)]
+    pub min_tcpa: Option<f64>,
+}
+
+impl TargetFilter {
+    /// Should this target be shown?
+    pub fn matches(&self, target: &ArpaTarget) -> bool {
+        match self.min_tcpa {
+            Some(min) => target.danger.tcpa >= min,
+            None => true,
+        }
+    }
+}
+
 /// SignalK notification alert states
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -194,6 +238,21 @@ pub struct ArpaSettings {
     pub detection_threshold: u8,
     /// Minimum speed (knots) for auto-acquisition
     pub min_speed: f64,
+    /// Contour lengths accepted as a target, tuned separately from
+    /// `detection_threshold`
+    #[serde(default)]
+    pub contour_limits: ContourLimits,
+    /// Largest gaps between contours that are merged into one target
+    #[serde(default)]
+    pub contour_merge: ContourMerge,
+    /// Keep moving missed targets along their last velocity until they
+    /// are marked lost
+    #[serde(default = "default_dead_reckoning")]
+    pub dead_reckoning: bool,
+}
+
+fn default_dead_reckoning() -> bool {
+    true
 }
 
 impl Default for ArpaSettings {
@@ -208,6 +267,9 @@ impl Default for ArpaSettings {
             min_target_size: 3,
             detection_threshold: 128,
             min_speed: 2.0,             // 2 knots minimum
+            contour_limits: ContourLimits::default(),
+            contour_merge: ContourMerge::default(),
+            dead_reckoning: default_dead_reckoning(),
         }
     }
 }
@@ -273,10 +335,57 @@ pub(crate) struct TrackingState {
     pub first_seen: u64,
     /// Unix timestamp (ms) of last update
     pub last_seen: u64,
+    /// Unix timestamp (ms) the position and covariance refer to
+    pub position_time: u64,
+    /// The sweep is within the bearing tolerance of the track
+    pub in_window: bool,
+    /// An echo matched while the sweep was within the bearing tolerance
+    pub hit_in_window: bool,
+    /// Position is dead-reckoned, the target was missed in recent scans
+    pub dead_reckoning: bool,
     /// Number of updates (for status transition)
     pub update_count: u32,
     /// Previous alert state (for change detection)
     pub prev_alert_state: AlertState,
+    /// Doppler state from the pixels of the last matched echo
+    pub doppler: DopplerState,
+    /// Distance in meters between the last measurement and the prediction
+    pub residual: Option<f64>,
+    /// Moving average of spokes at the track's bearing that held a
+    /// matching echo (1 = every spoke)
+    pub hit_rate: f64,
+}
+
+/// Moving average of hits (1) and misses (0)
+pub(crate) fn update_hit_rate(hit_rate: f64, hit: bool) -> f64 {
+    const WEIGHT: f64 = 0.2;
+    let sample = if hit { 1.0 } else { 0.0 };
+    hit_rate + (sample - hit_rate) * WEIGHT
+}
+
+/// Track quality from 0 to 1
+///
+/// Combines track age (number of updates), the Kalman position variance
+/// (meters²), the last measurement residual (meters) and how consistently
+/// the target is seen. A freshly acquired target scores low, and the
+/// score rises as the target keeps being refreshed close to where it
+/// was predicted.
+pub(crate) fn track_confidence(
+    updates: u32,
+    position_variance: f64,
+    residual: Option<f64>,
+    hit_rate: f64,
+) -> f64 {
+    const MATURE_UPDATES: f64 = 10.0;
+    const SIGMA_SCALE: f64 = 10.0; // meters
+    const RESIDUAL_SCALE: f64 = 50.0; // meters
+
+    let age = (updates as f64 / MATURE_UPDATES).min(1.0);
+    let sigma = position_variance.max(0.0).sqrt();
+    let precision = 1.0 / (1.0 + sigma / SIGMA_SCALE);
+    let residual = residual.map(|r| (-r / RESIDUAL_SCALE).exp()).unwrap_or(0.0);
+
+    (0.3 * age + 0.3 * precision + 0.2 * residual + 0.2 * hit_rate).clamp(0.0, 1.0)
 }
 
 impl TrackingState {
@@ -305,11 +414,29 @@ impl TrackingState {
             acquisition: method,
             first_seen: timestamp,
             last_seen: timestamp,
+            position_time: timestamp,
+            in_window: false,
+            hit_in_window: false,
+            dead_reckoning: false,
             update_count: 0,
             prev_alert_state: AlertState::Normal,
+            doppler: DopplerState::Any,
+            residual: None,
+            hit_rate: 0.0,
         }
     }
 
+    /// Record whether a spoke at the track's bearing held a matching echo
+    pub fn record_hit(&mut self, hit: bool) {
+        self.hit_rate = update_hit_rate(self.hit_rate, hit);
+    }
+
+    /// Track quality from 0 to 1, see [`track_confidence`]
+    pub fn confidence(&self) -> f64 {
+        let variance = (self.covariance[0] + self.covariance[5]) / 2.0;
+        track_confidence(self.update_count, variance, self.residual, self.hit_rate)
+    }
+
     /// Get distance from own ship in meters
     pub fn distance(&self) -> f64 {
         (self.x * self.x + self.y * self.y).sqrt()
@@ -366,6 +493,32 @@ impl TrackingState {
             acquisition: self.acquisition,
             first_seen: self.first_seen,
             last_seen: self.last_seen,
+            doppler_state: self.doppler,
+            confidence: self.confidence(),
+            dead_reckoning: self.dead_reckoning,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_tcpa_filter_hides_receding_target() {
+        let mut receding = ArpaTarget::new(1, 45.0, 1000.0, 0, AcquisitionMethod::Manual);
+        receding.danger.tcpa = -120.0;
+        let mut approaching = ArpaTarget::new(2, 90.0, 1000.0, 0, AcquisitionMethod::Manual);
+        approaching.danger.tcpa = 300.0;
+
+        let off = TargetFilter::default();
+        assert!(off.matches(&receding));
+        assert!(off.matches(&approaching));
+
+        let on = TargetFilter {
+            min_tcpa: Some(0.0),
+        };
+        assert!(!on.matches(&receding));
+        assert!(on.matches(&approaching));
+    }
+}
@@ -7,8 +7,9 @@ use crate::radar::RadarDiscovery;
 
 use super::controls::*;
 use super::{
-    CapabilityManifest, Characteristics, ConstraintCondition, ConstraintEffect, ConstraintType,
-    ControlConstraint, ControlDefinition, SupportedFeature,
+    BearingReference, CapabilityManifest, CapabilitySummary, Characteristics, ConstraintCondition,
+    ConstraintEffect, ConstraintType, ControlConstraint, ControlDefinition, RangeUnits,
+    SupportedFeature,
 };
 
 /// Build a capability manifest for a discovered radar
@@ -24,18 +25,30 @@ pub fn build_capabilities(
     radar_id: &str,
     supported_features: Vec<SupportedFeature>,
 ) -> CapabilityManifest {
-    // Try to find model in database
-    let model_info = discovery
+    // Try to find model in database, else use the generic profile
+    let generic;
+    let model_info = match discovery
         .model
         .as_deref()
         .and_then(|m| models::get_model(discovery.brand, m))
-        .unwrap_or(&models::UNKNOWN_MODEL);
+    {
+        Some(model_info) => model_info,
+        None => {
+            generic = models::generic_model(discovery.brand);
+            &generic
+        }
+    };
+    // Keep the reported name of an unknown model
+    let model = match (model_info.is_generic(), &discovery.model) {
+        (true, Some(reported)) => reported.clone(),
+        _ => model_info.model.to_string(),
+    };
 
     CapabilityManifest {
         id: radar_id.to_string(),
         key: None, // WASM sets this via state
         make: discovery.brand.as_str().to_string(),
-        model: model_info.model.to_string(),
+        model,
         model_family: Some(model_info.family.to_string()),
         serial_number: discovery.serial_number.clone(),
         firmware_version: None, // Set dynamically via state
@@ -44,17 +57,21 @@ pub fn build_capabilities(
             max_range: model_info.max_range,
             min_range: model_info.min_range,
             supported_ranges: model_info.range_table.to_vec(),
+            range_units: RangeUnits::classify(model_info.range_table),
+            bearing_reference: bearing_reference(model_info),
             spokes_per_revolution: model_info.spokes_per_revolution,
             max_spoke_length: model_info.max_spoke_length,
             has_doppler: model_info.has_doppler,
             has_dual_range: model_info.has_dual_range,
             max_dual_range: model_info.max_dual_range,
             no_transmit_zone_count: model_info.no_transmit_zone_count,
+            antenna_type: model_info.antenna_type,
         },
 
         controls: build_controls(model_info, discovery.serial_number.is_some()),
         constraints: build_constraints(model_info),
         supported_features,
+        generic_profile: model_info.is_generic(),
     }
 }
 
@@ -83,20 +100,33 @@ pub fn build_capabilities_from_model(
             max_range: model_info.max_range,
             min_range: model_info.min_range,
             supported_ranges: model_info.range_table.to_vec(),
+            range_units: RangeUnits::classify(model_info.range_table),
+            bearing_reference: bearing_reference(model_info),
             spokes_per_revolution: model_info.spokes_per_revolution,
             max_spoke_length: model_info.max_spoke_length,
             has_doppler: model_info.has_doppler,
             has_dual_range: model_info.has_dual_range,
             max_dual_range: model_info.max_dual_range,
             no_transmit_zone_count: model_info.no_transmit_zone_count,
+            antenna_type: model_info.antenna_type,
         },
 
         controls: build_controls(model_info, false), // No serial number available
         constraints: build_constraints(model_info),
         supported_features,
+        generic_profile: model_info.is_generic(),
     }
 }
 
+/// Build the compact capability summary included in discovery events
+#[inline(never)]
+pub fn build_capability_summary(
+    model_info: &ModelInfo,
+    supported_features: Vec<SupportedFeature>,
+) -> CapabilitySummary {
+    build_capabilities_from_model(model_info, "", supported_features).summary()
+}
+
 /// Build a capability manifest directly from model info with custom spokes configuration
 ///
 /// Useful when you don't have a RadarDiscovery but know the model and have
@@ -143,20 +173,61 @@ pub fn build_capabilities_from_model_with_key(
             max_range: model_info.max_range,
             min_range: model_info.min_range,
             supported_ranges: model_info.range_table.to_vec(),
+            range_units: RangeUnits::classify(model_info.range_table),
+            bearing_reference: bearing_reference(model_info),
             spokes_per_revolution,
             max_spoke_length,
             has_doppler: model_info.has_doppler,
             has_dual_range: model_info.has_dual_range,
             max_dual_range: model_info.max_dual_range,
             no_transmit_zone_count: model_info.no_transmit_zone_count,
+            antenna_type: model_info.antenna_type,
         },
 
         controls: build_controls(model_info, false),
         constraints: build_constraints(model_info),
         supported_features,
+        generic_profile: model_info.is_generic(),
     }
 }
 
+/// Navico controls only HALO radars implement
+const HALO_ONLY_CONTROLS: &[&str] = &[
+    "accentLight",
+    "seaState",
+    "dopplerMode",
+    "dopplerSpeed",
+    "presetMode",
+];
+
+/// What the spoke angles a model sends are measured from
+///
+/// The antennas of all supported brands report their angle from the bow
+/// mark; none of them turns the picture to north itself. A brand or model
+/// that does must be listed here as `True`.
+fn bearing_reference(model: &ModelInfo) -> BearingReference {
+    match model.brand {
+        crate::Brand::Furuno
+        | crate::Brand::Navico
+        | crate::Brand::Raymarine
+        | crate::Brand::Garmin => BearingReference::Relative,
+    }
+}
+
+/// Whether a model's manifest should offer a control
+///
+/// HALO-only controls are never offered on older Navico radars, even if
+/// the model table lists them, since writes to them fail on BR24/3G/4G.
+fn has_control(model: &ModelInfo, control_id: &str) -> bool {
+    if model.brand == crate::Brand::Navico
+        && model.family != "HALO"
+        && HALO_ONLY_CONTROLS.contains(&control_id)
+    {
+        return false;
+    }
+    model.controls.contains(&control_id)
+}
+
 /// Build the list of controls for a radar model
 ///
 /// NOTE: Controls are pushed one by one to avoid creating large stack frames.
@@ -177,6 +248,10 @@ fn build_controls(model: &ModelInfo, has_serial_number: bool) -> Vec<ControlDefi
     controls.push(control_firmware_version());
     controls.push(control_operating_hours());
     controls.push(control_transmit_hours());
+    // Computed from the operating hours, which Garmin radars do not report
+    if model.magnetron_life_hours > 0 && model.brand != crate::Brand::Garmin {
+        controls.push(control_magnetron_life_percent());
+    }
 
     // Only include serial number control if we have the data
     if has_serial_number {
@@ -188,6 +263,9 @@ fn build_controls(model: &ModelInfo, has_serial_number: bool) -> Vec<ControlDefi
     // in capabilities so clients can see the schema, but they won't appear in /state
     // since they're configuration values stored locally, not queried from the radar.
     for control_id in model.controls {
+        if !has_control(model, control_id) {
+            continue;
+        }
         if *control_id == "noTransmitZones" {
             if let Some(def) =
                 get_extended_control_with_zones(control_id, model.no_transmit_zone_count)
@@ -197,11 +275,22 @@ fn build_controls(model: &ModelInfo, has_serial_number: bool) -> Vec<ControlDefi
         } else if *control_id == "interferenceRejection"
             && model.brand == crate::Brand::Furuno
         {
-            // Furuno has simple on/off interference rejection
-            controls.push(control_interference_rejection_furuno());
-        } else if *control_id == "scanSpeed" && model.brand == crate::Brand::Furuno {
-            // Furuno uses 0=24RPM, 2=Auto
-            controls.push(control_scan_speed_furuno());
+            // Furuno FAR radars have IR levels, the others simple on/off
+            if model.has_leveled_interference_rejection() {
+                controls.push(control_interference_rejection_furuno_levels());
+            } else {
+                controls.push(control_interference_rejection_furuno());
+            }
+        } else if *control_id == "scanSpeed" {
+            // Options are RPM values, which differ per model
+            controls.push(control_scan_speed_for_model(model));
+        } else if *control_id == "targetExpansion" && model.family != "HALO" {
+            // "High" expansion is HALO only
+            let mut def = control_target_expansion();
+            if let Some(values) = def.values.as_mut() {
+                values.retain(|v| v.value != 2);
+            }
+            controls.push(def);
         } else if let Some(def) = get_extended_control(control_id) {
             controls.push(def);
         }
@@ -216,7 +305,7 @@ fn build_constraints(model: &ModelInfo) -> Vec<ControlConstraint> {
     let mut constraints = vec![];
 
     // If preset mode is available, add constraints for controls it locks
-    if model.controls.contains(&"presetMode") {
+    if has_control(model, "presetMode") {
         let locked_controls = ["gain", "sea", "rain", "interferenceRejection"];
 
         for control_id in locked_controls {
@@ -325,4 +414,300 @@ mod tests {
         assert!(caps.supported_features.contains(&SupportedFeature::Arpa));
         assert!(caps.supported_features.contains(&SupportedFeature::GuardZones));
     }
+
+    #[test]
+    fn test_reported_range_widens_characteristics() {
+        let model = models::get_model(Brand::Furuno, "DRS12A-NXT").unwrap();
+        let mut caps = build_capabilities_from_model(model, "1", vec![]);
+        assert_eq!(caps.characteristics.max_range, 133344); // 72 NM
+
+        // Within model limits: nothing changes
+        assert!(!caps.characteristics.include_reported_range(1852));
+
+        // Radar reports 96 NM
+        assert!(caps.characteristics.include_reported_range(177792));
+        assert_eq!(caps.characteristics.max_range, 177792);
+        assert_eq!(caps.characteristics.supported_ranges.last(), Some(&177792));
+    }
+
+    #[test]
+    fn test_units_advertised_per_brand() {
+        for brand in [
+            Brand::Furuno,
+            Brand::Navico,
+            Brand::Raymarine,
+            Brand::Garmin,
+        ] {
+            for model in models::get_models_for_brand(brand) {
+                let caps = build_capabilities_from_model(model, "1", vec![]);
+                let json = serde_json::to_value(&caps).unwrap();
+                let ch = &json["characteristics"];
+                assert!(ch["rangeUnits"].is_string(), "{}", model.model);
+                assert_eq!(ch["bearingReference"], "relative", "{}", model.model);
+                assert_eq!(caps.summary().bearing_reference, BearingReference::Relative);
+            }
+        }
+
+        // Range units follow the model's range table
+        let units = |brand, name| {
+            let model = models::get_model(brand, name).unwrap();
+            build_capabilities_from_model(model, "1", vec![])
+                .summary()
+                .range_units
+        };
+        assert_eq!(units(Brand::Furuno, "DRS4D-NXT"), RangeUnits::Nautical);
+        assert_eq!(units(Brand::Furuno, "FAR-1513"), RangeUnits::Metric);
+        assert_eq!(units(Brand::Navico, "HALO"), RangeUnits::Metric);
+    }
+
+    #[test]
+    fn test_metric_reported_ranges_replace_nm_table() {
+        let model = models::get_model(Brand::Furuno, "DRS4D-NXT").unwrap();
+        let mut caps = build_capabilities_from_model(model, "1", vec![]);
+        assert_eq!(caps.characteristics.range_units, RangeUnits::Nautical);
+        let nm_table = caps.characteristics.supported_ranges.clone();
+
+        let metric = [2000, 0, 100, 250, 500, 1000, 1500, 3000, 500];
+        assert!(caps.set_reported_ranges(&metric));
+        assert!(!caps.set_reported_ranges(&metric));
+
+        let ch = &caps.characteristics;
+        assert_ne!(ch.supported_ranges, nm_table);
+        assert_eq!(
+            ch.supported_ranges,
+            vec![100, 250, 500, 1000, 1500, 2000, 3000]
+        );
+        assert_eq!(ch.range_units, RangeUnits::Metric);
+        assert_eq!((ch.min_range, ch.max_range), (100, 3000));
+
+        let range = caps.controls.iter().find(|c| c.id == "range").unwrap();
+        assert_eq!(range.range.as_ref().unwrap().max, 3000.0);
+        assert_eq!(caps.summary().range_units, RangeUnits::Metric);
+        let json = serde_json::to_value(&caps).unwrap();
+        assert_eq!(json["characteristics"]["rangeUnits"], "metric");
+    }
+
+    #[test]
+    fn test_navico_target_boost_expansion_per_model() {
+        let expansion_values = |model: &str| {
+            let info = models::get_model(Brand::Navico, model).unwrap();
+            let caps = build_capabilities_from_model(info, "1", vec![]);
+            caps.controls
+                .iter()
+                .find(|c| c.id == "targetExpansion")
+                .map(|c| c.values.as_ref().unwrap().len())
+        };
+
+        assert_eq!(expansion_values("HALO20+"), Some(3));
+        assert_eq!(expansion_values("4G"), Some(2));
+        assert_eq!(expansion_values("BR24"), None);
+
+        let br24 = models::get_model(Brand::Navico, "BR24").unwrap();
+        let caps = build_capabilities_from_model(br24, "1", vec![]);
+        assert!(!caps.controls.iter().any(|c| c.id == "targetBoost"));
+    }
+
+    #[test]
+    fn test_scan_speed_rpm_options_per_model() {
+        let scan_speeds = |brand: Brand, model: &str| {
+            let info = models::get_model(brand, model).unwrap();
+            let caps = build_capabilities_from_model(info, "1", vec![]);
+            let def = caps
+                .controls
+                .into_iter()
+                .find(|c| c.id == "scanSpeed")
+                .unwrap();
+            let values: Vec<i64> = def
+                .values
+                .unwrap()
+                .iter()
+                .map(|v| v.value.as_i64().unwrap())
+                .collect();
+            (values, def.default)
+        };
+
+        let (halo, _) = scan_speeds(Brand::Navico, "HALO24");
+        assert_eq!(halo, vec![20, 30, 36, 48]);
+
+        let (furuno, default) = scan_speeds(Brand::Furuno, "DRS4D-NXT");
+        assert_eq!(furuno, vec![24, 0]);
+        assert_eq!(default, Some(0.into()));
+    }
+
+    #[test]
+    fn test_legacy_navico_omits_halo_only_controls() {
+        for name in ["BR24", "3G", "4G"] {
+            let model = models::get_model(Brand::Navico, name).unwrap();
+            let caps = build_capabilities_from_model(model, "1", vec![]);
+            for id in HALO_ONLY_CONTROLS {
+                assert!(
+                    !caps.controls.iter().any(|c| c.id == *id),
+                    "{} manifest offers {}",
+                    name,
+                    id
+                );
+            }
+            assert!(caps.constraints.is_empty());
+        }
+
+        let halo = models::get_model(Brand::Navico, "HALO24").unwrap();
+        let caps = build_capabilities_from_model(halo, "1", vec![]);
+        assert!(caps.controls.iter().any(|c| c.id == "accentLight"));
+        assert!(caps.controls.iter().any(|c| c.id == "seaState"));
+    }
+
+    #[test]
+    fn test_capability_summary() {
+        let model = models::get_model(Brand::Navico, "HALO20+").unwrap();
+        let summary = build_capability_summary(
+            model,
+            vec![SupportedFeature::Arpa, SupportedFeature::DualRange],
+        );
+        assert_eq!(
+            summary.supported_features,
+            vec![SupportedFeature::Arpa, SupportedFeature::DualRange]
+        );
+        assert_eq!(summary.supported_ranges, model.range_table.to_vec());
+        assert!(summary.control_ids.iter().any(|id| id == "gain"));
+        assert!(summary.control_ids.iter().any(|id| id == "dopplerMode"));
+    }
+
+    #[test]
+    fn test_bird_mode_per_model() {
+        let has_bird_mode = |brand: Brand, model: &str| {
+            let info = models::get_model(brand, model).unwrap();
+            let caps = build_capabilities_from_model(info, "1", vec![]);
+            caps.controls.iter().any(|c| c.id == "birdMode")
+        };
+
+        assert!(has_bird_mode(Brand::Navico, "HALO20+"));
+        assert!(has_bird_mode(Brand::Furuno, "DRS4D-NXT"));
+        assert!(!has_bird_mode(Brand::Navico, "4G"));
+        assert!(!has_bird_mode(Brand::Navico, "BR24"));
+    }
+
+    #[test]
+    fn test_timed_transmit_garmin_only() {
+        let has_timed_transmit = |brand: Brand| {
+            models::get_models_for_brand(brand).iter().all(|info| {
+                let caps = build_capabilities_from_model(info, "1", vec![]);
+                caps.controls.iter().any(|c| {
+                    c.id == "timedTransmit"
+                        && c.control_type == crate::capabilities::ControlType::Compound
+                })
+            })
+        };
+
+        assert!(has_timed_transmit(Brand::Garmin));
+        assert!(!has_timed_transmit(Brand::Navico));
+        assert!(!has_timed_transmit(Brand::Furuno));
+    }
+
+    #[test]
+    fn test_unknown_model_gets_generic_profile() {
+        let mut discovery = RadarDiscovery::for_test(Brand::Furuno, "RD003212", "172.31.3.212");
+        discovery.model = Some("DRS99X-FUTURE".to_string());
+        let caps = build_capabilities(&discovery, "1", vec![]);
+
+        assert!(caps.generic_profile);
+        assert_eq!(caps.make, "Furuno");
+        assert_eq!(caps.model, "DRS99X-FUTURE");
+        assert!(!caps.characteristics.supported_ranges.is_empty());
+        for id in ["power", "range", "gain", "sea", "rain"] {
+            assert!(caps.controls.iter().any(|c| c.id == id), "missing {}", id);
+        }
+        let json = serde_json::to_value(&caps).unwrap();
+        assert_eq!(json["genericProfile"], true);
+
+        discovery.model = Some("DRS4D-NXT".to_string());
+        let caps = build_capabilities(&discovery, "1", vec![]);
+        assert!(!caps.generic_profile);
+        assert!(serde_json::to_value(&caps)
+            .unwrap()
+            .get("genericProfile")
+            .is_none());
+    }
+
+    #[test]
+    fn test_tx_channel_enum_per_model() {
+        let tx_channel = |model: &str| {
+            let info = models::get_model(Brand::Furuno, model).unwrap();
+            let caps = build_capabilities_from_model(info, "1", vec![]);
+            caps.controls.into_iter().find(|c| c.id == "txChannel")
+        };
+
+        let def = tx_channel("DRS4D-NXT").unwrap();
+        assert_eq!(def.control_type, crate::capabilities::ControlType::Enum);
+        let labels: Vec<String> = def.values.unwrap().into_iter().map(|v| v.label).collect();
+        assert_eq!(labels, vec!["Auto", "Channel 1", "Channel 2", "Channel 3"]);
+        assert!(tx_channel("FAR-1513").is_some());
+        assert!(tx_channel("DRS4D").is_none());
+    }
+
+    #[test]
+    fn test_furuno_interference_rejection_levels_per_model() {
+        let ir = |model: &str| {
+            let info = models::get_model(Brand::Furuno, model).unwrap();
+            let caps = build_capabilities_from_model(info, "1", vec![]);
+            caps.controls
+                .into_iter()
+                .find(|c| c.id == "interferenceRejection")
+                .unwrap()
+        };
+
+        let def = ir("FAR-1513");
+        assert_eq!(def.control_type, crate::capabilities::ControlType::Enum);
+        assert_eq!(def.values.unwrap().len(), 3);
+        assert_eq!(
+            ir("DRS4D-NXT").control_type,
+            crate::capabilities::ControlType::Boolean
+        );
+    }
+
+    #[test]
+    fn test_antenna_type_declared_in_manifest() {
+        let antenna_type = |brand, name| {
+            let model = models::get_model(brand, name).unwrap();
+            let caps = build_capabilities_from_model(model, "1", vec![]);
+            serde_json::to_value(&caps).unwrap()["characteristics"]["antennaType"].clone()
+        };
+        assert_eq!(antenna_type(Brand::Furuno, "DRS4D-NXT"), "radome");
+        assert_eq!(antenna_type(Brand::Furuno, "DRS6A-NXT"), "openArray");
+        assert_eq!(antenna_type(Brand::Garmin, "Fantom 54"), "openArray");
+        // HALO comes as both radome and open array
+        assert!(antenna_type(Brand::Navico, "HALO").is_null());
+    }
+
+    #[test]
+    fn test_magnetron_life_for_magnetron_models() {
+        let info = ModelInfo {
+            magnetron_life_hours: 5000,
+            ..models::generic_model(Brand::Furuno)
+        };
+        assert_eq!(info.magnetron_life_percent(2500.0), Some(50.0));
+
+        let has_magnetron_life = |brand: Brand, model: &str| {
+            let info = models::get_model(brand, model).unwrap();
+            let caps = build_capabilities_from_model(info, "1", vec![]);
+            caps.controls
+                .iter()
+                .any(|c| c.id == "magnetronLifePercent" && c.read_only)
+        };
+        assert!(has_magnetron_life(Brand::Furuno, "DRS4D"));
+        assert!(has_magnetron_life(Brand::Raymarine, "RD418D"));
+        // Solid-state
+        assert!(!has_magnetron_life(Brand::Furuno, "DRS4D-NXT"));
+        let nxt = models::get_model(Brand::Furuno, "DRS4D-NXT").unwrap();
+        assert_eq!(nxt.magnetron_life_percent(2500.0), None);
+        // A magnetron, but no operating hours to compute it from
+        assert!(!has_magnetron_life(Brand::Garmin, "GMR 18 xHD"));
+
+        // Rated per model
+        let rating = |model: &str| {
+            models::get_model(Brand::Furuno, model)
+                .unwrap()
+                .magnetron_life_hours
+        };
+        assert!(rating("DRS4D") < rating("DRS12A"));
+    }
 }
@@ -9,6 +9,7 @@ use super::{
     ControlCategory, ControlDefinition, ControlType, EnumValue, PropertyDefinition, RangeSpec,
     WireProtocolHint,
 };
+use crate::models::ModelInfo;
 use crate::Brand;
 
 // =============================================================================
@@ -373,6 +374,31 @@ pub fn control_transmit_hours() -> ControlDefinition {
     }
 }
 
+/// Magnetron life: operating hours as a share of the model's rated
+/// magnetron life (read-only, magnetron radars only)
+pub fn control_magnetron_life_percent() -> ControlDefinition {
+    ControlDefinition {
+        id: "magnetronLifePercent".into(),
+        name: "Magnetron Life Used".into(),
+        description: "Operating hours as a percentage of the rated magnetron life. Above 100% the tube is past its rated life.".into(),
+        category: ControlCategory::Base,
+        control_type: ControlType::Number,
+        range: Some(RangeSpec {
+            min: 0.0,
+            max: 1000.0,
+            step: Some(0.1),
+            unit: Some("%".into()),
+        }),
+        values: None,
+        properties: None,
+        modes: None,
+        default_mode: None,
+        read_only: true,
+        default: None,
+        wire_hints: None,
+    }
+}
+
 /// Rotation speed: current antenna rotation speed (read-only)
 pub fn control_rotation_speed() -> ControlDefinition {
     ControlDefinition {
@@ -652,6 +678,59 @@ pub fn control_bird_mode() -> ControlDefinition {
     }
 }
 
+/// Timed transmit: alternate between transmit and standby (Garmin)
+#[inline(never)]
+pub fn control_timed_transmit() -> ControlDefinition {
+    let period = |description: &str| PropertyDefinition {
+        prop_type: "number".into(),
+        description: Some(description.into()),
+        range: Some(RangeSpec {
+            min: 1.0,
+            max: 60.0,
+            step: Some(1.0),
+            unit: Some("minutes".into()),
+        }),
+        values: None,
+    };
+
+    let mut properties = HashMap::new();
+    properties.insert(
+        "enabled".into(),
+        PropertyDefinition {
+            prop_type: "boolean".into(),
+            description: Some("Enable timed transmit".into()),
+            range: None,
+            values: None,
+        },
+    );
+    properties.insert(
+        "transmitPeriod".into(),
+        period("Minutes spent transmitting"),
+    );
+    properties.insert("standbyPeriod".into(), period("Minutes spent in standby"));
+
+    ControlDefinition {
+        id: "timedTransmit".into(),
+        name: "Timed Transmit".into(),
+        description: "Saves power by alternating between transmit and standby periods.".into(),
+        category: ControlCategory::Extended,
+        control_type: ControlType::Compound,
+        range: None,
+        values: None,
+        properties: Some(properties),
+        modes: None,
+        default_mode: None,
+        read_only: false,
+        default: Some(
+            serde_json::json!({"enabled": false, "transmitPeriod": 5, "standbyPeriod": 10}),
+        ),
+        wire_hints: Some(WireProtocolHint {
+            has_enabled: true,
+            ..Default::default()
+        }),
+    }
+}
+
 /// TX Channel: transmission frequency selection (Furuno)
 pub fn control_tx_channel() -> ControlDefinition {
     ControlDefinition {
@@ -751,6 +830,43 @@ pub fn control_interference_rejection_furuno() -> ControlDefinition {
     }
 }
 
+/// Interference rejection for Furuno models with levels (FAR series)
+///
+/// Furuno: Command 0x67 feature 0, value is the level
+pub fn control_interference_rejection_furuno_levels() -> ControlDefinition {
+    ControlDefinition {
+        id: "interferenceRejection".into(),
+        name: "Int. Rejection".into(),
+        description: "Interference Rejection: filters interference from other radars.".into(),
+        category: ControlCategory::Extended,
+        control_type: ControlType::Enum,
+        range: None,
+        values: Some(vec![
+            EnumValue {
+                value: 0.into(),
+                label: "Off".into(),
+                description: None,
+            },
+            EnumValue {
+                value: 1.into(),
+                label: "IR1".into(),
+                description: None,
+            },
+            EnumValue {
+                value: 2.into(),
+                label: "IR2".into(),
+                description: None,
+            },
+        ]),
+        properties: None,
+        modes: None,
+        default_mode: None,
+        read_only: false,
+        default: Some(0.into()),
+        wire_hints: None,
+    }
+}
+
 /// Preset mode: pre-configured operating modes (Navico, Raymarine)
 pub fn control_preset_mode() -> ControlDefinition {
     ControlDefinition {
@@ -863,6 +979,9 @@ pub fn control_bearing_alignment() -> ControlDefinition {
     }
 }
 
+/// Highest antenna height accepted, in meters
+pub const MAX_ANTENNA_HEIGHT_METERS: i32 = 99;
+
 /// Antenna height: height of radar antenna above waterline in meters
 ///
 /// Affects sea clutter calculations.
@@ -875,7 +994,7 @@ pub fn control_antenna_height() -> ControlDefinition {
         control_type: ControlType::Number,
         range: Some(RangeSpec {
             min: 0.0,
-            max: 99.0,
+            max: MAX_ANTENNA_HEIGHT_METERS as f64,
             step: Some(1.0),
             unit: Some("m".into()),
         }),
@@ -985,6 +1104,36 @@ pub fn control_scan_speed_furuno() -> ControlDefinition {
     }
 }
 
+/// Scan speed with the RPM values a specific model supports
+///
+/// Enum values are rotation speeds in RPM (0 = Auto); the controller maps
+/// them to the brand's wire value. Falls back to [`control_scan_speed`]
+/// when the model's speeds aren't known.
+pub fn control_scan_speed_for_model(model: &ModelInfo) -> ControlDefinition {
+    let options = model.scan_speed_options();
+    let mut def = control_scan_speed();
+    if options.is_empty() {
+        return def;
+    }
+    def.values = Some(
+        options
+            .iter()
+            .map(|o| EnumValue {
+                value: o.rpm.into(),
+                label: o.label.into(),
+                description: None,
+            })
+            .collect(),
+    );
+    // Prefer automatic where available, otherwise the slowest speed
+    let default = options.iter().find(|o| o.rpm == 0).unwrap_or(&options[0]);
+    def.default = Some(default.rpm.into());
+    if model.brand == Brand::Furuno {
+        def.category = ControlCategory::Installation;
+    }
+    def
+}
+
 /// Auto acquire: automatic ARPA target acquisition (Furuno)
 pub fn control_auto_acquire() -> ControlDefinition {
     ControlDefinition {
@@ -1613,6 +1762,7 @@ pub fn get_extended_control(id: &str) -> Option<ControlDefinition> {
         // Modes
         "presetMode" => Some(control_preset_mode()),
         "txChannel" => Some(control_tx_channel()),
+        "timedTransmit" => Some(control_timed_transmit()),
         "scanSpeed" => Some(control_scan_speed()),
         // Receiver
         "tune" => Some(control_tune()),
@@ -1731,9 +1881,17 @@ pub fn control_bearing_alignment_for_brand(brand: Brand) -> ControlDefinition {
 /// Antenna height control with brand-specific wire hints
 pub fn control_antenna_height_for_brand(brand: Brand) -> ControlDefinition {
     let mut def = control_antenna_height();
+    if brand == Brand::Navico {
+        if let Some(range) = def.range.as_mut() {
+            range.max = crate::protocol::navico::NAVICO_MAX_ANTENNA_HEIGHT_METERS as f64;
+        }
+    }
     def.wire_hints = Some(match brand {
         Brand::Navico => WireProtocolHint {
-            scale_factor: Some(99000.0), // cm to mm conversion
+            // millimeters on the wire
+            scale_factor: Some(
+                crate::protocol::navico::NAVICO_MAX_ANTENNA_HEIGHT_METERS as f32 * 1000.0,
+            ),
             write_only: true, // Cannot reliably read from hardware
             ..Default::default()
         },
@@ -1841,6 +1999,7 @@ pub fn get_base_control_for_brand(id: &str, brand: Brand) -> Option<ControlDefin
         "firmwareVersion" => Some(control_firmware_version()),
         "operatingHours" => Some(control_operating_hours()),
         "transmitHours" => Some(control_transmit_hours()),
+        "magnetronLifePercent" => Some(control_magnetron_life_percent()),
         "rotationSpeed" => Some(control_rotation_speed_for_brand(brand)),
         _ => None,
     }
@@ -1883,6 +2042,20 @@ pub fn get_control_for_brand(id: &str, brand: Brand) -> Option<ControlDefinition
     get_base_control_for_brand(id, brand).or_else(|| get_extended_control_for_brand(id, brand))
 }
 
+/// Get a control as a specific model offers it
+///
+/// Some controls differ per model, e.g. interference rejection has levels
+/// on Furuno FAR radars but is on/off on other Furunos.
+#[inline(never)]
+pub fn get_control_for_model(id: &str, model: &ModelInfo) -> Option<ControlDefinition> {
+    match id {
+        "interferenceRejection" if model.has_leveled_interference_rejection() => {
+            Some(control_interference_rejection_furuno_levels())
+        }
+        _ => get_control_for_brand(id, model.brand),
+    }
+}
+
 /// Base control IDs that all radars of a brand support (before model is known)
 const BASE_CONTROL_IDS: &[&str] = &[
     "power",
@@ -1920,7 +2093,7 @@ pub fn get_all_controls_for_model(brand: Brand, model_name: Option<&str>) -> Vec
                 if *control_id == "noTransmitZones" {
                     continue;
                 }
-                if let Some(def) = get_extended_control_for_brand(control_id, brand) {
+                if let Some(def) = get_control_for_model(control_id, model_info) {
                     controls.push(def);
                 }
             }
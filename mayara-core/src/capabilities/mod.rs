@@ -82,6 +82,85 @@ pub struct CapabilityManifest {
     /// - `dualRange`: Dual-range simultaneous display
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub supported_features: Vec<SupportedFeature>,
+
+    /// The model is not in the models database: only the conservative
+    /// generic profile (base controls, generic ranges) is offered
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub generic_profile: bool,
+}
+
+/// Is `control_id` exposed to clients by a radar's control allowlist
+/// (empty = all)?
+pub fn is_control_allowed(allowlist: &[String], control_id: &str) -> bool {
+    allowlist.is_empty() || allowlist.iter().any(|c| c == control_id)
+}
+
+impl CapabilityManifest {
+    /// Compact summary for the discovery emit
+    pub fn summary(&self) -> CapabilitySummary {
+        CapabilitySummary {
+            supported_features: self.supported_features.clone(),
+            supported_ranges: self.characteristics.supported_ranges.clone(),
+            range_units: self.characteristics.range_units,
+            bearing_reference: self.characteristics.bearing_reference,
+            control_ids: self.controls.iter().map(|c| c.id.clone()).collect(),
+        }
+    }
+
+    /// Drop controls not in `allowlist` (empty = keep all), along with
+    /// constraints on them, so clients never see hidden controls.
+    ///
+    /// Writes to hidden controls are rejected separately by the engine.
+    pub fn apply_control_allowlist(&mut self, allowlist: &[String]) {
+        self.controls.retain(|c| is_control_allowed(allowlist, &c.id));
+        self.constraints.retain(|c| is_control_allowed(allowlist, &c.control_id));
+    }
+
+    /// Replace the model's range table with the ranges the radar reported.
+    ///
+    /// Updates the characteristics (see [`Characteristics::set_reported_ranges`])
+    /// and the limits of the `range` control. Returns true if the supported
+    /// ranges changed.
+    pub fn set_reported_ranges(&mut self, ranges: &[u32]) -> bool {
+        if !self.characteristics.set_reported_ranges(ranges) {
+            return false;
+        }
+        let range = controls::control_range(&self.characteristics.supported_ranges);
+        match self.controls.iter_mut().find(|c| c.id == "range") {
+            Some(control) => *control = range,
+            None => self.controls.push(range),
+        }
+        true
+    }
+}
+
+/// Compact capability summary sent along with radar discovery
+///
+/// Lets a client render a radar straight away; the full schema (control
+/// definitions, constraints) is still only available from
+/// GET /radars/{id}/capabilities.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilitySummary {
+    /// Optional API features implemented by the provider
+    #[serde(default)]
+    pub supported_features: Vec<SupportedFeature>,
+
+    /// Discrete range values supported (in meters)
+    #[serde(default)]
+    pub supported_ranges: Vec<u32>,
+
+    /// Whether the ranges are nautical mile or metric steps
+    #[serde(default)]
+    pub range_units: RangeUnits,
+
+    /// Whether spoke angles are relative to the bow or true
+    #[serde(default)]
+    pub bearing_reference: BearingReference,
+
+    /// IDs of the controls in the full manifest
+    #[serde(default)]
+    pub control_ids: Vec<String>,
 }
 
 /// Hardware characteristics of the radar
@@ -97,10 +176,22 @@ pub struct Characteristics {
     /// Discrete range values supported (in meters)
     pub supported_ranges: Vec<u32>,
 
+    /// Whether `supported_ranges` are nautical mile or metric steps,
+    /// so clients can label them appropriately
+    #[serde(default)]
+    pub range_units: RangeUnits,
+
+    /// Whether spoke angles are relative to the bow or true
+    #[serde(default)]
+    pub bearing_reference: BearingReference,
+
     /// Number of spokes per antenna revolution
     pub spokes_per_revolution: u16,
 
-    /// Maximum spoke length in samples
+    /// Maximum spoke length in samples, as emitted to clients
+    ///
+    /// Spokes are sent at their wire length (no truncation or decimation),
+    /// so clients can size their render buffer from it.
     pub max_spoke_length: u16,
 
     /// Whether Doppler processing is available
@@ -115,6 +206,104 @@ pub struct Characteristics {
 
     /// Number of no-transmit zones supported
     pub no_transmit_zone_count: u8,
+
+    /// Radome or open array, when the model tells
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub antenna_type: Option<AntennaType>,
+}
+
+impl Characteristics {
+    /// Cross-check a range reported by the radar against the model limits.
+    ///
+    /// The model table can be stricter than the actual radar (firmware
+    /// updates, dual-range). If the radar reports a range outside
+    /// `min_range..=max_range`, widen the limits and add the range to
+    /// `supported_ranges`. Returns true if anything changed, so the caller
+    /// can log the discrepancy.
+    pub fn include_reported_range(&mut self, range: u32) -> bool {
+        if range == 0 || (range >= self.min_range && range <= self.max_range) {
+            return false;
+        }
+        self.max_range = self.max_range.max(range);
+        self.min_range = self.min_range.min(range);
+        if let Err(pos) = self.supported_ranges.binary_search(&range) {
+            self.supported_ranges.insert(pos, range);
+        }
+        true
+    }
+
+    /// Use the range steps the radar actually reported instead of the
+    /// model's (nautical mile derived) range table.
+    ///
+    /// Zero entries are ignored. Sets `min_range`, `max_range` and
+    /// `range_units` from the reported set. Returns true if
+    /// `supported_ranges` changed.
+    pub fn set_reported_ranges(&mut self, ranges: &[u32]) -> bool {
+        let mut ranges: Vec<u32> = ranges.iter().copied().filter(|r| *r > 0).collect();
+        ranges.sort_unstable();
+        ranges.dedup();
+        if ranges.is_empty() || ranges == self.supported_ranges {
+            return false;
+        }
+        self.min_range = ranges[0];
+        self.max_range = ranges[ranges.len() - 1];
+        self.range_units = RangeUnits::classify(&ranges);
+        self.supported_ranges = ranges;
+        true
+    }
+}
+
+/// Unit system a radar's range steps are based on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RangeUnits {
+    /// Fractions and multiples of a nautical mile (1/8 NM, 1/4 NM, ...)
+    #[default]
+    Nautical,
+    /// Round metric values (50 m, 100 m, 500 m, 1 km, ...)
+    Metric,
+}
+
+impl RangeUnits {
+    /// Is `range` (meters) a round metric step rather than a nautical mile step?
+    pub fn is_metric_range(range: u32) -> bool {
+        let near = |step: u32| {
+            let rem = range % step;
+            rem <= 1 || rem == step - 1
+        };
+        if range <= 100 {
+            near(25)
+        } else if range <= 750 {
+            near(50)
+        } else {
+            near(500)
+        }
+    }
+
+    /// Classify a set of ranges by the majority of its steps
+    pub fn classify(ranges: &[u32]) -> Self {
+        let metric = ranges
+            .iter()
+            .filter(|r| Self::is_metric_range(**r))
+            .count();
+        if metric * 2 > ranges.len() {
+            RangeUnits::Metric
+        } else {
+            RangeUnits::Nautical
+        }
+    }
+}
+
+/// What the angles of emitted spokes are measured from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BearingReference {
+    /// Clockwise from the bow (head-up). The spoke's `bearing` field adds
+    /// the true heading when one is known.
+    #[default]
+    Relative,
+    /// Clockwise from true north
+    True,
 }
 
 fn is_zero(v: &u32) -> bool {
@@ -409,6 +598,68 @@ pub struct RadarStateV5 {
     /// Controls currently disabled and why
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub disabled_controls: Vec<DisabledControl>,
+
+    /// Hardware modules with part number and firmware version (read-only).
+    /// Only Furuno reports these ($N96).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modules: Vec<ModuleInfo>,
+
+    /// Where clients draw own ship, if not in the center (display only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_center_offset: Option<DisplayCenterOffset>,
+}
+
+/// Largest allowed distance of own ship from the display center, as a
+/// fraction of the display radius
+pub const MAX_DISPLAY_CENTER_OFFSET: f64 = 0.75;
+
+/// Offset of own ship from the display center, e.g. to see more ahead
+/// when docking
+///
+/// Purely a display setting: the radar is not told about it. Fractions of
+/// the display radius, `x` to starboard and `y` ahead.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DisplayCenterOffset {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl DisplayCenterOffset {
+    /// Check the offset keeps own ship well inside the display
+    pub fn validate(&self) -> Result<(), ControlError> {
+        let magnitude = self.x.hypot(self.y);
+        if !magnitude.is_finite() || magnitude > MAX_DISPLAY_CENTER_OFFSET {
+            return Err(ControlError::InvalidValue(format!(
+                "display center offset {:.2} exceeds {}",
+                magnitude, MAX_DISPLAY_CENTER_OFFSET
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A hardware module reported by the radar, for service inventory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleInfo {
+    /// Part number (e.g., "0359360")
+    pub part: String,
+
+    /// Firmware version (e.g., "01.05")
+    pub version: String,
+}
+
+/// Antenna construction, for installers (read-only `antennaType` control)
+///
+/// Declared per model in the model database. Rotation direction is not in
+/// any report decoded so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AntennaType {
+    /// Enclosed antenna
+    Radome,
+    /// Open array antenna
+    OpenArray,
 }
 
 /// Information about a disabled control
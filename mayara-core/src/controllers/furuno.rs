@@ -31,17 +31,23 @@
 //! ```
 
 use super::ControllerEvent;
+use super::ReportCapture;
+use crate::capabilities::ControlError;
+use crate::connection::furuno::{KEEPALIVE_INTERVAL_MS, RECONNECT_DELAY_MS};
 use crate::io::{IoProvider, TcpSocketHandle};
+use crate::models::RangeIndex;
 use crate::protocol::furuno::command::{
     format_antenna_height_command, format_auto_acquire_command, format_bird_mode_command,
     format_blind_sector_command, format_gain_command, format_heading_align_command,
-    format_interference_rejection_command, format_keepalive, format_main_bang_command,
-    format_noise_reduction_command, format_rain_command, format_range_command,
+    format_interference_rejection_command, format_interference_rejection_level_command,
+    format_keepalive, format_login_message, format_main_bang_command,
+    format_noise_reduction_command, format_rain_command, format_range_index_command,
     format_request_modules, format_request_ontime, format_request_txtime, format_rezboost_command,
     format_scan_speed_command, format_sea_command, format_status_command,
     format_target_analyzer_command, format_tx_channel_command, parse_login_response,
-    LOGIN_MESSAGE,
+    BlindSectorZone, LOGIN_VARIABLE_BYTES, MAX_INTERFERENCE_REJECTION_LEVEL, RANGE_TABLE,
 };
+use crate::protocol::furuno::report::{parse_report, FurunoReport, ModulePart};
 use crate::protocol::furuno::{BASE_PORT, BEACON_PORT};
 use crate::state::{generate_state_requests, RadarState};
 
@@ -80,22 +86,31 @@ pub struct FurunoController {
     state: ControllerState,
     /// Command port received from login
     command_port: u16,
-    /// Last keep-alive time (poll count)
-    last_keepalive: u64,
-    /// Current poll count
-    poll_count: u64,
+    /// Last keep-alive time (ms)
+    last_keepalive_ms: u64,
     /// Pending command to send once connected
     pending_command: Option<String>,
     /// Retry count for connection attempts
     retry_count: u32,
-    /// Poll count when last retry started (for backoff)
-    last_retry_poll: u64,
+    /// Time (ms) the last retry started (for backoff)
+    last_retry_ms: u64,
     /// Index into login ports to try
     login_port_idx: usize,
+    /// Values to try for the variable login byte, preferred first
+    login_variables: Vec<u8>,
+    /// Index into `login_variables` to try; kept after a successful login
+    login_variable_idx: usize,
     /// Index into fallback command ports to try
     fallback_port_idx: usize,
     /// Firmware version from $N96 response (e.g., "01.05")
     firmware_version: Option<String>,
+    /// All modules from $N96 response (part code and version)
+    modules: Vec<ModulePart>,
+    /// Whether the model is known (set_model or $N96), see `deferred_reports`
+    model_resolved: bool,
+    /// Model-dependent reports received before the model was resolved,
+    /// latest per command
+    deferred_reports: Vec<String>,
     /// Radar model from UDP model report (e.g., "DRS4D-NXT")
     /// Note: $N96 contains part numbers, not model names
     model: Option<String>,
@@ -119,21 +134,33 @@ pub struct FurunoController {
     last_emitted_hours: Option<f64>,
     /// Last emitted transmit hours (to detect changes)
     last_emitted_tx_hours: Option<f64>,
+    /// Last emitted bearing alignment (to detect changes)
+    last_emitted_alignment: Option<f64>,
     /// Previous power state (to detect transitions)
     prev_power_state: crate::state::PowerState,
+    /// Last raw report per type (debugging)
+    report_capture: ReportCapture,
+    /// Passive mode: listen only, never send to the radar
+    passive: bool,
+    /// Cached meters -> wire index lookup for RANGE_TABLE
+    range_index: RangeIndex,
+}
+
+/// Reports for features that only some models have (RezBoost, bird mode,
+/// Target Analyzer/Doppler, TX channel)
+fn is_model_dependent_report(line: &str) -> bool {
+    ["$NEE", "$NED", "$NEF", "$NEC"]
+        .iter()
+        .any(|code| line.starts_with(code))
 }
 
 impl FurunoController {
     /// Maximum number of connection retries
     const MAX_RETRIES: u32 = 5;
-    /// Base delay between retries (in poll counts, ~100ms per poll)
-    const RETRY_DELAY_BASE: u64 = 10;
     /// Login ports to try (some radars use 10000, others use 10010)
     const LOGIN_PORTS: [u16; 2] = [BEACON_PORT, BASE_PORT];
     /// Fallback command ports when login port is refused
     const FALLBACK_PORTS: [u16; 3] = [10100, 10001, 10002];
-    /// Keep-alive interval in poll counts (~5 seconds at 10 polls/sec)
-    const KEEPALIVE_INTERVAL: u64 = 50;
 
     /// Create a new controller for a Furuno radar
     ///
@@ -146,14 +173,18 @@ impl FurunoController {
             command_socket: None,
             state: ControllerState::Disconnected,
             command_port: 0,
-            last_keepalive: 0,
-            poll_count: 0,
+            last_keepalive_ms: 0,
             pending_command: None,
             retry_count: 0,
-            last_retry_poll: 0,
+            last_retry_ms: 0,
             login_port_idx: 0,
+            login_variables: LOGIN_VARIABLE_BYTES.to_vec(),
+            login_variable_idx: 0,
             fallback_port_idx: 0,
             firmware_version: None,
+            modules: Vec::new(),
+            model_resolved: false,
+            deferred_reports: Vec::new(),
             model: None,
             operating_hours: None,
             transmit_hours: None,
@@ -165,7 +196,11 @@ impl FurunoController {
             model_event_emitted: false,
             last_emitted_hours: None,
             last_emitted_tx_hours: None,
+            last_emitted_alignment: None,
             prev_power_state: crate::state::PowerState::Off,
+            report_capture: ReportCapture::default(),
+            passive: false,
+            range_index: RangeIndex::new(RANGE_TABLE.iter().map(|(i, m)| (*i, *m as u32))),
         };
         // Queue keepalive to trigger connection
         controller.request_info();
@@ -181,6 +216,31 @@ impl FurunoController {
         }
     }
 
+    /// Enable or disable capturing the last raw report per report type
+    pub fn set_report_capture(&mut self, enabled: bool) {
+        self.report_capture.set_enabled(enabled);
+    }
+
+    /// Enable or disable passive mode
+    ///
+    /// A passive controller still receives and parses reports but sends
+    /// nothing to the radar: no report requests, keep-alives or commands.
+    pub fn set_passive(&mut self, passive: bool) {
+        self.passive = passive;
+    }
+
+    /// Check if passive mode is enabled
+    pub fn is_passive(&self) -> bool {
+        self.passive
+    }
+
+    /// Get the last raw report of a type as hex (report capture must be enabled)
+    ///
+    /// Report IDs are the response code without `$`, e.g. `"N63"` for `$N63,...`.
+    pub fn last_report_hex(&self, report_id: &str) -> Option<String> {
+        self.report_capture.last_report_hex(report_id)
+    }
+
     /// Get current connection state
     pub fn state(&self) -> ControllerState {
         self.state
@@ -191,6 +251,29 @@ impl FurunoController {
         self.state == ControllerState::Connected
     }
 
+    /// Radar IP address the controller connects to
+    pub fn radar_addr(&self) -> &str {
+        &self.radar_addr
+    }
+
+    /// Point the controller at a new radar IP (e.g. after a DHCP renewal).
+    ///
+    /// Drops the current connection so the next poll logs in at the new
+    /// address; radar state and model are kept. Returns true if the
+    /// address changed.
+    pub fn set_radar_addr<I: IoProvider>(&mut self, io: &mut I, radar_addr: &str) -> bool {
+        if self.radar_addr == radar_addr {
+            return false;
+        }
+        io.info(&format!(
+            "[{}] Radar address changed {} -> {}",
+            self.radar_id, self.radar_addr, radar_addr
+        ));
+        self.radar_addr = radar_addr.to_string();
+        self.disconnect(io);
+        true
+    }
+
     /// Get current radar state
     pub fn radar_state(&self) -> &RadarState {
         &self.radar_state
@@ -204,6 +287,16 @@ impl FurunoController {
     /// Set the radar model (called when UDP model report is received by locator)
     pub fn set_model(&mut self, model: &str) {
         self.model = Some(model.to_string());
+        self.resolve_model();
+    }
+
+    /// Mark the model as known and apply the reports held back until now
+    fn resolve_model(&mut self) {
+        self.model_resolved = true;
+        self.radar_state.leveled_interference_rejection = self.has_leveled_interference_rejection();
+        for line in std::mem::take(&mut self.deferred_reports) {
+            self.radar_state.update_from_response(&line);
+        }
     }
 
     /// Get firmware version if known
@@ -211,6 +304,11 @@ impl FurunoController {
         self.firmware_version.as_deref()
     }
 
+    /// Get the module inventory from $N96, empty until the radar answers
+    pub fn modules(&self) -> &[ModulePart] {
+        &self.modules
+    }
+
     /// Get operating hours if known (total power-on time)
     pub fn operating_hours(&self) -> Option<f64> {
         self.operating_hours
@@ -229,7 +327,9 @@ impl FurunoController {
 
     /// Set radar range in meters
     pub fn set_range<I: IoProvider>(&mut self, io: &mut I, range_meters: u32) {
-        let cmd = format_range_command(range_meters as i32);
+        // Falls back to 48 nm (wire index 15) like meters_to_range_index
+        let wire_index = self.range_index.index_for(range_meters).unwrap_or(15);
+        let cmd = format_range_index_command(wire_index);
         self.queue_command(io, cmd.trim());
     }
 
@@ -237,31 +337,54 @@ impl FurunoController {
     pub fn set_gain<I: IoProvider>(&mut self, io: &mut I, value: i32, auto: bool) {
         let cmd = format_gain_command(value, auto);
         self.queue_command(io, cmd.trim());
-        // Update local state immediately for responsive UI
-        self.radar_state.gain.value = value;
+        // Update local state immediately for responsive UI. In auto mode the
+        // radar reports the value it uses, so keep the last one until then.
+        if !auto {
+            self.radar_state.gain.value = value;
+        }
         self.radar_state.gain.mode = if auto { "auto".into() } else { "manual".into() };
     }
 
+    /// Switch gain between auto and manual, keeping the value in use
+    ///
+    /// Going to manual starts from the last value the radar reported in
+    /// auto mode, instead of a reset default.
+    pub fn set_gain_mode<I: IoProvider>(&mut self, io: &mut I, auto: bool) {
+        let value = self.radar_state.gain.value;
+        self.set_gain(io, value, auto);
+    }
+
     /// Set radar sea clutter
     pub fn set_sea<I: IoProvider>(&mut self, io: &mut I, value: i32, auto: bool) {
         let cmd = format_sea_command(value, auto);
         self.queue_command(io, cmd.trim());
-        // Update local state immediately for responsive UI
-        self.radar_state.sea.value = value;
+        // Update local state immediately for responsive UI (see set_gain)
+        if !auto {
+            self.radar_state.sea.value = value;
+        }
         self.radar_state.sea.mode = if auto { "auto".into() } else { "manual".into() };
     }
 
+    /// Switch sea clutter between auto and manual, keeping the value in use
+    pub fn set_sea_mode<I: IoProvider>(&mut self, io: &mut I, auto: bool) {
+        let value = self.radar_state.sea.value;
+        self.set_sea(io, value, auto);
+    }
+
     /// Set radar rain clutter
     pub fn set_rain<I: IoProvider>(&mut self, io: &mut I, value: i32, auto: bool) {
         let cmd = format_rain_command(value, auto);
         self.queue_command(io, cmd.trim());
-        // Update local state immediately for responsive UI
-        self.radar_state.rain.value = value;
+        // Update local state immediately for responsive UI (see set_gain)
+        if !auto {
+            self.radar_state.rain.value = value;
+        }
         self.radar_state.rain.mode = if auto { "auto".into() } else { "manual".into() };
     }
 
-    /// Set RezBoost (beam sharpening) level
+    /// Set RezBoost (beam sharpening) level, clamped to 0-3
     pub fn set_rezboost<I: IoProvider>(&mut self, io: &mut I, level: i32) {
+        let level = level.clamp(0, 3);
         let cmd = format_rezboost_command(level, 0);
         self.queue_command(io, cmd.trim());
         // Update local state immediately for responsive UI
@@ -274,6 +397,31 @@ impl FurunoController {
         self.queue_command(io, cmd.trim());
         // Update local state immediately for responsive UI
         self.radar_state.interference_rejection = enabled;
+        self.radar_state.interference_rejection_level = if enabled { 2 } else { 0 };
+    }
+
+    /// Whether this model has interference rejection levels (FAR series)
+    pub fn has_leveled_interference_rejection(&self) -> bool {
+        self.model
+            .as_deref()
+            .and_then(|m| crate::models::get_model(crate::Brand::Furuno, m))
+            .is_some_and(|m| m.has_leveled_interference_rejection())
+    }
+
+    /// Set interference rejection level (0=OFF, 1-2=IR1-IR2)
+    ///
+    /// Models without levels switch interference rejection on for any
+    /// level above 0.
+    pub fn set_interference_rejection_level<I: IoProvider>(&mut self, io: &mut I, level: i32) {
+        if !self.has_leveled_interference_rejection() {
+            return self.set_interference_rejection(io, level > 0);
+        }
+        let level = level.clamp(0, MAX_INTERFERENCE_REJECTION_LEVEL);
+        let cmd = format_interference_rejection_level_command(level);
+        self.queue_command(io, cmd.trim());
+        // Update local state immediately for responsive UI
+        self.radar_state.interference_rejection = level > 0;
+        self.radar_state.interference_rejection_level = level;
     }
 
     /// Set noise reduction
@@ -300,6 +448,48 @@ impl FurunoController {
         self.radar_state.bird_mode = level;
     }
 
+    /// Set an extended control by its semantic ID
+    pub fn set_extended_control<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        control_id: &str,
+        value: i32,
+    ) -> Result<(), ControlError> {
+        // RezBoost and bird mode levels are 0-3; anything else is undefined
+        let level = || -> Result<i32, ControlError> {
+            if (0..=3).contains(&value) {
+                Ok(value)
+            } else {
+                Err(ControlError::InvalidValue(format!(
+                    "{} must be 0-3, got {}",
+                    control_id, value
+                )))
+            }
+        };
+
+        match control_id {
+            "interferenceRejection" => {
+                // IR levels on FAR radars, on/off on the others
+                let max = if self.has_leveled_interference_rejection() {
+                    MAX_INTERFERENCE_REJECTION_LEVEL
+                } else {
+                    1
+                };
+                if !(0..=max).contains(&value) {
+                    return Err(ControlError::InvalidValue(format!(
+                        "{} must be 0-{}, got {}",
+                        control_id, max, value
+                    )));
+                }
+                self.set_interference_rejection_level(io, value)
+            }
+            "beamSharpening" => self.set_rezboost(io, level()?),
+            "birdMode" => self.set_bird_mode(io, level()?),
+            _ => return Err(ControlError::ControlNotFound(control_id.to_string())),
+        }
+        Ok(())
+    }
+
     /// Set target analyzer (Doppler mode)
     pub fn set_target_analyzer<I: IoProvider>(&mut self, io: &mut I, enabled: bool, mode: i32) {
         let cmd = format_target_analyzer_command(enabled, mode, 0);
@@ -330,6 +520,7 @@ impl FurunoController {
         self.queue_command(io, cmd.trim());
         // Update local state immediately for responsive UI
         self.radar_state.tx_channel = channel;
+        self.radar_state.tx_channel_active = None;
     }
 
     /// Set auto acquire (ARPA by Doppler)
@@ -344,7 +535,7 @@ impl FurunoController {
         self.queue_command(io, cmd.trim());
     }
 
-    /// Set blind sector (no-transmit zones)
+    /// Set blind sector (no-transmit zones), each zone as start/end angles
     /// Protocol: $S77,{s2_enable},{s1_start},{s1_width},{s2_start},{s2_width}
     /// - Sector 1 enabled when width > 0
     /// - Sector 2 enabled when s2_enable=1 AND width > 0
@@ -358,30 +549,15 @@ impl FurunoController {
         zone2_start: i32,
         zone2_end: i32,
     ) {
-        // Helper to normalize angle to 0-359
-        let normalize = |angle: i32| ((angle % 360) + 360) % 360;
-
-        // Zone 1: enabled by width > 0
-        let (z1_start, z1_width) = if zone1_enabled {
-            let start = normalize(zone1_start);
-            let end = normalize(zone1_end);
-            let width = ((end - start + 360) % 360).max(1);
-            (start, width)
-        } else {
-            (0, 0) // Disabled: start=0, width=0
-        };
-
-        // Zone 2: enabled by s2_enable flag AND width > 0
-        let (z2_start, z2_width) = if zone2_enabled {
-            let start = normalize(zone2_start);
-            let end = normalize(zone2_end);
-            let width = ((end - start + 360) % 360).max(1);
-            (start, width)
-        } else {
-            (0, 0) // Disabled: start=0, width=0
-        };
-
-        let cmd = format_blind_sector_command(zone2_enabled, z1_start, z1_width, z2_start, z2_width);
+        let zone1 = zone1_enabled.then_some(BlindSectorZone {
+            start: zone1_start,
+            end: zone1_end,
+        });
+        let zone2 = zone2_enabled.then_some(BlindSectorZone {
+            start: zone2_start,
+            end: zone2_end,
+        });
+        let cmd = format_blind_sector_command(zone1, zone2);
         self.queue_command(io, cmd.trim());
     }
 
@@ -406,7 +582,6 @@ impl FurunoController {
     /// - `ModelDetected` when model and firmware version are identified
     /// - `OperatingHoursUpdated` when operating hours change
     pub fn poll<I: IoProvider>(&mut self, io: &mut I) -> Vec<ControllerEvent> {
-        self.poll_count += 1;
         let mut events = Vec::new();
 
         // Track state before polling for disconnect detection
@@ -417,8 +592,8 @@ impl FurunoController {
                 if self.pending_command.is_some() {
                     // Check backoff
                     if self.retry_count > 0 {
-                        let delay = Self::RETRY_DELAY_BASE * (1 << self.retry_count.min(4) as u64);
-                        let elapsed = self.poll_count - self.last_retry_poll;
+                        let delay = RECONNECT_DELAY_MS * (1 << self.retry_count.min(4) as u64);
+                        let elapsed = io.current_time_ms().saturating_sub(self.last_retry_ms);
                         if elapsed < delay {
                             return events;
                         }
@@ -490,11 +665,58 @@ impl FurunoController {
             }
         }
 
+        // Emit BearingAlignmentReported when the radar's stored alignment changes
+        if let Some(degrees) = self.radar_state.bearing_alignment {
+            if self.last_emitted_alignment != Some(degrees) {
+                self.last_emitted_alignment = Some(degrees);
+                events.push(ControllerEvent::BearingAlignmentReported { degrees });
+            }
+        }
+
         events
     }
 
+    /// Prefer `variable` for the variable login byte
+    ///
+    /// The default alternates are still tried after it if the radar
+    /// rejects the login.
+    pub fn set_login_variable_byte(&mut self, variable: u8) {
+        self.login_variables = std::iter::once(variable)
+            .chain(
+                LOGIN_VARIABLE_BYTES
+                    .iter()
+                    .copied()
+                    .filter(|&b| b != variable),
+            )
+            .collect();
+        self.login_variable_idx = 0;
+    }
+
+    /// Variable login byte used for the current (or next) login
+    pub fn login_variable_byte(&self) -> u8 {
+        self.login_variables[self.login_variable_idx]
+    }
+
+    /// Move on to the next variable login byte after a rejected login
+    ///
+    /// Returns false, and starts over at the preferred byte, once all
+    /// alternates have been tried.
+    fn next_login_variable(&mut self) -> bool {
+        self.login_variable_idx += 1;
+        if self.login_variable_idx < self.login_variables.len() {
+            true
+        } else {
+            self.login_variable_idx = 0;
+            false
+        }
+    }
+
     /// Start the login process
     fn start_login<I: IoProvider>(&mut self, io: &mut I) {
+        if self.passive {
+            // Logging in means talking to the radar
+            return;
+        }
         if self.login_port_idx >= Self::LOGIN_PORTS.len() {
             io.debug(&format!(
                 "[{}] All login ports exhausted, trying fallback",
@@ -569,6 +791,21 @@ impl FurunoController {
         }
 
         if !io.tcp_is_connected(&socket) {
+            if self.login_sent {
+                // EOF before the radar answered: try the next variable
+                // byte, then the next login port
+                io.debug(&format!(
+                    "[{}] Login connection closed before response",
+                    self.radar_id
+                ));
+                io.tcp_close(socket);
+                self.login_socket = None;
+                if !self.next_login_variable() {
+                    self.login_port_idx += 1;
+                }
+                self.start_login(io);
+                return true;
+            }
             io.debug(&format!("[{}] Login socket still connecting...", self.radar_id));
             return true; // Still connecting
         }
@@ -576,8 +813,15 @@ impl FurunoController {
         // Send login message ONCE (not on every poll!)
         if !self.login_sent {
             self.login_sent = true;
-            io.debug(&format!("[{}] Sending login message", self.radar_id));
-            if io.tcp_send(&socket, &LOGIN_MESSAGE).is_err() {
+            let variable = self.login_variable_byte();
+            io.debug(&format!(
+                "[{}] Sending login message (variable byte {:#04x})",
+                self.radar_id, variable
+            ));
+            if io
+                .tcp_send(&socket, &format_login_message(variable))
+                .is_err()
+            {
                 io.debug(&format!("[{}] Failed to send login message", self.radar_id));
                 self.disconnect(io);
                 return false;
@@ -595,6 +839,15 @@ impl FurunoController {
                 io.tcp_close(socket);
                 self.login_socket = None;
                 self.start_command_connection(io);
+            } else if self.next_login_variable() {
+                io.debug(&format!(
+                    "[{}] Invalid login response, retrying with variable byte {:#04x}",
+                    self.radar_id,
+                    self.login_variable_byte()
+                ));
+                io.tcp_close(socket);
+                self.login_socket = None;
+                self.start_login(io);
             } else {
                 io.debug(&format!("[{}] Invalid login response", self.radar_id));
                 self.disconnect(io);
@@ -654,14 +907,14 @@ impl FurunoController {
             self.command_socket = None;
             self.state = ControllerState::Disconnected;
             self.retry_count += 1;
-            self.last_retry_poll = self.poll_count;
+            self.last_retry_ms = io.current_time_ms();
             return false;
         }
 
         if io.tcp_is_connected(&socket) {
             io.debug(&format!("[{}] Command connection established", self.radar_id));
             self.state = ControllerState::Connected;
-            self.last_keepalive = self.poll_count;
+            self.last_keepalive_ms = io.current_time_ms();
             self.retry_count = 0;
             self.login_port_idx = 0;
 
@@ -686,7 +939,7 @@ impl FurunoController {
 
         if !io.tcp_is_connected(&socket) {
             io.debug(&format!("[{}] Command connection lost", self.radar_id));
-            self.disconnect(io);
+            self.reconnect(io);
             return false;
         }
 
@@ -711,6 +964,17 @@ impl FurunoController {
             self.parse_response(io, line);
         }
 
+        // EOF while reading: the radar closed the connection (e.g. it
+        // rebooted). Log in again rather than waiting on a dead socket.
+        if !io.tcp_is_connected(&socket) {
+            io.debug(&format!(
+                "[{}] Command connection closed by radar",
+                self.radar_id
+            ));
+            self.reconnect(io);
+            return false;
+        }
+
         // Re-request state when radar transitions to transmit mode
         // Some controls (like mainBangSuppression) may only be available when transmitting
         use crate::state::PowerState;
@@ -726,9 +990,10 @@ impl FurunoController {
         self.prev_power_state = self.radar_state.power;
 
         // Send keep-alive
-        if self.poll_count - self.last_keepalive > Self::KEEPALIVE_INTERVAL {
+        let now = io.current_time_ms();
+        if now.saturating_sub(self.last_keepalive_ms) > KEEPALIVE_INTERVAL_MS {
             self.send_keepalive(io);
-            self.last_keepalive = self.poll_count;
+            self.last_keepalive_ms = now;
         }
 
         true
@@ -741,7 +1006,7 @@ impl FurunoController {
             self.fallback_port_idx = 0;
             self.state = ControllerState::Disconnected;
             self.retry_count += 1;
-            self.last_retry_poll = self.poll_count;
+            self.last_retry_ms = io.current_time_ms();
             return;
         }
 
@@ -795,7 +1060,7 @@ impl FurunoController {
                 self.radar_id, self.command_port
             ));
             self.state = ControllerState::Connected;
-            self.last_keepalive = self.poll_count;
+            self.last_keepalive_ms = io.current_time_ms();
             self.retry_count = 0;
             self.fallback_port_idx = 0;
 
@@ -809,6 +1074,9 @@ impl FurunoController {
 
     /// Send a command to the radar
     fn send_command<I: IoProvider>(&self, io: &mut I, cmd: &str) {
+        if self.passive {
+            return;
+        }
         if let Some(socket) = self.command_socket {
             io.debug(&format!("[{}] Sending: {}", self.radar_id, cmd));
             let cmd_with_crlf = format!("{}\r\n", cmd);
@@ -848,6 +1116,10 @@ impl FurunoController {
 
     /// Parse a response line from the radar
     fn parse_response<I: IoProvider>(&mut self, io: &I, line: &str) {
+        if let Some(code) = line.strip_prefix('$').and_then(|l| l.split(',').next()) {
+            self.report_capture.record(code, line.as_bytes());
+        }
+
         // Debug: Log main bang responses specifically (using INFO to ensure visibility)
         if line.starts_with("$N83") {
             io.info(&format!(
@@ -856,8 +1128,14 @@ impl FurunoController {
             ));
         }
 
-        // Update state from control responses
-        if self.radar_state.update_from_response(line) {
+        // Update state from control responses. Reports for model-dependent
+        // features are held back until the model is resolved, so they don't
+        // show up with Unknown-model defaults.
+        if !self.model_resolved && is_model_dependent_report(line) {
+            let code = &line[..line.len().min(4)];
+            self.deferred_reports.retain(|l| !l.starts_with(code));
+            self.deferred_reports.push(line.to_string());
+        } else if self.radar_state.update_from_response(line) {
             io.debug(&format!(
                 "[{}] State updated: power={:?}, range={}, mbs={}",
                 self.radar_id, self.radar_state.power, self.radar_state.range,
@@ -870,6 +1148,11 @@ impl FurunoController {
         // Example: $N96,0359360-01.05,0359358-01.01,0359359-01.01,0359361-01.05,,,
         // The first part code identifies the radar model (see protocol docs)
         if line.starts_with("$N96") {
+            // Keep the full inventory; trailing empty fields are skipped
+            if let Ok(FurunoReport::Modules(report)) = parse_report(line) {
+                self.modules = report.parts;
+            }
+
             let parts: Vec<&str> = line.split(',').collect();
             if parts.len() >= 2 {
                 // Parse first module: "0359360-01.05" -> code="0359360", version="01.05"
@@ -900,6 +1183,9 @@ impl FurunoController {
                         "[{}] Firmware version from $N96: {}",
                         self.radar_id, firmware_version
                     ));
+
+                    // Even an unrecognized part code is as much as we'll learn
+                    self.resolve_model();
                 }
             }
         }
@@ -946,9 +1232,322 @@ impl FurunoController {
         // This allows Connected to be emitted again on reconnection
     }
 
+    /// Disconnect after losing the connection, and log in again on the
+    /// next poll (with the usual retry backoff if that fails)
+    fn reconnect<I: IoProvider>(&mut self, io: &mut I) {
+        self.disconnect(io);
+        self.login_port_idx = 0;
+        self.request_info();
+    }
+
     /// Shutdown the controller
     pub fn shutdown<I: IoProvider>(&mut self, io: &mut I) {
         io.debug(&format!("[{}] Shutting down", self.radar_id));
         self.disconnect(io);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::mock::MockIo;
+
+    fn connected(io: &mut MockIo) -> FurunoController {
+        let mut controller = FurunoController::new("radar-1", "172.31.3.212");
+        controller.command_socket = Some(io.tcp_create().unwrap());
+        controller.state = ControllerState::Connected;
+        controller
+    }
+
+    #[test]
+    fn test_passive_never_connects() {
+        let mut io = MockIo::new();
+        let mut controller = FurunoController::new("radar-1", "172.31.3.212");
+        controller.set_passive(true);
+        controller.set_gain(&mut io, 60, false);
+        for _ in 0..100 {
+            controller.poll(&mut io);
+        }
+        assert_eq!(controller.state(), ControllerState::Disconnected);
+        assert!(io.tcp_sent.is_empty());
+
+        let mut controller = connected(&mut io);
+        controller.set_passive(true);
+        controller.set_gain(&mut io, 60, false);
+        for _ in 0..100 {
+            controller.poll(&mut io);
+        }
+        assert!(io.tcp_sent.is_empty());
+    }
+
+    #[test]
+    fn test_keepalive_follows_host_clock() {
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller.poll(&mut io);
+        io.tcp_sent.clear();
+
+        let keepalive = format_keepalive();
+        let keepalive = keepalive.trim();
+        let keepalives = |io: &MockIo| {
+            io.tcp_sent
+                .iter()
+                .filter(|d| String::from_utf8_lossy(d).trim() == keepalive)
+                .count()
+        };
+        for _ in 0..100 {
+            controller.poll(&mut io);
+        }
+        assert_eq!(keepalives(&io), 0);
+
+        io.time_ms += KEEPALIVE_INTERVAL_MS + 1;
+        controller.poll(&mut io);
+        controller.poll(&mut io);
+        assert_eq!(keepalives(&io), 1);
+    }
+
+    #[test]
+    fn test_eof_triggers_relogin() {
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller.poll(&mut io);
+        assert!(controller.is_connected());
+
+        // Radar sends a last report, then closes the connection
+        io.tcp_lines.push_back("$N63,0,60,0,80,0".to_string());
+        io.tcp_eof = true;
+        let events = controller.poll(&mut io);
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, ControllerEvent::Disconnected)));
+        assert_eq!(controller.radar_state().gain.value, 60);
+        assert_eq!(controller.state(), ControllerState::Disconnected);
+
+        controller.poll(&mut io);
+        assert_eq!(controller.state(), ControllerState::LoggingIn);
+    }
+
+    #[test]
+    fn test_rejected_login_retries_alternate_variable_byte() {
+        use crate::protocol::furuno::command::LOGIN_VARIABLE_BYTE_INDEX;
+
+        let mut io = MockIo::new();
+        let mut controller = FurunoController::new("radar-1", "172.31.3.212");
+        controller.set_gain(&mut io, 60, false);
+        assert_eq!(controller.state(), ControllerState::LoggingIn);
+
+        controller.poll(&mut io);
+        assert_eq!(
+            io.tcp_sent[0][LOGIN_VARIABLE_BYTE_INDEX],
+            LOGIN_VARIABLE_BYTES[0]
+        );
+
+        // The radar answers the default byte with garbage
+        io.push_line("rejected");
+        controller.poll(&mut io);
+        assert_eq!(controller.state(), ControllerState::LoggingIn);
+        controller.poll(&mut io);
+        assert_eq!(io.tcp_sent.len(), 2);
+        assert_eq!(
+            io.tcp_sent[1][LOGIN_VARIABLE_BYTE_INDEX],
+            LOGIN_VARIABLE_BYTES[1]
+        );
+
+        // A configured byte is tried first
+        controller.set_login_variable_byte(0x07);
+        assert_eq!(controller.login_variable_byte(), 0x07);
+        assert!(controller.next_login_variable());
+        assert_eq!(controller.login_variable_byte(), LOGIN_VARIABLE_BYTES[0]);
+    }
+
+    #[test]
+    fn test_bearing_alignment_readback_emitted_once() {
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller.parse_response(&io, "$N81,50,0");
+
+        let reported = |events: Vec<ControllerEvent>| {
+            events
+                .into_iter()
+                .filter_map(|e| match e {
+                    ControllerEvent::BearingAlignmentReported { degrees } => Some(degrees),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(reported(controller.poll(&mut io)), vec![5.0]);
+        assert!(reported(controller.poll(&mut io)).is_empty());
+    }
+
+    #[test]
+    fn test_model_dependent_reports_deferred() {
+        let io = MockIo::new();
+        let mut controller = FurunoController::new("radar-1", "172.31.3.212");
+        controller.parse_response(&io, "$NEE,2,0");
+        controller.parse_response(&io, "$NED,3,0");
+        controller.parse_response(&io, "$N63,0,60,0,80,0");
+        assert_eq!(controller.radar_state().beam_sharpening, 0);
+        assert_eq!(controller.radar_state().bird_mode, 0);
+        // Base controls are not held back
+        assert_eq!(controller.radar_state().gain.value, 60);
+
+        controller.parse_response(&io, "$NEE,3,0");
+        controller.set_model("DRS4D-NXT");
+        assert_eq!(controller.radar_state().beam_sharpening, 3);
+        assert_eq!(controller.radar_state().bird_mode, 3);
+
+        controller.parse_response(&io, "$NEE,1,0");
+        assert_eq!(controller.radar_state().beam_sharpening, 1);
+    }
+
+    #[test]
+    fn test_set_range_uses_cached_index() {
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller.set_range(&mut io, 66672);
+        controller.set_range(&mut io, 1000);
+        assert_eq!(
+            io.tcp_sent_strings(),
+            vec!["$S62,19,0,0\r\n", "$S62,3,0,0\r\n"]
+        );
+    }
+
+    #[test]
+    fn test_gain_auto_to_manual_keeps_value() {
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller.set_gain(&mut io, 0, true);
+        // The radar reports the gain it picked in auto mode
+        controller.parse_response(&io, "$N63,1,63,0,80,0");
+        assert_eq!(controller.radar_state().gain.value, 63);
+
+        controller.set_gain_mode(&mut io, false);
+        assert_eq!(controller.radar_state().gain.mode, "manual");
+        assert_eq!(controller.radar_state().gain.value, 63);
+        assert_eq!(
+            io.tcp_sent_strings().last().unwrap(),
+            "$S63,0,63,0,80,0\r\n"
+        );
+
+        // The manual confirmation keeps the value
+        controller.parse_response(&io, "$N63,0,63,0,80,0");
+        assert_eq!(controller.radar_state().gain.value, 63);
+    }
+
+    #[test]
+    fn test_antenna_height_round_trip() {
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller.set_antenna_height(&mut io, 15);
+        assert_eq!(io.tcp_sent_strings(), vec!["$S84,0,15,0\r\n"]);
+
+        // The radar echoes the setting back in meters
+        controller.parse_response(&io, "$N84,0,15,0");
+        assert_eq!(controller.radar_state().antenna_height, Some(15));
+
+        // ... which also gives the radar horizon, ~16 km for 15 m
+        let controls = controller.radar_state().to_controls_map();
+        assert_eq!(controls["radarHorizon"], serde_json::json!(15964.0));
+    }
+
+    #[test]
+    fn test_bird_mode_command() {
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller
+            .set_extended_control(&mut io, "birdMode", 2)
+            .unwrap();
+        assert_eq!(io.tcp_sent_strings(), vec!["$SED,2,0\r\n"]);
+        assert_eq!(controller.radar_state().bird_mode, 2);
+
+        assert!(matches!(
+            controller.set_extended_control(&mut io, "birdMode", 4),
+            Err(ControlError::InvalidValue(_))
+        ));
+        assert_eq!(io.tcp_sent.len(), 1);
+    }
+
+    #[test]
+    fn test_interference_rejection_levels_per_model() {
+        // DRS: on/off, sent as 2
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller.set_model("DRS4D-NXT");
+        controller
+            .set_extended_control(&mut io, "interferenceRejection", 1)
+            .unwrap();
+        assert_eq!(io.tcp_sent_strings(), vec!["$S67,0,0,2,0\r\n"]);
+        assert_eq!(
+            controller.radar_state().to_controls_map()["interferenceRejection"],
+            true
+        );
+        assert!(matches!(
+            controller.set_extended_control(&mut io, "interferenceRejection", 2),
+            Err(ControlError::InvalidValue(_))
+        ));
+
+        // FAR: levels IR1-IR2
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        controller.set_model("FAR-1513");
+        controller
+            .set_extended_control(&mut io, "interferenceRejection", 1)
+            .unwrap();
+        assert_eq!(io.tcp_sent_strings(), vec!["$S67,0,0,1,0\r\n"]);
+        controller.parse_response(&io, "$N67,0,0,2,0");
+        assert_eq!(
+            controller.radar_state().to_controls_map()["interferenceRejection"],
+            2
+        );
+        assert!(matches!(
+            controller.set_extended_control(&mut io, "interferenceRejection", 3),
+            Err(ControlError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_beam_sharpening_range() {
+        let mut io = MockIo::new();
+        let mut controller = connected(&mut io);
+        for level in 0..=3 {
+            controller
+                .set_extended_control(&mut io, "beamSharpening", level)
+                .unwrap();
+            assert_eq!(controller.radar_state().beam_sharpening, level);
+        }
+        assert_eq!(io.tcp_sent.len(), 4);
+
+        assert!(matches!(
+            controller.set_extended_control(&mut io, "beamSharpening", 5),
+            Err(ControlError::InvalidValue(_))
+        ));
+        assert_eq!(io.tcp_sent.len(), 4);
+        assert_eq!(controller.radar_state().beam_sharpening, 3);
+    }
+
+    #[test]
+    fn test_modules_parsed_from_n96() {
+        let io = MockIo::new();
+        let mut controller = FurunoController::new("radar-1", "172.31.3.212");
+        controller.parse_response(
+            &io,
+            "$N96,0359360-01.05,0359358-01.01,0359359-01.01,0359361-01.05,,,",
+        );
+
+        let modules: Vec<(&str, &str)> = controller
+            .modules()
+            .iter()
+            .map(|m| (m.code.as_str(), m.version.as_str()))
+            .collect();
+        assert_eq!(
+            modules,
+            vec![
+                ("0359360", "01.05"),
+                ("0359358", "01.01"),
+                ("0359359", "01.01"),
+                ("0359361", "01.05"),
+            ]
+        );
+        assert_eq!(controller.firmware_version(), Some("01.05"));
+    }
+}
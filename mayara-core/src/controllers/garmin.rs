@@ -19,6 +19,7 @@
 //! [4 bytes] value (LE u32)
 //! ```
 
+use super::{ReportCapture, REPORT_STALL_MS};
 use crate::io::{IoProvider, UdpSocketHandle};
 use crate::protocol::garmin;
 
@@ -33,6 +34,39 @@ pub enum GarminControllerState {
     Connected,
 }
 
+/// Timed transmit settings as reported by the radar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GarminTimedTransmit {
+    pub enabled: bool,
+    /// Minutes spent transmitting
+    pub transmit_minutes: u32,
+    /// Minutes spent in standby
+    pub standby_minutes: u32,
+}
+
+/// Radar state as read back from the reports
+///
+/// Fields are `None` until the radar has reported them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GarminRadarState {
+    pub transmit_state: Option<garmin::TransmitState>,
+    pub range_meters: Option<u32>,
+    /// Gain (0-100)
+    pub gain: Option<u32>,
+    pub gain_auto: Option<bool>,
+    /// Sea clutter (0-100)
+    pub sea: Option<u32>,
+    pub sea_auto: Option<bool>,
+    /// Rain clutter (0-100)
+    pub rain: Option<u32>,
+    pub rain_auto: Option<bool>,
+    /// Bearing alignment in degrees
+    pub bearing_alignment: Option<f32>,
+    /// Model named in the scanner message, see
+    /// [`model_from_scanner_message`](crate::models::garmin::model_from_scanner_message)
+    pub model: Option<&'static str>,
+}
+
 /// Garmin radar UDP controller
 ///
 /// Manages UDP communication for Garmin xHD radars.
@@ -47,8 +81,20 @@ pub struct GarminController {
     report_socket: Option<UdpSocketHandle>,
     /// Current state
     state: GarminControllerState,
-    /// Poll count
-    poll_count: u64,
+    /// Time (ms) the last report arrived
+    last_report_ms: u64,
+    /// Last raw report per type (debugging)
+    report_capture: ReportCapture,
+    /// Passive mode: listen only, never send to the radar
+    passive: bool,
+    /// Timed idle mode, from reports
+    timed_idle_mode: Option<bool>,
+    /// Timed idle transmit period (seconds), from reports
+    timed_idle_run_secs: Option<u32>,
+    /// Timed idle standby period (seconds), from reports
+    timed_idle_time_secs: Option<u32>,
+    /// Radar state, from reports
+    radar_state: GarminRadarState,
 }
 
 impl GarminController {
@@ -60,10 +106,30 @@ impl GarminController {
             command_socket: None,
             report_socket: None,
             state: GarminControllerState::Disconnected,
-            poll_count: 0,
+            last_report_ms: 0,
+            report_capture: ReportCapture::default(),
+            passive: false,
+            timed_idle_mode: None,
+            timed_idle_run_secs: None,
+            timed_idle_time_secs: None,
+            radar_state: GarminRadarState::default(),
         }
     }
 
+    /// Timed transmit settings, once the radar has reported all of them
+    pub fn timed_transmit(&self) -> Option<GarminTimedTransmit> {
+        Some(GarminTimedTransmit {
+            enabled: self.timed_idle_mode?,
+            transmit_minutes: self.timed_idle_run_secs? / 60,
+            standby_minutes: self.timed_idle_time_secs? / 60,
+        })
+    }
+
+    /// Radar state as last reported by the radar
+    pub fn radar_state(&self) -> &GarminRadarState {
+        &self.radar_state
+    }
+
     /// Get current state
     pub fn state(&self) -> GarminControllerState {
         self.state
@@ -74,9 +140,50 @@ impl GarminController {
         self.state == GarminControllerState::Connected
     }
 
+    /// Radar IP address commands are sent to
+    pub fn radar_addr(&self) -> &str {
+        &self.radar_addr
+    }
+
+    /// Point the controller at a new radar IP (e.g. after a DHCP renewal).
+    /// Sockets are recreated on the next poll. Returns true if the address
+    /// changed.
+    pub fn set_radar_addr<I: IoProvider>(&mut self, io: &mut I, radar_addr: &str) -> bool {
+        if self.radar_addr == radar_addr {
+            return false;
+        }
+        self.shutdown(io);
+        self.radar_addr = radar_addr.to_string();
+        true
+    }
+
+    /// Enable or disable capturing the last raw report per report type
+    pub fn set_report_capture(&mut self, enabled: bool) {
+        self.report_capture.set_enabled(enabled);
+    }
+
+    /// Enable or disable passive mode
+    ///
+    /// A passive controller still receives and parses reports but sends
+    /// nothing to the radar: no report requests, keep-alives or commands.
+    pub fn set_passive(&mut self, passive: bool) {
+        self.passive = passive;
+    }
+
+    /// Check if passive mode is enabled
+    pub fn is_passive(&self) -> bool {
+        self.passive
+    }
+
+    /// Get the last raw report of a type as hex (report capture must be enabled)
+    ///
+    /// Report IDs are the packet type as a hex number, e.g. `"00000919"`.
+    pub fn last_report_hex(&self, report_id: &str) -> Option<String> {
+        self.report_capture.last_report_hex(report_id)
+    }
+
     /// Poll the controller
     pub fn poll<I: IoProvider>(&mut self, io: &mut I) -> bool {
-        self.poll_count += 1;
 
         match self.state {
             GarminControllerState::Disconnected => {
@@ -149,16 +256,61 @@ impl GarminController {
             }
         }
 
+        if activity {
+            self.last_report_ms = io.current_time_ms();
+        } else if self.state == GarminControllerState::Connected
+            && io.current_time_ms().saturating_sub(self.last_report_ms) > REPORT_STALL_MS
+        {
+            io.info(&format!(
+                "[{}] No reports for {} ms, re-joining multicast",
+                self.radar_id, REPORT_STALL_MS
+            ));
+            // Sockets are recreated on the next poll
+            self.shutdown(io);
+            return false;
+        }
+
         activity
     }
 
     fn process_report<I: IoProvider>(&mut self, io: &I, data: &[u8]) {
+        if data.len() >= 4 {
+            let packet_type = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+            self.report_capture
+                .record(&format!("{:08X}", packet_type), data);
+        }
         if let Ok(report) = garmin::parse_report(data) {
             io.debug(&format!("[{}] Report: {:?}", self.radar_id, report));
+            let state = &mut self.radar_state;
+            match report {
+                garmin::Report::TransmitState(ts) => state.transmit_state = Some(ts),
+                garmin::Report::Range(meters) => state.range_meters = Some(meters),
+                garmin::Report::GainMode(mode) => {
+                    state.gain_auto = Some(mode == garmin::GainMode::Auto)
+                }
+                garmin::Report::Gain(value) => state.gain = Some(value),
+                garmin::Report::SeaMode(mode) => state.sea_auto = Some(mode != 0),
+                garmin::Report::Sea(value) => state.sea = Some(value),
+                garmin::Report::RainMode(mode) => state.rain_auto = Some(mode != 0),
+                garmin::Report::Rain(value) => state.rain = Some(value),
+                garmin::Report::BearingAlignment(deg) => state.bearing_alignment = Some(deg),
+                garmin::Report::ScannerMessage(msg) => {
+                    if let Some(model) = crate::models::garmin::model_from_scanner_message(&msg) {
+                        state.model = Some(model.model);
+                    }
+                }
+                garmin::Report::TimedIdleMode(enabled) => self.timed_idle_mode = Some(enabled),
+                garmin::Report::TimedIdleRun(secs) => self.timed_idle_run_secs = Some(secs),
+                garmin::Report::TimedIdleTime(secs) => self.timed_idle_time_secs = Some(secs),
+                _ => {}
+            }
         }
     }
 
     fn send_command<I: IoProvider>(&self, io: &mut I, data: &[u8]) {
+        if self.passive {
+            return;
+        }
         if let Some(socket) = self.command_socket {
             if let Err(e) = io.udp_send_to(&socket, data, &self.radar_addr, garmin::SEND_PORT) {
                 io.debug(&format!("[{}] Failed to send command: {}", self.radar_id, e));
@@ -220,6 +372,25 @@ impl GarminController {
         ));
     }
 
+    /// Set timed transmit (periods in minutes)
+    ///
+    /// The radar alternates between transmitting for `transmit_period` and
+    /// standby for `standby_period`. A period of 0 turns timed transmit off.
+    pub fn set_timed_transmit<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        transmit_period: u32,
+        standby_period: u32,
+    ) {
+        let enabled = transmit_period > 0 && standby_period > 0;
+        let cmd = garmin::create_timed_transmit_command(enabled, transmit_period, standby_period);
+        self.send_command(io, &cmd);
+        io.debug(&format!(
+            "[{}] Set timed transmit: enabled={} transmit={}min standby={}min",
+            self.radar_id, enabled, transmit_period, standby_period
+        ));
+    }
+
     /// Shutdown the controller
     pub fn shutdown<I: IoProvider>(&mut self, io: &mut I) {
         io.debug(&format!("[{}] Shutting down", self.radar_id));
@@ -232,3 +403,67 @@ impl GarminController {
         self.state = GarminControllerState::Disconnected;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::mock::MockIo;
+
+    #[test]
+    fn test_timed_transmit_read_back_from_reports() {
+        let mut io = MockIo::new();
+        let mut controller = GarminController::new("test", "172.16.2.0");
+        controller.poll(&mut io);
+        controller.set_timed_transmit(&mut io, 5, 10);
+        assert_eq!(controller.timed_transmit(), None);
+
+        // The radar reports each setting in a report of its own
+        let cmd = io.udp_sent[0].data.clone();
+        for report in cmd.chunks(12) {
+            io.push_udp(garmin::REPORT_PORT, report, "172.16.2.0");
+        }
+        controller.poll(&mut io);
+        assert_eq!(
+            controller.timed_transmit(),
+            Some(GarminTimedTransmit {
+                enabled: true,
+                transmit_minutes: 5,
+                standby_minutes: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn test_radar_state_read_back_from_reports() {
+        let mut io = MockIo::new();
+        let mut controller = GarminController::new("test", "172.16.2.0");
+        controller.poll(&mut io);
+        assert_eq!(controller.radar_state(), &GarminRadarState::default());
+
+        let reports = [
+            garmin::create_gain_command(true, 40),
+            garmin::create_sea_clutter_command(false, 25),
+            garmin::create_range_command(1500),
+            garmin::create_transmit_command(true),
+        ];
+        for report in reports.iter().flat_map(|r| r.chunks(12)) {
+            io.push_udp(garmin::REPORT_PORT, report, "172.16.2.0");
+        }
+        let mut message = vec![0u8; 88];
+        message[0..4].copy_from_slice(&garmin::REPORT_SCANNER_MESSAGE.to_le_bytes());
+        message[4..8].copy_from_slice(&80u32.to_le_bytes());
+        message[24..34].copy_from_slice(b"GMR 18 HD+");
+        io.push_udp(garmin::REPORT_PORT, &message, "172.16.2.0");
+        controller.poll(&mut io);
+
+        let state = controller.radar_state();
+        assert_eq!(state.gain, Some(40));
+        assert_eq!(state.gain_auto, Some(true));
+        assert_eq!(state.sea, Some(25));
+        assert_eq!(state.sea_auto, Some(false));
+        assert_eq!(state.rain, None);
+        assert_eq!(state.range_meters, Some(1500));
+        assert_eq!(state.transmit_state, Some(garmin::TransmitState::Transmit));
+        assert_eq!(state.model, Some("GMR 18 HD+"));
+    }
+}
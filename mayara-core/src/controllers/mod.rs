@@ -81,10 +81,67 @@ pub mod raymarine;
 
 // Re-export main types
 pub use furuno::{ControllerState, FurunoController};
-pub use garmin::{GarminController, GarminControllerState};
+pub use garmin::{GarminController, GarminControllerState, GarminRadarState, GarminTimedTransmit};
 pub use navico::{NavicoController, NavicoControllerState, NavicoModel};
 pub use raymarine::{RaymarineController, RaymarineControllerState, RaymarineVariant};
 
+use std::collections::HashMap;
+
+/// Milliseconds without a report after which a connected controller
+/// recreates its sockets and joins the multicast group again.
+///
+/// Multicast membership is dropped silently when the NIC goes down and up,
+/// e.g. when a switch is power cycled.
+pub const REPORT_STALL_MS: u64 = 10_000;
+
+/// Last raw report received per report type, for debugging.
+///
+/// Disabled by default so the controllers don't copy every report.
+/// Integrators debugging a control that "doesn't stick" can enable it and
+/// compare the raw report with what the control state shows.
+#[derive(Debug, Clone, Default)]
+pub struct ReportCapture {
+    enabled: bool,
+    last: HashMap<String, Vec<u8>>,
+}
+
+impl ReportCapture {
+    /// Enable or disable capture; disabling drops captured reports
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.last.clear();
+        }
+    }
+
+    /// Check if capture is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Remember `data` as the last report of type `report_id`
+    pub fn record(&mut self, report_id: &str, data: &[u8]) {
+        if self.enabled {
+            self.last.insert(report_id.to_string(), data.to_vec());
+        }
+    }
+
+    /// Get the last raw report of type `report_id`
+    pub fn last_report(&self, report_id: &str) -> Option<&[u8]> {
+        self.last.get(report_id).map(|v| v.as_slice())
+    }
+
+    /// Get the last raw report of type `report_id` as space-separated hex
+    pub fn last_report_hex(&self, report_id: &str) -> Option<String> {
+        self.last_report(report_id).map(|data| {
+            data.iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+    }
+}
+
 /// Events emitted by controllers for the shell to handle.
 ///
 /// Controllers are platform-independent and don't know about server's `SharedRadars`
@@ -129,4 +186,31 @@ pub enum ControllerEvent {
         /// Total transmit hours
         hours: f64,
     },
+
+    /// Bearing alignment stored in the radar was read back.
+    /// The radar is the source of truth (it may have been changed from an
+    /// MFD), so the shell should update its persisted setting if it differs.
+    BearingAlignmentReported {
+        /// Alignment in degrees (-180..180)
+        degrees: f64,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_capture_disabled_by_default() {
+        let mut capture = ReportCapture::default();
+        capture.record("02C4", &[0x02, 0xC4]);
+        assert!(capture.last_report("02C4").is_none());
+
+        capture.set_enabled(true);
+        capture.record("02C4", &[0x02, 0xC4, 0x0A]);
+        assert_eq!(capture.last_report_hex("02C4").as_deref(), Some("02 C4 0A"));
+
+        capture.set_enabled(false);
+        assert!(capture.last_report("02C4").is_none());
+    }
 }
@@ -19,14 +19,21 @@
 //! | 4G | 48 NM | No | Gen4 |
 //! | HALO | 96 NM | Yes | Advanced |
 
+use super::{ReportCapture, REPORT_STALL_MS};
+use crate::capabilities::ControlError;
 use crate::io::{IoProvider, UdpSocketHandle};
 use crate::protocol::navico;
 
+/// HALO preset modes sent with `0x10 0xC1`
+/// (Custom, Harbor, Offshore, Buoy, Weather, Bird)
+const MODE_CUSTOM: u8 = 0;
+const MODE_BIRD: u8 = 5;
+
 /// Navico radar model
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum NavicoModel {
     /// Unknown model - will be detected from Report 03
-    /// Behaves like Gen4 for command compatibility
+    /// Only the BR24-compatible command subset is sent until then
     #[default]
     Unknown,
     BR24,
@@ -35,6 +42,18 @@ pub enum NavicoModel {
     Halo,
 }
 
+impl From<navico::Model> for NavicoModel {
+    fn from(model: navico::Model) -> Self {
+        match model {
+            navico::Model::Unknown => NavicoModel::Unknown,
+            navico::Model::BR24 => NavicoModel::BR24,
+            navico::Model::Gen3 => NavicoModel::Gen3,
+            navico::Model::Gen4 => NavicoModel::Gen4,
+            navico::Model::HALO => NavicoModel::Halo,
+        }
+    }
+}
+
 impl NavicoModel {
     /// Check if this is a HALO model (has Doppler, accent light, etc.)
     pub fn is_halo(&self) -> bool {
@@ -45,6 +64,31 @@ impl NavicoModel {
     pub fn is_known(&self) -> bool {
         !matches!(self, NavicoModel::Unknown)
     }
+
+    /// Check if model supports target boost and target expansion (3G and later)
+    pub fn has_target_boost(&self) -> bool {
+        matches!(
+            self,
+            NavicoModel::Gen3 | NavicoModel::Gen4 | NavicoModel::Halo
+        )
+    }
+
+    /// Check if model supports target separation and noise rejection (3G and later)
+    pub fn has_noise_rejection(&self) -> bool {
+        matches!(
+            self,
+            NavicoModel::Gen3 | NavicoModel::Gen4 | NavicoModel::Halo
+        )
+    }
+
+    /// Highest target expansion level: HALO adds "High" (2)
+    pub fn max_target_expansion(&self) -> u8 {
+        if self.is_halo() {
+            2
+        } else {
+            1
+        }
+    }
 }
 
 /// Controller state
@@ -79,19 +123,33 @@ pub struct NavicoController {
     state: NavicoControllerState,
     /// Radar model
     model: NavicoModel,
-    /// Poll count
-    poll_count: u64,
-    /// Last report request time
-    last_report_request: u64,
-    /// Last stay-on command time
-    last_stay_on: u64,
+    /// Time (ms) the last report arrived
+    last_report_ms: u64,
+    /// Last report request time (ms)
+    last_report_request_ms: u64,
+    /// Last stay-on command time (ms)
+    last_stay_on_ms: u64,
+    /// Radar status, with preparing progress, from the last report 01
+    status: Option<navico::StatusReport>,
+    /// Range in meters from the last report 02
+    range_meters: Option<u32>,
+    /// Antenna height in millimeters from the last report 04
+    antenna_height_mm: Option<u16>,
+    /// Interference rejection (0-3) from the last report 02
+    interference_rejection: Option<u8>,
+    /// Local interference rejection (0-3) from the last report 08
+    local_interference_rejection: Option<u8>,
+    /// Last raw report per type (debugging)
+    report_capture: ReportCapture,
+    /// Passive mode: listen only, never send to the radar
+    passive: bool,
 }
 
 impl NavicoController {
-    /// Report request interval (poll counts, ~5 seconds at 10Hz)
-    const REPORT_REQUEST_INTERVAL: u64 = 50;
-    /// Stay-on command interval (poll counts, ~1 second)
-    const STAY_ON_INTERVAL: u64 = 10;
+    /// Report request interval (ms)
+    const REPORT_REQUEST_INTERVAL_MS: u64 = 5000;
+    /// Stay-on command interval (ms)
+    const STAY_ON_INTERVAL_MS: u64 = 1000;
 
     /// Create a new Navico controller
     pub fn new(
@@ -114,9 +172,16 @@ impl NavicoController {
             report_socket: None,
             state: NavicoControllerState::Disconnected,
             model,
-            poll_count: 0,
-            last_report_request: 0,
-            last_stay_on: 0,
+            last_report_ms: 0,
+            last_report_request_ms: 0,
+            last_stay_on_ms: 0,
+            status: None,
+            range_meters: None,
+            antenna_height_mm: None,
+            interference_rejection: None,
+            local_interference_rejection: None,
+            report_capture: ReportCapture::default(),
+            passive: false,
         }
     }
 
@@ -130,11 +195,95 @@ impl NavicoController {
         self.state == NavicoControllerState::Connected
     }
 
+    /// Command address and port
+    pub fn command_addr(&self) -> (&str, u16) {
+        (&self.command_addr, self.command_port)
+    }
+
+    /// Point the controller at new command and report addresses (e.g.
+    /// after a DHCP renewal). Sockets are recreated on the next poll.
+    /// Returns true if anything changed.
+    pub fn set_addresses<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        command_addr: &str,
+        command_port: u16,
+        report_addr: &str,
+        report_port: u16,
+    ) -> bool {
+        if (
+            self.command_addr.as_str(),
+            self.command_port,
+            self.report_addr.as_str(),
+            self.report_port,
+        ) == (command_addr, command_port, report_addr, report_port)
+        {
+            return false;
+        }
+        self.shutdown(io);
+        self.command_addr = command_addr.to_string();
+        self.command_port = command_port;
+        self.report_addr = report_addr.to_string();
+        self.report_port = report_port;
+        true
+    }
+
     /// Get radar model
     pub fn model(&self) -> NavicoModel {
         self.model
     }
 
+    /// Enable or disable capturing the last raw report per report type
+    pub fn set_report_capture(&mut self, enabled: bool) {
+        self.report_capture.set_enabled(enabled);
+    }
+
+    /// Enable or disable passive mode
+    ///
+    /// A passive controller still receives and parses reports but sends
+    /// nothing to the radar: no report requests, keep-alives or commands.
+    pub fn set_passive(&mut self, passive: bool) {
+        self.passive = passive;
+    }
+
+    /// Check if passive mode is enabled
+    pub fn is_passive(&self) -> bool {
+        self.passive
+    }
+
+    /// Get the last raw report of a type as hex (report capture must be enabled)
+    ///
+    /// Report IDs are the first two bytes in hex, e.g. `"02C4"` for report 02.
+    pub fn last_report_hex(&self, report_id: &str) -> Option<String> {
+        self.report_capture.last_report_hex(report_id)
+    }
+
+    /// Get the current range in meters, as last reported by the radar
+    pub fn range_meters(&self) -> Option<u32> {
+        self.range_meters
+    }
+
+    /// Radar status and preparing progress reported by the radar (report 01)
+    pub fn status_report(&self) -> Option<navico::StatusReport> {
+        self.status
+    }
+
+    /// Antenna height in meters reported by the radar (report 04)
+    pub fn antenna_height_meters(&self) -> Option<f32> {
+        self.antenna_height_mm
+            .map(navico::antenna_height_mm_to_meters)
+    }
+
+    /// Interference rejection level reported by the radar (report 02)
+    pub fn interference_rejection(&self) -> Option<u8> {
+        self.interference_rejection
+    }
+
+    /// Local interference rejection level reported by the radar (report 08)
+    pub fn local_interference_rejection(&self) -> Option<u8> {
+        self.local_interference_rejection
+    }
+
     /// Set radar model (called when model is detected from reports)
     pub fn set_model(&mut self, model: NavicoModel) {
         self.model = model;
@@ -142,7 +291,6 @@ impl NavicoController {
 
     /// Poll the controller
     pub fn poll<I: IoProvider>(&mut self, io: &mut I) -> bool {
-        self.poll_count += 1;
 
         match self.state {
             NavicoControllerState::Disconnected => {
@@ -229,16 +377,32 @@ impl NavicoController {
             }
         }
 
+        if activity {
+            self.last_report_ms = io.current_time_ms();
+        } else if self.state == NavicoControllerState::Connected
+            && io.current_time_ms().saturating_sub(self.last_report_ms) > REPORT_STALL_MS
+        {
+            io.info(&format!(
+                "[{}] No reports for {} ms, re-joining multicast",
+                self.radar_id, REPORT_STALL_MS
+            ));
+            // Sockets are recreated on the next poll
+            self.shutdown(io);
+            return false;
+        }
+
+        let now = io.current_time_ms();
+
         // Send periodic report requests
-        if self.poll_count - self.last_report_request > Self::REPORT_REQUEST_INTERVAL {
+        if now.saturating_sub(self.last_report_request_ms) > Self::REPORT_REQUEST_INTERVAL_MS {
             self.request_reports(io);
-            self.last_report_request = self.poll_count;
+            self.last_report_request_ms = now;
         }
 
         // Send stay-on command
-        if self.poll_count - self.last_stay_on > Self::STAY_ON_INTERVAL {
+        if now.saturating_sub(self.last_stay_on_ms) > Self::STAY_ON_INTERVAL_MS {
             self.stay_on(io);
-            self.last_stay_on = self.poll_count;
+            self.last_stay_on_ms = now;
         }
 
         activity
@@ -251,6 +415,8 @@ impl NavicoController {
 
         // Report type is in first two bytes
         let report_type = (data[1] as u16) << 8 | data[0] as u16;
+        self.report_capture
+            .record(&format!("{:02X}{:02X}", data[0], data[1]), data);
         io.debug(&format!(
             "[{}] Report type: 0x{:04X}, len: {}",
             self.radar_id, report_type, data.len()
@@ -260,7 +426,56 @@ impl NavicoController {
         // 0x01C4 = Report 01 (Status)
         // 0x02C4 = Report 02 (Settings)
         // 0x03C4 = Report 03 (Model)
-        // etc.
+        // 0x04C4 = Report 04 (Installation)
+        // 0x08C4 = Report 08 (Advanced settings)
+        if report_type == 0xC401 {
+            match navico::parse_report_01_detail(data) {
+                Ok(status) => self.status = Some(status),
+                Err(e) => io.debug(&format!("[{}] Invalid report 01: {}", self.radar_id, e)),
+            }
+        } else if report_type == 0xC402 {
+            match navico::parse_report_02(data) {
+                Ok(controls) => {
+                    self.range_meters =
+                        Some(navico::range_decimeters_to_meters(controls.range) as u32);
+                    self.interference_rejection = Some(controls.interference_rejection);
+                }
+                Err(e) => io.debug(&format!("[{}] Invalid report 02: {}", self.radar_id, e)),
+            }
+        } else if report_type == 0xC403 {
+            self.process_report_03(io, data);
+        } else if report_type == 0xC404 {
+            match navico::parse_report_04(data) {
+                Ok(installation) => self.antenna_height_mm = Some(installation.antenna_height),
+                Err(e) => io.debug(&format!("[{}] Invalid report 04: {}", self.radar_id, e)),
+            }
+        } else if report_type == 0xC408 {
+            match navico::parse_report_08(data) {
+                Ok(advanced) => {
+                    self.local_interference_rejection = Some(advanced.local_interference_rejection)
+                }
+                Err(e) => io.debug(&format!("[{}] Invalid report 08: {}", self.radar_id, e)),
+            }
+        }
+    }
+
+    /// Reconfigure the controller once Report 03 confirms the model
+    fn process_report_03<I: IoProvider>(&mut self, io: &I, data: &[u8]) {
+        let info = match navico::parse_report_03(data) {
+            Ok(info) => info,
+            Err(e) => {
+                io.debug(&format!("[{}] Invalid report 03: {}", self.radar_id, e));
+                return;
+            }
+        };
+        let model = NavicoModel::from(info.model);
+        if model.is_known() && model != self.model {
+            io.info(&format!(
+                "[{}] Radar model confirmed as {:?} (was {:?})",
+                self.radar_id, model, self.model
+            ));
+            self.model = model;
+        }
     }
 
     fn request_reports<I: IoProvider>(&self, io: &mut I) {
@@ -275,6 +490,9 @@ impl NavicoController {
     }
 
     fn send_command<I: IoProvider>(&self, io: &mut I, data: &[u8]) {
+        if self.passive {
+            return;
+        }
         if let Some(socket) = self.command_socket {
             if let Err(e) = io.udp_send_to(&socket, data, &self.command_addr, self.command_port) {
                 io.debug(&format!("[{}] Failed to send command: {}", self.radar_id, e));
@@ -299,8 +517,7 @@ impl NavicoController {
 
     /// Set range in decimeters
     pub fn set_range<I: IoProvider>(&mut self, io: &mut I, range_dm: i32) {
-        let mut cmd = vec![0x03, 0xC1];
-        cmd.extend_from_slice(&range_dm.to_le_bytes());
+        let cmd = navico::create_range_command(range_dm);
         self.send_command(io, &cmd);
         io.debug(&format!("[{}] Set range: {} dm", self.radar_id, range_dm));
     }
@@ -348,26 +565,41 @@ impl NavicoController {
         io.debug(&format!("[{}] Set IR: {}", self.radar_id, level));
     }
 
-    /// Set target expansion (0-2)
+    /// Set target expansion (0-1, HALO 0-2; not available on BR24)
     pub fn set_target_expansion<I: IoProvider>(&mut self, io: &mut I, level: u8) {
+        if !self.model.has_target_boost() {
+            io.debug(&format!(
+                "[{}] Target expansion not supported on {:?}",
+                self.radar_id, self.model
+            ));
+            return;
+        }
+        let level = level.min(self.model.max_target_expansion());
         let cmd_id = if self.model.is_halo() { 0x12 } else { 0x09 };
         let cmd = [cmd_id, 0xC1, level];
         self.send_command(io, &cmd);
         io.debug(&format!("[{}] Set target expansion: {}", self.radar_id, level));
     }
 
-    /// Set target boost (0-2)
+    /// Set target boost (0-2; not available on BR24)
     pub fn set_target_boost<I: IoProvider>(&mut self, io: &mut I, level: u8) {
-        let cmd = [0x0A, 0xC1, level];
+        if !self.model.has_target_boost() {
+            io.debug(&format!(
+                "[{}] Target boost not supported on {:?}",
+                self.radar_id, self.model
+            ));
+            return;
+        }
+        let cmd = [0x0A, 0xC1, level.min(2)];
         self.send_command(io, &cmd);
         io.debug(&format!("[{}] Set target boost: {}", self.radar_id, level));
     }
 
-    /// Set scan speed (0=normal, 1=fast)
-    pub fn set_scan_speed<I: IoProvider>(&mut self, io: &mut I, fast: bool) {
-        let cmd = [0x0F, 0xC1, if fast { 1 } else { 0 }];
+    /// Set scan speed (0=normal, 1=fast; HALO has 0-3)
+    pub fn set_scan_speed<I: IoProvider>(&mut self, io: &mut I, speed: u8) {
+        let cmd = [0x0F, 0xC1, speed];
         self.send_command(io, &cmd);
-        io.debug(&format!("[{}] Set scan speed: {}", self.radar_id, fast));
+        io.debug(&format!("[{}] Set scan speed: {}", self.radar_id, speed));
     }
 
     /// Set bearing alignment in deci-degrees
@@ -378,7 +610,7 @@ impl NavicoController {
         io.debug(&format!("[{}] Set bearing alignment: {}", self.radar_id, deci_degrees));
     }
 
-    /// Set antenna height in mm
+    /// Set antenna height in mm (see [`navico::antenna_height_meters_to_mm`])
     pub fn set_antenna_height<I: IoProvider>(&mut self, io: &mut I, height_mm: u16) {
         let mut cmd = vec![0x30, 0xC1, 0x01, 0x00, 0x00, 0x00];
         cmd.extend_from_slice(&height_mm.to_le_bytes());
@@ -406,6 +638,15 @@ impl NavicoController {
         }
     }
 
+    /// Set bird mode (HALO only)
+    ///
+    /// Navico has no separate bird setting; level 1-3 selects the HALO "Bird"
+    /// preset mode and 0 returns to "Custom".
+    pub fn set_bird_mode<I: IoProvider>(&mut self, io: &mut I, level: u8) {
+        let mode = if level > 0 { MODE_BIRD } else { MODE_CUSTOM };
+        self.set_mode(io, mode);
+    }
+
     /// Set mode (HALO only, 0-3)
     pub fn set_mode<I: IoProvider>(&mut self, io: &mut I, mode: u8) {
         if self.model.is_halo() {
@@ -431,21 +672,38 @@ impl NavicoController {
     }
 
     /// Set local interference rejection (0-3)
+    ///
+    /// Unlike [`Self::set_interference_rejection`] this filters sources on
+    /// board; the radar reports it back in report 08.
     pub fn set_local_interference_rejection<I: IoProvider>(&mut self, io: &mut I, level: u8) {
-        let cmd = [0x0E, 0xC1, level];
+        let cmd = navico::create_local_interference_rejection_command(level);
         self.send_command(io, &cmd);
         io.debug(&format!("[{}] Set local IR: {}", self.radar_id, level));
     }
 
-    /// Set noise rejection (0-3)
+    /// Set noise rejection (0-3; not available on BR24)
     pub fn set_noise_rejection<I: IoProvider>(&mut self, io: &mut I, level: u8) {
+        if !self.model.has_noise_rejection() {
+            io.debug(&format!(
+                "[{}] Noise rejection not supported on {:?}",
+                self.radar_id, self.model
+            ));
+            return;
+        }
         let cmd = [0x21, 0xC1, level];
         self.send_command(io, &cmd);
         io.debug(&format!("[{}] Set noise rejection: {}", self.radar_id, level));
     }
 
-    /// Set target separation (0-3)
+    /// Set target separation (0-3; not available on BR24)
     pub fn set_target_separation<I: IoProvider>(&mut self, io: &mut I, level: u8) {
+        if !self.model.has_noise_rejection() {
+            io.debug(&format!(
+                "[{}] Target separation not supported on {:?}",
+                self.radar_id, self.model
+            ));
+            return;
+        }
         let cmd = [0x22, 0xC1, level];
         self.send_command(io, &cmd);
         io.debug(&format!("[{}] Set target separation: {}", self.radar_id, level));
@@ -492,6 +750,56 @@ impl NavicoController {
         ));
     }
 
+    /// Set an extended control by its semantic ID (as used in the capability manifest)
+    ///
+    /// Checks that the control exists on this model and that the value is in
+    /// range before sending anything.
+    pub fn set_extended_control<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        control_id: &str,
+        value: i32,
+    ) -> Result<(), ControlError> {
+        let level = |max: i32| -> Result<u8, ControlError> {
+            if (0..=max).contains(&value) {
+                Ok(value as u8)
+            } else {
+                Err(ControlError::InvalidValue(format!(
+                    "{} must be 0-{}, got {}",
+                    control_id, max, value
+                )))
+            }
+        };
+
+        match control_id {
+            "targetBoost" | "targetExpansion" if !self.model.has_target_boost() => {
+                return Err(ControlError::ControlNotFound(control_id.to_string()));
+            }
+            "targetBoost" => self.set_target_boost(io, level(2)?),
+            "targetExpansion" => {
+                let max = self.model.max_target_expansion() as i32;
+                self.set_target_expansion(io, level(max)?)
+            }
+            "targetSeparation" | "noiseRejection" if !self.model.has_noise_rejection() => {
+                return Err(ControlError::ControlNotFound(control_id.to_string()));
+            }
+            "targetSeparation" => self.set_target_separation(io, level(3)?),
+            "noiseRejection" => self.set_noise_rejection(io, level(3)?),
+            "interferenceRejection" => self.set_interference_rejection(io, level(3)?),
+            "localInterferenceRejection" => self.set_local_interference_rejection(io, level(3)?),
+            "seaState" if !self.model.is_halo() => {
+                return Err(ControlError::ControlNotFound(control_id.to_string()));
+            }
+            "seaState" => self.set_sea_state(io, level(2)?),
+            "birdMode" if !self.model.is_halo() => {
+                return Err(ControlError::ControlNotFound(control_id.to_string()));
+            }
+            "birdMode" => self.set_bird_mode(io, level(3)?),
+            _ => return Err(ControlError::ControlNotFound(control_id.to_string())),
+        }
+        Ok(())
+    }
+
     /// Send report requests to the radar
     pub fn send_report_requests<I: IoProvider>(&mut self, io: &mut I) {
         self.send_command(io, &navico::REQUEST_03_REPORT);
@@ -511,3 +819,279 @@ impl NavicoController {
         self.state = NavicoControllerState::Disconnected;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::mock::MockIo;
+
+    fn connected(model: NavicoModel) -> (NavicoController, MockIo) {
+        let mut io = MockIo::new();
+        let mut controller =
+            NavicoController::new("test", "236.6.7.10", 6680, "236.6.7.9", 6679, "", model);
+        controller.poll(&mut io);
+        io.udp_sent.clear();
+        (controller, io)
+    }
+
+    fn report_03(model_byte: u8) -> Vec<u8> {
+        let mut data = vec![0u8; navico::REPORT_03_SIZE];
+        data[0] = 0x03;
+        data[1] = 0xC4;
+        data[2] = model_byte;
+        data
+    }
+
+    #[test]
+    fn test_recv_stall_rejoins_multicast() {
+        let (mut controller, mut io) = connected(NavicoModel::Halo);
+        assert_eq!(io.joined, vec!["236.6.7.9"]);
+
+        io.push_udp(6679, &report_03(0x00), "192.168.1.50");
+        controller.poll(&mut io);
+        assert!(controller.is_connected());
+
+        // Reports stop arriving, e.g. after the NIC went down and up.
+        // Only the host clock counts, not how often the controller is polled.
+        for _ in 0..1000 {
+            controller.poll(&mut io);
+        }
+        io.time_ms += REPORT_STALL_MS;
+        controller.poll(&mut io);
+        assert!(controller.is_connected());
+        io.time_ms += 1;
+        controller.poll(&mut io);
+        assert_eq!(controller.state(), NavicoControllerState::Disconnected);
+
+        // The next poll recreates the sockets and joins the group again
+        controller.poll(&mut io);
+        assert_eq!(controller.state(), NavicoControllerState::Listening);
+        assert_eq!(io.joined, vec!["236.6.7.9", "236.6.7.9"]);
+    }
+
+    #[test]
+    fn test_unknown_model_reconfigured_from_report_03() {
+        let (mut controller, mut io) = connected(NavicoModel::Unknown);
+
+        // Until the model is known, no 3G+ commands are sent
+        assert!(controller
+            .set_extended_control(&mut io, "targetBoost", 1)
+            .is_err());
+        assert!(io.udp_sent.is_empty());
+
+        io.push_udp(6679, &report_03(0x0F), "192.168.1.50");
+        controller.poll(&mut io);
+        assert_eq!(controller.model(), NavicoModel::BR24);
+        for id in [
+            "targetBoost",
+            "targetExpansion",
+            "targetSeparation",
+            "noiseRejection",
+        ] {
+            assert!(
+                controller.set_extended_control(&mut io, id, 1).is_err(),
+                "{}",
+                id
+            );
+        }
+        assert!(io.udp_sent.is_empty());
+        assert!(controller
+            .set_extended_control(&mut io, "interferenceRejection", 1)
+            .is_ok());
+
+        // An unknown model byte keeps the current model
+        io.push_udp(6679, &report_03(0x55), "192.168.1.50");
+        controller.poll(&mut io);
+        assert_eq!(controller.model(), NavicoModel::BR24);
+    }
+
+    #[test]
+    fn test_passive_poll_sends_nothing() {
+        let mut io = MockIo::new();
+        let mut controller = NavicoController::new(
+            "test",
+            "236.6.7.10",
+            6680,
+            "236.6.7.9",
+            6679,
+            "",
+            NavicoModel::Unknown,
+        );
+        controller.set_passive(true);
+
+        io.push_udp(6679, &report_03(0x0F), "192.168.1.50");
+        for _ in 0..20 {
+            io.time_ms += NavicoController::STAY_ON_INTERVAL_MS;
+            controller.poll(&mut io);
+        }
+        controller.send_report_requests(&mut io);
+        controller.set_gain(&mut io, 50, false);
+        assert!(io.udp_sent.is_empty());
+        // Reports are still processed
+        assert_eq!(controller.model(), NavicoModel::BR24);
+
+        controller.set_passive(false);
+        io.time_ms += NavicoController::REPORT_REQUEST_INTERVAL_MS + 1;
+        controller.poll(&mut io);
+        controller.poll(&mut io);
+        assert!(!io.udp_sent.is_empty());
+    }
+
+    /// Report 02 carrying the range from a range command
+    fn report_02_from_command(cmd: &[u8]) -> Vec<u8> {
+        assert_eq!(&cmd[..2], &[0x03, 0xC1]);
+        let mut data = vec![0u8; navico::REPORT_02_SIZE];
+        data[0] = 0x02;
+        data[1] = 0xC4;
+        data[2..6].copy_from_slice(&cmd[2..6]);
+        data
+    }
+
+    #[test]
+    fn test_range_round_trip() {
+        for model in ["HALO24", "4G"] {
+            let table = crate::models::get_model(crate::Brand::Navico, model)
+                .unwrap()
+                .range_table;
+            let ranges = [table[0], 1852, *table.last().unwrap()];
+            let (mut controller, mut io) = connected(NavicoModel::Unknown);
+            for meters in ranges {
+                io.udp_sent.clear();
+                controller.set_range(&mut io, navico::range_meters_to_decimeters(meters));
+                let cmd = io.udp_sent[0].data.clone();
+                assert_eq!(
+                    i32::from_le_bytes(cmd[2..6].try_into().unwrap()),
+                    meters as i32 * 10
+                );
+
+                io.push_udp(6679, &report_02_from_command(&cmd), "192.168.1.50");
+                controller.poll(&mut io);
+                assert_eq!(
+                    controller.range_meters(),
+                    Some(meters),
+                    "{} {} m",
+                    model,
+                    meters
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_antenna_height_round_trip() {
+        let (mut controller, mut io) = connected(NavicoModel::Gen4);
+        controller.set_antenna_height(&mut io, navico::antenna_height_meters_to_mm(15.0));
+        let cmd = io.udp_sent[0].data.clone();
+        assert_eq!(u16::from_le_bytes([cmd[6], cmd[7]]), 15000);
+
+        // Report 04 carries the height at offset 10, in the same millimeters
+        let mut report = vec![0u8; navico::REPORT_04_SIZE];
+        report[0] = 0x04;
+        report[1] = 0xC4;
+        report[10..12].copy_from_slice(&cmd[6..8]);
+        io.push_udp(6679, &report, "192.168.1.50");
+        controller.poll(&mut io);
+        assert_eq!(controller.antenna_height_meters(), Some(15.0));
+    }
+
+    #[test]
+    fn test_local_interference_rejection_round_trip() {
+        let (mut controller, mut io) = connected(NavicoModel::Halo);
+        controller
+            .set_extended_control(&mut io, "localInterferenceRejection", 2)
+            .unwrap();
+        assert_eq!(io.udp_sent[0].data, vec![0x0E, 0xC1, 0x02]);
+        assert!(controller
+            .set_extended_control(&mut io, "localInterferenceRejection", 4)
+            .is_err());
+
+        // Report 08 carries local IR at offset 3; report 02's IR is separate
+        let mut report = vec![0u8; navico::REPORT_08_BASE_SIZE];
+        report[0] = 0x08;
+        report[1] = 0xC4;
+        report[3] = io.udp_sent[0].data[2];
+        io.push_udp(6679, &report, "192.168.1.50");
+        controller.poll(&mut io);
+        assert_eq!(controller.local_interference_rejection(), Some(2));
+        assert_eq!(controller.interference_rejection(), None);
+    }
+
+    #[test]
+    fn test_last_report_captured() {
+        let (mut controller, mut io) = connected(NavicoModel::Gen4);
+        io.push_udp(6679, &report_03(0x01), "192.168.1.50");
+        controller.poll(&mut io);
+        assert!(controller.last_report_hex("03C4").is_none());
+
+        controller.set_report_capture(true);
+        io.push_udp(6679, &report_03(0x01), "192.168.1.50");
+        controller.poll(&mut io);
+        let hex = controller.last_report_hex("03C4").unwrap();
+        assert!(hex.starts_with("03 C4 01 00"));
+        assert_eq!(hex.split(' ').count(), navico::REPORT_03_SIZE);
+    }
+
+    #[test]
+    fn test_target_boost_bytes() {
+        let (mut controller, mut io) = connected(NavicoModel::Gen4);
+        controller
+            .set_extended_control(&mut io, "targetBoost", 2)
+            .unwrap();
+        assert_eq!(io.udp_sent.len(), 1);
+        assert_eq!(io.udp_sent[0].data, vec![0x0A, 0xC1, 0x02]);
+        assert_eq!(io.udp_sent[0].port, 6680);
+    }
+
+    #[test]
+    fn test_target_expansion_bytes() {
+        let (mut controller, mut io) = connected(NavicoModel::Halo);
+        controller
+            .set_extended_control(&mut io, "targetExpansion", 2)
+            .unwrap();
+        assert_eq!(io.udp_sent[0].data, vec![0x12, 0xC1, 0x02]);
+
+        let (mut controller, mut io) = connected(NavicoModel::Gen4);
+        controller
+            .set_extended_control(&mut io, "targetExpansion", 1)
+            .unwrap();
+        assert_eq!(io.udp_sent[0].data, vec![0x09, 0xC1, 0x01]);
+    }
+
+    #[test]
+    fn test_bird_mode_halo_preset() {
+        let (mut controller, mut io) = connected(NavicoModel::Halo);
+        controller
+            .set_extended_control(&mut io, "birdMode", 2)
+            .unwrap();
+        controller
+            .set_extended_control(&mut io, "birdMode", 0)
+            .unwrap();
+        assert_eq!(io.udp_sent[0].data, vec![0x10, 0xC1, MODE_BIRD]);
+        assert_eq!(io.udp_sent[1].data, vec![0x10, 0xC1, MODE_CUSTOM]);
+
+        let (mut controller, mut io) = connected(NavicoModel::Gen4);
+        assert!(matches!(
+            controller.set_extended_control(&mut io, "birdMode", 1),
+            Err(ControlError::ControlNotFound(_))
+        ));
+        assert!(io.udp_sent.is_empty());
+    }
+
+    #[test]
+    fn test_target_boost_model_gating() {
+        let (mut controller, mut io) = connected(NavicoModel::BR24);
+        assert!(matches!(
+            controller.set_extended_control(&mut io, "targetBoost", 1),
+            Err(ControlError::ControlNotFound(_))
+        ));
+        assert!(io.udp_sent.is_empty());
+
+        // "High" expansion is HALO only
+        let (mut controller, mut io) = connected(NavicoModel::Gen3);
+        assert!(matches!(
+            controller.set_extended_control(&mut io, "targetExpansion", 2),
+            Err(ControlError::InvalidValue(_))
+        ));
+        assert!(io.udp_sent.is_empty());
+    }
+}
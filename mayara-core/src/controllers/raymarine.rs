@@ -16,7 +16,9 @@
 //! | Quantum | Q24, Q24C, Q24D, Cyclone | 250 | Q24D, Cyclone |
 //! | RD | RD418/424 HD, Magnum | 2048 | No |
 
+use super::{ReportCapture, REPORT_STALL_MS};
 use crate::io::{IoProvider, UdpSocketHandle};
+use crate::models::RangeIndex;
 
 /// Raymarine radar variant
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -65,10 +67,16 @@ pub struct RaymarineController {
     state: RaymarineControllerState,
     /// Radar variant
     variant: RaymarineVariant,
-    /// Poll count
-    poll_count: u64,
+    /// Time (ms) the last report arrived
+    last_report_ms: u64,
     /// Has doppler capability
     has_doppler: bool,
+    /// Last raw report per type (debugging)
+    report_capture: ReportCapture,
+    /// Passive mode: listen only, never send to the radar
+    passive: bool,
+    /// Cached meters -> range index lookup for the model's range table
+    range_index: RangeIndex,
 }
 
 impl RaymarineController {
@@ -92,8 +100,11 @@ impl RaymarineController {
             report_socket: None,
             state: RaymarineControllerState::Disconnected,
             variant,
-            poll_count: 0,
+            last_report_ms: 0,
             has_doppler,
+            report_capture: ReportCapture::default(),
+            passive: false,
+            range_index: RangeIndex::default(),
         }
     }
 
@@ -107,6 +118,64 @@ impl RaymarineController {
         self.state == RaymarineControllerState::Connected
     }
 
+    /// Command address and port
+    pub fn command_addr(&self) -> (&str, u16) {
+        (&self.command_addr, self.command_port)
+    }
+
+    /// Point the controller at new command and report addresses (e.g.
+    /// after a DHCP renewal). Sockets are recreated on the next poll.
+    /// Returns true if anything changed.
+    pub fn set_addresses<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        command_addr: &str,
+        command_port: u16,
+        report_addr: &str,
+        report_port: u16,
+    ) -> bool {
+        if (
+            self.command_addr.as_str(),
+            self.command_port,
+            self.report_addr.as_str(),
+            self.report_port,
+        ) == (command_addr, command_port, report_addr, report_port)
+        {
+            return false;
+        }
+        self.shutdown(io);
+        self.command_addr = command_addr.to_string();
+        self.command_port = command_port;
+        self.report_addr = report_addr.to_string();
+        self.report_port = report_port;
+        true
+    }
+
+    /// Enable or disable capturing the last raw report per report type
+    pub fn set_report_capture(&mut self, enabled: bool) {
+        self.report_capture.set_enabled(enabled);
+    }
+
+    /// Enable or disable passive mode
+    ///
+    /// A passive controller still receives and parses reports but sends
+    /// nothing to the radar: no report requests, keep-alives or commands.
+    pub fn set_passive(&mut self, passive: bool) {
+        self.passive = passive;
+    }
+
+    /// Check if passive mode is enabled
+    pub fn is_passive(&self) -> bool {
+        self.passive
+    }
+
+    /// Get the last raw report of a type as hex (report capture must be enabled)
+    ///
+    /// Report IDs are the first four bytes as a little-endian hex number, e.g. `"00010001"`.
+    pub fn last_report_hex(&self, report_id: &str) -> Option<String> {
+        self.report_capture.last_report_hex(report_id)
+    }
+
     /// Get radar variant
     pub fn variant(&self) -> RaymarineVariant {
         self.variant
@@ -119,7 +188,6 @@ impl RaymarineController {
 
     /// Poll the controller
     pub fn poll<I: IoProvider>(&mut self, io: &mut I) -> bool {
-        self.poll_count += 1;
 
         match self.state {
             RaymarineControllerState::Disconnected => {
@@ -192,6 +260,20 @@ impl RaymarineController {
             }
         }
 
+        if activity {
+            self.last_report_ms = io.current_time_ms();
+        } else if self.state == RaymarineControllerState::Connected
+            && io.current_time_ms().saturating_sub(self.last_report_ms) > REPORT_STALL_MS
+        {
+            io.info(&format!(
+                "[{}] No reports for {} ms, re-joining multicast",
+                self.radar_id, REPORT_STALL_MS
+            ));
+            // Sockets are recreated on the next poll
+            self.shutdown(io);
+            return false;
+        }
+
         activity
     }
 
@@ -202,6 +284,8 @@ impl RaymarineController {
 
         // Report ID is first 4 bytes (little-endian)
         let report_id = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+        self.report_capture
+            .record(&format!("{:08X}", report_id), data);
         io.debug(&format!(
             "[{}] Report ID: 0x{:08X}, len: {}",
             self.radar_id, report_id, data.len()
@@ -212,6 +296,9 @@ impl RaymarineController {
     }
 
     fn send_command<I: IoProvider>(&self, io: &mut I, data: &[u8]) {
+        if self.passive {
+            return;
+        }
         if let Some(socket) = self.command_socket {
             if let Err(e) = io.udp_send_to(&socket, data, &self.command_addr, self.command_port) {
                 io.debug(&format!("[{}] Failed to send command: {}", self.radar_id, e));
@@ -281,6 +368,24 @@ impl RaymarineController {
         io.debug(&format!("[{}] Set power: {}", self.radar_id, transmit));
     }
 
+    /// Set the model's range table, used by [`Self::set_range_meters`]
+    pub fn set_range_table(&mut self, table: &[u32]) {
+        if !self.range_index.matches_table(table) {
+            self.range_index = RangeIndex::from_range_table(table);
+        }
+    }
+
+    /// Set range in meters, using the range table to find the index
+    pub fn set_range_meters<I: IoProvider>(&mut self, io: &mut I, range_meters: u32) {
+        match self.range_index.index_for(range_meters) {
+            Some(index) => self.set_range(io, index as u8),
+            None => io.debug(&format!(
+                "[{}] No range table yet, ignoring range {} m",
+                self.radar_id, range_meters
+            )),
+        }
+    }
+
     /// Set range index
     pub fn set_range<I: IoProvider>(&mut self, io: &mut I, range_index: u8) {
         let cmd = match self.variant {
@@ -62,6 +62,58 @@ impl Default for DualRangeState {
     }
 }
 
+/// Why a secondary range was rejected
+///
+/// Every variant carries the ranges the radar does accept.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DualRangeError {
+    /// Shorter than the radar's shortest range
+    BelowMinimum {
+        range: u32,
+        min: u32,
+        valid_ranges: Vec<u32>,
+    },
+    /// Longer than the hardware allows in dual-range mode
+    AboveMaximum {
+        range: u32,
+        max: u32,
+        valid_ranges: Vec<u32>,
+    },
+    /// Within limits, but not in the radar's range table
+    NotAvailable { range: u32, valid_ranges: Vec<u32> },
+}
+
+impl DualRangeError {
+    /// Secondary ranges (meters) the radar accepts
+    pub fn valid_ranges(&self) -> &[u32] {
+        match self {
+            DualRangeError::BelowMinimum { valid_ranges, .. }
+            | DualRangeError::AboveMaximum { valid_ranges, .. }
+            | DualRangeError::NotAvailable { valid_ranges, .. } => valid_ranges,
+        }
+    }
+}
+
+impl std::fmt::Display for DualRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DualRangeError::BelowMinimum { range, min, .. } => {
+                write!(f, "Secondary range {} m is below minimum {} m", range, min)?
+            }
+            DualRangeError::AboveMaximum { range, max, .. } => {
+                write!(f, "Secondary range {} m exceeds maximum {} m", range, max)?
+            }
+            DualRangeError::NotAvailable { range, .. } => {
+                write!(f, "Secondary range {} m is not supported", range)?
+            }
+        }
+        let valid: Vec<String> = self.valid_ranges().iter().map(|r| r.to_string()).collect();
+        write!(f, "; valid ranges (m): {}", valid.join(", "))
+    }
+}
+
+impl std::error::Error for DualRangeError {}
+
 /// Dual-range controller manages the state and validates configurations
 pub struct DualRangeController {
     state: DualRangeState,
@@ -102,6 +154,21 @@ impl DualRangeController {
         &self.available_ranges
     }
 
+    /// Replace the range table, e.g. with the ranges the radar reported
+    ///
+    /// Model range tables are approximate; a secondary range is only valid
+    /// if the radar itself offers it.
+    pub fn set_ranges(&mut self, ranges: &[u32]) {
+        let mut secondary_ranges: Vec<u32> = ranges
+            .iter()
+            .filter(|&&r| r > 0 && r <= self.state.max_secondary_range)
+            .copied()
+            .collect();
+        secondary_ranges.sort_unstable();
+        secondary_ranges.dedup();
+        self.available_ranges = secondary_ranges;
+    }
+
     /// Enable or disable dual-range mode
     pub fn set_enabled(&mut self, enabled: bool) {
         self.state.enabled = enabled;
@@ -114,27 +181,52 @@ impl DualRangeController {
 
     /// Set the secondary range
     ///
-    /// Returns true if the range was accepted, false if it exceeds the limit
-    pub fn set_secondary_range(&mut self, range: u32) -> bool {
-        if range > self.state.max_secondary_range {
-            return false;
-        }
+    /// The range must be one of [`available_ranges`](Self::available_ranges);
+    /// the error names the valid ranges so a client can offer them.
+    pub fn set_secondary_range(&mut self, range: u32) -> Result<(), DualRangeError> {
+        self.validate_secondary_range(range)?;
         self.state.secondary_range = range;
-        true
+        Ok(())
     }
 
     /// Apply a configuration update
     ///
-    /// Returns true if all values were accepted
-    pub fn apply_config(&mut self, config: &DualRangeConfig) -> bool {
-        self.state.enabled = config.enabled;
+    /// Nothing is changed if the secondary range is rejected.
+    pub fn apply_config(&mut self, config: &DualRangeConfig) -> Result<(), DualRangeError> {
         if config.secondary_range > 0 {
-            if config.secondary_range > self.state.max_secondary_range {
-                return false;
-            }
-            self.state.secondary_range = config.secondary_range;
+            self.set_secondary_range(config.secondary_range)?;
+        }
+        self.state.enabled = config.enabled;
+        Ok(())
+    }
+
+    fn validate_secondary_range(&self, range: u32) -> Result<(), DualRangeError> {
+        let valid_ranges = self.available_ranges.clone();
+        if range > self.state.max_secondary_range {
+            return Err(DualRangeError::AboveMaximum {
+                range,
+                max: self.state.max_secondary_range,
+                valid_ranges,
+            });
+        }
+        // Without a range table only the hardware limit can be checked
+        let Some(&min) = self.available_ranges.first() else {
+            return Ok(());
+        };
+        if range < min {
+            return Err(DualRangeError::BelowMinimum {
+                range,
+                min,
+                valid_ranges,
+            });
+        }
+        if !self.available_ranges.contains(&range) {
+            return Err(DualRangeError::NotAvailable {
+                range,
+                valid_ranges,
+            });
         }
-        true
+        Ok(())
     }
 
     /// Find the closest valid secondary range to the requested value
@@ -165,14 +257,69 @@ mod tests {
         assert!(controller.state().enabled);
 
         // Set valid secondary range
-        assert!(controller.set_secondary_range(7408));
+        assert!(controller.set_secondary_range(7408).is_ok());
         assert_eq!(controller.state().secondary_range, 7408);
 
         // Reject range that exceeds limit
-        assert!(!controller.set_secondary_range(44448));
+        assert!(controller.set_secondary_range(44448).is_err());
         assert_eq!(controller.state().secondary_range, 7408); // Unchanged
     }
 
+    #[test]
+    fn test_secondary_range_validation() {
+        let ranges = vec![231, 463, 926, 1852, 3704, 7408, 14816, 22224, 44448];
+        let mut controller = DualRangeController::new(22224, ranges);
+        let valid = vec![231, 463, 926, 1852, 3704, 7408, 14816, 22224];
+
+        let err = controller.set_secondary_range(100).unwrap_err();
+        assert_eq!(
+            err,
+            DualRangeError::BelowMinimum {
+                range: 100,
+                min: 231,
+                valid_ranges: valid.clone(),
+            }
+        );
+
+        let err = controller.set_secondary_range(44448).unwrap_err();
+        assert!(matches!(
+            err,
+            DualRangeError::AboveMaximum { max: 22224, .. }
+        ));
+        assert_eq!(err.valid_ranges(), valid.as_slice());
+        assert!(err.to_string().contains("231, 463, 926"));
+
+        let err = controller.set_secondary_range(1000).unwrap_err();
+        assert!(matches!(
+            err,
+            DualRangeError::NotAvailable { range: 1000, .. }
+        ));
+
+        // Rejected configs leave the state untouched
+        let config = DualRangeConfig {
+            enabled: true,
+            secondary_range: 100,
+        };
+        assert!(controller.apply_config(&config).is_err());
+        assert!(!controller.state().enabled);
+
+        assert!(controller.set_secondary_range(3704).is_ok());
+        assert_eq!(controller.state().secondary_range, 3704);
+    }
+
+    #[test]
+    fn test_detected_ranges_replace_model_table() {
+        // A model table that is off by a few meters from what the radar uses
+        let mut controller = DualRangeController::new(22224, vec![231, 463, 926, 1850, 3704]);
+        assert!(controller.set_secondary_range(1852).is_err());
+
+        controller.set_ranges(&[3704, 1852, 926, 44448, 1852]);
+        assert_eq!(controller.available_ranges(), &[926, 1852, 3704]);
+        assert!(controller.set_secondary_range(1852).is_ok());
+        let err = controller.set_secondary_range(1850).unwrap_err();
+        assert_eq!(err.valid_ranges(), &[926, 1852, 3704]);
+    }
+
     #[test]
     fn test_find_closest_range() {
         let ranges = vec![231, 463, 926, 1852, 3704, 7408];
@@ -0,0 +1,107 @@
+//! Per-radar log of recent lifecycle events
+//!
+//! Keeps a short, timestamped history (discovery, model, transmit state,
+//! errors) for troubleshooting. The log is bounded: when it is full the
+//! oldest event is dropped.
+
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+/// Default number of events kept per radar
+pub const DEFAULT_EVENT_LOG_CAPACITY: usize = 100;
+
+/// What happened to a radar
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RadarEventKind {
+    /// The radar was found by the locator
+    Discovered,
+    /// The radar's model became known
+    ModelLearned { model: String },
+    /// The radar beaconed from a new address
+    AddressChanged { address: String },
+    /// Transmit was switched on
+    Transmit,
+    /// Transmit was switched off
+    Standby,
+    /// Something went wrong, e.g. rejected control writes
+    Error { message: String },
+}
+
+/// A timestamped radar event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarEvent {
+    /// Time of the event (ms since epoch)
+    pub timestamp_ms: u64,
+    #[serde(flatten)]
+    pub kind: RadarEventKind,
+}
+
+/// Bounded event history for one radar
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    events: VecDeque<RadarEvent>,
+    capacity: usize,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_LOG_CAPACITY)
+    }
+}
+
+impl EventLog {
+    /// Create a log holding at most `capacity` events (at least 1)
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append an event, dropping the oldest one if the log is full
+    pub fn record(&mut self, timestamp_ms: u64, kind: RadarEventKind) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(RadarEvent { timestamp_ms, kind });
+    }
+
+    /// Events, oldest first
+    pub fn events(&self) -> impl DoubleEndedIterator<Item = &RadarEvent> {
+        self.events.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_log_drops_oldest() {
+        let mut log = EventLog::new(2);
+        log.record(1, RadarEventKind::Discovered);
+        log.record(2, RadarEventKind::Transmit);
+        log.record(3, RadarEventKind::Standby);
+
+        let times: Vec<u64> = log.events().map(|e| e.timestamp_ms).collect();
+        assert_eq!(times, vec![2, 3]);
+
+        let json = serde_json::to_value(log.events().next().unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"timestampMs": 2, "type": "transmit"})
+        );
+    }
+}
@@ -7,7 +7,6 @@
 //!
 //! The `RadarEngine` manages all radar instances and their associated feature processors:
 //! - ARPA (Automatic Radar Plotting Aid) target tracking
-//! - Guard Zones for collision detection
 //! - Trails for target history visualization
 //! - Dual-Range for secondary display
 //!
@@ -21,25 +20,37 @@
 //! │  │  ManagedRadar (per radar)                              │  │
 //! │  │  ├─ RadarController (brand-specific)                   │  │
 //! │  │  ├─ ArpaProcessor                                      │  │
-//! │  │  ├─ GuardZoneProcessor                                 │  │
 //! │  │  ├─ TrailStore                                         │  │
 //! │  │  └─ DualRangeController (optional)                     │  │
 //! │  └────────────────────────────────────────────────────────┘  │
 //! └──────────────────────────────────────────────────────────────┘
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::arpa::{ArpaProcessor, ArpaSettings, ArpaTarget};
+use serde::Serialize;
+
+mod events;
+mod rate_limit;
+
+pub use events::{EventLog, RadarEvent, RadarEventKind, DEFAULT_EVENT_LOG_CAPACITY};
+pub use rate_limit::{ControlRateLimiter, DEFAULT_CONTROL_RATE_LIMIT};
+
+use crate::arpa::{
+    bearing_distance_to, ArpaProcessor, ArpaSettings, ArpaTarget, HistoryBuffer, Legend, OwnShip,
+    TargetChangeTracker, TargetDelta, TargetFilter, RESERVED_TARGET_ID_START,
+};
+use crate::capabilities::builder::build_capability_summary;
+use crate::capabilities::{is_control_allowed, CapabilitySummary, ControlError, SupportedFeature};
 use crate::controllers::{
     FurunoController, GarminController, NavicoController, NavicoModel, RaymarineController,
     RaymarineVariant,
 };
 use crate::dual_range::{DualRangeConfig, DualRangeController, DualRangeState};
-use crate::guard_zones::{GuardZone, GuardZoneProcessor, GuardZoneStatus};
 use crate::io::IoProvider;
 use crate::models::{self, ModelInfo};
-use crate::state::RadarState;
+use crate::radar::{legend_colors, LegendEntry, ParsedAddress, RadarDiscovery};
+use crate::state::{ControlChangeTracker, ControlDelta, RadarState};
 use crate::trails::{TrailData, TrailSettings, TrailStore};
 use crate::Brand;
 
@@ -102,10 +113,11 @@ impl RadarController {
         match self {
             RadarController::Furuno(c) => c.set_range(io, range_meters),
             // Navico uses decimeters
-            RadarController::Navico(c) => c.set_range(io, (range_meters * 10) as i32),
-            // Raymarine uses range index - caller should convert meters to index
-            // TODO: Need range table lookup for proper conversion
-            RadarController::Raymarine(_) => {}
+            RadarController::Navico(c) => {
+                c.set_range(io, crate::protocol::navico::range_meters_to_decimeters(range_meters))
+            }
+            // Raymarine uses an index into the model's range table
+            RadarController::Raymarine(c) => c.set_range_meters(io, range_meters),
             RadarController::Garmin(c) => c.set_range(io, range_meters),
         }
     }
@@ -156,13 +168,91 @@ impl RadarController {
     /// Set interference rejection (level 0-3 or boolean)
     pub fn set_interference_rejection<I: IoProvider>(&mut self, io: &mut I, level: u8) {
         match self {
-            RadarController::Furuno(c) => c.set_interference_rejection(io, level > 0),
+            RadarController::Furuno(c) => c.set_interference_rejection_level(io, level as i32),
             RadarController::Navico(c) => c.set_interference_rejection(io, level),
             RadarController::Raymarine(c) => c.set_interference_rejection(io, level),
             // Garmin doesn't have this control
             RadarController::Garmin(_) => {}
         }
     }
+
+    /// Enable or disable capturing the last raw report per report type
+    pub fn set_report_capture(&mut self, enabled: bool) {
+        match self {
+            RadarController::Furuno(c) => c.set_report_capture(enabled),
+            RadarController::Navico(c) => c.set_report_capture(enabled),
+            RadarController::Raymarine(c) => c.set_report_capture(enabled),
+            RadarController::Garmin(c) => c.set_report_capture(enabled),
+        }
+    }
+
+    /// Enable or disable passive (listen-only) mode
+    pub fn set_passive(&mut self, passive: bool) {
+        match self {
+            RadarController::Furuno(c) => c.set_passive(passive),
+            RadarController::Navico(c) => c.set_passive(passive),
+            RadarController::Raymarine(c) => c.set_passive(passive),
+            RadarController::Garmin(c) => c.set_passive(passive),
+        }
+    }
+
+    /// Retarget the controller at the addresses of a fresh discovery of
+    /// the same radar (e.g. after its DHCP lease changed the IP).
+    ///
+    /// Returns true if the controller's addresses changed.
+    pub fn set_address<I: IoProvider>(&mut self, io: &mut I, discovery: &RadarDiscovery) -> bool {
+        fn host_port(addr: &str) -> Option<(&str, u16)> {
+            let (host, port) = addr.rsplit_once(':')?;
+            Some((ParsedAddress::host(host), port.parse().ok()?))
+        }
+        let host = ParsedAddress::host(&discovery.address);
+        let send = discovery.send_address.as_deref().and_then(host_port);
+        let report = discovery.report_address.as_deref().and_then(host_port);
+
+        match self {
+            RadarController::Furuno(c) => c.set_radar_addr(io, host),
+            RadarController::Garmin(c) => c.set_radar_addr(io, host),
+            RadarController::Navico(c) => match (send, report) {
+                (Some((send, send_port)), Some((report, report_port))) => {
+                    c.set_addresses(io, send, send_port, report, report_port)
+                }
+                _ => false,
+            },
+            RadarController::Raymarine(c) => match (send, report) {
+                (Some((send, send_port)), Some((report, report_port))) => {
+                    c.set_addresses(io, send, send_port, report, report_port)
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Get the last raw report of a type as hex (see the brand controllers for report IDs)
+    pub fn last_report_hex(&self, report_id: &str) -> Option<String> {
+        match self {
+            RadarController::Furuno(c) => c.last_report_hex(report_id),
+            RadarController::Navico(c) => c.last_report_hex(report_id),
+            RadarController::Raymarine(c) => c.last_report_hex(report_id),
+            RadarController::Garmin(c) => c.last_report_hex(report_id),
+        }
+    }
+
+    /// Set an extended control by its semantic ID
+    ///
+    /// Only Furuno and Navico dispatch extended controls by ID so far; other
+    /// brands report the control as not found.
+    pub fn set_extended_control<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        control_id: &str,
+        value: i32,
+    ) -> Result<(), ControlError> {
+        match self {
+            RadarController::Furuno(c) => c.set_extended_control(io, control_id, value),
+            RadarController::Navico(c) => c.set_extended_control(io, control_id, value),
+            _ => Err(ControlError::ControlNotFound(control_id.to_string())),
+        }
+    }
 }
 
 /// A managed radar instance with its controller and all feature processors.
@@ -173,14 +263,27 @@ pub struct ManagedRadar {
     pub controller: RadarController,
     /// ARPA target tracking processor
     pub arpa: ArpaProcessor,
-    /// Guard zone collision detection
-    pub guard_zones: GuardZoneProcessor,
     /// Target trail history
     pub trails: TrailStore,
     /// Dual-range controller (if supported by model)
     pub dual_range: Option<DualRangeController>,
     /// Model information (once detected)
     pub model_info: Option<ModelInfo>,
+    /// Last emitted control values, for per-control deltas
+    pub control_changes: ControlChangeTracker,
+    /// Last emitted ARPA targets, for per-target deltas
+    pub target_changes: TargetChangeTracker,
+    /// Last revolution of spokes, for sweep snapshots
+    pub sweep: Option<HistoryBuffer>,
+    /// Radar legend, used to color sweep snapshots
+    pub legend: Vec<LegendEntry>,
+    /// Limit on control writes, against command storms from clients
+    pub rate_limiter: ControlRateLimiter,
+    /// Controls exposed to clients (empty = all), e.g. to hide advanced
+    /// controls from guests
+    pub control_allowlist: Vec<String>,
+    /// Recent lifecycle events, for troubleshooting
+    pub events: EventLog,
 }
 
 impl ManagedRadar {
@@ -190,15 +293,24 @@ impl ManagedRadar {
             id,
             controller,
             arpa: ArpaProcessor::new(ArpaSettings::default()),
-            guard_zones: GuardZoneProcessor::new(),
             trails: TrailStore::new(TrailSettings::default()),
             dual_range: None,
             model_info: None,
+            control_changes: ControlChangeTracker::new(),
+            target_changes: TargetChangeTracker::new(),
+            sweep: None,
+            legend: Vec::new(),
+            rate_limiter: ControlRateLimiter::default(),
+            control_allowlist: Vec::new(),
+            events: EventLog::default(),
         }
     }
 
     /// Set the model info and initialize dual-range if supported
     pub fn set_model_info(&mut self, model_info: ModelInfo) {
+        if let RadarController::Raymarine(c) = &mut self.controller {
+            c.set_range_table(model_info.range_table);
+        }
         if model_info.has_dual_range {
             self.dual_range = Some(DualRangeController::new(
                 model_info.max_dual_range,
@@ -207,6 +319,78 @@ impl ManagedRadar {
         }
         self.model_info = Some(model_info);
     }
+
+    /// Is `control_id` exposed to clients by the control allowlist?
+    pub fn allows_control(&self, control_id: &str) -> bool {
+        is_control_allowed(&self.control_allowlist, control_id)
+    }
+
+    /// Map a 0-100 control value between the UI and the wire
+    ///
+    /// See [`ModelInfo::map_inverted`]; unchanged until the model is known.
+    pub fn map_inverted(&self, control_id: &str, value: i32) -> i32 {
+        match &self.model_info {
+            Some(model) => model.map_inverted(control_id, value),
+            None => value,
+        }
+    }
+
+    /// Current control values as clients see them, with inverted controls
+    /// mapped back to UI values. `None` if the controller has no `RadarState`.
+    fn controls_map(&self) -> Option<HashMap<String, serde_json::Value>> {
+        let mut controls = self.controller.radar_state()?.to_controls_map();
+        for (id, control) in controls.iter_mut() {
+            if let Some(value) = control.get("value").and_then(|v| v.as_i64()) {
+                control["value"] = serde_json::json!(self.map_inverted(id, value as i32));
+            }
+        }
+        Some(controls)
+    }
+
+    /// Optional API features the engine provides for this radar
+    fn supported_features(model_info: &ModelInfo) -> Vec<SupportedFeature> {
+        let mut features = vec![
+            SupportedFeature::Arpa,
+            SupportedFeature::GuardZones,
+            SupportedFeature::Trails,
+        ];
+        if model_info.has_dual_range {
+            features.push(SupportedFeature::DualRange);
+        }
+        features
+    }
+}
+
+/// Full state of one radar, sent to a client as soon as it subscribes
+///
+/// Serialized with `"type": "radarSnapshot"` so clients can tell it from
+/// control values on the same stream.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename = "radarSnapshot", rename_all = "camelCase")]
+pub struct RadarSnapshot {
+    /// The radar ID
+    pub radar_id: String,
+    /// Capability summary, once the model is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<CapabilitySummary>,
+    /// Every current control value, keyed by control ID
+    pub controls: BTreeMap<String, serde_json::Value>,
+}
+
+/// A radar that was discovered but reported a model missing from the
+/// models database, so it only runs with the generic profile.
+///
+/// Surfaced to hosts so users can see why a radar on the network only
+/// offers basic controls.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsupportedRadar {
+    /// The radar ID it was discovered under
+    pub id: String,
+    /// Radar brand
+    pub brand: Brand,
+    /// Raw model string as reported by the radar
+    pub model: String,
 }
 
 /// Central engine managing all radars and their features.
@@ -216,6 +400,8 @@ impl ManagedRadar {
 pub struct RadarEngine {
     /// Managed radars keyed by radar ID
     radars: HashMap<String, ManagedRadar>,
+    /// Discovered radars whose model isn't supported, keyed by radar ID
+    unsupported: HashMap<String, UnsupportedRadar>,
 }
 
 impl Default for RadarEngine {
@@ -229,6 +415,7 @@ impl RadarEngine {
     pub fn new() -> Self {
         Self {
             radars: HashMap::new(),
+            unsupported: HashMap::new(),
         }
     }
 
@@ -307,9 +494,14 @@ impl RadarEngine {
         self.radars.get_mut(id)
     }
 
-    /// Get all radar IDs
-    pub fn radar_ids(&self) -> Vec<&str> {
-        self.radars.keys().map(|s| s.as_str()).collect()
+    /// Get all radar IDs, sorted
+    ///
+    /// The IDs are owned so callers across an FFI or plugin boundary can
+    /// hand them out without leaking `'static` copies.
+    pub fn radar_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.radars.keys().cloned().collect();
+        ids.sort();
+        ids
     }
 
     /// Check if a radar exists
@@ -339,6 +531,15 @@ impl RadarEngine {
             .unwrap_or_default()
     }
 
+    /// Get the ARPA targets for a radar that pass `filter`
+    ///
+    /// Filtered targets are still tracked, they are only left out here.
+    pub fn get_targets_filtered(&self, radar_id: &str, filter: &TargetFilter) -> Vec<ArpaTarget> {
+        let mut targets = self.get_targets(radar_id);
+        targets.retain(|t| filter.matches(t));
+        targets
+    }
+
     /// Acquire a new ARPA target at the given position
     pub fn acquire_target(
         &mut self,
@@ -352,6 +553,65 @@ impl RadarEngine {
             .and_then(|r| r.arpa.acquire_target(bearing, distance, timestamp_ms))
     }
 
+    /// Acquire a new ARPA target under a caller-chosen ID
+    ///
+    /// IDs from [`RESERVED_TARGET_ID_START`] belong to automatically
+    /// acquired targets and are rejected, as are 0 and IDs already in use.
+    pub fn acquire_target_with_id(
+        &mut self,
+        radar_id: &str,
+        target_id: u32,
+        bearing: f64,
+        distance: f64,
+        timestamp_ms: u64,
+    ) -> Result<u32, ControlError> {
+        let radar = self
+            .radars
+            .get_mut(radar_id)
+            .ok_or(ControlError::RadarNotFound)?;
+        if target_id == 0 || target_id >= RESERVED_TARGET_ID_START {
+            return Err(ControlError::InvalidValue(format!(
+                "target id {} is reserved, use 1-{}",
+                target_id,
+                RESERVED_TARGET_ID_START - 1
+            )));
+        }
+        if radar.arpa.get_target(target_id).is_some() {
+            return Err(ControlError::InvalidValue(format!(
+                "target id {} is already in use",
+                target_id
+            )));
+        }
+        radar
+            .arpa
+            .acquire_target_with_id(target_id, bearing, distance, timestamp_ms)
+            .ok_or_else(|| ControlError::InvalidValue("max targets reached".to_string()))
+    }
+
+    /// Acquire a new ARPA target at a chart position
+    ///
+    /// Converts the position to a bearing and distance from own ship, so
+    /// needs own ship's position (see [`update_own_ship`](Self::update_own_ship)).
+    pub fn acquire_target_at_position(
+        &mut self,
+        radar_id: &str,
+        latitude: f64,
+        longitude: f64,
+        timestamp_ms: u64,
+    ) -> Option<u32> {
+        let own_ship = *self.radars.get(radar_id)?.arpa.own_ship()?;
+        let (bearing, distance) =
+            bearing_distance_to(own_ship.latitude, own_ship.longitude, latitude, longitude);
+        self.acquire_target(radar_id, bearing, distance, timestamp_ms)
+    }
+
+    /// Update own ship position and motion, for all radars
+    pub fn update_own_ship(&mut self, own_ship: OwnShip) {
+        for radar in self.radars.values_mut() {
+            radar.arpa.update_own_ship(own_ship);
+        }
+    }
+
     /// Cancel tracking of a target
     pub fn cancel_target(&mut self, radar_id: &str, target_id: u32) -> bool {
         self.radars
@@ -372,40 +632,6 @@ impl RadarEngine {
         }
     }
 
-    // =========================================================================
-    // Guard Zones
-    // =========================================================================
-
-    /// Get all guard zones for a radar
-    pub fn get_guard_zones(&self, radar_id: &str) -> Vec<GuardZoneStatus> {
-        self.radars
-            .get(radar_id)
-            .map(|r| r.guard_zones.get_all_zone_status())
-            .unwrap_or_default()
-    }
-
-    /// Get a specific guard zone
-    pub fn get_guard_zone(&self, radar_id: &str, zone_id: u32) -> Option<GuardZoneStatus> {
-        self.radars
-            .get(radar_id)
-            .and_then(|r| r.guard_zones.get_zone_status(zone_id))
-    }
-
-    /// Add or update a guard zone
-    pub fn set_guard_zone(&mut self, radar_id: &str, zone: GuardZone) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
-            radar.guard_zones.add_zone(zone);
-        }
-    }
-
-    /// Remove a guard zone
-    pub fn remove_guard_zone(&mut self, radar_id: &str, zone_id: u32) -> bool {
-        self.radars
-            .get_mut(radar_id)
-            .map(|r| r.guard_zones.remove_zone(zone_id))
-            .unwrap_or(false)
-    }
-
     // =========================================================================
     // Trails
     // =========================================================================
@@ -473,19 +699,43 @@ impl RadarEngine {
     }
 
     /// Apply dual-range configuration
-    pub fn set_dual_range(&mut self, radar_id: &str, config: &DualRangeConfig) -> bool {
-        self.radars
-            .get_mut(radar_id)
-            .and_then(|r| r.dual_range.as_mut())
-            .map(|dr| dr.apply_config(config))
-            .unwrap_or(false)
+    ///
+    /// A rejected secondary range is reported as `InvalidValue`; the ranges
+    /// the radar accepts are in [`get_dual_range_available_ranges`](Self::get_dual_range_available_ranges).
+    pub fn set_dual_range(
+        &mut self,
+        radar_id: &str,
+        config: &DualRangeConfig,
+    ) -> Result<(), ControlError> {
+        let radar = self.radars.get_mut(radar_id).ok_or(ControlError::RadarNotFound)?;
+        let dual_range = radar
+            .dual_range
+            .as_mut()
+            .ok_or_else(|| ControlError::ControlNotFound("dualRange".to_string()))?;
+        dual_range
+            .apply_config(config)
+            .map_err(|e| ControlError::InvalidValue(e.to_string()))
+    }
+
+    /// Validate secondary ranges against the ranges the radar reported
+    /// instead of the model's range table
+    pub fn set_dual_range_ranges(&mut self, radar_id: &str, ranges: &[u32]) {
+        if let Some(dual_range) = self.radars.get_mut(radar_id).and_then(|r| r.dual_range.as_mut()) {
+            dual_range.set_ranges(ranges);
+        }
     }
 
     /// Get available secondary ranges for dual-range
     pub fn get_dual_range_available_ranges(&self, radar_id: &str) -> Vec<u32> {
-        self.radars
-            .get(radar_id)
-            .and_then(|r| r.model_info.as_ref())
+        let Some(radar) = self.radars.get(radar_id) else {
+            return Vec::new();
+        };
+        if let Some(dual_range) = &radar.dual_range {
+            return dual_range.available_ranges().to_vec();
+        }
+        radar
+            .model_info
+            .as_ref()
             .map(|m| {
                 m.range_table
                     .iter()
@@ -500,37 +750,110 @@ impl RadarEngine {
     // Radar Controls (delegating to RadarController)
     // =========================================================================
 
+    /// Set the maximum control writes per second for a radar (0 = unlimited)
+    pub fn set_control_rate_limit(&mut self, radar_id: &str, max_writes: u32) {
+        if let Some(radar) = self.radars.get_mut(radar_id) {
+            radar.rate_limiter.set_max_writes(max_writes);
+        }
+    }
+
+    /// Restrict the controls clients may see and write (empty = all)
+    pub fn set_control_allowlist(&mut self, radar_id: &str, allowlist: Vec<String>) {
+        if let Some(radar) = self.radars.get_mut(radar_id) {
+            radar.control_allowlist = allowlist;
+        }
+    }
+
+    /// Look up a radar for a write to `control_id`, checking the radar's
+    /// control allowlist and counting the write against its rate limit.
+    /// Power is never rate limited, so standby gets through a storm.
+    ///
+    /// Setters without a result drop rejected writes, just as they
+    /// ignore unknown radars.
+    fn radar_for_write<I: IoProvider>(
+        &mut self,
+        io: &I,
+        radar_id: &str,
+        control_id: &str,
+    ) -> Result<&mut ManagedRadar, ControlError> {
+        let radar = self.radars.get_mut(radar_id).ok_or(ControlError::RadarNotFound)?;
+        if !radar.allows_control(control_id) {
+            return Err(ControlError::ControlNotFound(control_id.to_string()));
+        }
+        if control_id != "power" {
+            if let Err(e) = radar.rate_limiter.check(io.current_time_ms()) {
+                // Log once per window, not once per rejected write
+                if radar.rate_limiter.rejected_in_window() == 1 {
+                    io.info(&format!("[{}] Control writes rate limited", radar_id));
+                    radar.events.record(
+                        io.current_time_ms(),
+                        RadarEventKind::Error {
+                            message: "control writes rate limited".to_string(),
+                        },
+                    );
+                }
+                return Err(e);
+            }
+        }
+        radar
+            .control_changes
+            .note_client_write(control_id, io.current_time_ms());
+        Ok(radar)
+    }
+
     /// Set power/transmit state for a radar
     pub fn set_power<I: IoProvider>(&mut self, io: &mut I, radar_id: &str, transmit: bool) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
+        if let Ok(radar) = self.radar_for_write(io, radar_id, "power") {
             radar.controller.set_power(io, transmit);
+            let kind = if transmit {
+                RadarEventKind::Transmit
+            } else {
+                RadarEventKind::Standby
+            };
+            radar.events.record(io.current_time_ms(), kind);
         }
     }
 
     /// Set range for a radar (in meters)
-    pub fn set_range<I: IoProvider>(&mut self, io: &mut I, radar_id: &str, range_meters: u32) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
-            radar.controller.set_range(io, range_meters);
-        }
+    ///
+    /// Once the model is known the request is snapped to its range table
+    /// ([`ModelInfo::snap_range`]), so every brand ends up on the same
+    /// supported range. Returns the range sent, or None if the write was
+    /// rejected.
+    pub fn set_range<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        radar_id: &str,
+        range_meters: u32,
+    ) -> Option<u32> {
+        let radar = self.radar_for_write(io, radar_id, "range").ok()?;
+        let range_meters = match &radar.model_info {
+            Some(model) => model.snap_range(range_meters),
+            None => range_meters,
+        };
+        radar.controller.set_range(io, range_meters);
+        Some(range_meters)
     }
 
     /// Set gain for a radar (0-100)
     pub fn set_gain<I: IoProvider>(&mut self, io: &mut I, radar_id: &str, value: i32, auto: bool) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
+        if let Ok(radar) = self.radar_for_write(io, radar_id, "gain") {
+            let value = radar.map_inverted("gain", value);
             radar.controller.set_gain(io, value, auto);
         }
     }
 
     /// Set sea clutter for a radar (0-100)
     pub fn set_sea<I: IoProvider>(&mut self, io: &mut I, radar_id: &str, value: i32, auto: bool) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
+        if let Ok(radar) = self.radar_for_write(io, radar_id, "sea") {
+            let value = radar.map_inverted("sea", value);
             radar.controller.set_sea(io, value, auto);
         }
     }
 
     /// Set rain clutter for a radar (0-100)
     pub fn set_rain<I: IoProvider>(&mut self, io: &mut I, radar_id: &str, value: i32, auto: bool) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
+        if let Ok(radar) = self.radar_for_write(io, radar_id, "rain") {
             radar.controller.set_rain(io, value, auto);
         }
     }
@@ -542,7 +865,7 @@ impl RadarEngine {
         radar_id: &str,
         degrees: f64,
     ) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
+        if let Ok(radar) = self.radar_for_write(io, radar_id, "bearingAlignment") {
             radar.controller.set_bearing_alignment(io, degrees);
         }
     }
@@ -554,25 +877,301 @@ impl RadarEngine {
         radar_id: &str,
         level: u8,
     ) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
+        if let Ok(radar) = self.radar_for_write(io, radar_id, "interferenceRejection") {
             radar.controller.set_interference_rejection(io, level);
         }
     }
 
+    /// Set an extended control (e.g. "targetBoost") for a radar
+    pub fn set_extended_control<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        radar_id: &str,
+        control_id: &str,
+        value: i32,
+    ) -> Result<(), ControlError> {
+        let radar = self.radar_for_write(io, radar_id, control_id)?;
+        radar.controller.set_extended_control(io, control_id, value)
+    }
+
+    /// Set the `timedTransmit` compound control (periods in minutes)
+    ///
+    /// Only Garmin radars support timed transmit. A period of 0 turns it off.
+    pub fn set_timed_transmit<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        radar_id: &str,
+        transmit_period: u32,
+        standby_period: u32,
+    ) -> Result<(), ControlError> {
+        match &mut self.radar_for_write(io, radar_id, "timedTransmit")?.controller {
+            RadarController::Garmin(c) => {
+                c.set_timed_transmit(io, transmit_period, standby_period);
+                Ok(())
+            }
+            _ => Err(ControlError::ControlNotFound("timedTransmit".to_string())),
+        }
+    }
+
+    /// Set the scan speed in RPM (0 = Auto)
+    ///
+    /// The speed must be one of the model's `scanSpeed` options; it is
+    /// mapped to the brand's wire value before sending.
+    pub fn set_scan_speed<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        radar_id: &str,
+        rpm: u16,
+    ) -> Result<(), ControlError> {
+        let radar = self.radar_for_write(io, radar_id, "scanSpeed")?;
+        let options = radar
+            .model_info
+            .as_ref()
+            .map(|m| m.scan_speed_options())
+            .unwrap_or_default();
+        let Some(wire) = options.iter().find(|o| o.rpm == rpm).map(|o| o.wire) else {
+            if options.is_empty() {
+                return Err(ControlError::ControlNotFound("scanSpeed".to_string()));
+            }
+            return Err(ControlError::InvalidValue(format!(
+                "scanSpeed {} RPM not supported",
+                rpm
+            )));
+        };
+        match &mut radar.controller {
+            RadarController::Furuno(c) => c.set_scan_speed(io, wire as i32),
+            RadarController::Navico(c) => c.set_scan_speed(io, wire),
+            _ => return Err(ControlError::ControlNotFound("scanSpeed".to_string())),
+        }
+        Ok(())
+    }
+
+    /// Enable or disable raw report capture for a radar (debugging)
+    pub fn set_report_capture(&mut self, radar_id: &str, enabled: bool) {
+        if let Some(radar) = self.radars.get_mut(radar_id) {
+            radar.controller.set_report_capture(enabled);
+        }
+    }
+
+    /// Enable or disable passive mode for a radar
+    ///
+    /// A passive radar is only listened to: reports and spokes are still
+    /// processed, but no report requests or commands are sent.
+    pub fn set_passive(&mut self, radar_id: &str, passive: bool) {
+        if let Some(radar) = self.radars.get_mut(radar_id) {
+            radar.controller.set_passive(passive);
+        }
+    }
+
+    /// Follow a radar to a new address reported by the locator
+    /// ([`LocatorEvent::RadarAddressChanged`](crate::LocatorEvent::RadarAddressChanged)).
+    ///
+    /// The radar keeps its ID, settings and ARPA state.
+    /// Returns true if the controller was retargeted.
+    pub fn update_address<I: IoProvider>(
+        &mut self,
+        io: &mut I,
+        radar_id: &str,
+        discovery: &RadarDiscovery,
+    ) -> bool {
+        let Some(radar) = self.radars.get_mut(radar_id) else {
+            return false;
+        };
+        if !radar.controller.set_address(io, discovery) {
+            return false;
+        }
+        radar.events.record(
+            io.current_time_ms(),
+            RadarEventKind::AddressChanged {
+                address: discovery.address.clone(),
+            },
+        );
+        true
+    }
+
+    // =========================================================================
+    // Event Log
+    // =========================================================================
+
+    /// Add an event to a radar's log, e.g. [`RadarEventKind::Discovered`]
+    /// from the locator, which the engine doesn't see itself
+    pub fn record_event(&mut self, radar_id: &str, timestamp_ms: u64, kind: RadarEventKind) {
+        if let Some(radar) = self.radars.get_mut(radar_id) {
+            radar.events.record(timestamp_ms, kind);
+        }
+    }
+
+    /// Recent events for a radar, oldest first
+    pub fn get_radar_events(&self, radar_id: &str) -> Vec<RadarEvent> {
+        self.radars
+            .get(radar_id)
+            .map(|r| r.events.events().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the last raw report of a type received from a radar, as hex
+    ///
+    /// Returns `None` unless report capture was enabled with
+    /// [`set_report_capture`](Self::set_report_capture) before the report arrived.
+    pub fn get_last_report_hex(&self, radar_id: &str, report_id: &str) -> Option<String> {
+        self.radars
+            .get(radar_id)
+            .and_then(|r| r.controller.last_report_hex(report_id))
+    }
+
+    /// Get the controls that changed since the last call for a radar
+    ///
+    /// Emit one delta per entry (see [`ControlDelta::path`]) instead of the
+    /// full state. The first call after adding a radar returns all controls.
+    /// Returns an empty list for radars that don't expose `RadarState`.
+    ///
+    /// Each delta says whether a client write or the radar itself caused it
+    /// (see [`ControlDelta::last_change_source`]).
+    pub fn take_control_changes<I: IoProvider>(
+        &mut self,
+        io: &I,
+        radar_id: &str,
+    ) -> Vec<ControlDelta> {
+        let Some(radar) = self.radars.get_mut(radar_id) else {
+            return Vec::new();
+        };
+        let Some(controls) = radar.controls_map() else {
+            return Vec::new();
+        };
+        radar
+            .control_changes
+            .changes(&controls, io.current_time_ms())
+    }
+
+    /// Full state of every radar, for a client that just subscribed
+    ///
+    /// Deltas from [`take_control_changes`](Self::take_control_changes) only
+    /// carry what changed, so a client connecting mid-session would not
+    /// learn the rest until it changes. Send it this first. The change
+    /// trackers are left alone, so other clients get no duplicate deltas.
+    /// Snapshots are sorted by radar ID.
+    pub fn on_client_subscribed(&self) -> Vec<RadarSnapshot> {
+        let mut snapshots: Vec<RadarSnapshot> = self
+            .radars
+            .values()
+            .map(|radar| {
+                let capabilities = radar.model_info.as_ref().map(|model| {
+                    let mut summary =
+                        build_capability_summary(model, ManagedRadar::supported_features(model));
+                    summary.control_ids.retain(|id| radar.allows_control(id));
+                    summary
+                });
+                RadarSnapshot {
+                    radar_id: radar.id.clone(),
+                    capabilities,
+                    controls: radar
+                        .controls_map()
+                        .map(|controls| {
+                            controls
+                                .into_iter()
+                                .filter(|(id, _)| radar.allows_control(id))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+        snapshots.sort_by(|a, b| a.radar_id.cmp(&b.radar_id));
+        snapshots
+    }
+
+    /// Get the ARPA targets that changed since the last call
+    ///
+    /// Emit one delta per entry at [`TargetDelta::path`]; targets that are
+    /// no longer tracked come back once with a null value.
+    pub fn take_target_changes(&mut self, radar_id: &str) -> Vec<TargetDelta> {
+        let Some(radar) = self.radars.get_mut(radar_id) else {
+            return Vec::new();
+        };
+        let targets = radar.arpa.get_targets();
+        radar.target_changes.changes(&targets)
+    }
+
+    /// Set the legend used to color sweep snapshots
+    pub fn set_legend(&mut self, radar_id: &str, legend: Vec<LegendEntry>) {
+        if let Some(radar) = self.radars.get_mut(radar_id) {
+            radar.legend = legend;
+        }
+    }
+
+    /// Record a spoke for sweep snapshots (see [`render_sweep`](Self::render_sweep))
+    ///
+    /// `angle` is in spokes, `0..spokes_per_revolution` of the radar model.
+    pub fn record_spoke(&mut self, radar_id: &str, angle: usize, data: &[u8], time: u64) {
+        if let Some(radar) = self.radars.get_mut(radar_id) {
+            let spokes = radar
+                .model_info
+                .as_ref()
+                .map(|m| m.spokes_per_revolution as usize)
+                .unwrap_or(2048);
+            radar
+                .sweep
+                .get_or_insert_with(|| HistoryBuffer::new(spokes))
+                .update_spoke(angle, data, time, 0.0, 0.0, &Legend::default());
+        }
+    }
+
+    /// Render the last revolution of a radar as an RGBA image
+    ///
+    /// The image is `2 * spoke_len` pixels square, one pixel per spoke
+    /// sample, with angle 0 up. Hosts encode it (e.g. to PNG) for
+    /// diagnostics. Empty if no spokes were recorded.
+    pub fn render_sweep(&self, radar_id: &str) -> Vec<u8> {
+        let Some(radar) = self.radars.get(radar_id) else {
+            return Vec::new();
+        };
+        match &radar.sweep {
+            Some(sweep) => sweep.render_rgba(
+                &Legend::default(),
+                &legend_colors(&radar.legend),
+                sweep.spoke_len() * 2,
+            ),
+            None => Vec::new(),
+        }
+    }
+
     /// Get model info for a radar
     pub fn get_model_info(&self, radar_id: &str) -> Option<&ModelInfo> {
         self.radars.get(radar_id).and_then(|r| r.model_info.as_ref())
     }
 
     /// Set model info for a radar (after detection)
+    ///
+    /// A model missing from the models database falls back to the generic
+    /// profile ([`models::generic_model`]), so basic control keeps working,
+    /// and the radar is listed in [`Self::get_unsupported_radars`].
     pub fn set_model_info(&mut self, radar_id: &str, model_name: &str) {
-        if let Some(radar) = self.radars.get_mut(radar_id) {
-            let brand = radar.controller.brand();
-            if let Some(model_info) = models::get_model(brand, model_name) {
-                radar.set_model_info(model_info.clone());
+        let Some(radar) = self.radars.get_mut(radar_id) else {
+            return;
+        };
+        let brand = radar.controller.brand();
+        match models::get_model(brand, model_name) {
+            Some(model_info) => radar.set_model_info(model_info.clone()),
+            None => {
+                radar.set_model_info(models::generic_model(brand));
+                self.unsupported.insert(
+                    radar_id.to_string(),
+                    UnsupportedRadar {
+                        id: radar_id.to_string(),
+                        brand,
+                        model: model_name.to_string(),
+                    },
+                );
             }
         }
     }
+
+    /// Discovered radars whose model isn't supported, sorted by ID
+    pub fn get_unsupported_radars(&self) -> Vec<UnsupportedRadar> {
+        let mut radars: Vec<UnsupportedRadar> = self.unsupported.values().cloned().collect();
+        radars.sort_by(|a, b| a.id.cmp(&b.id));
+        radars
+    }
 }
 
 #[cfg(test)]
@@ -585,6 +1184,205 @@ mod tests {
         assert!(engine.radar_ids().is_empty());
     }
 
+    #[test]
+    fn test_radar_ids_are_owned() {
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("furuno", "172.31.3.212");
+        engine.add_garmin("garmin", "172.16.2.1");
+
+        let ids = engine.radar_ids();
+        // The IDs outlive changes to the engine
+        engine.remove_radar("furuno");
+        assert_eq!(ids, vec!["furuno".to_string(), "garmin".to_string()]);
+        assert_eq!(engine.radar_ids(), vec!["garmin".to_string()]);
+    }
+
+    #[test]
+    fn test_rebeacon_from_new_ip_retargets_controller() {
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("furuno", "172.31.3.212");
+
+        let discovery = RadarDiscovery::for_test(Brand::Furuno, "RD003212", "172.31.3.57");
+        assert!(engine.update_address(&mut io, "furuno", &discovery));
+        assert!(!engine.update_address(&mut io, "furuno", &discovery));
+
+        let radar = engine.get("furuno").unwrap();
+        match &radar.controller {
+            RadarController::Furuno(c) => assert_eq!(c.radar_addr(), "172.31.3.57"),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_unrecognized_model_listed_as_unsupported() {
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("known", "172.31.3.212");
+        engine.add_furuno("unknown", "172.31.3.213");
+        engine.set_model_info("known", "DRS4D-NXT");
+        engine.set_model_info("unknown", "DRS99X-FUTURE");
+
+        // Still controllable, with the generic profile
+        assert!(engine.contains("unknown"));
+        assert!(engine.get_model_info("unknown").unwrap().is_generic());
+        assert_eq!(
+            engine.get_unsupported_radars(),
+            vec![UnsupportedRadar {
+                id: "unknown".to_string(),
+                brand: Brand::Furuno,
+                model: "DRS99X-FUTURE".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_scan_speed_rpm_mapped_to_wire() {
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_navico("halo", "236.6.7.10", 6680, "236.6.7.9", 6679, "", NavicoModel::Halo);
+        engine.set_model_info("halo", "HALO24");
+        if let Some(RadarController::Navico(c)) = engine.get_mut("halo").map(|r| &mut r.controller) {
+            c.poll(&mut io);
+        }
+        io.udp_sent.clear();
+
+        assert!(engine.set_scan_speed(&mut io, "halo", 36).is_ok());
+        assert_eq!(io.udp_sent.last().unwrap().data, vec![0x0F, 0xC1, 2]);
+        assert!(matches!(
+            engine.set_scan_speed(&mut io, "halo", 24),
+            Err(ControlError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_control_storm_rate_limited() {
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("furuno", "172.31.3.212");
+        engine.set_control_rate_limit("furuno", 5);
+
+        for _ in 0..5 {
+            assert!(engine.set_extended_control(&mut io, "furuno", "birdMode", 1).is_ok());
+        }
+        assert!(matches!(
+            engine.set_extended_control(&mut io, "furuno", "birdMode", 1),
+            Err(ControlError::InvalidValue(msg)) if msg == "rate limited"
+        ));
+
+        // Standby must get through a control storm
+        engine.set_power(&mut io, "furuno", false);
+        assert!(engine.get_radar_events("furuno").iter().any(|e| e.kind == RadarEventKind::Standby));
+
+        io.time_ms += 1000;
+        assert!(engine.set_extended_control(&mut io, "furuno", "birdMode", 1).is_ok());
+    }
+
+    #[test]
+    fn test_control_allowlist_hides_and_blocks_control() {
+        use crate::capabilities::builder::build_capabilities_from_model;
+
+        let model = crate::models::get_model(Brand::Furuno, "DRS4D-NXT").unwrap();
+        let allowlist = vec!["power".to_string(), "range".to_string(), "gain".to_string()];
+        let mut manifest = build_capabilities_from_model(model, "furuno", vec![]);
+        assert!(manifest.controls.iter().any(|c| c.id == "birdMode"));
+        manifest.apply_control_allowlist(&allowlist);
+        let ids: Vec<&str> = manifest.controls.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["power", "range", "gain"]);
+
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("furuno", "172.31.3.212");
+        engine.set_control_allowlist("furuno", allowlist);
+        assert!(matches!(
+            engine.set_extended_control(&mut io, "furuno", "birdMode", 1),
+            Err(ControlError::ControlNotFound(id)) if id == "birdMode"
+        ));
+
+        // An empty allowlist exposes everything again
+        engine.set_control_allowlist("furuno", vec![]);
+        assert!(engine.set_extended_control(&mut io, "furuno", "birdMode", 1).is_ok());
+    }
+
+    #[test]
+    fn test_power_changes_logged_after_discovery() {
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("furuno", "172.31.3.212");
+        engine.record_event("furuno", io.time_ms, RadarEventKind::Discovered);
+
+        io.time_ms += 1000;
+        engine.set_power(&mut io, "furuno", true);
+        io.time_ms += 1000;
+        engine.set_power(&mut io, "furuno", false);
+
+        let events = engine.get_radar_events("furuno");
+        let kinds: Vec<_> = events.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![RadarEventKind::Discovered, RadarEventKind::Transmit, RadarEventKind::Standby]
+        );
+        assert!(events.windows(2).all(|w| w[0].timestamp_ms < w[1].timestamp_ms));
+        assert!(engine.get_radar_events("missing").is_empty());
+    }
+
+    #[test]
+    fn test_set_range_snaps_to_model_table() {
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_garmin("garmin", "172.16.2.1");
+        engine.set_model_info("garmin", "Fantom 24");
+        if let Some(RadarController::Garmin(c)) = engine.get_mut("garmin").map(|r| &mut r.controller) {
+            c.poll(&mut io);
+        }
+
+        // Garmin takes meters on the wire, so it used to pass 90 m through
+        assert_eq!(engine.set_range(&mut io, "garmin", 90), Some(100));
+        assert_eq!(
+            io.udp_sent.last().unwrap().data,
+            crate::protocol::garmin::create_range_command(100)
+        );
+        assert_eq!(engine.set_range(&mut io, "missing", 90), None);
+    }
+
+    #[test]
+    fn test_timed_transmit_gated_to_garmin() {
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_garmin("garmin", "172.16.2.1");
+        engine.add_furuno("furuno", "172.31.3.212");
+        if let Some(RadarController::Garmin(c)) = engine.get_mut("garmin").map(|r| &mut r.controller) {
+            c.poll(&mut io);
+        }
+
+        assert!(engine.set_timed_transmit(&mut io, "garmin", 5, 10).is_ok());
+        assert_eq!(io.udp_sent.len(), 1);
+        assert_eq!(io.udp_sent[0].data, crate::protocol::garmin::create_timed_transmit_command(true, 5, 10));
+        assert!(matches!(
+            engine.set_timed_transmit(&mut io, "furuno", 5, 10),
+            Err(ControlError::ControlNotFound(_))
+        ));
+        assert!(matches!(
+            engine.set_timed_transmit(&mut io, "missing", 5, 10),
+            Err(ControlError::RadarNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_render_sweep() {
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("test-radar", "192.168.1.1");
+        assert!(engine.render_sweep("test-radar").is_empty());
+
+        let mut data = vec![0u8; 64];
+        data[30] = 100;
+        for angle in 0..2048 {
+            engine.record_spoke("test-radar", angle, &data, 1000);
+        }
+        let image = engine.render_sweep("test-radar");
+        assert_eq!(image.len(), 128 * 128 * 4);
+        assert!(image.chunks(4).any(|p| p == [255, 255, 255, 255]));
+    }
+
     #[test]
     fn test_arpa_methods() {
         let mut engine = RadarEngine::new();
@@ -600,26 +1398,129 @@ mod tests {
     }
 
     #[test]
-    fn test_guard_zone_methods() {
+    fn test_acquire_target_at_position() {
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("test-radar", "192.168.1.1");
+
+        // Needs own ship's position
+        assert!(engine.acquire_target_at_position("test-radar", 52.0, 4.0, 1000).is_none());
+
+        engine.update_own_ship(OwnShip {
+            latitude: 52.0,
+            longitude: 4.0,
+            ..Default::default()
+        });
+        let id = engine
+            .acquire_target_at_position("test-radar", 52.0 + 1.0 / 60.0, 4.0, 1000)
+            .unwrap();
+
+        let target = engine.get_targets("test-radar").into_iter().find(|t| t.id == id).unwrap();
+        assert!(target.position.bearing.abs() < 1e-6);
+        assert!((target.position.distance - 1852.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_reserved_ids_rejected() {
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("test-radar", "192.168.1.1");
+
+        assert!(matches!(
+            engine.acquire_target_with_id("test-radar", RESERVED_TARGET_ID_START, 45.0, 1000.0, 0),
+            Err(ControlError::InvalidValue(_))
+        ));
+        assert_eq!(
+            engine
+                .acquire_target_with_id("test-radar", 7, 45.0, 1000.0, 0)
+                .unwrap(),
+            7
+        );
+        assert!(matches!(
+            engine.acquire_target_with_id("test-radar", 7, 90.0, 1000.0, 0),
+            Err(ControlError::InvalidValue(_))
+        ));
+        assert_eq!(engine.get_targets("test-radar").len(), 1);
+    }
+
+    #[test]
+    fn test_on_client_subscribed_emits_all_radars() {
+        let io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("furuno", "172.31.3.212");
+        engine.set_model_info("furuno", "DRS4D-NXT");
+        engine.add_furuno("unknown", "172.31.3.213");
+
+        // Long-running session: the deltas have been drained already
+        engine.take_control_changes(&io, "furuno");
+        engine.take_control_changes(&io, "unknown");
+
+        let snapshots = engine.on_client_subscribed();
+        let ids: Vec<&str> = snapshots.iter().map(|s| s.radar_id.as_str()).collect();
+        assert_eq!(ids, vec!["furuno", "unknown"]);
+        for snapshot in &snapshots {
+            assert!(snapshot.controls.contains_key("gain"));
+        }
+        let summary = snapshots[0].capabilities.as_ref().unwrap();
+        assert!(summary.supported_features.contains(&SupportedFeature::Arpa));
+        assert!(summary.control_ids.iter().any(|id| id == "gain"));
+        assert!(snapshots[1].capabilities.is_none());
+        let json = serde_json::to_value(&snapshots[0]).unwrap();
+        assert_eq!(json["type"], "radarSnapshot");
+        assert_eq!(json["radarId"], "furuno");
+
+        // Existing clients don't get the state again
+        assert!(engine.take_control_changes(&io, "furuno").is_empty());
+    }
+
+    #[test]
+    fn test_take_control_changes() {
+        let io = crate::io::mock::MockIo::new();
         let mut engine = RadarEngine::new();
         engine.add_furuno("test-radar", "192.168.1.1");
 
-        // Should return empty zones
-        let zones = engine.get_guard_zones("test-radar");
-        assert!(zones.is_empty());
+        // Initial call reports the full state, then nothing until a change
+        assert!(!engine.take_control_changes(&io, "test-radar").is_empty());
+        assert!(engine.take_control_changes(&io, "test-radar").is_empty());
+        assert!(engine.take_control_changes(&io, "nonexistent").is_empty());
+    }
 
-        // Add a zone using the constructor
-        let zone = GuardZone::new_arc(1, 0.0, 90.0, 100.0, 200.0);
-        engine.set_guard_zone("test-radar", zone);
+    #[test]
+    fn test_control_changes_tagged_by_source() {
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("test-radar", "192.168.1.1");
+        engine.take_control_changes(&io, "test-radar");
 
-        // Should now have one zone
-        let zones = engine.get_guard_zones("test-radar");
-        assert_eq!(zones.len(), 1);
+        engine.set_gain(&mut io, "test-radar", 70, false);
+        let changes = engine.take_control_changes(&io, "test-radar");
+        let gain = changes.iter().find(|d| d.control_id == "gain").unwrap();
+        assert_eq!(gain.last_change_source, crate::state::ChangeSource::Client);
+    }
 
-        // Remove the zone
-        assert!(engine.remove_guard_zone("test-radar", 1));
-        let zones = engine.get_guard_zones("test-radar");
-        assert!(zones.is_empty());
+    #[test]
+    fn test_inverted_model_complements_wire_value() {
+        let mut io = crate::io::mock::MockIo::new();
+        let mut engine = RadarEngine::new();
+        engine.add_furuno("legacy", "192.168.1.1");
+        engine.radars.get_mut("legacy").unwrap().set_model_info(ModelInfo {
+            inverted_controls: &["gain", "sea"],
+            ..models::generic_model(Brand::Furuno)
+        });
+
+        engine.set_gain(&mut io, "legacy", 80, false);
+        engine.set_sea(&mut io, "legacy", 30, false);
+        engine.set_rain(&mut io, "legacy", 10, false);
+
+        // The wire sees the complement, the UI reads back what it set
+        let state = engine.get("legacy").unwrap().controller.radar_state().unwrap();
+        assert_eq!(state.gain.value, 20);
+        assert_eq!(state.sea.value, 70);
+        assert_eq!(state.rain.value, 10);
+
+        let changes = engine.take_control_changes(&io, "legacy");
+        let value = |id: &str| changes.iter().find(|d| d.control_id == id).unwrap().value["value"].clone();
+        assert_eq!(value("gain"), 80);
+        assert_eq!(value("sea"), 30);
+        assert_eq!(value("rain"), 10);
     }
 
     #[test]
@@ -0,0 +1,104 @@
+//! Per-radar limit on control writes
+//!
+//! Protects the radar link from clients that send control changes in a
+//! tight loop. Writes beyond the limit within one window are rejected
+//! rather than queued, so the radar only sees a bounded command rate.
+
+use crate::capabilities::ControlError;
+
+/// Default maximum control writes per radar per second
+pub const DEFAULT_CONTROL_RATE_LIMIT: u32 = 20;
+
+/// Length of a rate limit window in milliseconds
+const WINDOW_MS: u64 = 1000;
+
+/// Fixed-window counter of control writes for one radar
+#[derive(Debug, Clone)]
+pub struct ControlRateLimiter {
+    max_writes: u32,
+    window_start: u64,
+    writes: u32,
+    window_rejected: u32,
+    rejected: u64,
+}
+
+impl Default for ControlRateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONTROL_RATE_LIMIT)
+    }
+}
+
+impl ControlRateLimiter {
+    /// Create a limiter allowing `max_writes` per second (0 = unlimited)
+    pub fn new(max_writes: u32) -> Self {
+        Self {
+            max_writes,
+            window_start: 0,
+            writes: 0,
+            window_rejected: 0,
+            rejected: 0,
+        }
+    }
+
+    /// Change the limit, keeping the current window
+    pub fn set_max_writes(&mut self, max_writes: u32) {
+        self.max_writes = max_writes;
+    }
+
+    /// Maximum writes per second (0 = unlimited)
+    pub fn max_writes(&self) -> u32 {
+        self.max_writes
+    }
+
+    /// Total writes rejected since creation
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+
+    /// Writes rejected in the current window
+    pub fn rejected_in_window(&self) -> u32 {
+        self.window_rejected
+    }
+
+    /// Count a write at `now` (ms), or reject it if the window is full
+    pub fn check(&mut self, now: u64) -> Result<(), ControlError> {
+        if self.max_writes == 0 {
+            return Ok(());
+        }
+        if now.saturating_sub(self.window_start) >= WINDOW_MS || now < self.window_start {
+            self.window_start = now;
+            self.writes = 0;
+            self.window_rejected = 0;
+        }
+        if self.writes >= self.max_writes {
+            self.window_rejected += 1;
+            self.rejected += 1;
+            return Err(ControlError::InvalidValue("rate limited".to_string()));
+        }
+        self.writes += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_beyond_limit_rejected_until_next_window() {
+        let mut limiter = ControlRateLimiter::new(3);
+        for t in 0..3 {
+            assert!(limiter.check(1000 + t).is_ok());
+        }
+        assert!(matches!(
+            limiter.check(1500),
+            Err(ControlError::InvalidValue(msg)) if msg == "rate limited"
+        ));
+        assert_eq!(limiter.rejected(), 1);
+
+        assert!(limiter.check(2000).is_ok());
+
+        let mut unlimited = ControlRateLimiter::new(0);
+        assert!((0..100).all(|_| unlimited.check(0).is_ok()));
+    }
+}
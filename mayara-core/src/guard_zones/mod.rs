@@ -7,13 +7,16 @@
 //! # Features
 //!
 //! - Arc-shaped guard zones (defined by bearing/distance range)
+//! - Zones relative to the bow or fixed to true north (`ZoneFrame`)
 //! - Multiple zones per radar
 //! - Configurable sensitivity and alert states
+//! - Optional spoke subsampling for low-power hosts
+//! - Doppler-only zones that ignore stationary returns such as land
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use mayara_core::guard_zones::{GuardZoneProcessor, GuardZone, ZoneShape};
+//! use mayara_core::guard_zones::{GuardZoneProcessor, GuardZone, ZoneFrame, ZoneShape};
 //!
 //! let mut processor = GuardZoneProcessor::new();
 //!
@@ -27,7 +30,10 @@
 //!         inner_radius: 500.0,
 //!         outer_radius: 1000.0,
 //!     },
+//!     frame: ZoneFrame::Relative,
 //!     sensitivity: 128,
+//!     doppler_only: false,
+//!     name: None,
 //! });
 //!
 //! // Check spoke for zone intrusions
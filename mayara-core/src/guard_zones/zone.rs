@@ -5,6 +5,8 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
+use crate::arpa::{DopplerState, Legend};
+
 /// Guard zone shape
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -69,6 +71,23 @@ impl ZoneShape {
     }
 }
 
+impl ZoneShape {
+    /// Inner and outer radius in meters
+    pub fn radii(&self) -> (f64, f64) {
+        match self {
+            ZoneShape::Arc {
+                inner_radius,
+                outer_radius,
+                ..
+            }
+            | ZoneShape::Ring {
+                inner_radius,
+                outer_radius,
+            } => (*inner_radius, *outer_radius),
+        }
+    }
+}
+
 /// Normalize bearing to 0-360 range
 fn normalize_bearing(bearing: f64) -> f64 {
     let mut b = bearing % 360.0;
@@ -78,6 +97,21 @@ fn normalize_bearing(bearing: f64) -> f64 {
     b
 }
 
+/// Reference frame for guard zone bearings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneFrame {
+    /// Bearings relative to the bow; the zone turns with the boat
+    #[default]
+    Relative,
+    /// Bearings relative to true north; the zone stays fixed as the boat turns
+    Ground,
+}
+
+/// First zone ID reserved for zones created internally, such as a
+/// proximity alarm. User zones must use lower IDs.
+pub const RESERVED_ZONE_ID_START: u32 = 1000;
+
 /// Guard zone definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -88,8 +122,15 @@ pub struct GuardZone {
     pub enabled: bool,
     /// Zone shape
     pub shape: ZoneShape,
+    /// Reference frame of the zone's bearings
+    #[serde(default)]
+    pub frame: ZoneFrame,
     /// Detection threshold (0-255)
     pub sensitivity: u8,
+    /// Only alarm on Doppler-flagged (approaching or receding) returns,
+    /// so stationary land or moorings inside the zone are ignored
+    #[serde(default)]
+    pub doppler_only: bool,
     /// Optional zone name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -113,7 +154,9 @@ impl GuardZone {
                 inner_radius,
                 outer_radius,
             },
+            frame: ZoneFrame::Relative,
             sensitivity: 128,
+            doppler_only: false,
             name: None,
         }
     }
@@ -127,7 +170,9 @@ impl GuardZone {
                 inner_radius,
                 outer_radius,
             },
+            frame: ZoneFrame::Relative,
             sensitivity: 128,
+            doppler_only: false,
             name: None,
         }
     }
@@ -174,6 +219,10 @@ struct ZoneState {
     last_alert: Option<u64>,
     /// Consecutive clear scans (for hysteresis)
     clear_count: u32,
+    /// Whether the previous spoke fell inside the zone's bearings
+    in_zone: bool,
+    /// In-zone spokes skipped since the last check (for subsampling)
+    skipped: u32,
 }
 
 /// Guard zone processor
@@ -187,6 +236,15 @@ pub struct GuardZoneProcessor {
     range_scale: f64,
     /// Number of clear scans required to clear alarm
     hysteresis_count: u32,
+    /// Own-ship true heading in degrees, for ground-referenced zones
+    heading: Option<f64>,
+    /// Check only every Nth spoke within a zone (1 = every spoke)
+    subsample: u32,
+    /// Distance in meters beyond which returns are ignored, on top of
+    /// the current range scale
+    max_range: Option<f64>,
+    /// Pixel values marking Doppler returns, for Doppler-only zones
+    legend: Legend,
 }
 
 impl GuardZoneProcessor {
@@ -197,14 +255,70 @@ impl GuardZoneProcessor {
             states: HashMap::new(),
             range_scale: 1852.0,
             hysteresis_count: 3,
+            heading: None,
+            subsample: 1,
+            max_range: None,
+            legend: Legend::default(),
         }
     }
 
+    /// Set the legend whose Doppler pixel values Doppler-only zones
+    /// react to
+    pub fn set_legend(&mut self, legend: Legend) {
+        self.legend = legend;
+    }
+
+    /// Check only every Nth spoke for intrusions, to save CPU on radars
+    /// with many spokes per revolution (0 and 1 check every spoke).
+    ///
+    /// The first spoke entering a zone's bearings is always checked, so
+    /// every zone is still checked at least once per revolution, however
+    /// narrow it is.
+    pub fn set_subsample(&mut self, every_nth: u32) {
+        self.subsample = every_nth.max(1);
+    }
+
+    /// Current spoke subsampling factor
+    pub fn subsample(&self) -> u32 {
+        self.subsample
+    }
+
+    /// Set own-ship true heading in degrees
+    ///
+    /// Ground-referenced zones are rotated by the heading before testing.
+    /// Without a heading they are tested as if the bow points north.
+    pub fn set_heading(&mut self, heading: Option<f64>) {
+        self.heading = heading;
+    }
+
     /// Set the current range scale
     pub fn set_range_scale(&mut self, range_meters: f64) {
         self.range_scale = range_meters;
     }
 
+    /// Set the distance in meters beyond which zones are not checked, or
+    /// None to only limit zones to the current range scale
+    ///
+    /// Far-field returns are mostly noise, so zones reaching past this
+    /// distance are clamped to it rather than raising false alarms.
+    pub fn set_max_range(&mut self, max_range: Option<f64>) {
+        self.max_range = max_range;
+    }
+
+    /// Configured maximum zone distance in meters
+    pub fn max_range(&self) -> Option<f64> {
+        self.max_range
+    }
+
+    /// Distance in meters up to which zones are checked: the range scale,
+    /// limited by the configured maximum
+    pub fn effective_range(&self) -> f64 {
+        match self.max_range {
+            Some(max_range) => max_range.min(self.range_scale),
+            None => self.range_scale,
+        }
+    }
+
     /// Add or update a guard zone
     pub fn add_zone(&mut self, zone: GuardZone) {
         let id = zone.id;
@@ -259,7 +373,7 @@ impl GuardZoneProcessor {
     /// # Arguments
     ///
     /// * `spoke_data` - Raw pixel data for the spoke
-    /// * `bearing` - Bearing of this spoke in degrees
+    /// * `bearing` - Bearing of this spoke in degrees, relative to the bow
     /// * `timestamp` - Current timestamp in milliseconds
     ///
     /// # Returns
@@ -272,6 +386,7 @@ impl GuardZoneProcessor {
         if samples == 0 {
             return alerts;
         }
+        let effective_range = self.effective_range();
 
         // Check each enabled zone
         for (&zone_id, zone) in &self.zones {
@@ -279,6 +394,13 @@ impl GuardZoneProcessor {
                 continue;
             }
 
+            // Ground zones are defined against north, so turn the spoke
+            // bearing into a true bearing first
+            let zone_bearing = match zone.frame {
+                ZoneFrame::Relative => bearing,
+                ZoneFrame::Ground => bearing + self.heading.unwrap_or(0.0),
+            };
+
             // Check if this bearing could intersect the zone
             let zone_matches_bearing = match &zone.shape {
                 ZoneShape::Arc {
@@ -286,7 +408,7 @@ impl GuardZoneProcessor {
                     end_bearing,
                     ..
                 } => {
-                    let bearing = normalize_bearing(bearing);
+                    let bearing = normalize_bearing(zone_bearing);
                     let start = normalize_bearing(*start_bearing);
                     let end = normalize_bearing(*end_bearing);
                     if start <= end {
@@ -298,42 +420,54 @@ impl GuardZoneProcessor {
                 ZoneShape::Ring { .. } => true,
             };
 
+            let state = self.states.entry(zone_id).or_default();
+            let entering = !state.in_zone;
+            state.in_zone = zone_matches_bearing;
             if !zone_matches_bearing {
                 continue;
             }
 
-            // Scan spoke for intrusions within zone distance range
-            let (inner, outer) = match &zone.shape {
-                ZoneShape::Arc {
-                    inner_radius,
-                    outer_radius,
-                    ..
-                } => (*inner_radius, *outer_radius),
-                ZoneShape::Ring {
-                    inner_radius,
-                    outer_radius,
-                } => (*inner_radius, *outer_radius),
-            };
+            // Subsample: skip this spoke unless it enters the zone or
+            // enough in-zone spokes were skipped already
+            if !entering && state.skipped + 1 < self.subsample {
+                state.skipped += 1;
+                continue;
+            }
+            state.skipped = 0;
+
+            // Scan spoke for intrusions within zone distance range,
+            // clamped to the effective range; a zone wholly beyond it
+            // scans no samples and so stays clear
+            let (inner, outer) = zone.shape.radii();
+            let outer = outer.min(effective_range);
 
             // Convert distance to sample indices
             let inner_idx = ((inner / self.range_scale) * samples as f64) as usize;
             let outer_idx = ((outer / self.range_scale) * samples as f64).min(samples as f64) as usize;
 
-            // Find peak intensity in the zone range
-            let mut peak_intensity: u8 = 0;
-            let mut peak_idx = 0;
+            // Find the peak intensity at or above the sensitivity. Pixels
+            // of Doppler-only zones are matched against the legend's Doppler
+            // entries instead: those are legend indices, not intensities,
+            // so the sensitivity does not apply and the first one counts.
+            let mut intrusion: Option<(usize, u8)> = None;
 
             for i in inner_idx..outer_idx.min(samples) {
-                if spoke_data[i] > peak_intensity {
-                    peak_intensity = spoke_data[i];
-                    peak_idx = i;
+                let pixel = spoke_data[i];
+                if zone.doppler_only {
+                    let approaching = pixel == self.legend.doppler_approaching;
+                    let receding = pixel == self.legend.doppler_receding;
+                    if DopplerState::AnyDoppler.matches_pixel(true, false, approaching, receding) {
+                        intrusion = Some((i, pixel));
+                        break;
+                    }
+                } else if pixel >= zone.sensitivity
+                    && !matches!(intrusion, Some((_, peak)) if peak >= pixel)
+                {
+                    intrusion = Some((i, pixel));
                 }
             }
 
-            // Check against threshold
-            let state = self.states.entry(zone_id).or_default();
-
-            if peak_intensity >= zone.sensitivity {
+            if let Some((peak_idx, peak_intensity)) = intrusion {
                 // Intrusion detected
                 let distance = (peak_idx as f64 / samples as f64) * self.range_scale;
 
@@ -566,6 +700,39 @@ mod tests {
         assert!(alerts.is_empty());
     }
 
+    #[test]
+    fn test_doppler_only_zone_ignores_static_returns() {
+        let mut processor = GuardZoneProcessor::new();
+        processor.set_range_scale(1852.0);
+
+        let mut zone = GuardZone::new_arc(1, 40.0, 50.0, 450.0, 950.0);
+        zone.doppler_only = true;
+        processor.add_zone(zone);
+
+        // Doppler entries right after 16 intensity levels, as on a HALO;
+        // far below the zone's sensitivity of 128
+        let legend = Legend {
+            doppler_approaching: 16,
+            doppler_receding: 17,
+            ..Legend::default()
+        };
+        processor.set_legend(legend);
+
+        // Strong static return, e.g. a shoreline, inside the zone
+        let mut spoke = vec![0u8; 512];
+        spoke[194] = 15;
+        let alerts = processor.check_spoke(&spoke, 45.0, 1000);
+        assert!(alerts.is_empty());
+        assert_eq!(processor.get_alert_state(1), ZoneAlertState::Clear);
+
+        // A receding vessel in the same zone does trigger
+        spoke[200] = legend.doppler_receding;
+        let alerts = processor.check_spoke(&spoke, 45.0, 2000);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].intensity, legend.doppler_receding);
+        assert_eq!(processor.get_alert_state(1), ZoneAlertState::Alarm);
+    }
+
     #[test]
     fn test_zone_below_threshold() {
         let mut processor = GuardZoneProcessor::new();
@@ -583,6 +750,119 @@ mod tests {
         assert_eq!(processor.get_alert_state(1), ZoneAlertState::Clear);
     }
 
+    #[test]
+    fn test_ground_zone_stays_fixed_to_north() {
+        let mut spoke = vec![0u8; 512];
+        spoke[194] = 200; // ~700m
+
+        // Zone covering 80-100 degrees true (east)
+        let mut ground = GuardZone::new_arc(1, 80.0, 100.0, 450.0, 950.0);
+        ground.frame = ZoneFrame::Ground;
+
+        for heading in [0.0, 45.0, 90.0, 270.0] {
+            let mut processor = GuardZoneProcessor::new();
+            processor.set_range_scale(1852.0);
+            processor.set_heading(Some(heading));
+            processor.add_zone(ground.clone());
+
+            // Target due east lies at (90 - heading) relative to the bow
+            let relative = normalize_bearing(90.0 - heading);
+            let alerts = processor.check_spoke(&spoke, relative, 1000);
+            assert_eq!(alerts.len(), 1, "heading {}", heading);
+
+            // Target dead ahead is only in the zone when heading east
+            let mut processor = GuardZoneProcessor::new();
+            processor.set_range_scale(1852.0);
+            processor.set_heading(Some(heading));
+            processor.add_zone(ground.clone());
+            let alerts = processor.check_spoke(&spoke, 0.0, 1000);
+            assert_eq!(
+                alerts.len(),
+                usize::from(heading == 90.0),
+                "heading {}",
+                heading
+            );
+        }
+    }
+
+    #[test]
+    fn test_relative_zone_turns_with_boat() {
+        let mut processor = GuardZoneProcessor::new();
+        processor.set_range_scale(1852.0);
+        processor.set_heading(Some(90.0));
+        processor.add_zone(GuardZone::new_arc(1, 350.0, 10.0, 450.0, 950.0));
+
+        let mut spoke = vec![0u8; 512];
+        spoke[194] = 200;
+        assert_eq!(processor.check_spoke(&spoke, 0.0, 1000).len(), 1);
+    }
+
+    #[test]
+    fn test_zone_frame_defaults_to_relative() {
+        let json = r#"{"id":1,"enabled":true,"shape":{"type":"ring","innerRadius":0,"outerRadius":100},"sensitivity":128}"#;
+        let zone: GuardZone = serde_json::from_str(json).unwrap();
+        assert_eq!(zone.frame, ZoneFrame::Relative);
+    }
+
+    #[test]
+    fn test_subsampling_still_covers_every_zone() {
+        const SPOKES: usize = 2048;
+        let mut processor = GuardZoneProcessor::new();
+        processor.set_subsample(4);
+        // Narrower than 4 spokes (4 * 360 / 2048 = 0.7 degrees)
+        processor.add_zone(GuardZone::new_arc(1, 10.0, 10.3, 500.0, 1000.0));
+        processor.add_zone(GuardZone::new_arc(2, 350.0, 20.0, 500.0, 1000.0));
+        processor.add_zone(GuardZone::new_ring(3, 500.0, 1000.0));
+
+        let spoke = vec![255u8; 512];
+        for i in 0..SPOKES {
+            processor.check_spoke(&spoke, i as f64 * 360.0 / SPOKES as f64, i as u64);
+        }
+        for zone_id in 1..=3 {
+            assert_eq!(
+                processor.get_alert_state(zone_id),
+                ZoneAlertState::Alarm,
+                "zone {}",
+                zone_id
+            );
+        }
+
+        // Spokes in between checks really are skipped
+        let mut processor = GuardZoneProcessor::new();
+        processor.set_subsample(4);
+        processor.add_zone(GuardZone::new_ring(1, 500.0, 1000.0));
+        let empty = vec![0u8; 512];
+        for i in 0..4 {
+            let data = if i == 2 { &spoke } else { &empty };
+            processor.check_spoke(data, i as f64, i as u64);
+        }
+        assert_eq!(processor.get_alert_state(1), ZoneAlertState::Clear);
+        processor.check_spoke(&spoke, 4.0, 4);
+        assert_eq!(processor.get_alert_state(1), ZoneAlertState::Alarm);
+    }
+
+    #[test]
+    fn test_zone_clamped_to_max_range() {
+        let mut processor = GuardZoneProcessor::new();
+        processor.set_range_scale(1852.0);
+        processor.set_max_range(Some(1000.0));
+        assert_eq!(processor.effective_range(), 1000.0);
+        processor.add_zone(GuardZone::new_ring(1, 800.0, 3000.0));
+
+        // Far-field noise at ~1500m is past the clamp
+        let mut spoke = vec![0u8; 512];
+        spoke[414] = 255;
+        assert!(processor.check_spoke(&spoke, 0.0, 1000).is_empty());
+
+        // The part of the zone within range still alarms (~900m)
+        spoke[248] = 200;
+        assert_eq!(processor.check_spoke(&spoke, 0.0, 2000).len(), 1);
+
+        // A shorter range scale limits the zone further
+        processor.set_range_scale(500.0);
+        assert_eq!(processor.effective_range(), 500.0);
+    }
+
     #[test]
     fn test_multiple_zones() {
         let mut processor = GuardZoneProcessor::new();
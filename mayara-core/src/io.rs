@@ -140,7 +140,8 @@ pub trait IoProvider {
 
     /// Join a multicast group.
     ///
-    /// The `interface` parameter is the local interface address to use (empty for default).
+    /// `group` is an IPv4 or IPv6 multicast address. The `interface` parameter
+    /// is the local interface address to use (empty for default).
     fn udp_join_multicast(
         &mut self,
         socket: &UdpSocketHandle,
@@ -150,6 +151,7 @@ pub trait IoProvider {
 
     /// Send data to a specific address.
     ///
+    /// `addr` is an IPv4 or IPv6 address without port.
     /// Returns the number of bytes sent.
     fn udp_send_to(
         &mut self,
@@ -230,13 +232,17 @@ pub trait IoProvider {
     /// Receive a complete line from a TCP socket (non-blocking).
     ///
     /// Only works in line-buffered mode.
-    /// Returns `None` if no complete line is available.
+    /// Returns `None` if no complete line is available. When the peer has
+    /// closed the connection (EOF) or the read failed, the socket must be
+    /// marked closed so that `tcp_is_connected()` returns false; that is how
+    /// callers tell a dropped connection from a quiet one.
     fn tcp_recv_line(&mut self, socket: &TcpSocketHandle, buf: &mut [u8]) -> Option<usize>;
 
     /// Receive raw data from a TCP socket (non-blocking).
     ///
     /// Only works in raw mode.
-    /// Returns `None` if no data is available.
+    /// Returns `None` if no data is available. EOF and read errors mark the
+    /// socket closed, as for `tcp_recv_line()`.
     fn tcp_recv_raw(&mut self, socket: &TcpSocketHandle, buf: &mut [u8]) -> Option<usize>;
 
     /// Get number of buffered items waiting to be received.
@@ -292,6 +298,218 @@ pub trait IoProviderExt: IoProvider {
 // Blanket implementation for all IoProvider types
 impl<T: IoProvider> IoProviderExt for T {}
 
+// =============================================================================
+// Mock provider (tests only)
+// =============================================================================
+
+/// In-memory [`IoProvider`] for unit tests of locators and controllers.
+///
+/// Sent data is recorded, incoming data is queued by the test. UDP packets are
+/// delivered to whichever socket is bound to the queued destination port.
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    /// A UDP datagram sent through the mock
+    #[derive(Debug, Clone)]
+    pub struct SentPacket {
+        pub data: Vec<u8>,
+        pub addr: String,
+        pub port: u16,
+    }
+
+    #[derive(Default)]
+    pub struct MockIo {
+        next_handle: i32,
+        udp_ports: HashMap<i32, u16>,
+        /// Multicast groups joined, in order
+        pub joined: Vec<String>,
+        /// UDP datagrams sent, in order
+        pub udp_sent: Vec<SentPacket>,
+        /// Queued incoming datagrams: (destination port, data, source addr)
+        pub udp_incoming: VecDeque<(u16, Vec<u8>, String)>,
+        /// TCP data sent, in order
+        pub tcp_sent: Vec<Vec<u8>>,
+        /// Queued incoming TCP lines
+        pub tcp_lines: VecDeque<String>,
+        /// Whether TCP connects succeed
+        pub tcp_connected: bool,
+        /// Close the connection (EOF) once the queued lines are read
+        pub tcp_eof: bool,
+        /// TCP sockets that have seen EOF
+        tcp_closed: HashSet<i32>,
+        /// Current time returned by `current_time_ms`
+        pub time_ms: u64,
+    }
+
+    impl MockIo {
+        pub fn new() -> Self {
+            Self {
+                tcp_connected: true,
+                ..Default::default()
+            }
+        }
+
+        /// Queue a datagram for the socket bound to `port`
+        pub fn push_udp(&mut self, port: u16, data: &[u8], from: &str) {
+            self.udp_incoming
+                .push_back((port, data.to_vec(), from.to_string()));
+        }
+
+        /// Queue a line for TCP sockets
+        pub fn push_line(&mut self, line: &str) {
+            self.tcp_lines.push_back(line.to_string());
+        }
+
+        /// TCP data sent so far, as strings
+        pub fn tcp_sent_strings(&self) -> Vec<String> {
+            self.tcp_sent
+                .iter()
+                .map(|d| String::from_utf8_lossy(d).to_string())
+                .collect()
+        }
+    }
+
+    impl IoProvider for MockIo {
+        fn udp_create(&mut self) -> Result<UdpSocketHandle, IoError> {
+            self.next_handle += 1;
+            Ok(UdpSocketHandle(self.next_handle))
+        }
+
+        fn udp_bind(&mut self, socket: &UdpSocketHandle, port: u16) -> Result<(), IoError> {
+            self.udp_ports.insert(socket.0, port);
+            Ok(())
+        }
+
+        fn udp_set_broadcast(
+            &mut self,
+            _socket: &UdpSocketHandle,
+            _enabled: bool,
+        ) -> Result<(), IoError> {
+            Ok(())
+        }
+
+        fn udp_join_multicast(
+            &mut self,
+            _socket: &UdpSocketHandle,
+            group: &str,
+            _interface: &str,
+        ) -> Result<(), IoError> {
+            self.joined.push(group.to_string());
+            Ok(())
+        }
+
+        fn udp_send_to(
+            &mut self,
+            _socket: &UdpSocketHandle,
+            data: &[u8],
+            addr: &str,
+            port: u16,
+        ) -> Result<usize, IoError> {
+            self.udp_sent.push(SentPacket {
+                data: data.to_vec(),
+                addr: addr.to_string(),
+                port,
+            });
+            Ok(data.len())
+        }
+
+        fn udp_recv_from(
+            &mut self,
+            socket: &UdpSocketHandle,
+            buf: &mut [u8],
+        ) -> Option<(usize, String, u16)> {
+            let port = *self.udp_ports.get(&socket.0)?;
+            let pos = self.udp_incoming.iter().position(|(p, _, _)| *p == port)?;
+            let (_, data, from) = self.udp_incoming.remove(pos)?;
+            let len = data.len().min(buf.len());
+            buf[..len].copy_from_slice(&data[..len]);
+            Some((len, from, port))
+        }
+
+        fn udp_pending(&self, socket: &UdpSocketHandle) -> i32 {
+            match self.udp_ports.get(&socket.0) {
+                Some(port) => self
+                    .udp_incoming
+                    .iter()
+                    .filter(|(p, _, _)| p == port)
+                    .count() as i32,
+                None => 0,
+            }
+        }
+
+        fn udp_close(&mut self, socket: UdpSocketHandle) {
+            self.udp_ports.remove(&socket.0);
+        }
+
+        fn tcp_create(&mut self) -> Result<TcpSocketHandle, IoError> {
+            self.next_handle += 1;
+            Ok(TcpSocketHandle(self.next_handle))
+        }
+
+        fn tcp_connect(
+            &mut self,
+            _socket: &TcpSocketHandle,
+            _addr: &str,
+            _port: u16,
+        ) -> Result<(), IoError> {
+            Ok(())
+        }
+
+        fn tcp_is_connected(&self, socket: &TcpSocketHandle) -> bool {
+            self.tcp_connected && !self.tcp_closed.contains(&socket.0)
+        }
+
+        fn tcp_is_valid(&self, _socket: &TcpSocketHandle) -> bool {
+            true
+        }
+
+        fn tcp_set_line_buffering(
+            &mut self,
+            _socket: &TcpSocketHandle,
+            _enabled: bool,
+        ) -> Result<(), IoError> {
+            Ok(())
+        }
+
+        fn tcp_send(&mut self, _socket: &TcpSocketHandle, data: &[u8]) -> Result<usize, IoError> {
+            self.tcp_sent.push(data.to_vec());
+            Ok(data.len())
+        }
+
+        fn tcp_recv_line(&mut self, socket: &TcpSocketHandle, buf: &mut [u8]) -> Option<usize> {
+            let Some(line) = self.tcp_lines.pop_front() else {
+                if std::mem::take(&mut self.tcp_eof) {
+                    self.tcp_closed.insert(socket.0);
+                }
+                return None;
+            };
+            let len = line.len().min(buf.len());
+            buf[..len].copy_from_slice(&line.as_bytes()[..len]);
+            Some(len)
+        }
+
+        fn tcp_recv_raw(&mut self, socket: &TcpSocketHandle, buf: &mut [u8]) -> Option<usize> {
+            self.tcp_recv_line(socket, buf)
+        }
+
+        fn tcp_pending(&self, _socket: &TcpSocketHandle) -> i32 {
+            self.tcp_lines.len() as i32
+        }
+
+        fn tcp_close(&mut self, _socket: TcpSocketHandle) {}
+
+        fn current_time_ms(&self) -> u64 {
+            self.time_ms
+        }
+
+        fn debug(&self, _msg: &str) {}
+
+        fn info(&self, _msg: &str) {}
+    }
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -48,6 +48,8 @@
 //! - [`io`] - Platform-agnostic I/O trait ([`IoProvider`])
 //! - [`locator`] - Radar discovery abstraction
 //! - [`arpa`] - Automatic Radar Plotting Aid (target tracking)
+//! - [`spoke`] - Display post-processing of spoke data (STC curve)
+//! - [`raster`] - Polar to cartesian grid of a revolution of spokes
 //!
 //! ## Feature Flags
 //!
@@ -117,6 +119,8 @@ pub mod locator;
 pub mod models;
 pub mod protocol;
 pub mod radar;
+pub mod raster;
+pub mod spoke;
 pub mod state;
 pub mod trails;
 
@@ -132,4 +136,6 @@ pub use engine::{ManagedRadar, RadarController, RadarEngine};
 pub use error::ParseError;
 pub use io::{IoError, IoProvider, TcpSocketHandle, UdpSocketHandle};
 pub use locator::{BrandStatus, DiscoveredRadar, LocatorEvent, LocatorStatus, RadarLocator};
-pub use state::{ControlValueState, PowerState, RadarState};
+pub use state::{
+    ChangeSource, ControlChangeTracker, ControlDelta, ControlValueState, PowerState, RadarState,
+};
@@ -7,12 +7,15 @@ use std::collections::BTreeMap;
 
 use crate::io::{IoProvider, UdpSocketHandle};
 use crate::protocol::{furuno, garmin, navico, raymarine};
-use crate::radar::RadarDiscovery;
+use crate::radar::{ParsedAddress, RadarDiscovery};
 use crate::Brand;
 
 /// Furuno beacon/announce broadcast address
 const FURUNO_BEACON_BROADCAST: &str = "172.31.255.255";
 
+/// How long a warm-started radar is kept without a confirming beacon
+pub const WARM_START_TIMEOUT_MS: u64 = 60_000;
+
 /// Event from the radar locator
 #[derive(Debug, Clone)]
 pub enum LocatorEvent {
@@ -20,6 +23,14 @@ pub enum LocatorEvent {
     RadarDiscovered(RadarDiscovery),
     /// An existing radar's info was updated (e.g., model report received)
     RadarUpdated(RadarDiscovery),
+    /// A known radar (same identity) beaconed from a new address, e.g.
+    /// after a DHCP renewal. Controllers should be retargeted.
+    RadarAddressChanged {
+        /// Address the radar was known at before
+        previous_address: String,
+        /// Discovery with the new addresses
+        discovery: RadarDiscovery,
+    },
 }
 
 /// A discovered radar with its metadata
@@ -27,6 +38,9 @@ pub enum LocatorEvent {
 pub struct DiscoveredRadar {
     pub discovery: RadarDiscovery,
     pub last_seen_ms: u64,
+    /// False for radars restored by [`RadarLocator::warm_start`] until a
+    /// beacon from the radar confirms it is still there
+    pub confirmed: bool,
 }
 
 /// Status of a single brand's listener
@@ -45,12 +59,30 @@ pub struct BrandStatus {
     pub multicast: Option<String>,
 }
 
+/// Beacons of one brand that were received but could not be parsed
+///
+/// A radar whose beacons fail to parse (e.g. after a firmware update
+/// changed the format) never appears, so these are surfaced to users.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeaconParseErrors {
+    /// Which brand the beacons were for
+    pub brand: Brand,
+    /// Number of beacons that failed to parse
+    pub count: u64,
+    /// The most recent parse error
+    pub last_error: String,
+}
+
 /// Overall locator status showing which brands are being listened for
 #[derive(Debug, Clone, Default, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LocatorStatus {
     /// Status of each brand's listener
     pub brands: Vec<BrandStatus>,
+    /// Beacon parse failures per brand, only brands with failures
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parse_errors: Vec<BeaconParseErrors>,
 }
 
 /// Startup phase for staggered brand initialization
@@ -102,6 +134,9 @@ pub struct RadarLocator {
 
     /// Current startup phase for staggered initialization
     startup_phase: StartupPhase,
+
+    /// Passive mode: listen for beacons but never send announces
+    passive: bool,
 }
 
 impl RadarLocator {
@@ -118,6 +153,7 @@ impl RadarLocator {
             status: LocatorStatus::default(),
             furuno_interface: None,
             startup_phase: StartupPhase::NotStarted,
+            passive: false,
         }
     }
 
@@ -129,6 +165,14 @@ impl RadarLocator {
         self.furuno_interface = Some(interface.to_string());
     }
 
+    /// Enable or disable passive mode
+    ///
+    /// In passive mode the locator only listens; Furuno announces and
+    /// beacon requests are not sent.
+    pub fn set_passive(&mut self, passive: bool) {
+        self.passive = passive;
+    }
+
     /// Start listening for beacons
     ///
     /// This begins staggered initialization - one brand is initialized per poll cycle
@@ -188,6 +232,41 @@ impl RadarLocator {
         &self.status
     }
 
+    /// Beacon parse failures per brand, for diagnostics
+    pub fn parse_errors(&self) -> &[BeaconParseErrors] {
+        &self.status.parse_errors
+    }
+
+    /// Number of beacons of `brand` that failed to parse
+    pub fn parse_error_count(&self, brand: Brand) -> u64 {
+        self.status
+            .parse_errors
+            .iter()
+            .find(|e| e.brand == brand)
+            .map_or(0, |e| e.count)
+    }
+
+    /// Count a beacon that was recognized but failed to parse
+    fn record_parse_error<I: IoProvider>(&mut self, io: &I, brand: Brand, error: &str) {
+        io.debug(&format!("{} beacon parse error: {}", brand, error));
+        match self
+            .status
+            .parse_errors
+            .iter_mut()
+            .find(|e| e.brand == brand)
+        {
+            Some(errors) => {
+                errors.count += 1;
+                errors.last_error = error.to_string();
+            }
+            None => self.status.parse_errors.push(BeaconParseErrors {
+                brand,
+                count: 1,
+                last_error: error.to_string(),
+            }),
+        }
+    }
+
     fn start_furuno<I: IoProvider>(&mut self, io: &mut I) {
         let status = match io.udp_create() {
             Ok(socket) => {
@@ -251,6 +330,9 @@ impl RadarLocator {
     /// This should be called before attempting TCP connections to Furuno radars,
     /// as the radar only accepts TCP from clients that have recently announced.
     pub fn send_furuno_announce<I: IoProvider>(&self, io: &mut I) {
+        if self.passive {
+            return;
+        }
         if let Some(socket) = &self.furuno_socket {
             let addr = FURUNO_BEACON_BROADCAST;
             let port = furuno::BEACON_PORT;
@@ -277,6 +359,53 @@ impl RadarLocator {
         }
     }
 
+    /// Send the Navico address request on both beacon groups
+    ///
+    /// Radars answer with a beacon, so one that was powered on while we
+    /// weren't listening is found without waiting for its next beacon.
+    pub fn send_navico_address_request<I: IoProvider>(&self, io: &mut I) {
+        if self.passive {
+            return;
+        }
+        let groups = [
+            (
+                &self.navico_br24_socket,
+                navico::BR24_BEACON_ADDR,
+                navico::BR24_BEACON_PORT,
+            ),
+            (
+                &self.navico_gen3_socket,
+                navico::GEN3_BEACON_ADDR,
+                navico::GEN3_BEACON_PORT,
+            ),
+        ];
+        for (socket, addr, port) in groups {
+            if let Some(socket) = socket {
+                if let Err(e) = io.udp_send_to(socket, navico::create_address_request(), addr, port)
+                {
+                    io.debug(&format!(
+                        "Failed to send Navico address request to {}: {}",
+                        addr, e
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Ask all radars to announce themselves now ("scan now")
+    ///
+    /// Re-sends every beacon request the locator knows of. Raymarine and
+    /// Garmin have no request packet; they beacon unprompted. Does nothing
+    /// in passive mode.
+    pub fn rescan<I: IoProvider>(&mut self, io: &mut I) {
+        if self.passive {
+            return;
+        }
+        io.info("Rescanning for radars");
+        self.send_furuno_announce(io);
+        self.send_navico_address_request(io);
+    }
+
     fn start_navico_br24<I: IoProvider>(&mut self, io: &mut I) {
         let status = match io.udp_create() {
             Ok(socket) => {
@@ -481,6 +610,68 @@ impl RadarLocator {
         self.status.brands.push(status);
     }
 
+    /// Pre-populate the radar list with last-known discoveries (e.g. from
+    /// a saved config), so they can be shown before any beacon arrives.
+    ///
+    /// Returns a [`LocatorEvent::RadarDiscovered`] for every radar added.
+    /// The radars are marked unconfirmed. A beacon from the radar confirms
+    /// it and emits [`LocatorEvent::RadarUpdated`], or
+    /// [`LocatorEvent::RadarAddressChanged`] if it moved; radars not heard
+    /// from within [`WARM_START_TIMEOUT_MS`] are dropped.
+    pub fn warm_start<I: IoProvider>(
+        &mut self,
+        io: &I,
+        discoveries: &[RadarDiscovery],
+    ) -> Vec<LocatorEvent> {
+        let now = io.current_time_ms();
+        let mut events = Vec::new();
+        for discovery in discoveries {
+            let id = self.make_radar_id(discovery);
+            if self.radars.contains_key(&id) {
+                continue;
+            }
+            io.debug(&format!(
+                "Warm start: {} radar {} last seen at {}",
+                discovery.brand, discovery.name, discovery.address
+            ));
+            self.radars.insert(
+                id,
+                DiscoveredRadar {
+                    discovery: discovery.clone(),
+                    last_seen_ms: now,
+                    confirmed: false,
+                },
+            );
+            events.push(LocatorEvent::RadarDiscovered(discovery.clone()));
+        }
+        events
+    }
+
+    /// Discoveries of all radars confirmed by a beacon, for saving and a
+    /// later [`warm_start`](Self::warm_start)
+    pub fn confirmed_discoveries(&self) -> Vec<RadarDiscovery> {
+        self.radars
+            .values()
+            .filter(|r| r.confirmed)
+            .map(|r| r.discovery.clone())
+            .collect()
+    }
+
+    /// Drop warm-started radars that never beaconed within the timeout
+    fn expire_unconfirmed<I: IoProvider>(&mut self, io: &I, current_time_ms: u64) {
+        self.radars.retain(|_, radar| {
+            let keep = radar.confirmed
+                || current_time_ms.saturating_sub(radar.last_seen_ms) < WARM_START_TIMEOUT_MS;
+            if !keep {
+                io.info(&format!(
+                    "{} radar {} did not reappear, forgetting it",
+                    radar.discovery.brand, radar.discovery.name
+                ));
+            }
+            keep
+        });
+    }
+
     /// Poll for incoming beacon packets
     ///
     /// Returns list of locator events (new discoveries and updates).
@@ -525,9 +716,7 @@ impl RadarLocator {
                         }
                         discoveries.extend(discovered);
                     }
-                    Err(e) => {
-                        io.debug(&format!("Navico BR24 parse error: {}", e));
-                    }
+                    Err(e) => self.record_parse_error(io, Brand::Navico, &format!("BR24: {}", e)),
                 }
             }
         }
@@ -546,9 +735,7 @@ impl RadarLocator {
                         }
                         discoveries.extend(discovered);
                     }
-                    Err(e) => {
-                        io.debug(&format!("Navico Gen3 parse error: {}", e));
-                    }
+                    Err(e) => self.record_parse_error(io, Brand::Navico, &e.to_string()),
                 }
             }
         }
@@ -565,9 +752,7 @@ impl RadarLocator {
                         io.debug(&format!("Raymarine beacon from {}: {:?}", addr, discovery.model));
                         discoveries.push(discovery);
                     }
-                    Err(e) => {
-                        io.debug(&format!("Raymarine parse error: {}", e));
-                    }
+                    Err(e) => self.record_parse_error(io, Brand::Raymarine, &e.to_string()),
                 }
             }
         }
@@ -586,11 +771,11 @@ impl RadarLocator {
 
         // Add all discoveries to the radar list
         for discovery in discoveries {
-            if self.add_radar(io, &discovery, current_time_ms) {
-                events.push(LocatorEvent::RadarDiscovered(discovery));
-            }
+            events.extend(self.add_radar(io, &discovery, current_time_ms));
         }
 
+        self.expire_unconfirmed(io, current_time_ms);
+
         // Apply model reports to existing radars (after discoveries are added)
         // This ensures the radar exists before we try to update its model info
         for (addr, model, serial) in model_reports {
@@ -603,7 +788,7 @@ impl RadarLocator {
     }
 
     fn poll_furuno<I: IoProvider>(
-        &self,
+        &mut self,
         io: &mut I,
         buf: &mut [u8],
         discoveries: &mut Vec<RadarDiscovery>,
@@ -619,9 +804,7 @@ impl RadarLocator {
                             io.debug(&format!("Furuno beacon from {}: {:?}", addr, discovery.model));
                             discoveries.push(discovery);
                         }
-                        Err(e) => {
-                            io.debug(&format!("Furuno beacon parse error: {}", e));
-                        }
+                        Err(e) => self.record_parse_error(io, Brand::Furuno, &e.to_string()),
                     }
                 } else if furuno::is_model_report(data) {
                     // UDP model reports (170 bytes) are often empty/unreliable
@@ -657,10 +840,10 @@ impl RadarLocator {
         model: Option<&str>,
         serial: Option<&str>,
     ) -> Option<RadarDiscovery> {
-        let source_ip = source_addr.split(':').next().unwrap_or(source_addr);
+        let source_ip = ParsedAddress::host(source_addr);
 
         for (_id, radar) in self.radars.iter_mut() {
-            let radar_ip = radar.discovery.address.split(':').next().unwrap_or(&radar.discovery.address);
+            let radar_ip = ParsedAddress::host(&radar.discovery.address);
 
             if radar_ip == source_ip {
                 let mut changed = false;
@@ -700,14 +883,68 @@ impl RadarLocator {
         None
     }
 
-    fn add_radar<I: IoProvider>(&mut self, io: &I, discovery: &RadarDiscovery, current_time_ms: u64) -> bool {
+    fn add_radar<I: IoProvider>(
+        &mut self,
+        io: &I,
+        discovery: &RadarDiscovery,
+        current_time_ms: u64,
+    ) -> Option<LocatorEvent> {
         let id = self.make_radar_id(discovery);
 
-        if self.radars.contains_key(&id) {
-            if let Some(radar) = self.radars.get_mut(&id) {
-                radar.last_seen_ms = current_time_ms;
+        if let Some(radar) = self.radars.get_mut(&id) {
+            radar.last_seen_ms = current_time_ms;
+            // Beacons without endpoints (Raymarine's identification beacon)
+            // keep the ones learned from earlier beacons
+            let discovery = &with_known_endpoints(discovery, &radar.discovery);
+            let known = &radar.discovery;
+            let moved = (
+                &known.address,
+                &known.send_address,
+                &known.report_address,
+                &known.data_address,
+            ) != (
+                &discovery.address,
+                &discovery.send_address,
+                &discovery.report_address,
+                &discovery.data_address,
+            );
+            if !radar.confirmed {
+                // First beacon from a warm-started radar, which was already
+                // announced by warm_start()
+                io.debug(&format!(
+                    "Confirmed {} radar: {} at {}",
+                    discovery.brand, discovery.name, discovery.address
+                ));
+                radar.confirmed = true;
+                if !moved {
+                    let mut updated = discovery.clone();
+                    updated.model = updated.model.or_else(|| radar.discovery.model.clone());
+                    updated.serial_number = updated
+                        .serial_number
+                        .or_else(|| radar.discovery.serial_number.clone());
+                    radar.discovery = updated.clone();
+                    return Some(LocatorEvent::RadarUpdated(updated));
+                }
+            } else if !moved {
+                return None;
             }
-            false
+            let known = &radar.discovery;
+            io.info(&format!(
+                "{} radar {} moved from {} to {}",
+                discovery.brand, discovery.name, known.address, discovery.address
+            ));
+            let previous_address = known.address.clone();
+            // Keep what was learned from model reports
+            let mut updated = discovery.clone();
+            updated.model = updated.model.or_else(|| radar.discovery.model.clone());
+            updated.serial_number = updated
+                .serial_number
+                .or_else(|| radar.discovery.serial_number.clone());
+            radar.discovery = updated.clone();
+            Some(LocatorEvent::RadarAddressChanged {
+                previous_address,
+                discovery: updated,
+            })
         } else {
             io.debug(&format!(
                 "Discovered {} radar: {} at {}",
@@ -718,9 +955,10 @@ impl RadarLocator {
                 DiscoveredRadar {
                     discovery: discovery.clone(),
                     last_seen_ms: current_time_ms,
+                    confirmed: true,
                 },
             );
-            true
+            Some(LocatorEvent::RadarDiscovered(discovery.clone()))
         }
     }
 
@@ -757,3 +995,221 @@ impl Default for RadarLocator {
         Self::new()
     }
 }
+
+/// Fill in endpoints missing from a beacon with those already known
+fn with_known_endpoints(discovery: &RadarDiscovery, known: &RadarDiscovery) -> RadarDiscovery {
+    let mut merged = discovery.clone();
+    if merged.data_port == 0 {
+        merged.data_port = known.data_port;
+    }
+    if merged.command_port == 0 {
+        merged.command_port = known.command_port;
+    }
+    merged.data_address = merged.data_address.or_else(|| known.data_address.clone());
+    merged.report_address = merged
+        .report_address
+        .or_else(|| known.report_address.clone());
+    merged.send_address = merged.send_address.or_else(|| known.send_address.clone());
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::mock::MockIo;
+
+    #[test]
+    fn test_rebeacon_from_new_ip_updates_address() {
+        let io = MockIo::new();
+        let mut locator = RadarLocator::new();
+        let beacon = |ip: &str| RadarDiscovery::for_test(Brand::Furuno, "RD003212", ip);
+
+        assert!(matches!(
+            locator.add_radar(&io, &beacon("172.31.3.212"), 0),
+            Some(LocatorEvent::RadarDiscovered(_))
+        ));
+        locator.radars.values_mut().next().unwrap().discovery.model = Some("DRS4D-NXT".to_string());
+        assert!(locator
+            .add_radar(&io, &beacon("172.31.3.212"), 100)
+            .is_none());
+
+        match locator.add_radar(&io, &beacon("172.31.3.57"), 200) {
+            Some(LocatorEvent::RadarAddressChanged {
+                previous_address,
+                discovery,
+            }) => {
+                assert_eq!(previous_address, "172.31.3.212");
+                assert_eq!(discovery.address, "172.31.3.57");
+                assert_eq!(discovery.model.as_deref(), Some("DRS4D-NXT"));
+            }
+            other => panic!("expected RadarAddressChanged, got {:?}", other),
+        }
+        assert_eq!(locator.radars.len(), 1);
+        assert_eq!(
+            locator.radars.values().next().unwrap().discovery.address,
+            "172.31.3.57"
+        );
+    }
+
+    #[test]
+    fn test_raymarine_identification_beacon_keeps_endpoints() {
+        let io = MockIo::new();
+        let mut locator = RadarLocator::new();
+        let endpoints =
+            raymarine::parse_beacon_response(&raymarine::tests::QUANTUM_BEACON_36, "198.18.6.214")
+                .unwrap();
+        let identification =
+            raymarine::parse_beacon_response(&raymarine::tests::QUANTUM_BEACON_56, "198.18.6.214")
+                .unwrap();
+
+        assert!(matches!(
+            locator.add_radar(&io, &endpoints, 0),
+            Some(LocatorEvent::RadarDiscovered(_))
+        ));
+        // The identification beacon must not look like a move
+        assert!(locator.add_radar(&io, &identification, 100).is_none());
+        let known = &locator.radars.values().next().unwrap().discovery;
+        assert_eq!(known.report_address, endpoints.report_address);
+        assert_eq!(known.send_address, endpoints.send_address);
+    }
+
+    #[test]
+    fn test_dual_range_beacon_adds_two_radars() {
+        let io = MockIo::new();
+        let mut locator = RadarLocator::new();
+        let range = |suffix: &str, data: &str| {
+            let mut discovery =
+                RadarDiscovery::for_test(Brand::Navico, "1403200123", "192.168.1.50");
+            discovery.suffix = Some(suffix.to_string());
+            discovery.data_address = Some(data.to_string());
+            discovery
+        };
+
+        for discovery in [range("A", "236.6.7.8:6678"), range("B", "236.6.7.13:6657")] {
+            assert!(matches!(
+                locator.add_radar(&io, &discovery, 0),
+                Some(LocatorEvent::RadarDiscovered(_))
+            ));
+        }
+        let mut ids: Vec<&String> = locator.radars.keys().collect();
+        ids.sort();
+        assert_eq!(ids, ["Navico-1403200123-A", "Navico-1403200123-B"]);
+
+        // Repeated beacons for either range are not new radars
+        assert!(locator
+            .add_radar(&io, &range("B", "236.6.7.13:6657"), 100)
+            .is_none());
+        assert_eq!(locator.radars.len(), 2);
+    }
+
+    #[test]
+    fn test_warm_started_radar_unconfirmed_until_beacon() {
+        let mut io = MockIo::new();
+        let mut locator = RadarLocator::new();
+        let mut saved = RadarDiscovery::for_test(Brand::Furuno, "RD003212", "172.31.3.212");
+        saved.model = Some("DRS4D-NXT".to_string());
+        saved.serial_number = Some("6424".to_string());
+        let mut gone = saved.clone();
+        gone.name = "RD003999".to_string();
+
+        let events = locator.warm_start(&io, &[saved.clone(), gone]);
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|e| matches!(e, LocatorEvent::RadarDiscovered(_))));
+        assert_eq!(locator.radars.len(), 2);
+        assert!(locator.radars.values().all(|r| !r.confirmed));
+        assert!(locator.confirmed_discoveries().is_empty());
+
+        // Beacons carry no model; the saved one is kept
+        let mut beacon = saved.clone();
+        beacon.model = None;
+        beacon.serial_number = None;
+        match locator.add_radar(&io, &beacon, 1000) {
+            Some(LocatorEvent::RadarUpdated(discovery)) => {
+                assert_eq!(discovery.model.as_deref(), Some("DRS4D-NXT"));
+            }
+            other => panic!("expected RadarUpdated, got {:?}", other),
+        }
+        assert_eq!(locator.confirmed_discoveries().len(), 1);
+
+        // The radar that never beaconed is dropped after the timeout
+        io.time_ms = WARM_START_TIMEOUT_MS + 1;
+        locator.poll(&mut io);
+        assert_eq!(locator.radars.len(), 1);
+        assert!(locator.radars.values().all(|r| r.confirmed));
+    }
+
+    #[test]
+    fn test_unparseable_beacon_counted_per_brand() {
+        let mut io = MockIo::new();
+        let mut locator = RadarLocator::new();
+        locator.start(&mut io);
+        while locator.is_starting() {
+            locator.poll(&mut io);
+        }
+
+        // A beacon response header, but far too short for any layout
+        let beacon = [0x01, 0xB2, 0x31, 0x32, 0x33];
+        for _ in 0..3 {
+            io.push_udp(navico::GEN3_BEACON_PORT, &beacon, "192.168.1.50");
+        }
+        assert!(locator.poll(&mut io).is_empty());
+
+        assert_eq!(locator.parse_error_count(Brand::Navico), 3);
+        assert_eq!(locator.parse_error_count(Brand::Furuno), 0);
+        let errors = locator.parse_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(!errors[0].last_error.is_empty());
+        let json = serde_json::to_value(locator.status()).unwrap();
+        assert_eq!(json["parseErrors"][0]["count"], 3);
+    }
+
+    #[test]
+    fn test_rescan_sends_beacon_requests() {
+        let mut io = MockIo::new();
+        let mut locator = RadarLocator::new();
+        locator.start(&mut io);
+        while locator.is_starting() {
+            locator.poll(&mut io);
+        }
+        io.udp_sent.clear();
+
+        locator.rescan(&mut io);
+        let sent = |data: &[u8], addr: &str, port: u16| {
+            io.udp_sent
+                .iter()
+                .any(|p| p.data == data && p.addr == addr && p.port == port)
+        };
+        assert!(sent(
+            &furuno::REQUEST_BEACON_PACKET,
+            FURUNO_BEACON_BROADCAST,
+            furuno::BEACON_PORT
+        ));
+        assert!(sent(
+            &furuno::REQUEST_MODEL_PACKET,
+            FURUNO_BEACON_BROADCAST,
+            furuno::BEACON_PORT
+        ));
+        assert!(sent(
+            &furuno::ANNOUNCE_PACKET,
+            FURUNO_BEACON_BROADCAST,
+            furuno::BEACON_PORT
+        ));
+        assert!(sent(
+            &navico::ADDRESS_REQUEST_PACKET,
+            navico::BR24_BEACON_ADDR,
+            navico::BR24_BEACON_PORT
+        ));
+        assert!(sent(
+            &navico::ADDRESS_REQUEST_PACKET,
+            navico::GEN3_BEACON_ADDR,
+            navico::GEN3_BEACON_PORT
+        ));
+
+        io.udp_sent.clear();
+        locator.set_passive(true);
+        locator.rescan(&mut io);
+        assert!(io.udp_sent.is_empty());
+    }
+}
@@ -3,6 +3,7 @@
 //! This module contains specifications for Furuno radar models.
 
 use super::ModelInfo;
+use crate::capabilities::AntennaType;
 use crate::Brand;
 
 /// Range table for DRS-NXT series (in meters)
@@ -120,7 +121,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 22224,  // 12 NM max in dual-range
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_NXT,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Furuno,
@@ -136,7 +140,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 22224,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_NXT,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Furuno,
@@ -152,7 +159,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 22224,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_NXT,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Furuno,
@@ -168,7 +178,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 22224,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_NXT,
+        inverted_controls: &[],
     },
 
     // Standard DRS Series (non-Doppler)
@@ -186,7 +199,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 2000,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_DRS,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Furuno,
@@ -202,7 +218,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 2000,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_DRS,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Furuno,
@@ -218,7 +237,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 3000,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_DRS,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Furuno,
@@ -234,7 +256,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 3000,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_DRS,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Furuno,
@@ -250,7 +275,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 3000,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_DRS,
+        inverted_controls: &[],
     },
 
     // FAR Series (Commercial)
@@ -268,7 +296,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 4,
+        magnetron_life_hours: 3000,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_FAR,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Furuno,
@@ -284,7 +315,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 4,
+        magnetron_life_hours: 3000,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_FAR,
+        inverted_controls: &[],
     },
 ];
 
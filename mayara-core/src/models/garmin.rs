@@ -3,6 +3,7 @@
 //! This module contains specifications for Garmin radar models.
 
 use super::ModelInfo;
+use crate::capabilities::AntennaType;
 use crate::Brand;
 
 /// Range table for xHD series (in meters)
@@ -64,6 +65,7 @@ static CONTROLS_FANTOM: &[&str] = &[
     "bearingAlignment",
     "antennaHeight",
     "scanSpeed",
+    "timedTransmit",
 ];
 
 /// Extended controls for xHD series
@@ -74,6 +76,7 @@ static CONTROLS_XHD: &[&str] = &[
     "noTransmitZones",
     "bearingAlignment",
     "antennaHeight",
+    "timedTransmit",
 ];
 
 /// All known Garmin radar models
@@ -93,7 +96,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_FANTOM,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Garmin,
@@ -109,7 +115,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_FANTOM,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Garmin,
@@ -125,7 +134,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_FANTOM,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Garmin,
@@ -141,7 +153,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_FANTOM,
+        inverted_controls: &[],
     },
 
     // xHD Series
@@ -159,7 +174,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 2000,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_XHD,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Garmin,
@@ -175,7 +193,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 2000,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_XHD,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Garmin,
@@ -191,7 +212,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 2000,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_XHD,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Garmin,
@@ -207,7 +231,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 2000,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_XHD,
+        inverted_controls: &[],
     },
 ];
 
@@ -216,6 +243,18 @@ pub fn get_model(model: &str) -> Option<&'static ModelInfo> {
     MODELS.iter().find(|m| m.model == model)
 }
 
+/// Find the model named in a scanner message (report 0x099b)
+///
+/// The message carries more than the model name, so the model whose name
+/// appears in it (ignoring case) is taken.
+pub fn model_from_scanner_message(message: &str) -> Option<&'static ModelInfo> {
+    let message = message.to_ascii_lowercase();
+    MODELS
+        .iter()
+        .filter(|m| message.contains(&m.model.to_ascii_lowercase()))
+        .max_by_key(|m| m.model.len())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,4 +271,13 @@ mod tests {
         let model = get_model("GMR 18 xHD").unwrap();
         assert!(!model.has_doppler);
     }
+
+    #[test]
+    fn test_model_from_scanner_message() {
+        let model = model_from_scanner_message("Garmin FANTOM 54 Radar").unwrap();
+        assert_eq!(model.model, "Fantom 54");
+        let model = model_from_scanner_message("GMR 24 HD+ 4kW").unwrap();
+        assert_eq!(model.model, "GMR 24 HD+");
+        assert!(model_from_scanner_message("unknown scanner").is_none());
+    }
 }
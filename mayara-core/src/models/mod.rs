@@ -4,12 +4,16 @@
 //! range tables, and available controls. This information is used to build
 //! capability manifests for the v5 API.
 
+use crate::capabilities::AntennaType;
 use crate::Brand;
 
 pub mod furuno;
 pub mod garmin;
 pub mod navico;
 pub mod raymarine;
+mod range_index;
+
+pub use range_index::RangeIndex;
 
 /// Information about a specific radar model
 #[derive(Debug, Clone)]
@@ -44,10 +48,38 @@ pub struct ModelInfo {
     pub max_dual_range: u32,
     /// Number of no-transmit zones supported
     pub no_transmit_zone_count: u8,
+    /// Rated magnetron life in hours (0 for solid-state radars)
+    ///
+    /// The rating grows with the tube's power: the 2-4 kW magnetrons of
+    /// radomes and small open arrays are rated for about 2000 hours, the
+    /// 6 kW and larger ones for about 3000.
+    pub magnetron_life_hours: u32,
+    /// Radome or open array (None if the model comes as both)
+    pub antenna_type: Option<AntennaType>,
 
     // Available extended controls (semantic IDs)
     /// List of extended control IDs available on this model
     pub controls: &'static [&'static str],
+    /// Controls whose wire value runs opposite to the UI (0 = most)
+    ///
+    /// Older radars report e.g. gain 0 as maximum gain. The engine and the
+    /// server complement these so the UI scale is always 0 = least,
+    /// 100 = most.
+    pub inverted_controls: &'static [&'static str],
+}
+
+impl ModelInfo {
+    /// Share of the rated magnetron life used after `operating_hours`, in
+    /// percent
+    ///
+    /// None for solid-state radars. Not clamped: a tube past its rated
+    /// life reports more than 100%.
+    pub fn magnetron_life_percent(&self, operating_hours: f64) -> Option<f64> {
+        if self.magnetron_life_hours == 0 {
+            return None;
+        }
+        Some(operating_hours.max(0.0) * 100.0 / self.magnetron_life_hours as f64)
+    }
 }
 
 /// Unknown/generic model used when a radar model isn't in the database
@@ -65,13 +97,16 @@ pub static UNKNOWN_MODEL: ModelInfo = ModelInfo {
     has_dual_range: false,
     max_dual_range: 0,
     no_transmit_zone_count: 0,
+    magnetron_life_hours: 0,
+    antenna_type: None,
     controls: &[],
+    inverted_controls: &[],
 };
 
 /// Look up a model by brand and model string
 ///
 /// Returns None if the model is not found in the database.
-/// Use `UNKNOWN_MODEL` as a fallback for unknown models.
+/// Use [`generic_model`] as a fallback for unknown models.
 pub fn get_model(brand: Brand, model: &str) -> Option<&'static ModelInfo> {
     match brand {
         Brand::Furuno => furuno::get_model(model),
@@ -81,6 +116,28 @@ pub fn get_model(brand: Brand, model: &str) -> Option<&'static ModelInfo> {
     }
 }
 
+/// Conservative profile for a model missing from the database
+///
+/// Only base controls and a generic range table, so a newly released radar
+/// can still be switched on and used. Manifests built from it are flagged
+/// with `genericProfile`.
+pub fn generic_model(brand: Brand) -> ModelInfo {
+    ModelInfo {
+        brand,
+        ..UNKNOWN_MODEL.clone()
+    }
+}
+
+/// Look up a model by name across all brands
+///
+/// Used where the brand is not known up front, e.g. when validating a
+/// model name given on the command line.
+pub fn find_model(model: &str) -> Option<&'static ModelInfo> {
+    [Brand::Furuno, Brand::Navico, Brand::Raymarine, Brand::Garmin]
+        .into_iter()
+        .find_map(|brand| get_model(brand, model))
+}
+
 /// Get all known models for a brand
 pub fn get_models_for_brand(brand: Brand) -> &'static [ModelInfo] {
     match brand {
@@ -118,6 +175,121 @@ pub fn get_all_ranges_for_brand(brand: Brand) -> Vec<u32> {
     ranges
 }
 
+/// A selectable antenna rotation speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanSpeedOption {
+    /// Nominal rotation speed in RPM, 0 for automatic (range dependent)
+    pub rpm: u16,
+    /// Value sent in the brand's scan speed command
+    pub wire: u8,
+    /// Human-readable label
+    pub label: &'static str,
+}
+
+/// Furuno `$S89`: mode 0 is a fixed 24 RPM, mode 2 varies with range
+static FURUNO_SCAN_SPEEDS: &[ScanSpeedOption] = &[
+    ScanSpeedOption { rpm: 24, wire: 0, label: "24 RPM" },
+    ScanSpeedOption { rpm: 0, wire: 2, label: "Auto" },
+];
+
+/// HALO `0F C1`: Normal, Medium, Medium Plus, Fast (nominal speeds)
+static HALO_SCAN_SPEEDS: &[ScanSpeedOption] = &[
+    ScanSpeedOption { rpm: 20, wire: 0, label: "20 RPM" },
+    ScanSpeedOption { rpm: 30, wire: 1, label: "30 RPM" },
+    ScanSpeedOption { rpm: 36, wire: 2, label: "36 RPM" },
+    ScanSpeedOption { rpm: 48, wire: 3, label: "48 RPM" },
+];
+
+/// 4G/3G `0F C1`: Normal or Fast
+static NAVICO_SCAN_SPEEDS: &[ScanSpeedOption] = &[
+    ScanSpeedOption { rpm: 24, wire: 0, label: "24 RPM" },
+    ScanSpeedOption { rpm: 36, wire: 1, label: "36 RPM" },
+];
+
+impl ModelInfo {
+    /// Is this the generic profile rather than a known model?
+    pub fn is_generic(&self) -> bool {
+        self.model == UNKNOWN_MODEL.model
+    }
+
+    /// Whether interference rejection has levels (IR1, IR2) rather than
+    /// just on/off
+    pub fn has_leveled_interference_rejection(&self) -> bool {
+        self.brand == Brand::Furuno
+            && self.family == "FAR"
+            && self.controls.contains(&"interferenceRejection")
+    }
+
+    /// Scan speeds this model can be set to
+    ///
+    /// Empty if the model has no scan speed control, or if its speeds
+    /// aren't known (the generic normal/fast control is used then).
+    pub fn scan_speed_options(&self) -> &'static [ScanSpeedOption] {
+        if !self.controls.contains(&"scanSpeed") {
+            return &[];
+        }
+        match (self.brand, self.family) {
+            (Brand::Furuno, _) => FURUNO_SCAN_SPEEDS,
+            (Brand::Navico, "HALO") => HALO_SCAN_SPEEDS,
+            (Brand::Navico, "4G") | (Brand::Navico, "3G") => NAVICO_SCAN_SPEEDS,
+            _ => &[],
+        }
+    }
+
+    /// Wire value for a scan speed in RPM (0 = auto), if this model supports it
+    pub fn scan_speed_wire(&self, rpm: u16) -> Option<u8> {
+        self.scan_speed_options()
+            .iter()
+            .find(|o| o.rpm == rpm)
+            .map(|o| o.wire)
+    }
+
+    /// Scan speed in RPM (0 = auto) for a wire value reported by the radar
+    pub fn scan_speed_rpm(&self, wire: u8) -> Option<u16> {
+        self.scan_speed_options()
+            .iter()
+            .find(|o| o.wire == wire)
+            .map(|o| o.rpm)
+    }
+
+    /// Map a 0-100 control value between the UI and the wire
+    ///
+    /// Returns `100 - value` for controls in `inverted_controls`, the value
+    /// unchanged otherwise. The mapping is its own inverse, so it converts
+    /// in both directions.
+    pub fn map_inverted(&self, control_id: &str, value: i32) -> i32 {
+        if self.inverted_controls.contains(&control_id) {
+            100 - value
+        } else {
+            value
+        }
+    }
+
+    /// Supported range the radar uses for a requested range, see [`snap_range`]
+    pub fn snap_range(&self, requested_meters: u32) -> u32 {
+        let supported = self.range_table.iter().copied().filter(|m| *m > 0);
+        let longest = supported.clone().max();
+        supported
+            .filter(|m| *m >= requested_meters)
+            .min()
+            .or(longest)
+            .unwrap_or(requested_meters)
+    }
+}
+
+/// Snap a requested range (meters) to the supported range the radar uses
+///
+/// All brands pick the same way from the model's range table: the exact
+/// entry, else the shortest range that still covers the request, else the
+/// longest range (as [`RangeIndex::index_for`] does for wire indexes).
+/// Models missing from the database use the generic range table.
+pub fn snap_range(brand: Brand, model: &str, requested_meters: u32) -> u32 {
+    match get_model(brand, model) {
+        Some(model_info) => model_info.snap_range(requested_meters),
+        None => generic_model(brand).snap_range(requested_meters),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +308,48 @@ mod tests {
         let model = get_model(Brand::Furuno, "NonExistent");
         assert!(model.is_none());
     }
+
+    #[test]
+    fn test_snap_range_per_brand() {
+        // Between 1 NM and 1.5 NM: the longer step, as the radar picks it
+        assert_eq!(snap_range(Brand::Furuno, "DRS4D-NXT", 2000), 2778);
+        assert_eq!(snap_range(Brand::Navico, "HALO24", 2500), 3000);
+        assert_eq!(snap_range(Brand::Raymarine, "Quantum 2", 200), 250);
+        assert_eq!(snap_range(Brand::Garmin, "Fantom 24", 90), 100);
+
+        // Exact steps are kept, requests beyond the table get the longest
+        assert_eq!(snap_range(Brand::Furuno, "DRS4D-NXT", 1852), 1852);
+        assert_eq!(snap_range(Brand::Furuno, "DRS4D-NXT", 200_000), 88896);
+        assert_eq!(snap_range(Brand::Navico, "Unknown", 900), 1000);
+    }
+
+    #[test]
+    fn test_generic_model_keeps_brand() {
+        let model = generic_model(Brand::Navico);
+        assert_eq!(model.brand, Brand::Navico);
+        assert!(model.is_generic());
+        assert!(!get_model(Brand::Navico, "HALO24").unwrap().is_generic());
+    }
+
+    #[test]
+    fn test_find_model_any_brand() {
+        assert_eq!(find_model("HALO24").map(|m| m.brand), Some(Brand::Navico));
+        assert_eq!(find_model("DRS6A-NXT").map(|m| m.brand), Some(Brand::Furuno));
+        assert!(find_model("NonExistent").is_none());
+    }
+
+    #[test]
+    fn test_scan_speed_options_per_model() {
+        let halo = get_model(Brand::Navico, "HALO24").unwrap();
+        let rpms: Vec<u16> = halo.scan_speed_options().iter().map(|o| o.rpm).collect();
+        assert_eq!(rpms, vec![20, 30, 36, 48]);
+        assert_eq!(halo.scan_speed_wire(36), Some(2));
+        assert_eq!(halo.scan_speed_wire(24), None);
+
+        let furuno = get_model(Brand::Furuno, "DRS4D-NXT").unwrap();
+        let rpms: Vec<u16> = furuno.scan_speed_options().iter().map(|o| o.rpm).collect();
+        assert_eq!(rpms, vec![24, 0]);
+        assert_eq!(furuno.scan_speed_wire(0), Some(2));
+        assert_eq!(furuno.scan_speed_rpm(0), Some(24));
+    }
 }
@@ -4,6 +4,7 @@
 //! (Simrad, Lowrance, B&G branded radars).
 
 use super::ModelInfo;
+use crate::capabilities::AntennaType;
 use crate::Brand;
 
 /// Range table for HALO series (in meters)
@@ -79,11 +80,9 @@ static CONTROLS_HALO: &[&str] = &[
 
 /// Extended controls for 4G/3G series
 static CONTROLS_4G: &[&str] = &[
-    "presetMode",
     "targetSeparation",
     "targetExpansion",
     "targetBoost",
-    "seaState",
     "noiseRejection",
     "interferenceRejection",
     "sidelobeSuppression",
@@ -110,7 +109,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 4,
+        magnetron_life_hours: 0,
+        antenna_type: None,
         controls: CONTROLS_HALO,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Navico,
@@ -126,7 +128,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_HALO,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Navico,
@@ -142,7 +147,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_HALO,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Navico,
@@ -158,7 +166,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_HALO,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Navico,
@@ -174,7 +185,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_HALO,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Navico,
@@ -190,7 +204,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: true,
         max_dual_range: 24000,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::OpenArray),
         controls: CONTROLS_HALO,
+        inverted_controls: &[],
     },
 
     // 4G Series
@@ -208,7 +225,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_4G,
+        inverted_controls: &[],
     },
 
     // 3G Series
@@ -226,7 +246,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_4G,
+        inverted_controls: &[],
     },
 
     // BR24
@@ -244,7 +267,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: &["interferenceRejection", "bearingAlignment"],
+        inverted_controls: &[],
     },
 ];
 
@@ -269,6 +295,8 @@ mod tests {
     fn test_4g() {
         let model = get_model("4G").unwrap();
         assert!(!model.has_doppler);
-        assert!(model.controls.contains(&"presetMode"));
+        // Preset modes and sea state are HALO only
+        assert!(!model.controls.contains(&"presetMode"));
+        assert!(!model.controls.contains(&"seaState"));
     }
 }
@@ -0,0 +1,98 @@
+//! Cached meters → range index lookup
+//!
+//! Radars take a range as an index into their own range table. Controllers
+//! build a [`RangeIndex`] once for the active model's table instead of
+//! scanning the table on every `set_range`.
+
+use std::collections::HashMap;
+
+/// Meters → wire range index mapping for one range table
+#[derive(Debug, Clone, Default)]
+pub struct RangeIndex {
+    /// (meters, wire index), sorted by meters
+    sorted: Vec<(u32, i32)>,
+    /// Exact meters → wire index
+    exact: HashMap<u32, i32>,
+}
+
+impl RangeIndex {
+    /// Build from (wire index, meters) pairs; entries with 0 meters are unused slots
+    pub fn new(entries: impl IntoIterator<Item = (i32, u32)>) -> Self {
+        let mut sorted: Vec<(u32, i32)> = entries
+            .into_iter()
+            .filter(|(_, meters)| *meters > 0)
+            .map(|(index, meters)| (meters, index))
+            .collect();
+        sorted.sort_by_key(|(meters, _)| *meters);
+        let exact = sorted.iter().copied().collect();
+        Self { sorted, exact }
+    }
+
+    /// Build from a model range table, where the wire index is the position
+    pub fn from_range_table(table: &[u32]) -> Self {
+        Self::new(table.iter().enumerate().map(|(i, m)| (i as i32, *m)))
+    }
+
+    /// Does this index cover exactly the ranges in `table`?
+    pub fn matches_table(&self, table: &[u32]) -> bool {
+        let mut meters: Vec<u32> = table.iter().copied().filter(|m| *m > 0).collect();
+        meters.sort_unstable();
+        meters.len() == self.sorted.len()
+            && meters.iter().zip(&self.sorted).all(|(a, (b, _))| a == b)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    /// Wire index for `meters`: the exact entry, else the smallest range
+    /// that is at least `meters`, else the largest range.
+    pub fn index_for(&self, meters: u32) -> Option<i32> {
+        if let Some(index) = self.exact.get(&meters) {
+            return Some(*index);
+        }
+        let pos = self.sorted.partition_point(|(m, _)| *m < meters);
+        self.sorted
+            .get(pos)
+            .or_else(|| self.sorted.last())
+            .map(|(_, index)| *index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::furuno::command::{meters_to_range_index, RANGE_TABLE};
+
+    #[test]
+    fn test_cached_lookup_matches_linear_scan() {
+        let index = RangeIndex::new(RANGE_TABLE.iter().map(|(i, m)| (*i, *m as u32)));
+        for (_, meters) in RANGE_TABLE.iter() {
+            for probe in [*meters - 1, *meters, *meters + 1] {
+                assert_eq!(
+                    index.index_for(probe as u32),
+                    Some(meters_to_range_index(probe)),
+                    "range {} m",
+                    probe
+                );
+            }
+        }
+        assert_eq!(index.index_for(1), Some(meters_to_range_index(1)));
+        assert_eq!(
+            index.index_for(200_000),
+            Some(meters_to_range_index(200_000))
+        );
+    }
+
+    #[test]
+    fn test_from_range_table() {
+        let table = [0, 125, 250, 500];
+        let index = RangeIndex::from_range_table(&table);
+        assert!(index.matches_table(&table));
+        assert!(!index.matches_table(&[125, 250]));
+        assert_eq!(index.index_for(250), Some(2));
+        assert_eq!(index.index_for(300), Some(3));
+        assert_eq!(index.index_for(10_000), Some(3));
+        assert_eq!(RangeIndex::default().index_for(100), None);
+    }
+}
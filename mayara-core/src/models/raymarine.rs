@@ -3,6 +3,7 @@
 //! This module contains specifications for Raymarine radar models.
 
 use super::ModelInfo;
+use crate::capabilities::AntennaType;
 use crate::Brand;
 
 /// Range table for Quantum series (in meters)
@@ -70,6 +71,12 @@ static CONTROLS_QUANTUM: &[&str] = &[
     "antennaHeight",
 ];
 
+/// Controls the RD series sends and takes with 0 as the most
+///
+/// As reported by users of the analog RD radomes; not yet confirmed with a
+/// capture.
+static INVERTED_RD: &[&str] = &["gain", "sea"];
+
 /// Extended controls for RD series
 static CONTROLS_RD: &[&str] = &[
     "interferenceRejection",
@@ -97,7 +104,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_QUANTUM2,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Raymarine,
@@ -113,7 +123,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_QUANTUM2,
+        inverted_controls: &[],
     },
 
     // Quantum Series (non-Doppler)
@@ -131,7 +144,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_QUANTUM,
+        inverted_controls: &[],
     },
     ModelInfo {
         brand: Brand::Raymarine,
@@ -147,7 +163,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 2,
+        magnetron_life_hours: 0,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_QUANTUM,
+        inverted_controls: &[],
     },
 
     // RD/Digital Series
@@ -165,7 +184,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 0,
+        magnetron_life_hours: 2000,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_RD,
+        inverted_controls: INVERTED_RD,
     },
     ModelInfo {
         brand: Brand::Raymarine,
@@ -181,7 +203,10 @@ pub static MODELS: &[ModelInfo] = &[
         has_dual_range: false,
         max_dual_range: 0,
         no_transmit_zone_count: 0,
+        magnetron_life_hours: 2000,
+        antenna_type: Some(AntennaType::Radome),
         controls: CONTROLS_RD,
+        inverted_controls: INVERTED_RD,
     },
 ];
 
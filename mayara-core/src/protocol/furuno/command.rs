@@ -5,6 +5,8 @@
 
 use std::fmt::Write;
 
+use crate::capabilities::controls::MAX_ANTENNA_HEIGHT_METERS;
+
 // =============================================================================
 // Command Mode
 // =============================================================================
@@ -90,6 +92,27 @@ pub const LOGIN_MESSAGE: [u8; 56] = [
     0x4f, 0x2e, 0x2c, 0x4c, 0x54, 0x44, 0x2e, 0x20,
 ];
 
+/// Index of the one byte in [`LOGIN_MESSAGE`] that is not fixed
+///
+/// The message as taken from fnet.dll `login_via_copyright` has 0x01 here,
+/// which the radars mayara has been used with accept. Some firmware
+/// reportedly expects a different (session/sequence) value.
+pub const LOGIN_VARIABLE_BYTE_INDEX: usize = 9;
+
+/// Values tried for the variable login byte, default first
+///
+/// Only 0x01 is confirmed. No capture of a radar rejecting it exists, so
+/// 0x00 and 0x02 are guesses at neighbouring session ids; a known value
+/// can be configured with `--furuno-login-byte`.
+pub const LOGIN_VARIABLE_BYTES: [u8; 3] = [0x01, 0x00, 0x02];
+
+/// Login message with the variable byte set to `variable`
+pub fn format_login_message(variable: u8) -> [u8; 56] {
+    let mut message = LOGIN_MESSAGE;
+    message[LOGIN_VARIABLE_BYTE_INDEX] = variable;
+    message
+}
+
 /// Expected header in login response (8 bytes)
 pub const LOGIN_RESPONSE_HEADER: [u8; 8] = [0x09, 0x01, 0x00, 0x0c, 0x01, 0x00, 0x00, 0x00];
 
@@ -168,7 +191,11 @@ pub fn format_status_command(transmit: bool) -> String {
 /// Formatted command: `$S62,{wire_index},0,0\r\n`
 /// The wire index is looked up from RANGE_TABLE based on the meters value.
 pub fn format_range_command(range_meters: i32) -> String {
-    let wire_index = meters_to_range_index(range_meters);
+    format_range_index_command(meters_to_range_index(range_meters))
+}
+
+/// Format range command for a wire index already looked up in RANGE_TABLE
+pub fn format_range_index_command(wire_index: i32) -> String {
     format_command(CommandMode::Set, CommandId::Range, &[wire_index, 0, 0])
 }
 
@@ -315,25 +342,44 @@ pub fn format_request_txtime() -> String {
     format_command(CommandMode::Request, CommandId::TxTime, &[0])
 }
 
+/// A no-transmit zone as the API sees it: clockwise from `start` to `end`,
+/// in degrees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlindSectorZone {
+    pub start: i32,
+    pub end: i32,
+}
+
+impl BlindSectorZone {
+    /// Convert to the start/width pair used on the wire
+    ///
+    /// Angles are normalized to 0-359 and the width is at least 1°, so a
+    /// zone across north (start=350, end=20) is 30° wide.
+    pub fn start_width(&self) -> (i32, i32) {
+        let start = self.start.rem_euclid(360);
+        let width = (self.end - start).rem_euclid(360).max(1);
+        (start, width)
+    }
+}
+
 /// Format blind sector (no-transmit zone) command
 ///
 /// # Arguments
-/// * `s2_enable` - true to enable sector 2
-/// * `s1_start` - Sector 1 start angle in degrees (0-359)
-/// * `s1_width` - Sector 1 width in degrees (0 to disable)
-/// * `s2_start` - Sector 2 start angle in degrees (0-359)
-/// * `s2_width` - Sector 2 width in degrees
+/// * `zone1` - Sector 1 as start/end, None to disable
+/// * `zone2` - Sector 2 as start/end, None to disable
+///
+/// The zones are converted to the start/width the radar expects; a
+/// disabled sector is sent as start=0, width=0.
 ///
 /// # Returns
 /// Formatted command: `$S77,{s2_enable},{s1_start},{s1_width},{s2_start},{s2_width}\r\n`
 pub fn format_blind_sector_command(
-    s2_enable: bool,
-    s1_start: i32,
-    s1_width: i32,
-    s2_start: i32,
-    s2_width: i32,
+    zone1: Option<BlindSectorZone>,
+    zone2: Option<BlindSectorZone>,
 ) -> String {
-    let s2_val = if s2_enable { 1 } else { 0 };
+    let (s1_start, s1_width) = zone1.map(|z| z.start_width()).unwrap_or((0, 0));
+    let (s2_start, s2_width) = zone2.map(|z| z.start_width()).unwrap_or((0, 0));
+    let s2_val = if zone2.is_some() { 1 } else { 0 };
     format_command(
         CommandMode::Set,
         CommandId::BlindSector,
@@ -379,6 +425,29 @@ pub fn format_interference_rejection_command(enabled: bool) -> String {
     format_command(CommandMode::Set, CommandId::SignalProcessing, &[0, 0, val, 0])
 }
 
+/// Highest interference rejection level on models with leveled IR
+///
+/// FAR series radars report levels 0-2 for feature 0; DRS models only
+/// use 0 (OFF) and 2 (ON).
+pub const MAX_INTERFERENCE_REJECTION_LEVEL: i32 = 2;
+
+/// Format interference rejection level command, for models with leveled IR
+///
+/// # Arguments
+/// * `level` - 0=OFF, 1-2=IR1-IR2 (clamped)
+///
+/// # Returns
+/// Formatted command: `$S67,0,0,{level},0\r\n`
+pub fn format_interference_rejection_level_command(level: i32) -> String {
+    let level = level.clamp(0, MAX_INTERFERENCE_REJECTION_LEVEL);
+    // Feature 0 = Interference Rejection
+    format_command(
+        CommandMode::Set,
+        CommandId::SignalProcessing,
+        &[0, 0, level, 0],
+    )
+}
+
 /// Format RezBoost command
 ///
 /// # Arguments
@@ -467,16 +536,43 @@ pub fn format_heading_align_command(degrees_x10: i32) -> String {
 /// Format antenna height command
 ///
 /// # Arguments
-/// * `meters` - Antenna height in meters
+/// * `meters` - Antenna height in meters, clamped to 0-99
 ///
 /// # Returns
 /// Formatted command: `$S84,0,{meters},0\r\n`
 ///
-/// Antenna height affects sea clutter calculations.
+/// Antenna height affects sea clutter calculations. The wire unit is
+/// whole meters, the same as the `antennaHeight` control.
 pub fn format_antenna_height_command(meters: i32) -> String {
+    let meters = meters.clamp(0, MAX_ANTENNA_HEIGHT_METERS);
     format_command(CommandMode::Set, CommandId::AntennaHeight, &[0, meters, 0])
 }
 
+/// Format request for antenna height
+///
+/// # Returns
+/// Formatted command: `$R84\r\n`
+///
+/// Response format: `$N84,0,{meters},0`
+pub fn format_request_antenna_height() -> String {
+    format_command(CommandMode::Request, CommandId::AntennaHeight, &[])
+}
+
+/// Parse antenna height response
+///
+/// Response: `$N84,0,{meters},0`
+///
+/// # Returns
+/// Antenna height in meters
+#[inline(never)]
+pub fn parse_antenna_height_response(line: &str) -> Option<i32> {
+    let (mode, cmd_id, args) = parse_response(line)?;
+    if mode != CommandMode::New || cmd_id != CommandId::AntennaHeight.as_hex() {
+        return None;
+    }
+    args.get(1).copied()
+}
+
 // =============================================================================
 // Response Parsing
 // =============================================================================
@@ -925,6 +1021,51 @@ pub fn parse_tx_channel_response(line: &str) -> Option<i32> {
     args.first().copied()
 }
 
+/// Parse the channel in use from a TX Channel response
+///
+/// Response: `$NEC,{channel},{active}`
+/// - active: channel the radar picked while in Auto (1-3)
+///
+/// The second field is inferred: no capture with a `$NEC` reply carrying it
+/// is on file yet. A reply with only `$NEC,{channel}` yields None.
+pub fn parse_tx_channel_active(line: &str) -> Option<i32> {
+    let (mode, cmd_id, args) = parse_response(line)?;
+    if mode != CommandMode::New || cmd_id != CommandId::TxChannel.as_hex() {
+        return None;
+    }
+    args.get(1).copied().filter(|c| (1..=3).contains(c))
+}
+
+/// Format request for heading alignment (bearing alignment)
+///
+/// # Returns
+/// Formatted command: `$R81\r\n`
+///
+/// Response format: `$N81,{degrees_x10},0`
+pub fn format_request_heading_align() -> String {
+    format_command(CommandMode::Request, CommandId::HeadingAlign, &[])
+}
+
+/// Parse heading alignment response
+///
+/// Response: `$N81,{degrees_x10},0`
+///
+/// # Returns
+/// Alignment in degrees, normalized to -180..180
+#[inline(never)]
+pub fn parse_heading_align_response(line: &str) -> Option<f64> {
+    let (mode, cmd_id, args) = parse_response(line)?;
+    if mode != CommandMode::New || cmd_id != CommandId::HeadingAlign.as_hex() {
+        return None;
+    }
+    let degrees = *args.first()? as f64 / 10.0;
+    Some(if degrees > 180.0 {
+        degrees - 360.0
+    } else {
+        degrees
+    })
+}
+
 /// Format request for Blind Sector (no-transmit zones) settings
 ///
 /// # Returns
@@ -1052,6 +1193,15 @@ mod tests {
         assert_eq!(cmd, "$RE3\r\n");
     }
 
+    #[test]
+    fn test_format_login_message() {
+        assert_eq!(format_login_message(LOGIN_VARIABLE_BYTES[0]), LOGIN_MESSAGE);
+        let message = format_login_message(0x02);
+        assert_eq!(message[LOGIN_VARIABLE_BYTE_INDEX], 0x02);
+        assert_eq!(message[..9], LOGIN_MESSAGE[..9]);
+        assert_eq!(message[10..], LOGIN_MESSAGE[10..]);
+    }
+
     #[test]
     fn test_parse_login_response() {
         // Simulated response with port offset 0x0001 = 1
@@ -1105,19 +1255,33 @@ mod tests {
 
     #[test]
     fn test_format_blind_sector() {
+        let zone = |start, end| Some(BlindSectorZone { start, end });
+
         // Sector 1 only (200°-300° = width 100°)
-        let cmd = format_blind_sector_command(false, 200, 100, 0, 0);
+        let cmd = format_blind_sector_command(zone(200, 300), None);
         assert_eq!(cmd, "$S77,0,200,100,0,0\r\n");
 
-        // Both sectors
-        let cmd = format_blind_sector_command(true, 200, 100, 320, 60);
+        // Both sectors, the second across north
+        let cmd = format_blind_sector_command(zone(200, 300), zone(320, 20));
         assert_eq!(cmd, "$S77,1,200,100,320,60\r\n");
 
         // Disable all
-        let cmd = format_blind_sector_command(false, 0, 0, 0, 0);
+        let cmd = format_blind_sector_command(None, None);
         assert_eq!(cmd, "$S77,0,0,0,0,0\r\n");
     }
 
+    #[test]
+    fn test_blind_sector_zone_wraparound() {
+        let width = |start, end| BlindSectorZone { start, end }.start_width();
+        assert_eq!(width(350, 20), (350, 30));
+        assert_eq!(width(-10, 20), (350, 30));
+        assert_eq!(width(90, 90), (90, 1));
+
+        // The report converts back to the same start/end
+        let state = parse_blind_sector_response("$N77,0,350,30,0,0").unwrap();
+        assert_eq!((state.sector1_start, state.sector1_end()), (350, 20));
+    }
+
     #[test]
     fn test_format_scan_speed() {
         let cmd = format_scan_speed_command(0); // 24 RPM
@@ -1145,6 +1309,27 @@ mod tests {
         assert_eq!(cmd, "$S67,0,0,0,0\r\n");
     }
 
+    #[test]
+    fn test_format_interference_rejection_level() {
+        assert_eq!(
+            format_interference_rejection_level_command(0),
+            "$S67,0,0,0,0\r\n"
+        );
+        assert_eq!(
+            format_interference_rejection_level_command(1),
+            "$S67,0,0,1,0\r\n"
+        );
+        assert_eq!(
+            format_interference_rejection_level_command(2),
+            "$S67,0,0,2,0\r\n"
+        );
+        // Out of range levels are clamped
+        assert_eq!(
+            format_interference_rejection_level_command(5),
+            "$S67,0,0,2,0\r\n"
+        );
+    }
+
     #[test]
     fn test_format_rezboost() {
         let cmd = format_rezboost_command(0, 0); // OFF, primary
@@ -1221,6 +1406,9 @@ mod tests {
 
         let cmd = format_antenna_height_command(15);
         assert_eq!(cmd, "$S84,0,15,0\r\n");
+
+        assert_eq!(format_antenna_height_command(150), "$S84,0,99,0\r\n");
+        assert_eq!(parse_antenna_height_response("$N84,0,15,0"), Some(15));
     }
 
     // Tests for new response parsers and request formatters
@@ -1398,5 +1586,10 @@ mod tests {
         assert_eq!(parse_tx_channel_response("$NEC,0"), Some(0));
         // Channel 2
         assert_eq!(parse_tx_channel_response("$NEC,2"), Some(2));
+        // Auto, running on channel 3
+        assert_eq!(parse_tx_channel_response("$NEC,0,3"), Some(0));
+        assert_eq!(parse_tx_channel_active("$NEC,0,3"), Some(3));
+        assert_eq!(parse_tx_channel_active("$NEC,0"), None);
+        assert_eq!(parse_tx_channel_active("$NEC,0,0"), None);
     }
 }
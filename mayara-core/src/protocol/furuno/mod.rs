@@ -308,11 +308,33 @@ pub fn parse_model_report(data: &[u8]) -> Result<(Option<String>, Option<String>
     let report: ModelReport = bincode::deserialize(data)?;
 
     let model = c_string(&report.model);
-    let serial_no = c_string(&report.serial_no);
+    let serial_no = clean_serial_no(&report.serial_no);
 
     Ok((model, serial_no))
 }
 
+/// Extract the serial number from a model report field
+///
+/// The field is padded inconsistently between firmware versions: leading
+/// or trailing NULs, spaces or 0xFF bytes. Padding is stripped; anything
+/// left that isn't printable ASCII means the field is garbage, and `None`
+/// is returned rather than surfacing a corrupted `serialNumber`.
+pub fn clean_serial_no(bytes: &[u8]) -> Option<String> {
+    let is_padding = |b: &u8| matches!(b, 0x00 | 0xFF | b' ');
+    let start = bytes.iter().position(|b| !is_padding(b))?;
+    let rest = &bytes[start..];
+    let end = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+    let serial = &rest[..end];
+    let len = serial.iter().rposition(|b| !is_padding(b))? + 1;
+    let serial = &serial[..len];
+
+    if serial.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        Some(String::from_utf8_lossy(serial).into_owned())
+    } else {
+        None
+    }
+}
+
 /// Create the beacon request packet
 pub fn create_beacon_request() -> &'static [u8] {
     &REQUEST_BEACON_PACKET
@@ -648,6 +670,26 @@ mod tests {
         assert_eq!(discovery.max_spoke_len, 884);
     }
 
+    #[test]
+    fn test_spoke_frame_keeps_native_angles() {
+        // Two raw (encoding 0) spokes of 4 samples at the last two angles
+        let mut frame = vec![0u8; 16];
+        frame[0] = 0x02;
+        frame[9] = 2 << 1;
+        frame[10] = 4;
+        for angle in [8190u16, 8191] {
+            frame.extend_from_slice(&angle.to_le_bytes());
+            frame.extend_from_slice(&[0, 0, 0x04, 0x08, 0x0c, 0xfc]);
+        }
+
+        // Spokes are not decimated: angles span all 8192 per revolution
+        let spokes = parse_spoke_frame(&frame, &mut Vec::new()).unwrap();
+        let angles: Vec<u16> = spokes.iter().map(|s| s.angle).collect();
+        assert_eq!(angles, vec![8190, 8191]);
+        assert!(spokes.iter().all(|s| s.angle < SPOKES_PER_REVOLUTION));
+        assert_eq!(spokes[0].data, vec![1, 2, 3, 63]);
+    }
+
     #[test]
     fn test_parse_short_packet() {
         let result = parse_beacon_response(&[0u8; 16], "172.31.6.1");
@@ -677,4 +719,28 @@ mod tests {
         assert!(!is_valid_furuno_ip("172.32.1.1"));
         assert!(!is_valid_furuno_ip("invalid"));
     }
+
+    #[test]
+    fn test_parse_model_report_padded_serial() {
+        let mut report = [0u8; 170];
+        report[48..57].copy_from_slice(b"DRS4D-NXT");
+        // serial_no is the last 26 bytes: leading NUL, trailing spaces and NULs
+        report[144] = 0;
+        report[145..152].copy_from_slice(b"6424180");
+        report[152..155].copy_from_slice(b"   ");
+
+        let (model, serial) = parse_model_report(&report).unwrap();
+        assert_eq!(model.as_deref(), Some("DRS4D-NXT"));
+        assert_eq!(serial.as_deref(), Some("6424180"));
+    }
+
+    #[test]
+    fn test_clean_serial_no() {
+        assert_eq!(clean_serial_no(b"6424180\0\0\0"), Some("6424180".to_string()));
+        assert_eq!(clean_serial_no(b"\xff\xff 6424180 \xff"), Some("6424180".to_string()));
+        assert_eq!(clean_serial_no(b"AB-12\0garbage"), Some("AB-12".to_string()));
+        assert_eq!(clean_serial_no(&[0u8; 26]), None);
+        assert_eq!(clean_serial_no(&[0xFF; 26]), None);
+        assert_eq!(clean_serial_no(b"64\x0124"), None);
+    }
 }
@@ -80,11 +80,11 @@ pub const REPORT_NTZ_MODE: u32 = 0x093f;
 pub const REPORT_NTZ_START: u32 = 0x0940;
 /// No transmit zone end (value/32 = degrees)
 pub const REPORT_NTZ_END: u32 = 0x0941;
-/// Timed idle mode
+/// Timed idle mode (0 = off, 1 = on)
 pub const REPORT_TIMED_IDLE_MODE: u32 = 0x0942;
-/// Timed idle time
+/// Timed idle time: standby period (seconds)
 pub const REPORT_TIMED_IDLE_TIME: u32 = 0x0943;
-/// Timed idle run time
+/// Timed idle run time: transmit period (seconds)
 pub const REPORT_TIMED_IDLE_RUN: u32 = 0x0944;
 /// Scanner status
 pub const REPORT_SCANNER_STATUS: u32 = 0x0992;
@@ -106,24 +106,34 @@ pub enum Report {
     TransmitState(TransmitState),
     /// Range in meters
     Range(u32),
-    /// Gain settings (mode, value, level)
-    Gain {
-        mode: GainMode,
-        value: u32,
-        level: GainLevel,
-    },
+    /// Gain mode (manual or auto)
+    GainMode(GainMode),
+    /// Manual gain (0-100)
+    Gain(u32),
+    /// Autogain level
+    GainLevel(GainLevel),
     /// Bearing alignment in degrees
     BearingAlignment(f32),
     /// Crosstalk rejection
     CrosstalkRejection(u32),
-    /// Rain clutter settings
-    RainClutter { mode: u32, level: u32 },
-    /// Sea clutter settings
-    SeaClutter { mode: u32, level: u32, auto_level: u32 },
+    /// Rain clutter mode, 0 = manual, 1 = auto
+    RainMode(u32),
+    /// Rain clutter (0-100)
+    Rain(u32),
+    /// Sea clutter mode, 0 = manual, 1 = auto
+    SeaMode(u32),
+    /// Sea clutter (0-100)
+    Sea(u32),
+    /// Sea clutter auto level
+    SeaAutoLevel(u32),
     /// No transmit zone settings
     NoTransmitZone { mode: u32, start_deg: f32, end_deg: f32 },
-    /// Timed idle settings
-    TimedIdle { mode: u32, time: u32, run_time: u32 },
+    /// Timed idle (timed transmit) on or off
+    TimedIdleMode(bool),
+    /// Timed idle standby period in seconds
+    TimedIdleTime(u32),
+    /// Timed idle transmit period in seconds
+    TimedIdleRun(u32),
     /// Scanner status
     ScannerStatus { status: u32, change_in_ms: u32 },
     /// Scanner message (model info etc.)
@@ -234,6 +244,52 @@ pub struct ParsedSpokeHeader {
     pub bearing_raw: u16,
 }
 
+/// Packet type of an xHD spoke line
+pub const XHD_LINE_PACKET_TYPE: u32 = 0x02a3;
+
+/// Garmin xHD spoke line header
+///
+/// Every packet on the data address carries a single spoke: this header
+/// followed by one byte per pixel.
+#[derive(Deserialize, Debug, Copy, Clone)]
+#[repr(C, packed)]
+pub struct XhdLineHeader {
+    /// Packet type (0x02a3)
+    pub packet_type: [u8; 4],
+    /// Length of the rest of the packet
+    pub len: [u8; 4],
+    _u01: [u8; 2],
+    /// Number of pixels in the spoke
+    pub scan_length: [u8; 2],
+    /// Angle in 1/8 degrees
+    pub angle: [u8; 2],
+    _u02: [u8; 2],
+    /// Range of the spoke in meters
+    pub range: [u8; 4],
+    /// Range shown on the chartplotter in meters
+    pub display_range: [u8; 4],
+    _u03: [u8; 2],
+    /// Number of pixel bytes that follow
+    pub line_bytes: [u8; 2],
+    _u04: [u8; 2],
+    _line_bytes_u32: [u8; 4],
+    _u05: [u8; 2],
+}
+
+/// xHD spoke line header size in bytes
+pub const XHD_LINE_HEADER_SIZE: usize = std::mem::size_of::<XhdLineHeader>();
+
+/// Parsed xHD spoke line
+#[derive(Debug, Clone)]
+pub struct ParsedXhdLine<'a> {
+    /// Spoke number (0..SPOKES_PER_REVOLUTION)
+    pub angle: u16,
+    /// Range of the spoke in meters
+    pub range_m: u32,
+    /// Raw pixel bytes
+    pub data: &'a [u8],
+}
+
 // =============================================================================
 // Parsing Functions
 // =============================================================================
@@ -281,8 +337,19 @@ pub fn parse_report(data: &[u8]) -> Result<Report, ParseError> {
         REPORT_SCAN_SPEED => Report::ScanSpeed(value),
         REPORT_TRANSMIT_STATE => Report::TransmitState(TransmitState::from_value(value)),
         REPORT_RANGE => Report::Range(value),
+        REPORT_AUTOGAIN => Report::GainMode(GainMode::from_value(value)),
+        REPORT_GAIN => Report::Gain(value),
+        REPORT_AUTOGAIN_LEVEL => Report::GainLevel(GainLevel::from_value(value)),
         REPORT_BEARING_ALIGNMENT => Report::BearingAlignment(value as i32 as f32 / 32.0),
         REPORT_CROSSTALK => Report::CrosstalkRejection(value),
+        REPORT_RAIN_MODE => Report::RainMode(value),
+        REPORT_RAIN_LEVEL => Report::Rain(value),
+        REPORT_SEA_MODE => Report::SeaMode(value),
+        REPORT_SEA_LEVEL => Report::Sea(value),
+        REPORT_SEA_AUTO_LEVEL => Report::SeaAutoLevel(value),
+        REPORT_TIMED_IDLE_MODE => Report::TimedIdleMode(value != 0),
+        REPORT_TIMED_IDLE_TIME => Report::TimedIdleTime(value),
+        REPORT_TIMED_IDLE_RUN => Report::TimedIdleRun(value),
         REPORT_SCANNER_STATUS => Report::ScannerStatus {
             status: value,
             change_in_ms: 0,
@@ -331,6 +398,49 @@ pub fn parse_spoke_header(data: &[u8]) -> Result<ParsedSpokeHeader, ParseError>
     })
 }
 
+/// Parse an xHD spoke line packet
+pub fn parse_xhd_line(data: &[u8]) -> Result<ParsedXhdLine<'_>, ParseError> {
+    if data.len() < XHD_LINE_HEADER_SIZE {
+        return Err(ParseError::TooShort {
+            expected: XHD_LINE_HEADER_SIZE,
+            actual: data.len(),
+        });
+    }
+
+    let header: XhdLineHeader = bincode::deserialize(&data[..XHD_LINE_HEADER_SIZE])?;
+
+    let packet_type = u32::from_le_bytes(header.packet_type);
+    if packet_type != XHD_LINE_PACKET_TYPE {
+        return Err(ParseError::InvalidPacket(format!(
+            "Not an xHD spoke line: packet type 0x{:04x}",
+            packet_type
+        )));
+    }
+
+    let line_bytes = u16::from_le_bytes(header.line_bytes) as usize;
+    let pixels = &data[XHD_LINE_HEADER_SIZE..];
+    if pixels.len() < line_bytes {
+        return Err(ParseError::LengthMismatch {
+            header_len: line_bytes,
+            actual_len: pixels.len(),
+        });
+    }
+
+    // 2880 angle units per revolution, two per spoke
+    let angle = (u16::from_le_bytes(header.angle) / 2) % SPOKES_PER_REVOLUTION;
+
+    Ok(ParsedXhdLine {
+        angle,
+        range_m: u32::from_le_bytes(header.range),
+        data: &pixels[..line_bytes.min(MAX_SPOKE_LEN as usize)],
+    })
+}
+
+/// Convert xHD pixel bytes (0..255) to PIXEL_VALUES intensity levels
+pub fn decode_xhd_pixels(data: &[u8]) -> Vec<u8> {
+    data.iter().map(|p| p >> 4).collect()
+}
+
 /// Create a RadarDiscovery from a Garmin report source
 ///
 /// Garmin discovery is different from other brands - we just need the
@@ -415,6 +525,25 @@ pub fn create_ntz_command(enabled: bool, start_deg: f32, end_deg: f32) -> Vec<u8
     cmds
 }
 
+/// Create a timed transmit command (periods in minutes)
+///
+/// The radar alternates between transmitting for `transmit_minutes` and
+/// standby for `standby_minutes` while the mode is enabled.
+pub fn create_timed_transmit_command(
+    enabled: bool,
+    transmit_minutes: u32,
+    standby_minutes: u32,
+) -> Vec<u8> {
+    let mut cmds = Vec::new();
+    let mode = if enabled { 1u32 } else { 0u32 };
+    cmds.extend(create_command(REPORT_TIMED_IDLE_MODE, mode));
+    if enabled {
+        cmds.extend(create_command(REPORT_TIMED_IDLE_RUN, transmit_minutes * 60));
+        cmds.extend(create_command(REPORT_TIMED_IDLE_TIME, standby_minutes * 60));
+    }
+    cmds
+}
+
 /// Create a raw command packet
 fn create_command(packet_type: u32, value: u32) -> Vec<u8> {
     let mut cmd = Vec::with_capacity(12);
@@ -508,6 +637,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_timed_idle_reports() {
+        // The radar reports the values it was sent by create_timed_transmit_command
+        let cmd = create_timed_transmit_command(true, 5, 10);
+        assert!(matches!(
+            parse_report(&cmd[0..12]),
+            Ok(Report::TimedIdleMode(true))
+        ));
+        assert!(matches!(
+            parse_report(&cmd[12..24]),
+            Ok(Report::TimedIdleRun(300))
+        ));
+        assert!(matches!(
+            parse_report(&cmd[24..36]),
+            Ok(Report::TimedIdleTime(600))
+        ));
+    }
+
+    #[test]
+    fn test_parse_clutter_reports() {
+        // The radar reports each setting in a report of its own
+        let gain = create_gain_command(true, 40);
+        assert!(matches!(
+            parse_report(&gain[0..12]),
+            Ok(Report::GainMode(GainMode::Auto))
+        ));
+        assert!(matches!(parse_report(&gain[12..24]), Ok(Report::Gain(40))));
+        let sea = create_sea_clutter_command(false, 25);
+        assert!(matches!(parse_report(&sea[0..12]), Ok(Report::SeaMode(0))));
+        assert!(matches!(parse_report(&sea[12..24]), Ok(Report::Sea(25))));
+        let rain = create_rain_clutter_command(true, 60);
+        assert!(matches!(
+            parse_report(&rain[0..12]),
+            Ok(Report::RainMode(1))
+        ));
+        assert!(matches!(parse_report(&rain[12..24]), Ok(Report::Rain(60))));
+    }
+
     #[test]
     fn test_create_discovery() {
         let disc = create_discovery("192.168.1.100");
@@ -543,6 +710,42 @@ mod tests {
         assert_eq!(value, 5000);
     }
 
+    #[test]
+    fn test_create_timed_transmit_command() {
+        let cmd = create_timed_transmit_command(true, 5, 10);
+        assert_eq!(cmd.len(), 36);
+        let field = |i: usize| u32::from_le_bytes(cmd[i..i + 4].try_into().unwrap());
+        assert_eq!((field(0), field(4), field(8)), (0x0942, 4, 1));
+        assert_eq!((field(12), field(20)), (0x0944, 300)); // transmit period
+        assert_eq!((field(24), field(32)), (0x0943, 600)); // standby period
+
+        let cmd = create_timed_transmit_command(false, 5, 10);
+        assert_eq!(cmd, create_command(0x0942, 0));
+    }
+
+    #[test]
+    fn test_parse_xhd_line() {
+        let mut data = vec![0u8; XHD_LINE_HEADER_SIZE];
+        data[0..4].copy_from_slice(&XHD_LINE_PACKET_TYPE.to_le_bytes());
+        data[10..12].copy_from_slice(&4u16.to_le_bytes()); // scan length
+        data[12..14].copy_from_slice(&1442u16.to_le_bytes()); // 180.25 degrees
+        data[16..20].copy_from_slice(&1852u32.to_le_bytes());
+        data[26..28].copy_from_slice(&4u16.to_le_bytes()); // pixel bytes
+        data.extend_from_slice(&[0x00, 0x10, 0x80, 0xff, 0xaa]); // one stray byte
+
+        assert_eq!(XHD_LINE_HEADER_SIZE, 36);
+        let line = parse_xhd_line(&data).unwrap();
+        assert_eq!(line.angle, 721);
+        assert_eq!(line.range_m, 1852);
+        assert_eq!(decode_xhd_pixels(line.data), vec![0, 1, 8, 15]);
+
+        // Truncated pixel data
+        assert!(parse_xhd_line(&data[..XHD_LINE_HEADER_SIZE + 2]).is_err());
+        // Report packets on the wrong socket
+        data[0..4].copy_from_slice(&REPORT_RANGE.to_le_bytes());
+        assert!(parse_xhd_line(&data).is_err());
+    }
+
     #[test]
     fn test_spoke_header_size() {
         // Verify our header struct is the expected size
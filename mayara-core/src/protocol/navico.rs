@@ -322,7 +322,10 @@ pub struct Report01 {
     pub what: u8,       // 0x01
     pub command: u8,    // 0xC4
     pub status: u8,
-    _u00: [u8; 15],
+    pub substage: u8, // Progress while preparing
+    _u00: [u8; 2],
+    pub counter: [u8; 2], // u16 LE, progress counter while preparing
+    _u01: [u8; 10],
 }
 
 pub const REPORT_01_SIZE: usize = 18;
@@ -433,7 +436,7 @@ pub struct Report08Base {
     pub what: u8,                   // 0x08
     pub command: u8,                // 0xC4
     pub sea_state: u8,              // 2
-    pub interference_rejection: u8, // 3
+    pub local_interference_rejection: u8, // 3 (not report 02's interference rejection)
     pub scan_speed: u8,             // 4
     pub sls_auto: u8,               // 5 sidelobe suppression auto
     _field6: u8,                    // 6
@@ -626,6 +629,20 @@ impl Status {
 // Parsed Data Structures
 // =============================================================================
 
+/// Parsed Report 01 (status)
+///
+/// While the radar is preparing (spinning up, warming up) it also sends a
+/// substage and a counter. Navico does not document their meaning, so
+/// they are passed through as-is for clients to show as progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusReport {
+    pub status: Status,
+    /// Preparing substage (0 when not preparing)
+    pub substage: u8,
+    /// Preparing progress counter (0 when not preparing)
+    pub counter: u16,
+}
+
 /// Parsed beacon result containing radar endpoint information
 #[derive(Debug, Clone)]
 pub struct ParsedBeacon {
@@ -657,6 +674,7 @@ pub struct ParsedSpoke {
 /// Parsed Report 02 (controls)
 #[derive(Debug, Clone)]
 pub struct ParsedControls {
+    /// Range in decimeters, see [`range_decimeters_to_meters`]
     pub range: i32,
     pub mode: u8,
     pub gain: u8,
@@ -682,7 +700,9 @@ pub struct ParsedModelInfo {
 /// Parsed Report 04 (installation settings)
 #[derive(Debug, Clone)]
 pub struct ParsedInstallation {
+    /// Tenths of a degree
     pub bearing_alignment: i16,
+    /// Millimeters, see [`antenna_height_mm_to_meters`]
     pub antenna_height: u16,
     pub accent_light: u8,
 }
@@ -804,42 +824,43 @@ fn parse_beacon_dual(data: &[u8], source_addr: &str) -> Result<Vec<RadarDiscover
         .ok_or(ParseError::InvalidString)?;
 
     // Dual-range radars have two independent radar endpoints (A and B)
-    Ok(vec![
-        RadarDiscovery {
+    Ok(dual_endpoints(&beacon)
+        .into_iter()
+        .map(|(suffix, radar)| RadarDiscovery {
             brand: Brand::Navico,
             model: None, // Model comes from Report 03
             name: serial_no.clone(),
             address: source_addr.to_string(),
-            data_port: beacon.a.data.port(),
-            command_port: beacon.a.send.port(),
+            data_port: radar.data.port(),
+            command_port: radar.send.port(),
             spokes_per_revolution: SPOKES_PER_REVOLUTION,
             max_spoke_len: MAX_SPOKE_LEN,
             pixel_values: 16, // 4-bit pixels
             serial_number: None,
             nic_address: None, // Set by locator
-            suffix: Some("A".into()),
-            data_address: Some(beacon.a.data.as_string()),
-            report_address: Some(beacon.a.report.as_string()),
-            send_address: Some(beacon.a.send.as_string()),
-        },
-        RadarDiscovery {
-            brand: Brand::Navico,
-            model: None,
-            name: serial_no,
-            address: source_addr.to_string(),
-            data_port: beacon.b.data.port(),
-            command_port: beacon.b.send.port(),
-            spokes_per_revolution: SPOKES_PER_REVOLUTION,
-            max_spoke_len: MAX_SPOKE_LEN,
-            pixel_values: 16,
-            serial_number: None,
-            nic_address: None,
-            suffix: Some("B".into()),
-            data_address: Some(beacon.b.data.as_string()),
-            report_address: Some(beacon.b.report.as_string()),
-            send_address: Some(beacon.b.send.as_string()),
-        },
-    ])
+            suffix: suffix.map(String::from),
+            data_address: Some(radar.data.as_string()),
+            report_address: Some(radar.report.as_string()),
+            send_address: Some(radar.send.as_string()),
+        })
+        .collect())
+}
+
+/// The usable radar endpoints in a dual-range beacon, with their suffix
+///
+/// Some radars send the dual-range beacon layout with the B endpoint unset
+/// (0.0.0.0) or repeating the A endpoint. Treating that as a second range
+/// would create two radars on the same spoke multicast, so such a beacon
+/// yields a single radar without suffix.
+fn dual_endpoints(beacon: &BeaconDual) -> Vec<(Option<&'static str>, BeaconRadar)> {
+    let (a, b) = (beacon.a, beacon.b);
+    let b_unset = b.data.addr == [0, 0, 0, 0];
+    let b_same = b.data.addr == a.data.addr && b.data.port == a.data.port;
+    if b_unset || b_same {
+        vec![(None, a)]
+    } else {
+        vec![(Some("A"), a), (Some("B"), b)]
+    }
 }
 
 fn parse_beacon_single(data: &[u8], source_addr: &str) -> Result<Vec<RadarDiscovery>, ParseError> {
@@ -905,26 +926,22 @@ pub fn parse_beacon_endpoints(data: &[u8]) -> Result<ParsedBeacon, ParseError> {
         let beacon: BeaconDual = bincode::deserialize(data)?;
         let serial_no = c_string(&beacon.header.serial_no)
             .ok_or(ParseError::InvalidString)?;
+        let radars: Vec<ParsedRadarEndpoints> = dual_endpoints(&beacon)
+            .into_iter()
+            .map(|(suffix, radar)| ParsedRadarEndpoints {
+                suffix: suffix.map(String::from),
+                data_addr: radar.data.as_string(),
+                send_addr: radar.send.as_string(),
+                report_addr: radar.report.as_string(),
+            })
+            .collect();
 
         Ok(ParsedBeacon {
             serial_no,
             radar_addr: beacon.header.radar_addr.as_string(),
-            is_dual_range: true,
+            is_dual_range: radars.len() > 1,
             is_br24: false,
-            radars: vec![
-                ParsedRadarEndpoints {
-                    suffix: Some("A".into()),
-                    data_addr: beacon.a.data.as_string(),
-                    send_addr: beacon.a.send.as_string(),
-                    report_addr: beacon.a.report.as_string(),
-                },
-                ParsedRadarEndpoints {
-                    suffix: Some("B".into()),
-                    data_addr: beacon.b.data.as_string(),
-                    send_addr: beacon.b.send.as_string(),
-                    report_addr: beacon.b.report.as_string(),
-                },
-            ],
+            radars,
         })
     } else if data.len() >= BEACON_SINGLE_SIZE {
         let beacon: BeaconSingle = bincode::deserialize(data)?;
@@ -974,6 +991,11 @@ pub fn parse_beacon_endpoints(data: &[u8]) -> Result<ParsedBeacon, ParseError> {
 
 /// Parse Report 01 (status)
 pub fn parse_report_01(data: &[u8]) -> Result<Status, ParseError> {
+    parse_report_01_detail(data).map(|report| report.status)
+}
+
+/// Parse Report 01 (status) including the preparing substage and counter
+pub fn parse_report_01_detail(data: &[u8]) -> Result<StatusReport, ParseError> {
     if data.len() < REPORT_01_SIZE {
         return Err(ParseError::TooShort {
             expected: REPORT_01_SIZE,
@@ -990,8 +1012,20 @@ pub fn parse_report_01(data: &[u8]) -> Result<Status, ParseError> {
         });
     }
 
-    Status::from_byte(report.status)
-        .ok_or(ParseError::InvalidPacket(format!("Unknown status: {}", report.status)))
+    let status = Status::from_byte(report.status).ok_or(ParseError::InvalidPacket(format!(
+        "Unknown status: {}",
+        report.status
+    )))?;
+    let (substage, counter) = if status == Status::Preparing {
+        (report.substage, u16::from_le_bytes(report.counter))
+    } else {
+        (0, 0)
+    };
+    Ok(StatusReport {
+        status,
+        substage,
+        counter,
+    })
 }
 
 /// Parse Report 02 (controls)
@@ -1173,7 +1207,7 @@ pub fn parse_report_08(data: &[u8]) -> Result<ParsedAdvancedSettings, ParseError
 
     Ok(ParsedAdvancedSettings {
         sea_state: report.sea_state,
-        local_interference_rejection: report.interference_rejection,
+        local_interference_rejection: report.local_interference_rejection,
         scan_speed: report.scan_speed,
         sidelobe_suppression_auto: report.sls_auto > 0,
         sidelobe_suppression: report.side_lobe_suppression,
@@ -1353,6 +1387,30 @@ pub fn create_status_command(transmit: bool) -> Vec<u8> {
     vec![0x00, 0xc1, 0x01, 0x01, 0xc1, value]
 }
 
+/// Convert a range in meters to the decimeters used on the wire
+pub fn range_meters_to_decimeters(meters: u32) -> i32 {
+    (meters as i64 * 10).min(i32::MAX as i64) as i32
+}
+
+/// Convert a wire range in decimeters to meters, rounding to the nearest meter
+pub fn range_decimeters_to_meters(decimeters: i32) -> i32 {
+    (decimeters.max(0) + 5) / 10
+}
+
+/// Highest antenna height in whole meters that fits the u16 millimeters on the wire
+pub const NAVICO_MAX_ANTENNA_HEIGHT_METERS: i32 = (u16::MAX / 1000) as i32;
+
+/// Convert an antenna height in meters to the millimeters used on the wire,
+/// clamped to what fits in a u16 (65.535 m)
+pub fn antenna_height_meters_to_mm(meters: f32) -> u16 {
+    (meters * 1000.0).round().clamp(0.0, u16::MAX as f32) as u16
+}
+
+/// Convert a wire antenna height in millimeters to meters
+pub fn antenna_height_mm_to_meters(mm: u16) -> f32 {
+    mm as f32 / 1000.0
+}
+
 /// Generate range command (range in decimeters)
 pub fn create_range_command(decimeters: i32) -> Vec<u8> {
     let mut cmd = vec![0x03, 0xc1];
@@ -1379,6 +1437,14 @@ pub fn create_interference_rejection_command(level: u8) -> Vec<u8> {
     vec![0x08, 0xc1, level]
 }
 
+/// Generate local interference rejection command
+///
+/// Separate from [`create_interference_rejection_command`]; read back in
+/// report 08 rather than report 02.
+pub fn create_local_interference_rejection_command(level: u8) -> Vec<u8> {
+    vec![0x0e, 0xc1, level]
+}
+
 /// Generate scan speed command
 pub fn create_scan_speed_command(speed: u8) -> Vec<u8> {
     vec![0x0f, 0xc1, speed]
@@ -1543,6 +1609,28 @@ mod tests {
         assert!(!Model::BR24.has_doppler());
     }
 
+    #[test]
+    fn test_range_decimeters_conversion() {
+        assert_eq!(range_meters_to_decimeters(1852), 18520);
+        assert_eq!(range_decimeters_to_meters(18520), 1852);
+        // Radars may report ranges that are not whole meters
+        assert_eq!(range_decimeters_to_meters(18524), 1852);
+        assert_eq!(range_decimeters_to_meters(18525), 1853);
+        assert_eq!(range_decimeters_to_meters(0), 0);
+        assert_eq!(range_decimeters_to_meters(-1), 0);
+        assert_eq!(range_meters_to_decimeters(u32::MAX), i32::MAX);
+    }
+
+    #[test]
+    fn test_antenna_height_clamped_to_u16_millimeters() {
+        assert_eq!(antenna_height_meters_to_mm(15.0), 15000);
+        assert_eq!(antenna_height_meters_to_mm(65.535), u16::MAX);
+        // 99 m does not fit in u16 millimeters
+        assert_eq!(antenna_height_meters_to_mm(99.0), u16::MAX);
+        assert_eq!(antenna_height_meters_to_mm(-1.0), 0);
+        assert_eq!(NAVICO_MAX_ANTENNA_HEIGHT_METERS, 65);
+    }
+
     #[test]
     fn test_heading_extraction() {
         // True heading 1000 = 0x4000 | 1000 = 0x43E8
@@ -1611,6 +1699,28 @@ mod tests {
         assert_eq!(Status::from_byte(3), None);
     }
 
+    #[test]
+    fn test_parse_report_01_preparing_substage() {
+        let mut data = [0u8; REPORT_01_SIZE];
+        data[0] = 0x01;
+        data[1] = 0xC4;
+        data[2] = 5; // Preparing
+        data[3] = 2;
+        data[6..8].copy_from_slice(&45u16.to_le_bytes());
+
+        let report = parse_report_01_detail(&data).unwrap();
+        assert_eq!(report.status, Status::Preparing);
+        assert_eq!(report.substage, 2);
+        assert_eq!(report.counter, 45);
+        assert_eq!(parse_report_01(&data).unwrap(), Status::Preparing);
+
+        // Substage bytes mean nothing outside of preparing
+        data[2] = 2; // Transmit
+        let report = parse_report_01_detail(&data).unwrap();
+        assert_eq!(report.status, Status::Transmit);
+        assert_eq!((report.substage, report.counter), (0, 0));
+    }
+
     #[test]
     fn test_create_commands() {
         let status_cmd = create_status_command(true);
@@ -1634,6 +1744,87 @@ mod tests {
         assert!(BEACON_DUAL_SIZE > BEACON_SINGLE_SIZE);
     }
 
+    /// Build a dual-range beacon; endpoints are (data, send, report) as
+    /// (ip, port), written at their offsets in [`BeaconRadar`]
+    fn dual_beacon(a: [([u8; 4], u16); 3], b: [([u8; 4], u16); 3]) -> Vec<u8> {
+        let mut data = vec![0u8; BEACON_DUAL_SIZE];
+        data[0..2].copy_from_slice(&BEACON_RESPONSE_HEADER);
+        data[2..12].copy_from_slice(b"1403200123");
+        data[18..22].copy_from_slice(&[192, 168, 1, 50]);
+        data[22..24].copy_from_slice(&6878u16.to_be_bytes());
+        let header_size = std::mem::size_of::<BeaconHeader>();
+        let radar_size = std::mem::size_of::<BeaconRadar>();
+        for (start, endpoints) in [(header_size, a), (header_size + radar_size, b)] {
+            for ((ip, port), offset) in endpoints.into_iter().zip([10, 20, 30]) {
+                data[start + offset..start + offset + 4].copy_from_slice(&ip);
+                data[start + offset + 4..start + offset + 6].copy_from_slice(&port.to_be_bytes());
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_dual_beacon_two_endpoints() {
+        let a = [
+            ([236, 6, 7, 8], 6678),
+            ([236, 6, 7, 10], 6680),
+            ([236, 6, 7, 9], 6679),
+        ];
+        let b = [
+            ([236, 6, 7, 13], 6657),
+            ([236, 6, 7, 14], 6658),
+            ([236, 6, 7, 15], 6659),
+        ];
+        let beacon = dual_beacon(a, b);
+
+        let parsed = parse_beacon_endpoints(&beacon).unwrap();
+        assert_eq!(parsed.serial_no, "1403200123");
+        assert_eq!(parsed.radar_addr, "192.168.1.50:6878");
+        assert!(parsed.is_dual_range);
+        assert_eq!(parsed.radars.len(), 2);
+        assert_eq!(parsed.radars[0].suffix.as_deref(), Some("A"));
+        assert_eq!(parsed.radars[0].data_addr, "236.6.7.8:6678");
+        assert_eq!(parsed.radars[0].send_addr, "236.6.7.10:6680");
+        assert_eq!(parsed.radars[0].report_addr, "236.6.7.9:6679");
+        assert_eq!(parsed.radars[1].suffix.as_deref(), Some("B"));
+        assert_eq!(parsed.radars[1].data_addr, "236.6.7.13:6657");
+        assert_eq!(parsed.radars[1].send_addr, "236.6.7.14:6658");
+        assert_eq!(parsed.radars[1].report_addr, "236.6.7.15:6659");
+
+        let discoveries = parse_beacon_response(&beacon, "192.168.1.50:6878").unwrap();
+        assert_eq!(discoveries.len(), 2);
+        assert!(discoveries.iter().all(|d| d.name == "1403200123"));
+        assert_eq!(discoveries[0].suffix.as_deref(), Some("A"));
+        assert_eq!(discoveries[1].suffix.as_deref(), Some("B"));
+        assert_eq!(discoveries[0].data_port, 6678);
+        assert_eq!(discoveries[1].data_port, 6657);
+        assert_ne!(discoveries[0].data_address, discoveries[1].data_address);
+        assert_ne!(discoveries[0].report_address, discoveries[1].report_address);
+        assert_ne!(discoveries[0].send_address, discoveries[1].send_address);
+    }
+
+    #[test]
+    fn test_dual_beacon_without_second_range() {
+        let a = [
+            ([236, 6, 7, 8], 6678),
+            ([236, 6, 7, 10], 6680),
+            ([236, 6, 7, 9], 6679),
+        ];
+        for b in [a, [([0, 0, 0, 0], 0); 3]] {
+            let beacon = dual_beacon(a, b);
+
+            let parsed = parse_beacon_endpoints(&beacon).unwrap();
+            assert!(!parsed.is_dual_range);
+            assert_eq!(parsed.radars.len(), 1);
+            assert_eq!(parsed.radars[0].suffix, None);
+            assert_eq!(parsed.radars[0].data_addr, "236.6.7.8:6678");
+
+            let discoveries = parse_beacon_response(&beacon, "192.168.1.50:6878").unwrap();
+            assert_eq!(discoveries.len(), 1);
+            assert_eq!(discoveries[0].suffix, None);
+        }
+    }
+
     #[test]
     fn test_format_heading_packet() {
         let packet = format_heading_packet(90.0, 1, 1234567890000);
@@ -1691,7 +1882,7 @@ mod tests {
         let data = vec![
             0x08, 0xC4, // what, command
             0x01,       // sea_state = 1
-            0x02,       // interference_rejection = 2
+            0x02,       // local_interference_rejection = 2
             0x01,       // scan_speed = 1
             0x01,       // sls_auto = 1 (true)
             0x00, 0x00, 0x00, // fields 6-8
@@ -481,9 +481,10 @@ pub fn parse_beacon_response(data: &[u8], source_addr: &str) -> Result<RadarDisc
             serial_number: None,
             nic_address: None, // Set by locator
             suffix: None,
-            data_address: None,
-            report_address: None,
-            send_address: None,
+            // Spokes and reports share the report multicast group
+            data_address: Some(beacon.report_addr.clone()),
+            report_address: Some(beacon.report_addr),
+            send_address: Some(beacon.command_addr),
         });
     }
 
@@ -908,17 +909,17 @@ pub fn create_mfd_beacon() -> &'static [u8] {
 // =============================================================================
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
     // Real Quantum beacon data from pcap
-    const QUANTUM_BEACON_36: [u8; 36] = [
+    pub(crate) const QUANTUM_BEACON_36: [u8; 36] = [
         0x0, 0x0, 0x0, 0x0, 0x58, 0x6b, 0x80, 0xd6, 0x28, 0x0, 0x0, 0x0, 0x3, 0x0, 0x64, 0x0,
         0x6, 0x8, 0x10, 0x0, 0x1, 0xf3, 0x1, 0xe8, 0xe, 0xa, 0x11, 0x0, 0xd6, 0x6, 0x12, 0xc6,
         0xf, 0xa, 0x36, 0x0,
     ];
 
-    const QUANTUM_BEACON_56: [u8; 56] = [
+    pub(crate) const QUANTUM_BEACON_56: [u8; 56] = [
         0x1, 0x0, 0x0, 0x0, 0x66, 0x0, 0x0, 0x0, 0x58, 0x6b, 0x80, 0xd6, 0xf3, 0x0, 0x0, 0x0,
         0xf3, 0x0, 0xa8, 0xc0, 0x51, 0x75, 0x61, 0x6e, 0x74, 0x75, 0x6d, 0x52, 0x61, 0x64,
         0x61, 0x72, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
@@ -975,6 +976,19 @@ mod tests {
         assert!(beacon.command_addr.starts_with("198.18.6.214:"));
     }
 
+    #[test]
+    fn test_beacon_36_discovery_endpoints() {
+        let discovery = parse_beacon_response(&QUANTUM_BEACON_36, "198.18.6.214").unwrap();
+        assert_eq!(
+            discovery.report_address.as_deref(),
+            Some("232.1.243.1:2574")
+        );
+        assert_eq!(discovery.data_address, discovery.report_address);
+        assert_eq!(discovery.send_address.as_deref(), Some("198.18.6.214:2575"));
+        assert_eq!(discovery.data_port, 2574);
+        assert_eq!(discovery.command_port, 2575);
+    }
+
     #[test]
     fn test_parse_rd_beacon_56() {
         let result = parse_beacon_56(&RD_BEACON_56);
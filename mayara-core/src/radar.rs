@@ -4,6 +4,8 @@
 //! independent of any I/O or networking code.
 
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr, SocketAddrV6};
+use crate::capabilities::CapabilitySummary;
 use crate::Brand;
 
 /// Basic radar information discovered from beacon response
@@ -48,6 +50,30 @@ pub struct RadarDiscovery {
     pub send_address: Option<String>,
 }
 
+#[cfg(test)]
+impl RadarDiscovery {
+    /// A discovery as a beacon would report it, for tests
+    pub(crate) fn for_test(brand: Brand, name: &str, address: &str) -> Self {
+        RadarDiscovery {
+            brand,
+            model: None,
+            name: name.to_string(),
+            address: address.to_string(),
+            data_port: 0,
+            command_port: 0,
+            spokes_per_revolution: 2048,
+            max_spoke_len: 1024,
+            pixel_values: 16,
+            serial_number: None,
+            nic_address: None,
+            suffix: None,
+            data_address: None,
+            report_address: None,
+            send_address: None,
+        }
+    }
+}
+
 /// Legend entry for mapping pixel values to colors
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LegendEntry {
@@ -58,6 +84,42 @@ pub struct LegendEntry {
     pub color: String,
 }
 
+/// Parse legend colors into RGBA, indexed by pixel value
+///
+/// Accepts `#RRGGBBAA` and `#RRGGBB` (opaque); unparseable colors become
+/// transparent.
+pub fn legend_colors(legend: &[LegendEntry]) -> Vec<[u8; 4]> {
+    legend
+        .iter()
+        .map(|entry| {
+            let hex = entry.color.trim_start_matches('#');
+            let byte = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+            };
+            match (hex.len(), byte(0), byte(2), byte(4)) {
+                (6, Some(r), Some(g), Some(b)) => [r, g, b, 255],
+                (8, Some(r), Some(g), Some(b)) => [r, g, b, byte(6).unwrap_or(0)],
+                _ => [0, 0, 0, 0],
+            }
+        })
+        .collect()
+}
+
+/// Effective earth radius for radar propagation: the standard atmosphere
+/// bends radar waves as if the earth were 4/3 its real size
+const RADAR_EARTH_RADIUS_M: f64 = 6_371_000.0 * 4.0 / 3.0;
+
+/// Radar horizon in meters for an antenna `antenna_height_m` above the water
+///
+/// With a `target_height_m`, this is the distance at which the top of such
+/// a target rises above the horizon. Lower or more distant targets cannot
+/// be seen, whatever the range setting.
+pub fn radar_horizon_m(antenna_height_m: f64, target_height_m: Option<f64>) -> f64 {
+    let horizon = |height: f64| (2.0 * RADAR_EARTH_RADIUS_M * height.max(0.0)).sqrt();
+    horizon(antenna_height_m) + target_height_m.map_or(0.0, horizon)
+}
+
 /// Radar control value
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -124,6 +186,54 @@ pub struct RadarState {
     /// Optional external stream URL
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream_url: Option<String>,
+    /// Compact capability summary, sent with discovery only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<CapabilitySummary>,
+}
+
+/// Payload size above which [`RadarState::discovery_json`] sends the legend
+/// separately instead of inline. Kept well under typical host message limits.
+pub const MAX_STATE_PAYLOAD_BYTES: usize = 16 * 1024;
+
+impl RadarState {
+    /// SignalK path for the legend when it is emitted on its own
+    pub fn legend_path(&self) -> String {
+        format!("radars.{}.legend", self.id)
+    }
+
+    /// State for the frequent periodic emit.
+    ///
+    /// The legend doesn't change after discovery and can have 256 entries,
+    /// so it is left out here, as is the capability summary; clients get
+    /// both from the discovery emit.
+    pub fn periodic_json(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("legend");
+            obj.remove("capabilities");
+        }
+        value
+    }
+
+    /// State for the one-off discovery emit.
+    ///
+    /// Returns the full state when it serializes to at most `max_bytes`.
+    /// Otherwise the legend is split off: the first value is the state
+    /// without it, the second is the legend to emit at [`Self::legend_path`].
+    pub fn discovery_json(
+        &self,
+        max_bytes: usize,
+    ) -> (serde_json::Value, Option<serde_json::Value>) {
+        let full = serde_json::to_value(self).unwrap_or_default();
+        let size = serde_json::to_string(&full)
+            .map(|s| s.len())
+            .unwrap_or(usize::MAX);
+        if size <= max_bytes {
+            return (full, None);
+        }
+        let legend = serde_json::to_value(&self.legend).unwrap_or_default();
+        (self.periodic_json(), Some(legend))
+    }
 }
 
 /// Radar operational status
@@ -160,38 +270,213 @@ impl std::fmt::Display for RadarStatus {
     }
 }
 
-/// Parsed IPv4 address with port
+/// Parsed IPv4 or IPv6 address with port
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ParsedAddress {
-    pub ip: [u8; 4],
+    pub ip: IpAddr,
     pub port: u16,
+    /// Interface index of an IPv6 address ("fe80::1%3"), 0 if none.
+    /// Link-local addresses cannot be reached without it.
+    pub scope_id: u32,
 }
 
 impl ParsedAddress {
-    /// Parse address string "ip:port" or just "ip" (port defaults to 0)
+    /// Parse address string "ip:port", "[ipv6]:port" or just the ip
+    /// (port defaults to 0). IPv6 addresses may carry a numeric scope id,
+    /// as in "[fe80::1%3]:10010".
     pub fn parse(addr: &str) -> Result<Self, &'static str> {
-        if let Some(colon_pos) = addr.rfind(':') {
-            let ip_str = &addr[..colon_pos];
-            let port_str = &addr[colon_pos + 1..];
-            let ip = Self::parse_ipv4(ip_str)?;
-            let port: u16 = port_str.parse().map_err(|_| "Invalid port")?;
-            Ok(ParsedAddress { ip, port })
+        if let Ok(sa) = addr.parse::<SocketAddr>() {
+            let scope_id = match sa {
+                SocketAddr::V6(v6) => v6.scope_id(),
+                SocketAddr::V4(_) => 0,
+            };
+            return Ok(ParsedAddress {
+                ip: sa.ip(),
+                port: sa.port(),
+                scope_id,
+            });
+        }
+        let (host, scope_id) = match Self::host(addr).split_once('%') {
+            Some((host, scope)) => (host, scope.parse().map_err(|_| "Invalid scope id")?),
+            None => (Self::host(addr), 0),
+        };
+        let ip = host.parse::<IpAddr>().map_err(|_| "Invalid IP address")?;
+        if scope_id != 0 && ip.is_ipv4() {
+            return Err("Invalid scope id");
+        }
+        let has_port = if ip.is_ipv4() {
+            addr.contains(':')
         } else {
-            let ip = Self::parse_ipv4(addr)?;
-            Ok(ParsedAddress { ip, port: 0 })
+            addr.starts_with('[') && !addr.ends_with(']')
+        };
+        if has_port {
+            return Err("Invalid port");
+        }
+        Ok(ParsedAddress {
+            ip,
+            port: 0,
+            scope_id,
+        })
+    }
+
+    /// The address as a socket address, keeping the IPv6 scope id
+    pub fn socket_addr(&self) -> SocketAddr {
+        match self.ip {
+            IpAddr::V6(ip) => SocketAddr::V6(SocketAddrV6::new(ip, self.port, 0, self.scope_id)),
+            IpAddr::V4(_) => SocketAddr::new(self.ip, self.port),
         }
     }
 
-    /// Parse IPv4 address string into bytes
-    fn parse_ipv4(s: &str) -> Result<[u8; 4], &'static str> {
-        let parts: Vec<&str> = s.split('.').collect();
-        if parts.len() != 4 {
-            return Err("Invalid IPv4 format");
+    /// The host part of an address string, without port or brackets
+    ///
+    /// A bare IPv6 address is returned as is, as its colons are not a
+    /// port separator.
+    pub fn host(addr: &str) -> &str {
+        if let Some(rest) = addr.strip_prefix('[') {
+            return rest.split(']').next().unwrap_or(rest);
+        }
+        match addr.rsplit_once(':') {
+            Some((host, _)) if !host.contains(':') => host,
+            _ => addr,
         }
-        let mut ip = [0u8; 4];
-        for (i, part) in parts.iter().enumerate() {
-            ip[i] = part.parse().map_err(|_| "Invalid IPv4 octet")?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radar_horizon() {
+        // Standard 4/3-earth radar horizon: d (km) = 4.12 * sqrt(h (m))
+        let close = |actual: f64, expected_km: f64| (actual / 1000.0 - expected_km).abs() < 0.05;
+        assert!(close(radar_horizon_m(4.0, None), 4.12 * 2.0));
+        assert!(close(radar_horizon_m(10.0, None), 4.12 * 10f64.sqrt()));
+        assert!(close(radar_horizon_m(25.0, None), 4.12 * 5.0));
+
+        // A 16 m target is visible from further away than the sea surface
+        assert!(close(radar_horizon_m(9.0, Some(16.0)), 4.12 * (3.0 + 4.0)));
+
+        assert_eq!(radar_horizon_m(0.0, None), 0.0);
+        assert_eq!(radar_horizon_m(-1.0, Some(0.0)), 0.0);
+    }
+
+    fn state_with_legend(entries: usize) -> RadarState {
+        RadarState {
+            id: "radar-1".to_string(),
+            name: "Test".to_string(),
+            brand: Brand::Navico,
+            model: None,
+            status: RadarStatus::Standby,
+            spokes_per_revolution: 2048,
+            max_spoke_len: 1024,
+            legend: (0..entries)
+                .map(|i| LegendEntry {
+                    pixel_type: "Normal".to_string(),
+                    color: format!("#{:02X}{:02X}00FF", i % 256, i % 256),
+                })
+                .collect(),
+            controls: std::collections::HashMap::new(),
+            stream_url: None,
+            capabilities: None,
         }
-        Ok(ip)
+    }
+
+    #[test]
+    fn test_legend_colors() {
+        let entry = |color: &str| LegendEntry {
+            pixel_type: "Normal".to_string(),
+            color: color.to_string(),
+        };
+        let colors = legend_colors(&[entry("#00FF0080"), entry("#102030"), entry("red")]);
+        assert_eq!(
+            colors,
+            vec![[0, 255, 0, 128], [16, 32, 48, 255], [0, 0, 0, 0]]
+        );
+    }
+
+    #[test]
+    fn test_periodic_json_excludes_legend() {
+        let state = state_with_legend(256);
+        let json = state.periodic_json();
+        assert!(json.get("legend").is_none());
+        assert!(json.get("capabilities").is_none());
+        assert_eq!(json["id"], "radar-1");
+        assert_eq!(json["maxSpokeLen"], 1024);
+    }
+
+    #[test]
+    fn test_discovery_json_splits_large_legend() {
+        let state = state_with_legend(4);
+        let (full, legend) = state.discovery_json(MAX_STATE_PAYLOAD_BYTES);
+        assert_eq!(full["legend"].as_array().unwrap().len(), 4);
+        assert!(legend.is_none());
+
+        let state = state_with_legend(256);
+        let (full, legend) = state.discovery_json(1024);
+        assert!(full.get("legend").is_none());
+        assert_eq!(legend.unwrap().as_array().unwrap().len(), 256);
+        assert_eq!(state.legend_path(), "radars.radar-1.legend");
+    }
+
+    #[test]
+    fn test_discovery_json_includes_features() {
+        use crate::capabilities::SupportedFeature;
+
+        let mut state = state_with_legend(4);
+        state.capabilities = Some(CapabilitySummary {
+            supported_features: vec![SupportedFeature::Arpa, SupportedFeature::GuardZones],
+            supported_ranges: vec![50, 100],
+            control_ids: vec!["gain".to_string()],
+            ..Default::default()
+        });
+        let (full, _) = state.discovery_json(MAX_STATE_PAYLOAD_BYTES);
+        assert_eq!(
+            full["capabilities"]["supportedFeatures"],
+            serde_json::json!(["arpa", "guardZones"])
+        );
+    }
+
+    #[test]
+    fn test_parsed_address() {
+        let a = ParsedAddress::parse("192.168.1.100:10010").unwrap();
+        assert_eq!(a.ip, IpAddr::from([192, 168, 1, 100]));
+        assert_eq!(a.port, 10010);
+        assert_eq!(ParsedAddress::parse("172.31.1.4").unwrap().port, 0);
+
+        let a = ParsedAddress::parse("[fe80::1:2]:10010").unwrap();
+        assert_eq!(a.ip, "fe80::1:2".parse::<IpAddr>().unwrap());
+        assert_eq!(a.socket_addr().to_string(), "[fe80::1:2]:10010");
+        let a = ParsedAddress::parse("fd00::5").unwrap();
+        assert_eq!(a.ip, "fd00::5".parse::<IpAddr>().unwrap());
+        assert_eq!(a.port, 0);
+
+        assert!(ParsedAddress::parse("192.168.1.100:port").is_err());
+        assert!(ParsedAddress::parse("[fd00::5]:port").is_err());
+        assert!(ParsedAddress::parse("not an address").is_err());
+
+        assert_eq!(ParsedAddress::host("192.168.1.100:10010"), "192.168.1.100");
+        assert_eq!(ParsedAddress::host("192.168.1.100"), "192.168.1.100");
+        assert_eq!(ParsedAddress::host("[fd00::5]:10010"), "fd00::5");
+        assert_eq!(ParsedAddress::host("fd00::5"), "fd00::5");
+    }
+
+    #[test]
+    fn test_parsed_link_local_address_keeps_scope_id() {
+        let a = ParsedAddress::parse("[fe80::1:2%3]:10010").unwrap();
+        assert_eq!(a.ip, "fe80::1:2".parse::<IpAddr>().unwrap());
+        assert_eq!(a.scope_id, 3);
+        assert_eq!(a.socket_addr().to_string(), "[fe80::1:2%3]:10010");
+
+        let a = ParsedAddress::parse("fe80::1:2%3").unwrap();
+        assert_eq!((a.port, a.scope_id), (0, 3));
+        assert_eq!(ParsedAddress::parse("[fe80::1:2%3]").unwrap().scope_id, 3);
+        assert_eq!(
+            ParsedAddress::parse("[fe80::1:2]:10010").unwrap().scope_id,
+            0
+        );
+
+        assert!(ParsedAddress::parse("fe80::1:2%eth0").is_err());
+        assert!(ParsedAddress::parse("172.31.1.4%3").is_err());
     }
 }
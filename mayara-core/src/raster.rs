@@ -0,0 +1,156 @@
+//! Polar to cartesian rasterization of spokes
+//!
+//! Turns one revolution of spokes into a square intensity grid, for
+//! consumers such as image analysis that want a raster rather than polar
+//! spokes. This is independent of the display path in [`crate::spoke`]
+//! and of any legend: grid cells hold the raw spoke pixel values.
+//!
+//! The grid has north (spoke angle 0) up and angles run clockwise. Rows
+//! are top to bottom, one byte per cell; the radar is at the center and
+//! the edge of the spokes touches the middle of each side of the grid.
+
+use crate::arpa::PolarConverter;
+
+/// How grid cells sample the spokes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Value of the nearest spoke sample; blocky at low spoke counts
+    #[default]
+    Nearest,
+    /// Blend of the two nearest spokes and samples, which smooths the
+    /// wedges that far-out cells see when there are few spokes
+    Bilinear,
+}
+
+/// Rasterize a revolution of spokes into a `size` x `size` grid
+///
+/// `spokes` is indexed by spoke angle, so its length is the number of
+/// spokes per revolution. Spokes not received yet may be empty; shorter
+/// spokes read as 0 beyond their end. Cells outside the radar circle are 0.
+///
+/// Cells are sampled at their centers, so no cell falls exactly on the
+/// radar position where the angle is undefined; near the center bilinear
+/// interpolation averages across the spokes the cell touches.
+pub fn rasterize_spokes<S: AsRef<[u8]>>(
+    spokes: &[S],
+    size: usize,
+    interpolation: Interpolation,
+) -> Vec<u8> {
+    let mut grid = vec![0u8; size * size];
+    let spoke_len = spokes.iter().map(|s| s.as_ref().len()).max().unwrap_or(0);
+    if size == 0 || spoke_len == 0 {
+        return grid;
+    }
+
+    let center = size as f64 / 2.0;
+    // One grid cell is one "meter" to the converter
+    let converter = PolarConverter::new(spokes.len() as i32, spoke_len as f64 / center);
+    let sample = |angle: i32, r: usize| -> f64 {
+        let spoke = spokes[converter.mod_spokes(angle) as usize].as_ref();
+        spoke.get(r).copied().unwrap_or(0) as f64
+    };
+
+    for y in 0..size {
+        for x in 0..size {
+            let north = center - (y as f64 + 0.5);
+            let east = x as f64 + 0.5 - center;
+            let (angle, r) = converter.local_to_polar_f64(north, east);
+            if r >= spoke_len as f64 {
+                continue;
+            }
+            let value = match interpolation {
+                Interpolation::Nearest => sample(angle.round() as i32, r as usize),
+                Interpolation::Bilinear => {
+                    // Sample centers are at half-integer positions
+                    let r = (r - 0.5).max(0.0);
+                    let (a0, r0) = (angle.floor(), r.floor());
+                    let (fa, fr) = (angle - a0, r - r0);
+                    let (a0, r0) = (a0 as i32, r0 as usize);
+                    let inner = sample(a0, r0) * (1.0 - fa) + sample(a0 + 1, r0) * fa;
+                    let outer = sample(a0, r0 + 1) * (1.0 - fa) + sample(a0 + 1, r0 + 1) * fa;
+                    inner * (1.0 - fr) + outer * fr
+                }
+            };
+            grid[y * size + x] = value.round() as u8;
+        }
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_range_ring_is_circle() {
+        const SIZE: usize = 101;
+        const SPOKE_LEN: usize = 256;
+        const RING: usize = 128; // Half way out: radius 25.25 cells
+        let spoke: Vec<u8> = (0..SPOKE_LEN)
+            .map(|r| {
+                if (RING - 2..=RING + 2).contains(&r) {
+                    200
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        for (spokes, interpolation) in [
+            (2048, Interpolation::Nearest),
+            (64, Interpolation::Nearest),
+            (64, Interpolation::Bilinear),
+        ] {
+            let grid = rasterize_spokes(&vec![spoke.clone(); spokes], SIZE, interpolation);
+            let center = SIZE as f64 / 2.0;
+            let radius = RING as f64 + 0.5;
+            let cell_radius = radius * center / SPOKE_LEN as f64;
+
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    let dx = x as f64 + 0.5 - center;
+                    let dy = y as f64 + 0.5 - center;
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    if grid[y * SIZE + x] > 0 {
+                        assert!(
+                            (distance - cell_radius).abs() < 1.0,
+                            "{} spokes {:?}: lit cell at {} cells, ring at {}",
+                            spokes,
+                            interpolation,
+                            distance,
+                            cell_radius
+                        );
+                    }
+                }
+            }
+            // The circle is closed: every direction hits a lit cell
+            for degrees in 0..360 {
+                let bearing = (degrees as f64).to_radians();
+                let x = (center + cell_radius * bearing.sin()) as usize;
+                let y = (center - cell_radius * bearing.cos()) as usize;
+                let hit = (y - 1..=y + 1).any(|y| (x - 1..=x + 1).any(|x| grid[y * SIZE + x] > 0));
+                assert!(
+                    hit,
+                    "{} spokes {:?}: gap at {} degrees",
+                    spokes, interpolation, degrees
+                );
+            }
+            let mid = SIZE / 2;
+            let ring_cell = (center + cell_radius) as usize;
+            assert!(grid[mid * SIZE + ring_cell] > 0);
+            assert!(grid[mid * SIZE + (SIZE - 1 - ring_cell)] > 0);
+            assert!(grid[ring_cell * SIZE + mid] > 0);
+            assert_eq!(grid[mid * SIZE + mid], 0);
+        }
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let spokes: Vec<Vec<u8>> = Vec::new();
+        assert_eq!(
+            rasterize_spokes(&spokes, 4, Interpolation::Nearest),
+            vec![0; 16]
+        );
+        assert!(rasterize_spokes(&[vec![1u8; 8]], 0, Interpolation::Bilinear).is_empty());
+    }
+}
@@ -0,0 +1,717 @@
+//! Spoke post-processing for display output
+//!
+//! Functions in this module only change what is *displayed*. They must be
+//! applied after ARPA, guard zones and trails have seen the raw spoke, so that
+//! detection thresholds keep working on the values the radar actually sent.
+//!
+//! [`RotationMonitor`] watches the spoke stream for a stalled antenna.
+//! [`FrameTearDetector`] counts spoke groups lost between radar frames;
+//! [`interpolate_spokes`] can fill the resulting wedge for display.
+//! [`SweepFade`] gives emitted spokes an afterglow over successive sweeps.
+//! [`SpokeQuantizer`] reduces the bit depth of emitted pixels.
+//! [`SpokeConvention`] renumbers emitted spokes for renderers that expect
+//! spoke 0 elsewhere or a counter-clockwise rotation.
+
+use serde::{Deserialize, Serialize};
+
+/// Sensitivity-time-control (STC) curve applied to emitted spokes.
+///
+/// Radar echoes weaken with distance, so weak far targets fade while near
+/// clutter saturates. The curve multiplies each pixel by a gain that grows
+/// linearly with the sample index (distance from the antenna):
+///
+/// ```text
+/// gain(i) = 1.0 + slope * i / (len - 1)
+/// ```
+///
+/// A slope of `0.0` leaves the spoke unchanged, a slope of `1.0` doubles
+/// the intensity of the last sample.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StcCurve {
+    /// Extra gain at the end of the spoke (0.0 = off)
+    pub slope: f32,
+}
+
+impl Default for StcCurve {
+    fn default() -> Self {
+        Self { slope: 0.0 }
+    }
+}
+
+impl StcCurve {
+    /// Create a curve with the given slope; negative slopes are clamped to 0
+    pub fn new(slope: f32) -> Self {
+        Self {
+            slope: slope.max(0.0),
+        }
+    }
+
+    /// Does the curve change any sample?
+    pub fn is_enabled(&self) -> bool {
+        self.slope > 0.0
+    }
+
+    /// Gain factor for sample `index` of a spoke with `len` samples
+    pub fn gain(&self, index: usize, len: usize) -> f32 {
+        if len < 2 {
+            return 1.0;
+        }
+        1.0 + self.slope * index as f32 / (len - 1) as f32
+    }
+
+    /// Apply the curve to a spoke in place.
+    ///
+    /// Only intensity values in `1..=max_value` are scaled (and clamped to
+    /// `max_value`). Zero (no return) stays zero, and values above `max_value`
+    /// are legend specials such as Doppler or history colors and are left alone.
+    pub fn apply(&self, data: &mut [u8], max_value: u8) {
+        if !self.is_enabled() {
+            return;
+        }
+        let len = data.len();
+        for (i, pixel) in data.iter_mut().enumerate() {
+            if *pixel == 0 || *pixel > max_value {
+                continue;
+            }
+            let boosted = (*pixel as f32 * self.gain(i, len)).round();
+            *pixel = boosted.min(max_value as f32) as u8;
+        }
+    }
+}
+
+/// Local rotation of emitted spokes, e.g. to line the image up with a chart.
+///
+/// Unlike `bearingAlignment`, which is sent to the radar and changes its
+/// internal processing, this is applied only to the spokes we emit and never
+/// results in a radar command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayRotation {
+    /// Clockwise rotation in degrees
+    pub degrees: f64,
+}
+
+impl DisplayRotation {
+    pub fn new(degrees: f64) -> Self {
+        Self { degrees }
+    }
+
+    /// Does the rotation change any angle?
+    pub fn is_enabled(&self) -> bool {
+        self.degrees.rem_euclid(360.0) != 0.0
+    }
+
+    /// Rotate a spoke angle (or bearing) given in spokes
+    pub fn rotate(&self, angle: u32, spokes_per_revolution: u32) -> u32 {
+        if spokes_per_revolution == 0 {
+            return angle;
+        }
+        let spokes = spokes_per_revolution as f64;
+        let offset = (self.degrees.rem_euclid(360.0) * spokes / 360.0).round() as u32;
+        (angle + offset) % spokes_per_revolution
+    }
+}
+
+/// Numbering of emitted spokes expected by the client renderer.
+///
+/// Radars number spokes clockwise with spoke 0 at the bow (or north, see
+/// `bearingReference`), and that is what we emit by default. Renderers built
+/// on a mathematical convention instead want spoke 0 pointing east and
+/// angles increasing counter-clockwise. With this convention spoke `i` is
+/// emitted at `zero_bearing` plus `i` steps, clockwise or counter-clockwise.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpokeConvention {
+    /// Bearing of emitted spoke 0, in degrees clockwise from the native
+    /// spoke 0
+    pub zero_bearing: f64,
+    /// Whether emitted spoke angles increase clockwise
+    pub clockwise: bool,
+}
+
+impl Default for SpokeConvention {
+    fn default() -> Self {
+        Self {
+            zero_bearing: 0.0,
+            clockwise: true,
+        }
+    }
+}
+
+impl SpokeConvention {
+    /// Spoke 0 east, angles increasing counter-clockwise
+    pub const MATHEMATICAL: SpokeConvention = SpokeConvention {
+        zero_bearing: 90.0,
+        clockwise: false,
+    };
+
+    /// Does the mapping change any angle?
+    pub fn is_enabled(&self) -> bool {
+        !self.clockwise || self.zero_bearing.rem_euclid(360.0) != 0.0
+    }
+
+    /// Map a native spoke angle (or bearing) to the emitted one
+    pub fn map(&self, angle: u32, spokes_per_revolution: u32) -> u32 {
+        if spokes_per_revolution == 0 {
+            return angle;
+        }
+        let spokes = spokes_per_revolution as f64;
+        let zero = (self.zero_bearing.rem_euclid(360.0) * spokes / 360.0).round() as u32
+            % spokes_per_revolution;
+        let angle = angle % spokes_per_revolution;
+        if self.clockwise {
+            (angle + spokes_per_revolution - zero) % spokes_per_revolution
+        } else {
+            (zero + spokes_per_revolution - angle) % spokes_per_revolution
+        }
+    }
+}
+
+/// Time without angle change (or without spokes) before a rotation alarm
+pub const DEFAULT_STALL_TIMEOUT_MS: u64 = 3000;
+
+/// Rotation problems detected by [`RotationMonitor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RotationAlarm {
+    /// Spokes keep arriving but their angle doesn't advance: the radar
+    /// transmits while the antenna motor has stopped
+    AntennaStalled,
+    /// The radar is meant to be transmitting but no spokes arrive at all
+    NoData,
+}
+
+/// Detects a stuck antenna from the spoke stream.
+///
+/// Feed every spoke to [`RotationMonitor::on_spoke`], and call
+/// [`RotationMonitor::check`] periodically so a total loss of spokes is
+/// noticed as well. Both return the alarm only when it is first raised.
+#[derive(Debug, Clone)]
+pub struct RotationMonitor {
+    timeout_ms: u64,
+    last_angle: Option<u32>,
+    angle_changed_ms: u64,
+    last_spoke_ms: Option<u64>,
+    alarm: Option<RotationAlarm>,
+}
+
+impl Default for RotationMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_STALL_TIMEOUT_MS)
+    }
+}
+
+impl RotationMonitor {
+    pub fn new(timeout_ms: u64) -> Self {
+        Self {
+            timeout_ms,
+            last_angle: None,
+            angle_changed_ms: 0,
+            last_spoke_ms: None,
+            alarm: None,
+        }
+    }
+
+    /// Current alarm, if any
+    pub fn alarm(&self) -> Option<RotationAlarm> {
+        self.alarm
+    }
+
+    /// Record a spoke with `angle` received at `now_ms`
+    pub fn on_spoke(&mut self, angle: u32, now_ms: u64) -> Option<RotationAlarm> {
+        self.last_spoke_ms = Some(now_ms);
+        if self.last_angle != Some(angle) {
+            self.last_angle = Some(angle);
+            self.angle_changed_ms = now_ms;
+            self.alarm = None;
+            return None;
+        }
+        if now_ms.saturating_sub(self.angle_changed_ms) >= self.timeout_ms {
+            return self.raise(RotationAlarm::AntennaStalled);
+        }
+        None
+    }
+
+    /// Periodic check; outside transmit there is nothing to monitor and
+    /// any alarm is cleared.
+    pub fn check(&mut self, now_ms: u64, transmitting: bool) -> Option<RotationAlarm> {
+        if !transmitting {
+            *self = Self::new(self.timeout_ms);
+            return None;
+        }
+        match self.last_spoke_ms {
+            None => {
+                // Start the no-data timer from the first check in transmit
+                self.last_spoke_ms = Some(now_ms);
+                None
+            }
+            Some(last) if now_ms.saturating_sub(last) >= self.timeout_ms => {
+                self.raise(RotationAlarm::NoData)
+            }
+            Some(_) => None,
+        }
+    }
+
+    fn raise(&mut self, alarm: RotationAlarm) -> Option<RotationAlarm> {
+        if self.alarm == Some(alarm) {
+            return None;
+        }
+        self.alarm = Some(alarm);
+        Some(alarm)
+    }
+}
+
+/// Detects lost spoke groups ("tears") in a framed spoke stream.
+///
+/// Some radars, e.g. Navico, send a fixed number of consecutive spokes in
+/// each UDP frame. When a frame is dropped the next one starts further
+/// round than expected and the picture gets an empty wedge. Feed the first
+/// angle and spoke count of every frame to [`FrameTearDetector::on_frame`].
+#[derive(Debug, Clone)]
+pub struct FrameTearDetector {
+    spokes_per_frame: u32,
+    spokes_per_revolution: u32,
+    expected_angle: Option<u32>,
+    tears: u64,
+    missing_frames: u64,
+}
+
+impl FrameTearDetector {
+    pub fn new(spokes_per_frame: u32, spokes_per_revolution: u32) -> Self {
+        Self {
+            spokes_per_frame: spokes_per_frame.max(1),
+            spokes_per_revolution: spokes_per_revolution.max(1),
+            expected_angle: None,
+            tears: 0,
+            missing_frames: 0,
+        }
+    }
+
+    /// Number of tears seen since creation or the last reset
+    pub fn tears(&self) -> u64 {
+        self.tears
+    }
+
+    /// Number of spoke groups lost in those tears
+    pub fn missing_frames(&self) -> u64 {
+        self.missing_frames
+    }
+
+    /// Record a frame of `spokes` spokes starting at `first_angle`.
+    ///
+    /// Returns the number of spokes missing before this frame, 0 if it
+    /// follows on from the previous one (or is the first frame seen).
+    pub fn on_frame(&mut self, first_angle: u32, spokes: u32) -> u32 {
+        let first_angle = first_angle % self.spokes_per_revolution;
+        let gap = match self.expected_angle {
+            Some(expected) => {
+                (first_angle + self.spokes_per_revolution - expected) % self.spokes_per_revolution
+            }
+            None => 0,
+        };
+        self.expected_angle = Some((first_angle + spokes) % self.spokes_per_revolution);
+        if gap > 0 {
+            self.tears += 1;
+            self.missing_frames += gap.div_ceil(self.spokes_per_frame) as u64;
+        }
+        gap
+    }
+
+    /// Forget the expected angle and start counting afresh, e.g. after
+    /// the radar went to standby
+    pub fn reset(&mut self) {
+        self.expected_angle = None;
+        self.tears = 0;
+        self.missing_frames = 0;
+    }
+}
+
+/// Synthesize `count` spokes between `before` and `after` for display.
+///
+/// Normal intensities (`0..=max_value`) are blended linearly so the wedge
+/// of a tear fades from one neighbour into the other. Legend specials
+/// above `max_value` cannot be blended and are copied from the nearer
+/// neighbour. The result is as long as the shorter of the two spokes.
+pub fn interpolate_spokes(
+    before: &[u8],
+    after: &[u8],
+    count: usize,
+    max_value: u8,
+) -> Vec<Vec<u8>> {
+    let len = before.len().min(after.len());
+    (1..=count)
+        .map(|n| {
+            let t = n as f32 / (count + 1) as f32;
+            (0..len)
+                .map(|i| {
+                    let (a, b) = (before[i], after[i]);
+                    if a > max_value || b > max_value {
+                        if t < 0.5 {
+                            a
+                        } else {
+                            b
+                        }
+                    } else {
+                        (a as f32 + (b as f32 - a as f32) * t).round() as u8
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Afterglow ("sweep fade") persistence for emitted spokes.
+///
+/// Each pixel shows the brighter of the new return and the previous
+/// sweep's value at the same angle multiplied by `decay`, so echoes fade
+/// out over a few revolutions like on a phosphor display. A `decay` of
+/// `0.0` disables persistence; values near `1.0` fade slowly.
+///
+/// Only display output is faded: ARPA and guard zones must keep getting
+/// the raw spoke (see the module docs).
+#[derive(Debug, Clone, Default)]
+pub struct SweepFade {
+    decay: f32,
+    /// Range in meters of the stored sweep
+    range: u32,
+    /// Last emitted intensities, indexed by spoke angle
+    previous: Vec<Vec<u8>>,
+}
+
+impl SweepFade {
+    /// Create a fade with `decay` clamped to `0.0..=1.0`
+    pub fn new(decay: f32) -> Self {
+        Self {
+            decay: decay.clamp(0.0, 1.0),
+            range: 0,
+            previous: Vec::new(),
+        }
+    }
+
+    /// Is persistence applied at all?
+    pub fn is_enabled(&self) -> bool {
+        self.decay > 0.0
+    }
+
+    pub fn decay(&self) -> f32 {
+        self.decay
+    }
+
+    /// Change the decay; disabling it also forgets the stored sweep
+    pub fn set_decay(&mut self, decay: f32) {
+        self.decay = decay.clamp(0.0, 1.0);
+        if !self.is_enabled() {
+            self.clear();
+        }
+    }
+
+    /// Forget the previous sweep, e.g. after a range change
+    pub fn clear(&mut self) {
+        self.previous.clear();
+    }
+
+    /// Set the range in meters of the spokes to come; a change clears the
+    /// stored sweep, whose afterglow would be drawn at the wrong scale
+    pub fn set_range(&mut self, range: u32) {
+        if range != self.range {
+            self.range = range;
+            self.clear();
+        }
+    }
+
+    /// Blend a spoke with the faded previous sweep, in place.
+    ///
+    /// As with [`StcCurve::apply`], values above `max_value` are legend
+    /// specials (Doppler, history); they are emitted as-is and don't leave
+    /// an afterglow.
+    pub fn apply(&mut self, angle: usize, data: &mut [u8], max_value: u8) {
+        if !self.is_enabled() {
+            return;
+        }
+        if self.previous.len() <= angle {
+            self.previous.resize(angle + 1, Vec::new());
+        }
+        let previous = &mut self.previous[angle];
+        previous.resize(data.len(), 0);
+
+        for (pixel, prev) in data.iter_mut().zip(previous.iter_mut()) {
+            let faded = (*prev as f32 * self.decay) as u8;
+            if *pixel > max_value {
+                *prev = faded;
+                continue;
+            }
+            *pixel = (*pixel).max(faded);
+            *prev = *pixel;
+        }
+    }
+}
+
+/// Reduces emitted pixels to fewer intensity levels, for slow links.
+///
+/// Normal pixels `0..pixel_values` are mapped to `0..levels`, keeping 0
+/// as "no return" and any return at least level 1. Legend specials
+/// (Doppler, target border, history) are shifted down to follow the
+/// reduced normal range, matching a legend built for
+/// [`output_pixel_values`](Self::output_pixel_values) pixel values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpokeQuantizer {
+    bits: u8,
+}
+
+impl SpokeQuantizer {
+    /// Create a quantizer emitting `bits` bits per pixel (clamped to 1-8)
+    pub fn new(bits: u8) -> Self {
+        Self {
+            bits: bits.clamp(1, 8),
+        }
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// Number of emitted intensity levels
+    pub fn levels(&self) -> u16 {
+        1 << self.bits
+    }
+
+    /// Pixel values of the emitted spokes, for building the client legend
+    pub fn output_pixel_values(&self, pixel_values: u8) -> u8 {
+        (pixel_values as u16).min(self.levels()) as u8
+    }
+
+    /// Quantize a spoke with `pixel_values` normal levels, in place
+    pub fn apply(&self, data: &mut [u8], pixel_values: u8) {
+        let levels = self.output_pixel_values(pixel_values);
+        if levels >= pixel_values {
+            return;
+        }
+        let shift = pixel_values - levels;
+        for pixel in data.iter_mut() {
+            *pixel = if *pixel >= pixel_values {
+                *pixel - shift
+            } else if *pixel == 0 {
+                0
+            } else {
+                1 + ((*pixel as u16 - 1) * (levels as u16 - 1) / (pixel_values as u16 - 1)) as u8
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stc_boosts_far_samples() {
+        let stc = StcCurve::new(1.0);
+        let mut spoke = vec![4u8; 512];
+        stc.apply(&mut spoke, 15);
+
+        assert_eq!(spoke[0], 4);
+        assert!(spoke[511] > spoke[256]);
+        assert!(spoke[256] > spoke[0]);
+        assert_eq!(spoke[511], 8);
+    }
+
+    #[test]
+    fn test_stc_keeps_zero_and_specials() {
+        let stc = StcCurve::new(2.0);
+        let mut spoke = vec![0u8, 10, 16, 0, 14, 16];
+        stc.apply(&mut spoke, 15);
+
+        assert_eq!(spoke, vec![0, 14, 16, 0, 15, 16]);
+    }
+
+    #[test]
+    fn test_stc_disabled() {
+        let stc = StcCurve::default();
+        let mut spoke = vec![1u8, 2, 3, 4];
+        stc.apply(&mut spoke, 15);
+        assert_eq!(spoke, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_display_rotation() {
+        let rotation = DisplayRotation::new(90.0);
+        assert!(rotation.is_enabled());
+        assert_eq!(rotation.rotate(0, 2048), 512);
+        assert_eq!(rotation.rotate(1800, 2048), 264);
+
+        let rotation = DisplayRotation::new(-90.0);
+        assert_eq!(rotation.rotate(0, 2048), 1536);
+
+        let rotation = DisplayRotation::new(360.0);
+        assert!(!rotation.is_enabled());
+        assert_eq!(rotation.rotate(100, 2048), 100);
+    }
+
+    #[test]
+    fn test_spoke_convention() {
+        let native = SpokeConvention::default();
+        assert!(!native.is_enabled());
+        assert_eq!(native.map(100, 2048), 100);
+
+        // Spoke 0 at 90°, clockwise: the bow is a quarter turn back
+        let east = SpokeConvention {
+            zero_bearing: 90.0,
+            clockwise: true,
+        };
+        assert_eq!(east.map(512, 2048), 0);
+        assert_eq!(east.map(0, 2048), 1536);
+
+        // Spoke 0 east, counter-clockwise: bow (0) is emitted at 90° CCW
+        // from east, starboard beam (512) at 0 and port beam (1536) at 1024
+        let math = SpokeConvention::MATHEMATICAL;
+        assert!(math.is_enabled());
+        assert_eq!(math.map(0, 2048), 512);
+        assert_eq!(math.map(512, 2048), 0);
+        assert_eq!(math.map(1536, 2048), 1024);
+        assert_eq!(math.map(1024, 2048), 1536);
+
+        // Counter-clockwise with spoke 0 at the bow mirrors the image
+        let mirrored = SpokeConvention {
+            zero_bearing: 0.0,
+            clockwise: false,
+        };
+        assert_eq!(mirrored.map(0, 8192), 0);
+        assert_eq!(mirrored.map(1, 8192), 8191);
+    }
+
+    #[test]
+    fn test_antenna_stalled_alarm() {
+        let mut monitor = RotationMonitor::new(1000);
+        assert_eq!(monitor.check(0, true), None);
+
+        // Same angle over and over while transmitting
+        let mut raised = Vec::new();
+        for t in (0..=2000).step_by(100) {
+            if let Some(alarm) = monitor.on_spoke(42, t) {
+                raised.push(alarm);
+            }
+            assert_eq!(monitor.check(t, true), None);
+        }
+        assert_eq!(raised, vec![RotationAlarm::AntennaStalled]);
+        assert_eq!(monitor.alarm(), Some(RotationAlarm::AntennaStalled));
+
+        // Rotation resumes
+        assert_eq!(monitor.on_spoke(43, 2100), None);
+        assert_eq!(monitor.alarm(), None);
+    }
+
+    #[test]
+    fn test_no_data_alarm_distinct_from_stall() {
+        let mut monitor = RotationMonitor::new(1000);
+        assert_eq!(monitor.check(0, true), None);
+        assert_eq!(monitor.check(1500, true), Some(RotationAlarm::NoData));
+        assert_eq!(monitor.check(1600, true), None);
+
+        // Standby clears the alarm
+        assert_eq!(monitor.check(1700, false), None);
+        assert_eq!(monitor.alarm(), None);
+    }
+
+    #[test]
+    fn test_quantize_4_bit_to_2_bit() {
+        let quantizer = SpokeQuantizer::new(2);
+        assert_eq!(quantizer.output_pixel_values(16), 4);
+
+        let mut spoke: Vec<u8> = (0..16).collect();
+        quantizer.apply(&mut spoke, 16);
+        let mut levels = spoke.clone();
+        levels.dedup();
+        assert_eq!(levels, vec![0, 1, 2, 3]);
+        // The weakest return stays visible
+        assert_eq!(spoke[1], 1);
+
+        // Specials follow the shorter normal range
+        let mut special = vec![16u8, 17];
+        quantizer.apply(&mut special, 16);
+        assert_eq!(special, vec![4, 5]);
+
+        // Already shallow enough: unchanged
+        let mut shallow = vec![0u8, 1, 2, 3];
+        quantizer.apply(&mut shallow, 4);
+        assert_eq!(shallow, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_sweep_fade_decays_bright_return() {
+        let mut fade = SweepFade::new(0.5);
+        let mut spoke = vec![0u8, 15, 0, 0];
+        fade.apply(10, &mut spoke, 15);
+        assert_eq!(spoke, vec![0, 15, 0, 0]);
+
+        // The return is gone; it fades out over successive sweeps
+        let mut glow = Vec::new();
+        for _ in 0..5 {
+            let mut spoke = vec![0u8; 4];
+            fade.apply(10, &mut spoke, 15);
+            glow.push(spoke[1]);
+        }
+        assert_eq!(glow, vec![7, 3, 1, 0, 0]);
+
+        // Other angles and specials are unaffected
+        let mut other = vec![0u8, 16, 0, 0];
+        fade.apply(11, &mut other, 15);
+        assert_eq!(other, vec![0, 16, 0, 0]);
+        let mut other = vec![0u8; 4];
+        fade.apply(11, &mut other, 15);
+        assert_eq!(other, vec![0; 4]);
+    }
+
+    #[test]
+    fn test_sweep_fade_cleared_on_range_change() {
+        let mut fade = SweepFade::new(0.5);
+        fade.set_range(1852);
+        let mut spoke = vec![0u8, 15, 0, 0];
+        fade.apply(10, &mut spoke, 15);
+
+        fade.set_range(1852);
+        let mut spoke = vec![0u8; 4];
+        fade.apply(10, &mut spoke, 15);
+        assert_eq!(spoke[1], 7);
+
+        fade.set_range(3704);
+        let mut spoke = vec![0u8; 4];
+        fade.apply(10, &mut spoke, 15);
+        assert_eq!(spoke, vec![0; 4]);
+    }
+
+    #[test]
+    fn test_missing_frame_counted_as_tear() {
+        let mut detector = FrameTearDetector::new(32, 2048);
+        assert_eq!(detector.on_frame(0, 32), 0);
+        assert_eq!(detector.on_frame(32, 32), 0);
+        // Frame starting at 64 never arrives
+        assert_eq!(detector.on_frame(96, 32), 32);
+        assert_eq!(detector.on_frame(128, 32), 0);
+        assert_eq!(detector.tears(), 1);
+        assert_eq!(detector.missing_frames(), 1);
+
+        // Wrapping over north without a gap is not a tear
+        let mut detector = FrameTearDetector::new(32, 2048);
+        assert_eq!(detector.on_frame(2016, 32), 0);
+        assert_eq!(detector.on_frame(0, 32), 0);
+        assert_eq!(detector.tears(), 0);
+
+        // Standby: the counters start afresh
+        let mut detector = FrameTearDetector::new(32, 2048);
+        detector.on_frame(0, 32);
+        detector.on_frame(96, 32);
+        assert_eq!(detector.tears(), 1);
+        detector.reset();
+        assert_eq!(detector.on_frame(1000, 32), 0);
+        assert_eq!(detector.tears(), 0);
+        assert_eq!(detector.missing_frames(), 0);
+    }
+
+    #[test]
+    fn test_interpolate_spokes_blends_normal_values() {
+        let filled = interpolate_spokes(&[0, 12, 16], &[12, 0, 17], 3, 15);
+        assert_eq!(filled, vec![vec![3, 9, 16], vec![6, 6, 17], vec![9, 3, 17]]);
+        assert!(interpolate_spokes(&[1], &[2], 0, 15).is_empty());
+    }
+}
@@ -8,12 +8,14 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::protocol::furuno::command::{
-    parse_bird_mode_response, parse_blind_sector_response, parse_gain_response,
-    parse_main_bang_response, parse_rain_response, parse_range_response,
-    parse_rezboost_response, parse_scan_speed_response, parse_sea_response,
-    parse_signal_processing_response, parse_status_response, parse_target_analyzer_response,
-    parse_tx_channel_response, range_index_to_meters, ControlValue as ParsedControlValue,
+    parse_antenna_height_response, parse_bird_mode_response, parse_blind_sector_response,
+    parse_gain_response, parse_heading_align_response, parse_main_bang_response,
+    parse_rain_response, parse_range_response, parse_rezboost_response, parse_scan_speed_response,
+    parse_sea_response, parse_signal_processing_response, parse_status_response,
+    parse_target_analyzer_response, parse_tx_channel_active, parse_tx_channel_response,
+    range_index_to_meters, ControlValue as ParsedControlValue,
 };
+use crate::radar::radar_horizon_m;
 
 /// Power state of the radar
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -112,6 +114,15 @@ pub struct RadarState {
     /// Interference rejection enabled
     pub interference_rejection: bool,
 
+    /// Interference rejection level as reported: 0=OFF, 1-2=IR1-IR2 on
+    /// models with leveled IR, 2=ON on the others
+    pub interference_rejection_level: i32,
+
+    /// Whether the model has IR levels; selects how interference
+    /// rejection is reported in the controls map
+    #[serde(skip)]
+    pub leveled_interference_rejection: bool,
+
     /// RezBoost (beam sharpening) level: 0=OFF, 1=Low, 2=Medium, 3=High
     pub beam_sharpening: i32,
 
@@ -130,9 +141,21 @@ pub struct RadarState {
     /// TX Channel: 0=Auto, 1-3=Channel 1-3
     pub tx_channel: i32,
 
+    /// Channel in use while TX Channel is Auto, if the radar reports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_channel_active: Option<i32>,
+
     /// No-Transmit Zones (sector blanking)
     pub no_transmit_zones: NoTransmitZonesState,
 
+    /// Bearing alignment stored in the radar, in degrees (None until reported)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bearing_alignment: Option<f64>,
+
+    /// Antenna height stored in the radar, in meters (None until reported)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub antenna_height: Option<i32>,
+
     /// Timestamp of last update (milliseconds since epoch)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<u64>,
@@ -206,8 +229,9 @@ impl RadarState {
         if let Some((feature, value)) = parse_signal_processing_response(line) {
             match feature {
                 0 => {
-                    // Interference rejection: 0=OFF, 2=ON
-                    self.interference_rejection = value == 2;
+                    // Interference rejection: 0=OFF, 2=ON (1 = IR1 on FAR)
+                    self.interference_rejection = value != 0;
+                    self.interference_rejection_level = value;
                     return true;
                 }
                 3 => {
@@ -255,6 +279,19 @@ impl RadarState {
         // Try TX Channel response ($NEC)
         if let Some(channel) = parse_tx_channel_response(line) {
             self.tx_channel = channel;
+            self.tx_channel_active = parse_tx_channel_active(line);
+            return true;
+        }
+
+        // Try Heading Align response ($N81)
+        if let Some(degrees) = parse_heading_align_response(line) {
+            self.bearing_alignment = Some(degrees);
+            return true;
+        }
+
+        // Try Antenna Height response ($N84)
+        if let Some(meters) = parse_antenna_height_response(line) {
+            self.antenna_height = Some(meters);
             return true;
         }
 
@@ -265,12 +302,12 @@ impl RadarState {
                     NoTransmitZone {
                         enabled: bs.sector1_width > 0,
                         start: bs.sector1_start,
-                        end: (bs.sector1_start + bs.sector1_width) % 360,
+                        end: bs.sector1_end(),
                     },
                     NoTransmitZone {
                         enabled: bs.sector2_width > 0,
                         start: bs.sector2_start,
-                        end: (bs.sector2_start + bs.sector2_width) % 360,
+                        end: bs.sector2_end(),
                     },
                 ],
             };
@@ -331,10 +368,14 @@ impl RadarState {
             serde_json::json!(self.noise_reduction),
         );
 
-        // Interference rejection
+        // Interference rejection, a level on models that have them
         map.insert(
             "interferenceRejection".to_string(),
-            serde_json::json!(self.interference_rejection),
+            if self.leveled_interference_rejection {
+                serde_json::json!(self.interference_rejection_level)
+            } else {
+                serde_json::json!(self.interference_rejection)
+            },
         );
 
         // RezBoost (beam sharpening)
@@ -376,6 +417,11 @@ impl RadarState {
             serde_json::json!(self.tx_channel),
         );
 
+        // Channel picked by Auto, when reported
+        if let (0, Some(active)) = (self.tx_channel, self.tx_channel_active) {
+            map.insert("txChannelActive".to_string(), serde_json::json!(active));
+        }
+
         // No-Transmit Zones
         map.insert(
             "noTransmitZones".to_string(),
@@ -390,21 +436,143 @@ impl RadarState {
             }),
         );
 
+        // Bearing alignment, once the radar reported it
+        if let Some(degrees) = self.bearing_alignment {
+            map.insert("bearingAlignment".to_string(), serde_json::json!(degrees));
+        }
+
+        // Antenna height in meters, once the radar reported it, and the
+        // radar horizon it gives for targets at sea level
+        if let Some(meters) = self.antenna_height {
+            map.insert("antennaHeight".to_string(), serde_json::json!(meters));
+            let horizon = radar_horizon_m(meters as f64, None).round();
+            map.insert("radarHorizon".to_string(), serde_json::json!(horizon));
+        }
+
         map
     }
 }
 
+/// How long a client write is held responsible for a later change of the
+/// same control. Radars often confirm a write only in their next report.
+pub const CLIENT_WRITE_WINDOW_MS: u64 = 5000;
+
+/// Who caused a control value to change
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSource {
+    /// The radar reported a new value nobody here asked for, e.g. an MFD or
+    /// the radar's own panel changed it
+    #[default]
+    Radar,
+    /// A client wrote the control through this server
+    Client,
+}
+
+/// A single control whose value changed since the last emit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlDelta {
+    /// Control ID (e.g. "gain")
+    pub control_id: String,
+    /// New value, in the same format as [`RadarState::to_controls_map`]
+    pub value: serde_json::Value,
+    /// Whether the change came from a radar report or a client write
+    #[serde(default)]
+    pub last_change_source: ChangeSource,
+}
+
+impl ControlDelta {
+    /// SignalK path for this control, e.g. `radars.{id}.controls.gain`
+    pub fn path(&self, radar_id: &str) -> String {
+        format!("radars.{}.controls.{}", radar_id, self.control_id)
+    }
+}
+
+/// Tracks the last emitted control values so only changes are sent to clients
+///
+/// Instead of re-emitting the full state for every radar, providers feed the
+/// current controls map into [`ControlChangeTracker::changes`] and emit one
+/// delta per returned entry.
+///
+/// Writes noted with [`ControlChangeTracker::note_client_write`] tag the next
+/// change of that control as [`ChangeSource::Client`]; every other change is
+/// tagged [`ChangeSource::Radar`], so clients can tell when a control was
+/// changed externally.
+#[derive(Debug, Clone, Default)]
+pub struct ControlChangeTracker {
+    last: HashMap<String, serde_json::Value>,
+    /// Control ID -> time of the pending client write (ms)
+    client_writes: HashMap<String, u64>,
+}
+
+impl ControlChangeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that a client wrote `control_id` at `time_ms`
+    pub fn note_client_write(&mut self, control_id: &str, time_ms: u64) {
+        self.client_writes.insert(control_id.to_string(), time_ms);
+    }
+
+    /// Return the controls that differ from the previous call, and remember
+    /// the new values. The first call returns every control.
+    ///
+    /// A change is attributed to a client if that control was written within
+    /// [`CLIENT_WRITE_WINDOW_MS`] before `now_ms`. Deltas are sorted by
+    /// control ID so output is deterministic.
+    pub fn changes(
+        &mut self,
+        controls: &HashMap<String, serde_json::Value>,
+        now_ms: u64,
+    ) -> Vec<ControlDelta> {
+        self.client_writes
+            .retain(|_, written| now_ms.saturating_sub(*written) <= CLIENT_WRITE_WINDOW_MS);
+
+        let mut deltas: Vec<ControlDelta> = controls
+            .iter()
+            .filter(|(id, value)| self.last.get(*id) != Some(*value))
+            .map(|(id, value)| ControlDelta {
+                control_id: id.clone(),
+                value: value.clone(),
+                last_change_source: if self.client_writes.contains_key(id) {
+                    ChangeSource::Client
+                } else {
+                    ChangeSource::Radar
+                },
+            })
+            .collect();
+        deltas.sort_by(|a, b| a.control_id.cmp(&b.control_id));
+
+        for delta in &deltas {
+            // A write is answered by one change; anything after it is external
+            self.client_writes.remove(&delta.control_id);
+            self.last
+                .insert(delta.control_id.clone(), delta.value.clone());
+        }
+        deltas
+    }
+
+    /// Forget all previous values, so the next call emits the full state
+    pub fn reset(&mut self) {
+        self.last.clear();
+        self.client_writes.clear();
+    }
+}
+
 /// Generate all request commands to query current state
 ///
 /// Returns a vector of command strings that should be sent to the radar
 /// to query all readable control values.
 pub fn generate_state_requests() -> Vec<String> {
     use crate::protocol::furuno::command::{
-        format_request_bird_mode, format_request_blind_sector, format_request_gain,
-        format_request_interference_rejection, format_request_main_bang,
-        format_request_noise_reduction, format_request_rain, format_request_range,
-        format_request_rezboost, format_request_scan_speed, format_request_sea,
-        format_request_status, format_request_target_analyzer, format_request_tx_channel,
+        format_request_antenna_height, format_request_bird_mode, format_request_blind_sector,
+        format_request_gain, format_request_heading_align, format_request_interference_rejection,
+        format_request_main_bang, format_request_noise_reduction, format_request_rain,
+        format_request_range, format_request_rezboost, format_request_scan_speed,
+        format_request_sea, format_request_status, format_request_target_analyzer,
+        format_request_tx_channel,
     };
 
     vec![
@@ -424,6 +592,8 @@ pub fn generate_state_requests() -> Vec<String> {
         format_request_main_bang(),
         format_request_tx_channel(),
         format_request_blind_sector(),
+        format_request_heading_align(),
+        format_request_antenna_height(),
     ]
 }
 
@@ -546,11 +716,85 @@ mod tests {
         assert_eq!(gain["value"], 60);
     }
 
+    #[test]
+    fn test_auto_gain_surfaces_applied_value() {
+        let mut state = RadarState::new();
+        state.update_from_response("$N63,0,60,0,80,0");
+        assert_eq!(state.to_controls_map()["gain"]["value"], 60);
+
+        // In auto the radar reports the gain its auto algorithm applied
+        state.update_from_response("$N63,1,72,0,80,0");
+        let gain = &state.to_controls_map()["gain"];
+        assert_eq!(gain["mode"], "auto");
+        assert_eq!(gain["value"], 72);
+
+        state.update_from_response("$N63,1,68,0,80,0");
+        assert_eq!(state.to_controls_map()["gain"]["value"], 68);
+    }
+
+    #[test]
+    fn test_control_change_tracker_single_delta() {
+        let mut state = RadarState::new();
+        let mut tracker = ControlChangeTracker::new();
+
+        // First emit contains everything
+        let initial = tracker.changes(&state.to_controls_map(), 0);
+        assert_eq!(initial.len(), state.to_controls_map().len());
+
+        // Nothing changed, nothing to emit
+        assert!(tracker.changes(&state.to_controls_map(), 0).is_empty());
+
+        // Only gain changed: exactly one control-level delta
+        state.gain = ControlValueState {
+            mode: "manual".to_string(),
+            value: 70,
+        };
+        let deltas = tracker.changes(&state.to_controls_map(), 0);
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].control_id, "gain");
+        assert_eq!(deltas[0].value["value"], 70);
+        assert_eq!(deltas[0].path("radar-1"), "radars.radar-1.controls.gain");
+    }
+
+    #[test]
+    fn test_control_change_source() {
+        let mut state = RadarState::new();
+        let mut tracker = ControlChangeTracker::new();
+        tracker.changes(&state.to_controls_map(), 0);
+
+        // A report-driven change nobody asked for (auto gain moving) is external
+        state.update_from_response("$N63,1,55,0,80,0");
+        let deltas = tracker.changes(&state.to_controls_map(), 1000);
+        assert_eq!(deltas[0].control_id, "gain");
+        assert_eq!(deltas[0].last_change_source, ChangeSource::Radar);
+        assert_eq!(
+            serde_json::to_value(&deltas[0]).unwrap()["lastChangeSource"],
+            "radar"
+        );
+
+        // The radar confirming a client write is not
+        tracker.note_client_write("gain", 2000);
+        state.update_from_response("$N63,1,60,0,80,0");
+        let deltas = tracker.changes(&state.to_controls_map(), 2500);
+        assert_eq!(deltas[0].last_change_source, ChangeSource::Client);
+
+        // Once answered, the next change is external again
+        state.update_from_response("$N63,1,65,0,80,0");
+        let deltas = tracker.changes(&state.to_controls_map(), 3000);
+        assert_eq!(deltas[0].last_change_source, ChangeSource::Radar);
+
+        // A write that never produced a change expires
+        tracker.note_client_write("gain", 4000);
+        state.update_from_response("$N63,1,70,0,80,0");
+        let deltas = tracker.changes(&state.to_controls_map(), 4000 + CLIENT_WRITE_WINDOW_MS + 1);
+        assert_eq!(deltas[0].last_change_source, ChangeSource::Radar);
+    }
+
     #[test]
     fn test_generate_state_requests() {
         let requests = generate_state_requests();
 
-        assert_eq!(requests.len(), 14); // Base + signal processing (2) + extended controls
+        assert_eq!(requests.len(), 16); // Base + signal processing (2) + extended controls + installation
         // Base controls
         assert!(requests.contains(&"$R69\r\n".to_string()));
         assert!(requests.contains(&"$R62\r\n".to_string()));
@@ -568,6 +812,20 @@ mod tests {
         assert!(requests.contains(&"$R83\r\n".to_string()));
         assert!(requests.contains(&"$REC\r\n".to_string()));
         assert!(requests.contains(&"$R77\r\n".to_string())); // Blind sector
+        assert!(requests.contains(&"$R81\r\n".to_string())); // Bearing alignment
+        assert!(requests.contains(&"$R84\r\n".to_string())); // Antenna height
+    }
+
+    #[test]
+    fn test_update_from_heading_align_response() {
+        let mut state = RadarState::new();
+        assert!(!state.to_controls_map().contains_key("bearingAlignment"));
+
+        assert!(state.update_from_response("$N81,35,0"));
+        assert_eq!(state.bearing_alignment, Some(3.5));
+        assert!(state.update_from_response("$N81,3550,0"));
+        assert_eq!(state.bearing_alignment, Some(-5.0));
+        assert_eq!(state.to_controls_map()["bearingAlignment"], -5.0);
     }
 
     #[test]
@@ -603,5 +861,9 @@ mod tests {
         // TX Channel
         assert!(state.update_from_response("$NEC,2"));
         assert_eq!(state.tx_channel, 2);
+        assert!(!state.to_controls_map().contains_key("txChannelActive"));
+        assert!(state.update_from_response("$NEC,0,3"));
+        assert_eq!(state.tx_channel, 0);
+        assert_eq!(state.to_controls_map()["txChannelActive"], 3);
     }
 }
@@ -1,4 +1,4 @@
-use crate::network::{self, create_udp_multicast_listen};
+use crate::network::{self, create_udp_multicast_listen, ListenSocket};
 use crate::protos::RadarMessage::radar_message::Spoke;
 use crate::protos::RadarMessage::RadarMessage;
 use crate::settings::DataUpdate;
@@ -7,10 +7,9 @@ use crate::{radar::*, Session};
 
 use core::panic;
 use protobuf::Message;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{io, time::Duration};
-use tokio::net::UdpSocket;
 use tokio::time::sleep;
 use tokio_graceful_shutdown::SubsystemHandle;
 use trail::TrailBuffer;
@@ -29,8 +28,9 @@ pub struct FurunoDataReceiver {
     key: String,
     info: RadarInfo,
     receive_type: ReceiveAddressType,
-    multicast_socket: Option<UdpSocket>,
-    broadcast_socket: Option<UdpSocket>,
+    source_filter: SourceFilter,
+    multicast_socket: Option<ListenSocket>,
+    broadcast_socket: Option<ListenSocket>,
     data_update_rx: tokio::sync::broadcast::Receiver<DataUpdate>,
 
     // pixel_to_blob: [[u8; BYTE_LOOKUP_LENGTH]; LOOKUP_SPOKE_LENGTH],
@@ -40,6 +40,31 @@ pub struct FurunoDataReceiver {
     trails: TrailBuffer,
 }
 
+/// Decides which spoke packets belong to this radar
+///
+/// Every DRS sends its spokes to the same multicast group (or broadcast
+/// address), so with two radars on one network their packets arrive on
+/// the same socket and only the source address tells them apart.
+#[derive(Debug, Clone, Copy)]
+struct SourceFilter {
+    radar_ip: IpAddr,
+    accept_any: bool,
+}
+
+impl SourceFilter {
+    fn new(radar_addr: SocketAddr, replay: bool) -> Self {
+        SourceFilter {
+            radar_ip: radar_addr.ip(),
+            // Replayed packets on macOS do not carry the radar's address
+            accept_any: cfg!(target_os = "macos") && replay,
+        }
+    }
+
+    fn accepts(&self, addr: &SocketAddr) -> bool {
+        self.accept_any || addr.ip() == self.radar_ip
+    }
+}
+
 #[derive(Debug)]
 struct FurunoSpokeMetadata {
     sweep_count: u32,
@@ -64,11 +89,20 @@ impl FurunoDataReceiver {
             }
         }
 
+        let source_filter = SourceFilter::new(info.addr, session.read().unwrap().args.replay);
+        // The broadcast fallback only exists on IPv4 networks
+        let receive_type = if info.addr.is_ipv4() {
+            ReceiveAddressType::Both
+        } else {
+            ReceiveAddressType::Multicast
+        };
+
         FurunoDataReceiver {
             session,
             key,
             info,
-            receive_type: ReceiveAddressType::Both,
+            receive_type,
+            source_filter,
             multicast_socket: None,
             broadcast_socket: None,
             data_update_rx,
@@ -138,7 +172,7 @@ impl FurunoDataReceiver {
     }
 
     async fn conditional_receive(
-        socket: &Option<UdpSocket>,
+        socket: &Option<ListenSocket>,
         buf: &mut Vec<u8>,
     ) -> Option<io::Result<(usize, SocketAddr)>> {
         match socket {
@@ -147,14 +181,14 @@ impl FurunoDataReceiver {
         }
     }
 
-    #[cfg(target_os = "macos")]
-    fn verify_source_address(&self, addr: &SocketAddr) -> bool {
-        addr.ip() == std::net::SocketAddr::V4(self.info.addr).ip()
-            || self.session.read().unwrap().args.replay
-    }
-    #[cfg(not(target_os = "macos"))]
-    fn verify_source_address(&self, addr: &SocketAddr) -> bool {
-        addr.ip() == std::net::SocketAddr::V4(self.info.addr).ip()
+    // Process a datagram received from `addr`, unless another radar sent it.
+    // Returns whether it was processed.
+    fn receive_from(&mut self, data: &[u8], addr: &SocketAddr) -> bool {
+        if !self.source_filter.accepts(addr) {
+            return false;
+        }
+        self.process_frame(data);
+        true
     }
 
     async fn socket_loop(&mut self, subsys: &SubsystemHandle) -> Result<(), RadarError> {
@@ -186,8 +220,7 @@ impl FurunoDataReceiver {
                     log::trace!("Furuno data multicast recv {:?}", r);
                     match r {
                         Ok((len, addr)) => {
-                            if self.verify_source_address(&addr) {
-                                self.process_frame(&buf[..len]);
+                            if self.receive_from(&buf[..len], &addr) {
                                 self.receive_type = ReceiveAddressType::Multicast;
                                 broadcast_socket = None;
                             }
@@ -203,8 +236,7 @@ impl FurunoDataReceiver {
                     log::trace!("Furuno data broadcast recv {:?}", r);
                     match r {
                         Ok((len, addr)) => {
-                            if self.verify_source_address(&addr) {
-                                self.process_frame(&buf2[..len]);
+                            if self.receive_from(&buf2[..len], &addr) {
                                 self.receive_type = ReceiveAddressType::Broadcast;
                                 // Note: DON'T disable multicast here! Multicast is preferred
                                 // because it's more reliable. Broadcast is a fallback for setups
@@ -270,6 +302,10 @@ impl FurunoDataReceiver {
                     }
                 };
             }
+            DataUpdate::Target(_) => {
+                // Handled by the ARPA subsystem
+            }
+            DataUpdate::Standby => {}
         }
         Ok(())
     }
@@ -607,3 +643,73 @@ impl FurunoDataReceiver {
         metadata
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receiver(session: &Session, radar_addr: &str) -> FurunoDataReceiver {
+        let addr: SocketAddr = radar_addr.parse().unwrap();
+        let data_addr: SocketAddr = "239.255.0.2:10024".parse().unwrap();
+        let info = RadarInfo::new(
+            session.clone(),
+            crate::locator::LocatorId::Furuno,
+            crate::Brand::Furuno,
+            Some(radar_addr),
+            None,
+            64,
+            super::super::FURUNO_SPOKES,
+            FURUNO_SPOKE_LEN,
+            addr,
+            IpAddr::V4(std::net::Ipv4Addr::LOCALHOST),
+            data_addr,
+            addr,
+            addr,
+            super::super::settings::new(session.clone()),
+            true,
+        );
+        FurunoDataReceiver::new(session.clone(), info)
+    }
+
+    // A frame holding one encoding 0 spoke at `angle`
+    fn frame(angle: u16) -> Vec<u8> {
+        let mut frame = vec![0x02, 0, 0, 1, 0, 0, 0, 0, 0, 1 << 1, 8, 0, 0, 0, 0, 0];
+        frame.extend_from_slice(&angle.to_le_bytes());
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(&[0x80; 8]);
+        frame
+    }
+
+    fn received_angles(rx: &mut tokio::sync::broadcast::Receiver<Vec<u8>>) -> Vec<u32> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .flat_map(|bytes| RadarMessage::parse_from_bytes(&bytes).unwrap().spokes)
+            .map(|spoke| spoke.angle)
+            .collect()
+    }
+
+    #[test]
+    fn test_interleaved_spokes_separated_by_source() {
+        let session = Session::new_fake();
+        let mut first = receiver(&session, "172.31.3.212:10010");
+        let mut second = receiver(&session, "172.31.3.213:10010");
+        let mut first_rx = first.info.message_tx.subscribe();
+        let mut second_rx = second.info.message_tx.subscribe();
+
+        // Both radars send to 239.255.0.2:10024, so each receiver's socket
+        // gets the datagrams of both, from their own address
+        for angle in 0..8u16 {
+            let ip = if angle % 2 == 0 {
+                "172.31.3.212"
+            } else {
+                "172.31.3.213"
+            };
+            let src = SocketAddr::new(ip.parse().unwrap(), 10024);
+            let data = frame(angle);
+            assert_eq!(first.receive_from(&data, &src), angle % 2 == 0);
+            assert_eq!(second.receive_from(&data, &src), angle % 2 == 1);
+        }
+
+        assert_eq!(received_angles(&mut first_rx), vec![0, 2, 4, 6]);
+        assert_eq!(received_angles(&mut second_rx), vec![1, 3, 5, 7]);
+    }
+}
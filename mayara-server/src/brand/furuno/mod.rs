@@ -1,5 +1,5 @@
 use std::io;
-use std::net::{Ipv4Addr, SocketAddrV4};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
 
 use crate::locator::LocatorId;
@@ -30,15 +30,45 @@ const FURUNO_SPOKE_LEN: usize = FURUNO_SPOKE_LEN_U16 as usize;
 
 // Construct broadcast address from core's constants
 // Note: Furuno uses broadcast on 172.31.255.255 for data fallback
-fn furuno_broadcast_addr() -> SocketAddrV4 {
+fn furuno_broadcast_addr() -> SocketAddr {
     let ip: Ipv4Addr = FURUNO_BROADCAST_ADDR.parse().expect("Invalid FURUNO_BROADCAST_ADDR");
-    SocketAddrV4::new(ip, FURUNO_DATA_PORT)
+    SocketAddr::new(IpAddr::V4(ip), FURUNO_DATA_PORT)
 }
 
 // Construct multicast data address from core's constants
-fn furuno_data_multicast_addr() -> SocketAddrV4 {
+fn furuno_data_multicast_addr() -> SocketAddr {
     let ip: Ipv4Addr = FURUNO_DATA_MULTICAST_ADDR.parse().expect("Invalid FURUNO_DATA_MULTICAST_ADDR");
-    SocketAddrV4::new(ip, FURUNO_DATA_PORT)
+    SocketAddr::new(IpAddr::V4(ip), FURUNO_DATA_PORT)
+}
+
+// Spoke data address for a discovered radar: use the address from discovery
+// when it carries one, then the configured `--furuno-data-address` (FAR
+// series and some DRS configs), otherwise the well-known DRS multicast
+// address. A bare IP gets the discovery data port.
+fn furuno_data_addr(discovery: &RadarDiscovery, configured: Option<&str>) -> SocketAddr {
+    let port = if discovery.data_port > 0 {
+        discovery.data_port
+    } else {
+        FURUNO_DATA_PORT
+    };
+    let data_address = discovery.data_address.as_deref().or(configured);
+    match data_address.map(ParsedAddress::parse) {
+        Some(Ok(parsed)) => SocketAddr::new(
+            parsed.ip,
+            if parsed.port > 0 { parsed.port } else { port },
+        ),
+        Some(Err(e)) => {
+            log::warn!(
+                "{}: Invalid data address {:?} ({}), using {}",
+                discovery.name,
+                data_address,
+                e,
+                furuno_data_multicast_addr()
+            );
+            furuno_data_multicast_addr()
+        }
+        None => SocketAddr::new(furuno_data_multicast_addr().ip(), port),
+    }
 }
 
 // Beacon packet structures are now in mayara-core
@@ -191,7 +221,16 @@ impl FurunoLocatorState {
                     report_receiver.run(s)
                 }));
             } else {
-                let model = RadarModel::DRS4DNXT; // Default model for replay
+                // Default model for replay, unless --replay-model names a Furuno model
+                let model = self
+                    .session
+                    .read()
+                    .unwrap()
+                    .args
+                    .replay_model(mayara_core::Brand::Furuno)
+                    .map(|m| RadarModel::from_name(m.model))
+                    .filter(|m| *m != RadarModel::Unknown)
+                    .unwrap_or(RadarModel::DRS4DNXT);
                 let version = "01.05";
                 log::info!(
                     "{}: Radar model {} assumed for replay mode",
@@ -262,8 +301,7 @@ impl FurunoLocatorState {
 
         let radar_addr: SocketAddrV4 = from.clone();
 
-        // DRS: spoke data all on a well-known multicast address from core
-        let spoke_data_addr: SocketAddrV4 = furuno_data_multicast_addr();
+        let spoke_data_addr: SocketAddrV4 = furuno_data_addr(&discovery);
 
         let report_addr: SocketAddrV4 = SocketAddrV4::new(*from.ip(), 0); // Port is set in login_to_radar
         let send_command_addr: SocketAddrV4 = report_addr.clone();
@@ -393,23 +431,25 @@ use mayara_core::radar::{ParsedAddress, RadarDiscovery};
 pub fn process_discovery(
     session: Session,
     discovery: &RadarDiscovery,
-    nic_addr: Ipv4Addr,
+    nic_addr: IpAddr,
     radars: &SharedRadars,
     subsys: &SubsystemHandle,
 ) -> Result<(), io::Error> {
     // Parse address from discovery using core's parser
     let parsed = ParsedAddress::parse(&discovery.address)
         .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
-    let radar_addr = SocketAddrV4::new(
-        Ipv4Addr::from(parsed.ip),
+    let radar_addr = SocketAddr::new(
+        parsed.ip,
         if parsed.port > 0 { parsed.port } else { FURUNO_BEACON_PORT },
     );
 
-    // DRS: spoke data all on a well-known multicast address from core
-    let spoke_data_addr: SocketAddrV4 = furuno_data_multicast_addr();
+    // Spoke data address from discovery or configuration, or the well-known
+    // DRS multicast address
+    let configured = session.read().unwrap().args.furuno_data_address.clone();
+    let spoke_data_addr: SocketAddr = furuno_data_addr(discovery, configured.as_deref());
 
-    let report_addr: SocketAddrV4 = SocketAddrV4::new(*radar_addr.ip(), 0); // Port is set in login_to_radar
-    let send_command_addr: SocketAddrV4 = report_addr.clone();
+    let report_addr: SocketAddr = SocketAddr::new(radar_addr.ip(), 0); // Port is set in login_to_radar
+    let send_command_addr: SocketAddr = report_addr;
 
     // Use name (e.g., "RD003212") as serial identifier for unique key generation
     // Use None for 'which' since Furuno doesn't have multi-unit setups like Navico A/B
@@ -440,9 +480,24 @@ pub fn process_discovery(
         return Ok(());
     };
 
-    // Apply model-specific settings if known from discovery OR from persistence
+    // Apply model-specific settings if known from --replay-model, discovery OR persistence
     // After located(), model_name may be set from persisted config
-    let model_name = discovery.model.clone().or_else(|| info.controls.model_name());
+    let replay_model_name = session
+        .read()
+        .unwrap()
+        .args
+        .replay_model(mayara_core::Brand::Furuno)
+        .map(|m| m.model.to_string());
+    let source = if replay_model_name.is_some() {
+        "replay-model"
+    } else if discovery.model.is_some() {
+        "discovery"
+    } else {
+        "persistence"
+    };
+    let model_name = replay_model_name
+        .or_else(|| discovery.model.clone())
+        .or_else(|| info.controls.model_name());
     if let Some(ref model_name) = model_name {
         let model = RadarModel::from_name(model_name);
         let version = "unknown"; // Version comes from $N96 via report receiver
@@ -451,7 +506,7 @@ pub fn process_discovery(
             info.key(),
             model_name,
             model,
-            if discovery.model.is_some() { "discovery" } else { "persistence" }
+            source
         );
         settings::update_when_model_known(&mut info, model, version);
 
@@ -475,6 +530,8 @@ pub fn process_discovery(
             info_clone.forward_output(s)
         }));
     }
+    crate::recording::capture::start_capture(&session, &info, subsys);
+    crate::radar::arpa::start_arpa(&session, &info, subsys);
 
     log::debug!("{}: Creating data receiver", info.key());
     let data_receiver = data::FurunoDataReceiver::new(session.clone(), info.clone());
@@ -511,3 +568,97 @@ pub fn process_discovery(
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discovery(data_address: Option<&str>) -> RadarDiscovery {
+        RadarDiscovery {
+            brand: mayara_core::Brand::Furuno,
+            model: None,
+            name: "RD003212".into(),
+            address: "172.31.3.212:10010".into(),
+            data_port: FURUNO_DATA_PORT,
+            command_port: 0,
+            spokes_per_revolution: FURUNO_SPOKES_U16,
+            max_spoke_len: FURUNO_SPOKE_LEN_U16,
+            pixel_values: 64,
+            serial_number: None,
+            nic_address: None,
+            suffix: None,
+            data_address: data_address.map(String::from),
+            report_address: None,
+            send_address: None,
+        }
+    }
+
+    #[test]
+    fn test_data_addr_from_discovery() {
+        let addr = furuno_data_addr(&discovery(Some("239.255.0.3:10034")), None);
+        assert_eq!(addr, "239.255.0.3:10034".parse().unwrap());
+
+        // Bare IP uses the data port
+        let addr = furuno_data_addr(&discovery(Some("239.255.0.3")), None);
+        assert_eq!(addr, SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 0, 3)), FURUNO_DATA_PORT));
+
+        // FAR-3000 on an IPv6 network
+        let addr = furuno_data_addr(&discovery(Some("[ff02::2]:10034")), None);
+        assert_eq!(addr, "[ff02::2]:10034".parse().unwrap());
+    }
+
+    #[test]
+    fn test_data_addr_from_configuration() {
+        // Beacons carry no data address, the configured one is used
+        let addr = furuno_data_addr(&discovery(None), Some("239.255.0.3"));
+        assert_eq!(addr, SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 0, 3)), FURUNO_DATA_PORT));
+
+        // An address from discovery wins over the configured one
+        let addr = furuno_data_addr(&discovery(Some("239.255.0.4:10034")), Some("239.255.0.3"));
+        assert_eq!(addr, "239.255.0.4:10034".parse().unwrap());
+    }
+
+    #[test]
+    fn test_tx_channel_active_per_model() {
+        let session = Session::new_fake();
+        let info = |model: RadarModel| {
+            let addr: SocketAddr = "172.31.3.212:10010".parse().unwrap();
+            let mut info = RadarInfo::new(
+                session.clone(),
+                LocatorId::Furuno,
+                Brand::Furuno,
+                Some("RD003212"),
+                None,
+                64,
+                FURUNO_SPOKES,
+                FURUNO_SPOKE_LEN,
+                addr,
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                addr,
+                addr,
+                addr,
+                settings::new(session.clone()),
+                true,
+            );
+            settings::update_when_model_known(&mut info, model, "unknown");
+            info
+        };
+
+        let nxt = info(RadarModel::DRS4DNXT);
+        assert_eq!(nxt.controls.get("txChannelActive").unwrap().value, Some(0.));
+        assert!(nxt.controls.set("txChannelActive", 3., None).unwrap().is_some());
+        assert!(info(RadarModel::DRS).controls.get("txChannelActive").is_none());
+    }
+
+    #[test]
+    fn test_data_addr_fallback() {
+        assert_eq!(furuno_data_addr(&discovery(None), None), furuno_data_multicast_addr());
+        assert_eq!(
+            furuno_data_addr(&discovery(Some("not-an-ip")), None),
+            furuno_data_multicast_addr()
+        );
+        assert_eq!(
+            furuno_data_addr(&discovery(None), Some("not-an-ip")),
+            furuno_data_multicast_addr()
+        );
+    }
+}
@@ -11,14 +11,15 @@ use tokio::time::{interval, MissedTickBehavior};
 use tokio_graceful_shutdown::SubsystemHandle;
 
 // Use unified controller and events from mayara-core
+use mayara_core::capabilities::ModuleInfo;
 use mayara_core::controllers::FurunoController;
-use mayara_core::ControllerEvent;
+use mayara_core::{models, Brand, ControllerEvent};
 
 use super::settings;
 use super::RadarModel;
-use crate::radar::{RadarError, RadarInfo, SharedRadars, Status};
-use crate::settings::ControlUpdate;
-use crate::storage::load_installation_settings;
+use crate::radar::{RadarError, RadarInfo, SharedRadars, Status, ROTATION_CHECK_INTERVAL};
+use crate::settings::{ControlError, ControlUpdate};
+use crate::storage::{load_installation_settings, store_installation_setting};
 use crate::tokio_io::TokioIoProvider;
 use crate::Session;
 
@@ -52,7 +53,11 @@ impl FurunoReportReceiver {
             .expect("SharedRadars must be initialized before creating report receiver");
 
         // Create the unified controller from mayara-core
-        let controller = FurunoController::new(&key, &radar_addr);
+        let mut controller = FurunoController::new(&key, &radar_addr);
+        controller.set_passive(session.read().unwrap().args.passive);
+        if let Some(variable) = session.read().unwrap().args.furuno_login_byte {
+            controller.set_login_variable_byte(variable);
+        }
         let io = TokioIoProvider::new();
 
         FurunoReportReceiver {
@@ -72,11 +77,20 @@ impl FurunoReportReceiver {
 
         let mut command_rx = self.info.control_update_subscribe();
         // Check if model was already known from persistence (loaded before we start)
-        let mut model_known = self.info.controls.model_name().is_some();
+        let mut model_known = match self.info.controls.model_name() {
+            Some(model) => {
+                // Lets the controller apply model-dependent reports right away
+                self.controller.set_model(&model);
+                true
+            }
+            None => false,
+        };
 
         // Use interval instead of sleep - sleep() in select! doesn't work correctly
         let mut poll_interval = interval(self.poll_interval);
         poll_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut rotation_check = interval(ROTATION_CHECK_INTERVAL);
+        rotation_check.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
         loop {
             tokio::select! {
@@ -86,6 +100,8 @@ impl FurunoReportReceiver {
                     return Ok(());
                 },
 
+                _ = rotation_check.tick() => self.info.check_rotation(),
+
                 _ = poll_interval.tick() => {
                     // Poll the controller and handle events
                     let events = self.controller.poll(&mut self.io);
@@ -152,10 +168,42 @@ impl FurunoReportReceiver {
             }
             ControllerEvent::OperatingHoursUpdated { hours } => {
                 self.set_value("operatingHours", hours as f32);
+                let life = self
+                    .info
+                    .controls
+                    .model_name()
+                    .and_then(|name| models::get_model(Brand::Furuno, &name))
+                    .and_then(|model| model.magnetron_life_percent(hours));
+                if let Some(percent) = life {
+                    self.set_value("magnetronLifePercent", percent as f32);
+                }
             }
             ControllerEvent::TransmitHoursUpdated { hours } => {
                 self.set_value("transmitHours", hours as f32);
             }
+            ControllerEvent::BearingAlignmentReported { degrees } => {
+                self.reconcile_bearing_alignment(degrees);
+            }
+        }
+    }
+
+    /// The radar is the source of truth for bearing alignment: it may have
+    /// been changed from an MFD. If the readback differs from the persisted
+    /// setting, update the setting, and push the value to clients.
+    fn reconcile_bearing_alignment(&mut self, degrees: f64) {
+        // Installation settings hold whole degrees
+        let stored = degrees.round() as i32;
+        match store_installation_setting(&self.key, "bearingAlignment", serde_json::json!(stored)) {
+            Ok(true) => log::info!(
+                "{}: Radar reports bearingAlignment = {}°, updated stored setting",
+                self.key,
+                degrees
+            ),
+            Ok(false) => {}
+            Err(e) => log::warn!("{}: Cannot store bearingAlignment: {}", self.key, e),
+        }
+        if self.set_value_changed("bearingAlignment", degrees as f32) {
+            self.radars.update(&self.info);
         }
     }
 
@@ -166,6 +214,21 @@ impl FurunoReportReceiver {
         let state = self.controller.radar_state().clone();
         let mut changed = false;
 
+        // Module inventory from $N96 (read-only, exposed in the state API)
+        let modules: Vec<ModuleInfo> = self
+            .controller
+            .modules()
+            .iter()
+            .map(|m| ModuleInfo {
+                part: m.code.clone(),
+                version: m.version.clone(),
+            })
+            .collect();
+        if modules != self.info.modules {
+            self.info.modules = modules;
+            changed = true;
+        }
+
         // Apply power state
         let power_status = match state.power {
             mayara_core::state::PowerState::Off => Status::Off,
@@ -193,14 +256,32 @@ impl FurunoReportReceiver {
 
         // Apply signal processing controls
         changed |= self.set_value_changed("noiseReduction", if state.noise_reduction { 1.0 } else { 0.0 });
-        changed |= self.set_value_changed("interferenceRejection", if state.interference_rejection { 1.0 } else { 0.0 });
+        let interference_rejection = if state.leveled_interference_rejection {
+            state.interference_rejection_level as f32
+        } else if state.interference_rejection {
+            1.0
+        } else {
+            0.0
+        };
+        changed |= self.set_value_changed("interferenceRejection", interference_rejection);
 
         // Apply extended controls
         changed |= self.set_value_changed("beamSharpening", state.beam_sharpening as f32);
         changed |= self.set_value_changed("birdMode", state.bird_mode as f32);
-        changed |= self.set_value_changed("scanSpeed", state.scan_speed as f32);
+        let scan_speed_rpm = self
+            .info
+            .scan_speed_rpm(Brand::Furuno, state.scan_speed as u8);
+        changed |= self.set_value_changed("scanSpeed", scan_speed_rpm as f32);
         changed |= self.set_value_changed("mainBangSuppression", state.main_bang_suppression as f32);
         changed |= self.set_value_changed("txChannel", state.tx_channel as f32);
+        let tx_channel_active = match state.tx_channel {
+            0 => state.tx_channel_active.unwrap_or(0),
+            _ => 0,
+        };
+        changed |= self.set_value_changed("txChannelActive", tx_channel_active as f32);
+        if let Some(meters) = state.antenna_height {
+            changed |= self.set_value_changed("antennaHeight", meters as f32);
+        }
 
         // Apply Doppler mode (mode is "target" or "rain" string)
         // Protocol uses: mode=0 for Target, mode=1 for Rain
@@ -286,15 +367,31 @@ impl FurunoReportReceiver {
 
         // Dispatch to appropriate controller method
         match id {
-            "range" => self.controller.set_range(&mut self.io, num_value as u32),
+            "range" => {
+                let meters = self
+                    .info
+                    .snap_range(mayara_core::Brand::Furuno, num_value as u32);
+                self.controller.set_range(&mut self.io, meters)
+            }
             "gain" => self.controller.set_gain(&mut self.io, num_value, auto),
             "sea" => self.controller.set_sea(&mut self.io, num_value, auto),
             "rain" => self.controller.set_rain(&mut self.io, num_value, auto),
-            "beamSharpening" => self.controller.set_rezboost(&mut self.io, num_value),
-            "interferenceRejection" => self.controller.set_interference_rejection(&mut self.io, num_value != 0),
+            "beamSharpening" | "birdMode" => self
+                .controller
+                .set_extended_control(&mut self.io, id, num_value)
+                .map_err(|_| ControlError::Invalid(id.to_string(), value.to_string()))?,
+            "interferenceRejection" => self
+                .controller
+                .set_extended_control(&mut self.io, id, num_value)
+                .map_err(|_| ControlError::Invalid(id.to_string(), value.to_string()))?,
             "noiseReduction" => self.controller.set_noise_reduction(&mut self.io, num_value != 0),
-            "scanSpeed" => self.controller.set_scan_speed(&mut self.io, num_value),
-            "birdMode" => self.controller.set_bird_mode(&mut self.io, num_value),
+            "scanSpeed" => {
+                let wire = self
+                    .info
+                    .scan_speed_wire(Brand::Furuno, num_value)
+                    .ok_or_else(|| ControlError::Invalid(id.to_string(), value.to_string()))?;
+                self.controller.set_scan_speed(&mut self.io, wire as i32)
+            }
             "mainBangSuppression" => self.controller.set_main_bang_suppression(&mut self.io, num_value),
             "txChannel" => self.controller.set_tx_channel(&mut self.io, num_value),
             "bearingAlignment" => self.controller.set_bearing_alignment(&mut self.io, num_value as f64),
@@ -444,9 +541,10 @@ impl FurunoReportReceiver {
 
             let mut restored_any = false;
 
-            // Restore bearing alignment
+            // Restore bearing alignment. Not sent to the radar: the radar
+            // stores it and is the source of truth, its readback ($N81)
+            // reconciles the stored setting.
             if let Some(degrees) = settings.bearing_alignment {
-                self.controller.set_bearing_alignment(&mut self.io, degrees as f64);
                 self.set_value("bearingAlignment", degrees as f32);
                 log::info!("{}: Restored bearingAlignment = {}°", self.key, degrees);
                 restored_any = true;
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use mayara_core::capabilities::controls::get_control_for_brand;
+use mayara_core::capabilities::controls::get_control_for_model;
 use mayara_core::{models, Brand};
 
 use crate::{
@@ -79,6 +79,10 @@ pub fn update_when_model_known(info: &mut RadarInfo, model: RadarModel, version:
     let model_name = model.as_str();
     log::debug!("update_when_model_known: {}", model_name);
     info.controls.set_model_name(model_name.to_string());
+    let model_info = models::get_model(Brand::Furuno, model_name);
+    info.antenna_type = model_info.and_then(|m| m.antenna_type);
+    info.controls
+        .set_inverted_controls(model_info.map_or(&[], |m| m.inverted_controls));
 
     let mut control = control_factory::serial_number_control();
     if let Some(serial_number) = info.serial_no.as_ref() {
@@ -154,12 +158,27 @@ pub fn update_when_model_known(info: &mut RadarInfo, model: RadarModel, version:
 
     // Dynamically add extended controls from mayara-core based on model capabilities
     if let Some(model_info) = models::get_model(Brand::Furuno, model_name) {
+        if model_info.magnetron_life_hours > 0 {
+            info.controls.insert(
+                "magnetronLifePercent",
+                control_factory::magnetron_life_percent_control(),
+            );
+        }
+
         log::info!(
             "{}: Adding {} extended controls from model capabilities",
             info.key(),
             model_info.controls.len()
         );
 
+        // The channel Auto picked, 0 until the radar reports one
+        if model_info.controls.contains(&"txChannel") {
+            info.controls.insert(
+                "txChannelActive",
+                Control::new_numeric("txChannelActive", 0., 3.).read_only(true),
+            );
+        }
+
         for control_id in model_info.controls {
             // Skip controls that are already added (like noTransmitZones which maps to Start/End controls)
             if *control_id == "noTransmitZones" {
@@ -167,7 +186,7 @@ pub fn update_when_model_known(info: &mut RadarInfo, model: RadarModel, version:
             }
 
             // Get control definition from mayara-core
-            if let Some(core_def) = get_control_for_brand(control_id, Brand::Furuno) {
+            if let Some(core_def) = get_control_for_model(control_id, model_info) {
                 log::info!(
                     "{}: Building control '{}' (type: {:?})",
                     info.key(),
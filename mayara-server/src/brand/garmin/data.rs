@@ -0,0 +1,217 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{io, time::Duration};
+use tokio::time::sleep;
+use tokio_graceful_shutdown::SubsystemHandle;
+use trail::TrailBuffer;
+
+// Use mayara-core for spoke parsing (pure, WASM-compatible)
+use mayara_core::protocol::garmin::{decode_xhd_pixels, parse_xhd_line, XHD_LINE_HEADER_SIZE};
+
+use crate::network::{
+    create_udp_multicast_listen, ListenSocket, StallDetector, MULTICAST_STALL_TIMEOUT,
+};
+use crate::protos::RadarMessage::RadarMessage;
+use crate::radar::spoke::to_protobuf_spoke;
+use crate::settings::DataUpdate;
+use crate::{radar::*, Session};
+
+use super::{GARMIN_SPOKES, GARMIN_SPOKE_LEN};
+
+// One spoke line per packet
+const RADAR_LINE_BUFFER_SIZE: usize = XHD_LINE_HEADER_SIZE + GARMIN_SPOKE_LEN;
+
+pub struct GarminDataReceiver {
+    key: String,
+    statistics: Statistics,
+    info: RadarInfo,
+    sock: Option<ListenSocket>,
+    stall: StallDetector,
+    data_update_rx: tokio::sync::broadcast::Receiver<DataUpdate>,
+    trails: TrailBuffer,
+    prev_angle: u16,
+}
+
+impl GarminDataReceiver {
+    pub fn new(session: &Session, info: RadarInfo) -> GarminDataReceiver {
+        let key = info.key();
+
+        let data_update_rx = info.controls.data_update_subscribe();
+        let trails = TrailBuffer::new(session.clone(), &info);
+
+        log::debug!("{}: Creating GarminDataReceiver", key);
+
+        GarminDataReceiver {
+            key,
+            statistics: Statistics::new(),
+            info,
+            sock: None,
+            stall: StallDetector::new(),
+            data_update_rx,
+            trails,
+            prev_angle: 0,
+        }
+    }
+
+    async fn start_socket(&mut self) -> io::Result<()> {
+        match create_udp_multicast_listen(&self.info.spoke_data_addr, &self.info.nic_addr) {
+            Ok(sock) => {
+                self.sock = Some(sock);
+                self.stall.reset();
+                log::debug!(
+                    "{} via {}: listening for spoke data",
+                    &self.info.spoke_data_addr,
+                    &self.info.nic_addr
+                );
+                Ok(())
+            }
+            Err(e) => {
+                sleep(Duration::from_millis(1000)).await;
+                log::debug!(
+                    "{} via {}: create multicast failed: {}",
+                    &self.info.spoke_data_addr,
+                    &self.info.nic_addr,
+                    e
+                );
+                Ok(())
+            }
+        }
+    }
+
+    async fn handle_data_update(&mut self, r: DataUpdate) -> Result<(), RadarError> {
+        log::debug!("{}: Received data update: {:?}", self.key, r);
+        match r {
+            DataUpdate::Doppler(_) => {
+                // xHD radars have no Doppler
+            }
+            DataUpdate::Legend(legend) => {
+                self.info.legend = legend;
+            }
+            DataUpdate::Ranges(_) => {
+                // Every spoke line carries its own range
+            }
+            DataUpdate::ControlValue(reply_tx, cv) => {
+                if let Err(e) = self.trails.set_control_value(&self.info.controls, &cv) {
+                    return self
+                        .info
+                        .controls
+                        .send_error_to_client(reply_tx, &cv, &e)
+                        .await;
+                }
+            }
+            DataUpdate::Target(_) => {
+                // Handled by the ARPA subsystem
+            }
+            DataUpdate::Standby => {}
+        }
+
+        Ok(())
+    }
+
+    pub async fn run(mut self, subsys: SubsystemHandle) -> Result<(), RadarError> {
+        self.start_socket().await.unwrap();
+        loop {
+            if self.sock.is_some() {
+                match self.socket_loop(&subsys).await {
+                    Err(RadarError::Shutdown) => {
+                        return Ok(());
+                    }
+                    _ => {
+                        // Ignore, reopen socket
+                    }
+                }
+                self.sock = None;
+            } else {
+                sleep(Duration::from_millis(1000)).await;
+                self.start_socket().await.unwrap();
+            }
+        }
+    }
+
+    async fn socket_loop(&mut self, subsys: &SubsystemHandle) -> Result<(), RadarError> {
+        let mut buf = Vec::with_capacity(RADAR_LINE_BUFFER_SIZE);
+        log::trace!(
+            "{}: Starting socket loop on {}",
+            self.key,
+            self.info.spoke_data_addr
+        );
+
+        loop {
+            tokio::select! {
+                _ = subsys.on_shutdown_requested() => {
+                    return Err(RadarError::Shutdown);
+                },
+                r = self.data_update_rx.recv() => {
+                    match r {
+                        Ok(data_update) => {
+                            self.handle_data_update(data_update).await?;
+                        }
+                        Err(_) => {
+                            panic!("data_update closed");
+                        }
+                    }
+                },
+                _ = self.stall.stalled() => {
+                    log::warn!(
+                        "{}: no spoke data for {:?}, re-joining multicast",
+                        self.key,
+                        MULTICAST_STALL_TIMEOUT
+                    );
+                    return Err(RadarError::Timeout);
+                },
+                r = self.sock.as_ref().unwrap().recv_buf_from(&mut buf)  => {
+                    match r {
+                        Ok(_) => {
+                            self.stall.packet_received();
+                            self.process_line(&buf);
+                        },
+                        Err(e) => {
+                            return Err(RadarError::Io(e));
+                        }
+                    }
+                },
+            }
+            buf.clear();
+        }
+    }
+
+    fn process_line(&mut self, data: &[u8]) {
+        let line = match parse_xhd_line(data) {
+            Ok(line) => line,
+            Err(e) => {
+                log::trace!("{}: Dropping packet: {}", self.key, e);
+                self.statistics.broken_packets += 1;
+                return;
+            }
+        };
+        let angle = line.angle;
+        log::trace!("range {} angle {}", line.range_m, angle);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .ok();
+
+        let mut message = RadarMessage::new();
+        message.radar = self.info.id as u32;
+
+        let generic_spoke = decode_xhd_pixels(line.data);
+        let mut spoke =
+            to_protobuf_spoke(&self.info, line.range_m, angle, None, now, generic_spoke);
+        self.trails.update_trails(&mut spoke, &self.info.legend);
+        message.spokes.push(spoke);
+
+        if ((self.prev_angle + 1) % GARMIN_SPOKES as u16) != angle {
+            self.statistics.missing_spokes +=
+                (angle + GARMIN_SPOKES as u16 - self.prev_angle - 1) as usize % GARMIN_SPOKES;
+        }
+        self.statistics.received_spokes += 1;
+        if angle < self.prev_angle {
+            let ms = self.info.full_rotation();
+            self.trails.set_rotation_speed(ms);
+            self.statistics.full_rotation(&self.key);
+        }
+        self.prev_angle = angle;
+
+        self.info.broadcast_radar_message(message);
+    }
+}
@@ -1,130 +1,102 @@
-use async_trait::async_trait;
-use log::debug;
 use std::io;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
-use tokio::net::UdpSocket;
-use tokio::time::sleep;
-use tokio_shutdown::Shutdown;
+use std::net::{IpAddr, SocketAddr};
+use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
 
-use crate::locator::RadarLocator;
-use crate::radar::{located, RadarLocationInfo, Radars};
-use crate::util::join_multicast;
+use crate::locator::LocatorId;
+use crate::radar::{RadarInfo, SharedRadars};
+use crate::{Brand, Session};
 
+mod data;
 mod report;
+mod settings;
 
-const GARMIN_REPORT_ADDRESS: SocketAddr =
-    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 254, 2, 0)), 50100);
-const GARMIN_SEND_PORT: u16 = 50101;
-const GARMIN_DATA_ADDRESS: SocketAddr =
-    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 254, 2, 0)), 50102);
+// Use constants from core (single source of truth)
+use mayara_core::protocol::garmin::{
+    DATA_ADDR, DATA_PORT, MAX_SPOKE_LEN as GARMIN_SPOKE_LEN_U16, PIXEL_VALUES, REPORT_ADDR,
+    REPORT_PORT, SEND_PORT, SPOKES_PER_REVOLUTION as GARMIN_SPOKES_U16,
+};
+use mayara_core::radar::{ParsedAddress, RadarDiscovery};
 
-fn process_beacon(report: &[u8]) {
-    if report.len() < 2 {
-        return;
-    }
+const GARMIN_SPOKES: usize = GARMIN_SPOKES_U16 as usize;
+const GARMIN_SPOKE_LEN: usize = GARMIN_SPOKE_LEN_U16 as usize;
 
-    report::process(report);
-}
+/// Process a Garmin radar discovery from the core locator.
+///
+/// Garmin radars have no beacon: any packet on the report address is a
+/// radar. Spoke data is received here; controls are sent by
+/// `GarminController` in mayara-core, wrapped by the report receiver.
+pub fn process_discovery(
+    session: Session,
+    discovery: &RadarDiscovery,
+    nic_addr: IpAddr,
+    radars: &SharedRadars,
+    subsys: &SubsystemHandle,
+) -> Result<(), io::Error> {
+    let parsed = ParsedAddress::parse(&discovery.address)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let radar_addr = SocketAddr::new(parsed.ip, SEND_PORT);
 
-struct GarminLocator {
-    found: bool,
-    buf: Vec<u8>,
-    sock: Option<UdpSocket>,
-}
+    let group = |addr: &str, port| SocketAddr::new(addr.parse().unwrap(), port);
+    let data_addr = group(DATA_ADDR, DATA_PORT);
+    let report_addr = group(REPORT_ADDR, REPORT_PORT);
 
-impl GarminLocator {
-    async fn start(&mut self) -> io::Result<()> {
-        match join_multicast(&GARMIN_REPORT_ADDRESS).await {
-            Ok(sock) => {
-                self.sock = Some(sock);
-                debug!(
-                    "Listening on {} for Garmin xHD (and others?)",
-                    GARMIN_REPORT_ADDRESS
-                );
-                Ok(())
-            }
-            Err(e) => {
-                sleep(Duration::from_millis(1000)).await;
-                debug!("Beacon multicast failed: {}", e);
-                Ok(())
-            }
-        }
-    }
-}
+    let mut info: RadarInfo = RadarInfo::new(
+        session.clone(),
+        LocatorId::Garmin,
+        Brand::Garmin,
+        discovery.serial_number.as_deref(),
+        None,
+        PIXEL_VALUES,
+        GARMIN_SPOKES,
+        GARMIN_SPOKE_LEN,
+        radar_addr,
+        nic_addr,
+        data_addr,
+        report_addr,
+        radar_addr,
+        settings::new(session.clone(), discovery.model.as_deref()),
+        false,
+    );
 
-async fn found(
-    info: RadarLocationInfo,
-    radars: &SharedRadars,
-    shutdown: &Shutdown,
-) -> io::Result<()> {
-    if let Some(info) = located(info, radars) {
-        // It's new, start the RadarProcessor thread
-        // let shutdown = shutdown.clone();
-        //tokio::spawn(async move {
-        //   receive::Receive::run(info, shutdown).await.unwrap();
-        //});
-        // TODO do something with the join handle
+    info.controls.set_string("userName", info.key()).ok();
+    if let Err(e) = info.set_ranges(settings::ranges(discovery.model.as_deref())) {
+        log::warn!("{}: Cannot set ranges: {}", info.key(), e);
     }
-    Ok(())
-}
 
-#[async_trait]
-impl RadarLocator for GarminLocator {
-    async fn process_beacons(
-        &mut self,
-        radars: SharedRadars,
-        shutdown: Shutdown,
-    ) -> io::Result<()> {
-        self.start().await.unwrap();
-        loop {
-            match &self.sock {
-                Some(sock) => {
-                    self.buf.clear();
-                    match sock.recv_buf_from(&mut self.buf).await {
-                        Ok((_len, from)) => {
-                            if self.found {
-                                process_beacon(&self.buf);
-                            } else {
-                                let mut radar_send = from.clone();
+    // Check if this is a new radar
+    let Some(info) = radars.located(info) else {
+        log::debug!("Garmin radar {} already known", discovery.name);
+        return Ok(());
+    };
+
+    // Spawn subsystems
+    if session.read().unwrap().args.output {
+        let info_clone = info.clone();
+        subsys.start(SubsystemBuilder::new("stdout", move |s| {
+            info_clone.forward_output(s)
+        }));
+    }
+    crate::recording::capture::start_capture(&session, &info, subsys);
+    crate::radar::arpa::start_arpa(&session, &info, subsys);
 
-                                radar_send.set_port(GARMIN_SEND_PORT);
-                                let location_info: RadarLocationInfo = RadarLocationInfo::new(
-                                    "Garmin",
-                                    None,
-                                    None,
-                                    None,
-                                    from,
-                                    GARMIN_DATA_ADDRESS,
-                                    GARMIN_REPORT_ADDRESS,
-                                    radar_send,
-                                );
-                                found(location_info, &radars, &shutdown).await.unwrap();
+    let data_name = info.key() + " data";
+    let report_name = info.key() + " reports";
+    let data_receiver = data::GarminDataReceiver::new(&session, info.clone());
+    subsys.start(SubsystemBuilder::new(
+        data_name,
+        move |s: SubsystemHandle| data_receiver.run(s),
+    ));
 
-                                self.found = true;
-                            }
-                        }
-                        Err(e) => {
-                            debug!("Beacon read failed: {}", e);
-                            self.sock = None;
-                        }
-                    }
-                }
-                None => {
-                    sleep(Duration::from_millis(1000)).await;
-                    self.start().await.unwrap();
-                }
-            }
-        }
+    if !session.read().unwrap().args.replay {
+        let report_receiver = report::GarminReportReceiver::new(&session, info);
+        subsys.start(SubsystemBuilder::new(report_name, |s| {
+            report_receiver.run(s)
+        }));
     }
-}
 
-pub fn create_locator() -> Box<dyn RadarLocator + Send> {
-    let locator = GarminLocator {
-        found: false,
-        buf: Vec::with_capacity(2048),
-        sock: None,
-    };
-    Box::new(locator)
+    log::info!(
+        "{}: Garmin radar activated via CoreLocatorAdapter",
+        discovery.name
+    );
+    Ok(())
 }
@@ -1,53 +1,309 @@
-use log::{debug, trace};
-
-// Use mayara-core for report parsing (pure, WASM-compatible)
-use mayara_core::protocol::garmin::{parse_report, Report};
-
-pub fn process(report: &[u8]) {
-    match parse_report(report) {
-        Ok(r) => {
-            match r {
-                Report::ScanSpeed(v) => debug!("Scan speed {}", v),
-                Report::TransmitState(state) => debug!("Transmit state {:?}", state),
-                Report::Range(m) => debug!("Range {} m", m),
-                Report::Gain { mode, value, level } => {
-                    debug!("Gain mode={:?} value={} level={:?}", mode, value, level);
-                }
-                Report::BearingAlignment(deg) => debug!("Bearing alignment {:.1}", deg),
-                Report::CrosstalkRejection(v) => debug!("Crosstalk rejection {}", v),
-                Report::RainClutter { mode, level } => {
-                    debug!("Rain clutter mode={} level={}", mode, level);
-                }
-                Report::SeaClutter { mode, level, auto_level } => {
-                    debug!("Sea clutter mode={} level={} auto_level={}", mode, level, auto_level);
-                }
-                Report::NoTransmitZone { mode, start_deg, end_deg } => {
-                    debug!("No transmit zone mode={} start={:.1} end={:.1}", mode, start_deg, end_deg);
-                }
-                Report::TimedIdle { mode, time, run_time } => {
-                    debug!("Timed idle mode={} time={} run_time={}", mode, time, run_time);
-                }
-                Report::ScannerStatus { status, change_in_ms } => {
-                    if change_in_ms > 0 {
-                        debug!("Scanner status change in {} ms", change_in_ms);
-                    } else {
-                        debug!("Scanner status {}", status);
+//! Garmin report receiver using the unified mayara-core controller
+//!
+//! Wraps the platform-independent `GarminController`, polling it so it keeps
+//! listening for reports, and routes control requests from clients to it.
+//! Control values are shown as the radar reports them back.
+
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tokio_graceful_shutdown::SubsystemHandle;
+
+use mayara_core::controllers::{GarminController, GarminRadarState, GarminTimedTransmit};
+use mayara_core::protocol::garmin::TransmitState;
+use mayara_core::{models, Brand};
+
+use crate::radar::{RadarError, RadarInfo, SharedRadars, Status, ROTATION_CHECK_INTERVAL};
+use crate::settings::{ControlError, ControlUpdate, ControlValue};
+
+use super::settings;
+use crate::tokio_io::TokioIoProvider;
+use crate::Session;
+
+pub struct GarminReportReceiver {
+    radars: SharedRadars,
+    info: RadarInfo,
+    key: String,
+    controller: GarminController,
+    io: TokioIoProvider,
+    poll_interval: Duration,
+    /// Timed transmit settings last read back from the radar
+    timed_transmit: Option<GarminTimedTransmit>,
+    /// Radar state last read back from the radar
+    radar_state: GarminRadarState,
+}
+
+impl GarminReportReceiver {
+    pub fn new(session: &Session, info: RadarInfo) -> GarminReportReceiver {
+        let key = info.key();
+        let radar_addr = info.addr.ip().to_string();
+
+        let radars = session
+            .read()
+            .unwrap()
+            .radars
+            .clone()
+            .expect("SharedRadars must be initialized before creating report receiver");
+
+        let mut controller = GarminController::new(&key, &radar_addr);
+        controller.set_passive(session.read().unwrap().args.passive);
+
+        GarminReportReceiver {
+            radars,
+            info,
+            key,
+            controller,
+            io: TokioIoProvider::new(),
+            poll_interval: Duration::from_millis(100),
+            timed_transmit: None,
+            radar_state: GarminRadarState::default(),
+        }
+    }
+
+    pub async fn run(mut self, subsys: SubsystemHandle) -> Result<(), RadarError> {
+        log::info!(
+            "{}: report receiver starting (unified controller)",
+            self.key
+        );
+
+        let mut command_rx = self.info.control_update_subscribe();
+        let mut poll_interval = interval(self.poll_interval);
+        poll_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut rotation_check = interval(ROTATION_CHECK_INTERVAL);
+        rotation_check.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = subsys.on_shutdown_requested() => {
+                    log::info!("{}: shutdown", self.key);
+                    self.controller.shutdown(&mut self.io);
+                    return Ok(());
+                },
+
+                _ = rotation_check.tick() => self.info.check_rotation(),
+
+                _ = poll_interval.tick() => {
+                    self.controller.poll(&mut self.io);
+                    self.update_radar_state();
+                    self.update_timed_transmit();
+                },
+
+                r = command_rx.recv() => {
+                    if let Ok(update) = r {
+                        if let Err(e) = self.process_control_update(update).await {
+                            log::error!("{}: control update error: {:?}", self.key, e);
+                        }
                     }
                 }
-                Report::ScannerMessage(msg) => debug!("Scanner message \"{}\"", msg),
-                Report::Unknown { packet_type, value, raw } => {
-                    trace!(
-                        "0x{:04X}: value 0x{:X} / {} len {}",
-                        packet_type,
-                        value,
-                        value,
-                        raw.len()
-                    );
+            }
+        }
+    }
+
+    /// Process a control update from a client
+    async fn process_control_update(&mut self, update: ControlUpdate) -> Result<(), RadarError> {
+        let cv = update.control_value;
+        let reply_tx = update.reply_tx;
+
+        log::debug!("{}: set_control {} = {}", self.key, cv.id, cv.value);
+
+        if cv.id == "timedTransmit" {
+            return match self.send_timed_transmit(&cv) {
+                Ok(()) => Ok(()),
+                Err(e) => {
+                    self.info
+                        .controls
+                        .send_error_to_client(reply_tx, &cv, &e)
+                        .await
                 }
+            };
+        }
+
+        let auto = cv.auto.unwrap_or(false);
+        match self.send_control_to_radar(&cv.id, &cv.value, auto) {
+            // The new value is shown once the radar reports it back
+            Ok(()) => Ok(()),
+            Err(e) => {
+                self.info
+                    .controls
+                    .send_error_to_client(reply_tx, &cv, &e)
+                    .await
+            }
+        }
+    }
+
+    /// Send timed transmit, the value is "transmit,standby" in minutes
+    fn send_timed_transmit(&mut self, cv: &ControlValue) -> Result<(), RadarError> {
+        let invalid = || RadarError::from(ControlError::Invalid(cv.id.clone(), cv.value.clone()));
+        let (transmit, standby) = cv.value.split_once(',').ok_or_else(invalid)?;
+        let transmit: u32 = transmit.trim().parse::<f32>().map_err(|_| invalid())? as u32;
+        let standby: u32 = standby.trim().parse::<f32>().map_err(|_| invalid())? as u32;
+        let enabled = cv.enabled.unwrap_or(true);
+        if enabled && !((1..=60).contains(&transmit) && (1..=60).contains(&standby)) {
+            return Err(invalid());
+        }
+
+        if enabled {
+            self.controller
+                .set_timed_transmit(&mut self.io, transmit, standby);
+        } else {
+            self.controller.set_timed_transmit(&mut self.io, 0, 0);
+        }
+        // Show what was sent until the radar reports it back
+        self.show_timed_transmit(GarminTimedTransmit {
+            enabled,
+            transmit_minutes: transmit,
+            standby_minutes: standby,
+        });
+        Ok(())
+    }
+
+    /// Show the timed transmit settings the radar reported, when they change
+    fn update_timed_transmit(&mut self) {
+        if let Some(reported) = self.controller.timed_transmit() {
+            if self.timed_transmit != Some(reported) {
+                self.timed_transmit = Some(reported);
+                self.show_timed_transmit(reported);
+            }
+        }
+    }
+
+    /// Show the control values the radar reported, when they change
+    fn update_radar_state(&mut self) {
+        let reported = self.controller.radar_state().clone();
+        if reported == self.radar_state {
+            return;
+        }
+
+        if let Some(model) = reported
+            .model
+            .filter(|&m| Some(m) != self.radar_state.model)
+        {
+            self.set_model(model);
+        }
+
+        let controls = &self.info.controls;
+        let status = reported.transmit_state.and_then(|ts| match ts {
+            TransmitState::Off => Some(Status::Off),
+            TransmitState::Standby => Some(Status::Standby),
+            TransmitState::Transmit => Some(Status::Transmit),
+            TransmitState::WarmingUp => Some(Status::Preparing),
+            TransmitState::Unknown(_) => None,
+        });
+        let with_auto = |id: &str, value: Option<u32>, auto: Option<bool>| match (value, auto) {
+            (Some(value), Some(auto)) => Some(controls.set_value_auto(id, auto, value as f32)),
+            (Some(value), None) => Some(controls.set(id, value as f32, None)),
+            (None, _) => None,
+        };
+        let results = [
+            status.map(|s| controls.set("power", s as i32 as f32, None)),
+            reported
+                .range_meters
+                .map(|m| controls.set("range", m as f32, None)),
+            with_auto("gain", reported.gain, reported.gain_auto),
+            with_auto("sea", reported.sea, reported.sea_auto),
+            with_auto("rain", reported.rain, reported.rain_auto),
+            reported
+                .bearing_alignment
+                .map(|deg| controls.set("bearingAlignment", deg, None)),
+        ];
+        let mut changed = false;
+        for result in results.into_iter().flatten() {
+            match result {
+                Ok(Some(())) => changed = true,
+                Ok(None) => {}
+                Err(e) => log::warn!("{}: {}", self.key, e),
+            }
+        }
+        if changed {
+            self.radars.update(&self.info);
+        }
+        self.radar_state = reported;
+    }
+
+    /// Use the model named in the scanner message for the model name and
+    /// range table
+    fn set_model(&mut self, model: &str) {
+        log::info!("{}: detected model {}", self.key, model);
+        if let Err(e) = self
+            .info
+            .controls
+            .set_string("modelName", model.to_string())
+        {
+            log::warn!("{}: {}", self.key, e);
+        }
+        if let Err(e) = self.info.set_ranges(settings::ranges(Some(model))) {
+            log::warn!("{}: Cannot set ranges: {}", self.key, e);
+        }
+        let model_info = models::get_model(Brand::Garmin, model);
+        self.info.antenna_type = model_info.and_then(|m| m.antenna_type);
+        self.info
+            .controls
+            .set_inverted_controls(model_info.map_or(&[], |m| m.inverted_controls));
+        self.radars.update(&self.info);
+    }
+
+    fn show_timed_transmit(&mut self, timed: GarminTimedTransmit) {
+        let controls = &self.info.controls;
+        let results = [
+            controls.set_value_auto_enabled(
+                "timedTransmit",
+                timed.transmit_minutes as f32,
+                None,
+                Some(timed.enabled),
+            ),
+            controls.set("timedStandby", timed.standby_minutes as f32, None),
+        ];
+        let mut changed = false;
+        for result in results {
+            match result {
+                Ok(Some(())) => changed = true,
+                Ok(None) => {}
+                Err(e) => log::warn!("{}: {}", self.key, e),
             }
         }
-        Err(e) => {
-            trace!("Failed to parse Garmin report: {}", e);
+        if changed {
+            self.radars.update(&self.info);
+        }
+    }
+
+    /// Send a control command to the radar
+    fn send_control_to_radar(
+        &mut self,
+        id: &str,
+        value: &str,
+        auto: bool,
+    ) -> Result<(), RadarError> {
+        if id == "power" {
+            let transmit = value == "transmit" || value == "Transmit";
+            self.controller.set_power(&mut self.io, transmit);
+            return Ok(());
         }
+
+        let num_value: f32 = value
+            .parse()
+            .map_err(|_| RadarError::MissingValue(id.to_string()))?;
+
+        match id {
+            "range" => {
+                let meters = self.info.snap_range(Brand::Garmin, num_value as u32);
+                self.controller.set_range(&mut self.io, meters);
+            }
+            "gain" | "sea" | "rain" => {
+                if !(0. ..=100.).contains(&num_value) {
+                    return Err(ControlError::Invalid(id.to_string(), value.to_string()).into());
+                }
+                let level = num_value as u32;
+                match id {
+                    "gain" => self.controller.set_gain(&mut self.io, level, auto),
+                    "sea" => self.controller.set_sea(&mut self.io, level, auto),
+                    _ => self.controller.set_rain(&mut self.io, level, auto),
+                }
+            }
+            "bearingAlignment" => self
+                .controller
+                .set_bearing_alignment(&mut self.io, num_value),
+            _ => return Err(RadarError::CannotSetControlType(id.to_string())),
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use mayara_core::{models, Brand};
+
+use crate::{
+    control_factory,
+    radar::{range::Ranges, NAUTICAL_MILE},
+    settings::{Control, SharedControls},
+    Session,
+};
+
+// Used for the range table when the radar does not tell us its model
+const DEFAULT_MODEL: &str = "GMR 24 xHD";
+
+pub fn new(session: Session, model: Option<&str>) -> SharedControls {
+    let mut controls = HashMap::new();
+
+    let model = model.unwrap_or("xHD");
+
+    let mut control = Control::new_string("userName");
+    control.set_string(model.to_string());
+    controls.insert("userName".to_string(), control.read_only(false));
+
+    let mut control = Control::new_string("modelName");
+    control.set_string(model.to_string());
+    controls.insert("modelName".to_string(), control);
+
+    // Power, range, gain, sea and rain are sent via GarminController
+    controls.insert(
+        "power".to_string(),
+        control_factory::power_control_for_brand(Brand::Garmin),
+    );
+
+    let max_value = 48. * NAUTICAL_MILE as f32;
+    controls.insert(
+        "range".to_string(),
+        Control::new_numeric("range", 0., max_value).unit("m"),
+    );
+
+    controls.insert(
+        "gain".to_string(),
+        control_factory::gain_control_for_brand(Brand::Garmin),
+    );
+    controls.insert(
+        "sea".to_string(),
+        control_factory::sea_control_for_brand(Brand::Garmin),
+    );
+    controls.insert(
+        "rain".to_string(),
+        control_factory::rain_control_for_brand(Brand::Garmin),
+    );
+    controls.insert(
+        "bearingAlignment".to_string(),
+        control_factory::bearing_alignment_control_for_brand(Brand::Garmin),
+    );
+
+    // The timedTransmit compound control: transmit period in minutes with
+    // the on/off state, and the standby period
+    controls.insert(
+        "timedTransmit".to_string(),
+        Control::new_numeric("timedTransmit", 0., 60.)
+            .unit("min")
+            .has_enabled(),
+    );
+    controls.insert(
+        "timedStandby".to_string(),
+        Control::new_numeric("timedStandby", 0., 60.).unit("min"),
+    );
+
+    SharedControls::new(session, controls)
+}
+
+/// Ranges from the mayara-core model database, the xHD table if the
+/// model is not known.
+pub fn ranges(model: Option<&str>) -> Ranges {
+    let model_info = model
+        .and_then(|m| models::get_model(Brand::Garmin, m))
+        .or_else(|| models::get_model(Brand::Garmin, DEFAULT_MODEL))
+        .expect("Garmin default model in mayara-core");
+    let ranges: Vec<i32> = model_info.range_table.iter().map(|&r| r as i32).collect();
+    Ranges::new_by_distance(&ranges)
+}
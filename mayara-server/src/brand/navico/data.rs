@@ -1,10 +1,11 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{io, time::Duration};
-use tokio::net::UdpSocket;
 use tokio::time::sleep;
 use tokio_graceful_shutdown::SubsystemHandle;
 use trail::TrailBuffer;
 
+use mayara_core::spoke::{interpolate_spokes, FrameTearDetector};
+
 // Use mayara-core for spoke header parsing (pure, WASM-compatible)
 use mayara_core::protocol::navico::{
     parse_4g_spoke_header, parse_br24_spoke_header, SPOKE_HEADER_SIZE,
@@ -12,7 +13,9 @@ use mayara_core::protocol::navico::{
 
 use crate::brand::navico::NAVICO_SPOKE_LEN;
 use crate::locator::LocatorId;
-use crate::network::create_udp_multicast_listen;
+use crate::network::{
+    create_udp_multicast_listen, ListenSocket, StallDetector, MULTICAST_STALL_TIMEOUT,
+};
 use crate::protos::RadarMessage::RadarMessage;
 use crate::radar::spoke::{to_protobuf_spoke, GenericSpoke};
 use crate::settings::DataUpdate;
@@ -26,6 +29,8 @@ const RADAR_LINE_HEADER_LENGTH: usize = SPOKE_HEADER_SIZE;
 const RADAR_LINE_LENGTH: usize = SPOKE_HEADER_SIZE + RADAR_LINE_DATA_LENGTH;
 // Buffer size for UDP frame: header + 32 spokes
 const RADAR_FRAME_BUFFER_SIZE: usize = FRAME_HEADER_LENGTH + (SPOKES_PER_FRAME * RADAR_LINE_LENGTH);
+// Larger gaps are left empty rather than smeared over a wide wedge
+const MAX_INTERPOLATED_SPOKES: usize = 2 * SPOKES_PER_FRAME;
 
 // The LookupSpokeEnum is an index into an array, really
 enum LookupDoppler {
@@ -42,12 +47,16 @@ pub struct NavicoDataReceiver {
     key: String,
     statistics: Statistics,
     info: RadarInfo,
-    sock: Option<UdpSocket>,
+    sock: Option<ListenSocket>,
+    stall: StallDetector,
     data_update_rx: tokio::sync::broadcast::Receiver<DataUpdate>,
     doppler: DopplerMode,
     pixel_to_blob: [[u8; BYTE_LOOKUP_LENGTH]; LOOKUP_DOPPLER_LENGTH],
     trails: TrailBuffer,
     prev_angle: u16,
+    tears: FrameTearDetector,
+    interpolate_tears: bool,
+    last_spoke: Option<Vec<u8>>,
     replay: bool,
 }
 
@@ -59,7 +68,10 @@ impl NavicoDataReceiver {
 
         let pixel_to_blob = Self::pixel_to_blob(&info.legend);
         let trails = TrailBuffer::new(session.clone(), &info);
-        let replay = session.read().unwrap().args.replay;
+        let (replay, interpolate_tears) = {
+            let args = &session.read().unwrap().args;
+            (args.replay, args.interpolate_tears)
+        };
 
         log::debug!(
             "{}: Creating NavicoDataReceiver with pixel_to_blob {:?}",
@@ -72,11 +84,15 @@ impl NavicoDataReceiver {
             statistics: Statistics::new(),
             info,
             sock: None,
+            stall: StallDetector::new(),
             data_update_rx,
             doppler: DopplerMode::None,
             pixel_to_blob,
             trails,
             prev_angle: 0,
+            tears: FrameTearDetector::new(SPOKES_PER_FRAME as u32, NAVICO_SPOKES as u32),
+            interpolate_tears,
+            last_spoke: None,
             replay,
         }
     }
@@ -85,6 +101,7 @@ impl NavicoDataReceiver {
         match create_udp_multicast_listen(&self.info.spoke_data_addr, &self.info.nic_addr) {
             Ok(sock) => {
                 self.sock = Some(sock);
+                self.stall.reset();
                 log::debug!(
                     "{} via {}: listening for spoke data",
                     &self.info.spoke_data_addr,
@@ -166,6 +183,15 @@ impl NavicoDataReceiver {
                     }
                 };
             }
+            DataUpdate::Target(_) => {
+                // Handled by the ARPA subsystem
+            }
+            DataUpdate::Standby => {
+                // Spokes resume at an arbitrary angle, and tears count
+                // per transmit period
+                self.tears.reset();
+                self.publish_tears();
+            }
         }
 
         Ok(())
@@ -214,9 +240,18 @@ impl NavicoDataReceiver {
                         }
                     }
                 },
+                _ = self.stall.stalled() => {
+                    log::warn!(
+                        "{}: no spoke data for {:?}, re-joining multicast",
+                        self.key,
+                        MULTICAST_STALL_TIMEOUT
+                    );
+                    return Err(RadarError::Timeout);
+                },
                 r = self.sock.as_ref().unwrap().recv_buf_from(&mut buf)  => {
                     match r {
                         Ok(_) => {
+                            self.stall.packet_received();
                             self.process_frame(&mut buf);
                         },
                         Err(e) => {
@@ -257,6 +292,7 @@ impl NavicoDataReceiver {
         let mut message = RadarMessage::new();
         message.radar = self.info.id as u32;
 
+        let mut first_in_frame = true;
         let mut offset: usize = FRAME_HEADER_LENGTH;
         for scanline in 0..spokes_in_frame {
             let header_slice = &data[offset..offset + RADAR_LINE_HEADER_LENGTH];
@@ -270,14 +306,39 @@ impl NavicoDataReceiver {
                     scanline,
                     PrintableSpoke::new(spoke_slice)
                 );
-                let mut spoke = to_protobuf_spoke(
-                    &self.info,
-                    range,
-                    angle,
-                    heading,
-                    now,
-                    self.process_spoke(spoke_slice),
-                );
+                let generic_spoke = self.process_spoke(spoke_slice);
+                if first_in_frame {
+                    first_in_frame = false;
+                    let missing = self
+                        .tears
+                        .on_frame(angle as u32, (spokes_in_frame - scanline) as u32);
+                    if missing > 0 {
+                        log::debug!(
+                            "{}: Frame tear before angle {}, {} spokes missing ({} tears so far)",
+                            self.key,
+                            angle,
+                            missing,
+                            self.tears.tears()
+                        );
+                        self.publish_tears();
+                        if self.interpolate_tears && missing as usize <= MAX_INTERPOLATED_SPOKES {
+                            self.fill_tear(
+                                &mut message,
+                                &generic_spoke,
+                                angle,
+                                missing,
+                                range,
+                                heading,
+                                now,
+                            );
+                        }
+                    }
+                }
+                if self.interpolate_tears {
+                    self.last_spoke = Some(generic_spoke.clone());
+                }
+                let mut spoke =
+                    to_protobuf_spoke(&self.info, range, angle, heading, now, generic_spoke);
                 self.trails.update_trails(&mut spoke, &self.info.legend);
                 message.spokes.push(spoke);
 
@@ -305,11 +366,54 @@ impl NavicoDataReceiver {
             let ms = self.info.full_rotation();
             self.trails.set_rotation_speed(ms);
             self.statistics.full_rotation(&self.key);
+            log::debug!(
+                "{}: {} frame tears, {} spoke groups lost in total",
+                self.key,
+                self.tears.tears(),
+                self.tears.missing_frames()
+            );
         }
 
         self.info.broadcast_radar_message(message);
     }
 
+    fn publish_tears(&self) {
+        let _ = self
+            .info
+            .controls
+            .set("frameTears", self.tears.tears() as f32, None);
+    }
+
+    /// Add `missing` blended spokes ending just before `angle` to the message.
+    /// They bypass trails (and so ARPA and guard zones) as they hold no real data.
+    #[allow(clippy::too_many_arguments)]
+    fn fill_tear(
+        &self,
+        message: &mut RadarMessage,
+        after: &[u8],
+        angle: SpokeBearing,
+        missing: u32,
+        range: u32,
+        heading: Option<u16>,
+        now: Option<u64>,
+    ) {
+        let Some(before) = &self.last_spoke else {
+            return;
+        };
+        let max_value = self.info.pixel_values.saturating_sub(1);
+        let spokes = NAVICO_SPOKES as u32;
+        let first = (angle as u32 + spokes - missing) % spokes;
+        for (n, data) in interpolate_spokes(before, after, missing as usize, max_value)
+            .into_iter()
+            .enumerate()
+        {
+            let fill_angle = ((first + n as u32) % spokes) as SpokeBearing;
+            message.spokes.push(to_protobuf_spoke(
+                &self.info, range, fill_angle, heading, now, data,
+            ));
+        }
+    }
+
     fn validate_header(
         &self,
         header_slice: &[u8],
@@ -3,7 +3,7 @@
 //! Sends heading, SOG, and COG packets to Navico radars.
 //! Uses formatting functions from mayara-core for packet construction.
 
-use std::net::{Ipv4Addr, SocketAddrV4};
+use std::net::{IpAddr, SocketAddr};
 use tokio::net::UdpSocket;
 
 use mayara_core::protocol::navico::{
@@ -17,16 +17,16 @@ use crate::radar::{RadarError, RadarInfo};
 
 
 // Socket addresses constructed from core constants
-fn info_socket_addr() -> SocketAddrV4 {
-    SocketAddrV4::new(INFO_ADDR.parse().unwrap(), INFO_PORT)
+fn info_socket_addr() -> SocketAddr {
+    SocketAddr::new(INFO_ADDR.parse().unwrap(), INFO_PORT)
 }
 
-fn speed_a_socket_addr() -> SocketAddrV4 {
-    SocketAddrV4::new(SPEED_ADDR_A.parse().unwrap(), SPEED_PORT_A)
+fn speed_a_socket_addr() -> SocketAddr {
+    SocketAddr::new(SPEED_ADDR_A.parse().unwrap(), SPEED_PORT_A)
 }
 
-fn speed_b_socket_addr() -> SocketAddrV4 {
-    SocketAddrV4::new(SPEED_ADDR_B.parse().unwrap(), SPEED_PORT_B)
+fn speed_b_socket_addr() -> SocketAddr {
+    SocketAddr::new(SPEED_ADDR_B.parse().unwrap(), SPEED_PORT_B)
 }
 
 // Socket index for the socket array
@@ -36,7 +36,7 @@ enum SocketIndex {
     SpeedB = 2,
 }
 
-fn socket_address(index: usize) -> SocketAddrV4 {
+fn socket_address(index: usize) -> SocketAddr {
     match index {
         0 => info_socket_addr(),
         1 => speed_a_socket_addr(),
@@ -47,7 +47,7 @@ fn socket_address(index: usize) -> SocketAddrV4 {
 
 pub(crate) struct Information {
     key: String,
-    nic_addr: Ipv4Addr,
+    nic_addr: IpAddr,
     sock: [Option<UdpSocket>; 3], // Heading/Navigation, Speed A, Speed B
     counter: u16,
 }
@@ -1,4 +1,4 @@
-use std::net::{Ipv4Addr, SocketAddrV4};
+use std::net::{IpAddr, SocketAddr};
 use std::io;
 use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
 
@@ -9,7 +9,7 @@ use crate::{Brand, Session};
 mod data;
 mod info;
 mod report;
-mod settings;
+pub(crate) mod settings;
 
 // Re-export core's Model type for compatibility
 pub use mayara_core::protocol::navico::Model;
@@ -24,14 +24,9 @@ use mayara_core::protocol::navico::{
 const NAVICO_SPOKES: usize = NAVICO_SPOKES_U16 as usize;
 const NAVICO_SPOKE_LEN: usize = NAVICO_SPOKE_LEN_U16 as usize;
 
-/// Parse a socket address string "ip:port" into SocketAddrV4
-fn parse_socket_addr(s: &str) -> Option<SocketAddrV4> {
-    let colon_pos = s.rfind(':')?;
-    let ip_str = &s[..colon_pos];
-    let port_str = &s[colon_pos + 1..];
-    let ip: Ipv4Addr = ip_str.parse().ok()?;
-    let port: u16 = port_str.parse().ok()?;
-    Some(SocketAddrV4::new(ip, port))
+/// Parse a socket address string "ip:port" or "[ipv6]:port" into SocketAddr
+fn parse_socket_addr(s: &str) -> Option<SocketAddr> {
+    s.parse().ok()
 }
 
 // Spoke numbers go from [0..4096>, but only half of them are used.
@@ -357,84 +352,38 @@ use mayara_core::radar::{ParsedAddress, RadarDiscovery};
 pub fn process_discovery(
     session: Session,
     discovery: &RadarDiscovery,
-    nic_addr: Ipv4Addr,
+    nic_addr: IpAddr,
     radars: &SharedRadars,
     subsys: &SubsystemHandle,
 ) -> Result<(), io::Error> {
-    // Parse radar's main address
-    let parsed = ParsedAddress::parse(&discovery.address)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
-    let radar_ip = Ipv4Addr::from(parsed.ip);
-    let radar_addr = SocketAddrV4::new(radar_ip, parsed.port);
-
-    // Use the full addresses from beacon if available, otherwise fall back to port-based
-    let data_addr: SocketAddrV4 = if let Some(addr) = &discovery.data_address {
-        parse_socket_addr(addr).unwrap_or_else(|| SocketAddrV4::new(radar_ip, discovery.data_port))
-    } else {
-        SocketAddrV4::new(radar_ip, discovery.data_port)
-    };
-
-    let report_addr: SocketAddrV4 = if let Some(addr) = &discovery.report_address {
-        parse_socket_addr(addr).unwrap_or_else(|| SocketAddrV4::new(radar_ip, discovery.command_port))
-    } else {
-        SocketAddrV4::new(radar_ip, discovery.command_port)
-    };
-
-    let send_addr: SocketAddrV4 = if let Some(addr) = &discovery.send_address {
-        parse_socket_addr(addr).unwrap_or_else(|| SocketAddrV4::new(radar_ip, discovery.command_port))
-    } else {
-        SocketAddrV4::new(radar_ip, discovery.command_port)
-    };
-
-    // Determine locator ID and model
-    let is_br24 = discovery.model.as_deref() == Some("BR24");
-    let locator_id = if is_br24 {
-        LocatorId::GenBR24
-    } else {
-        LocatorId::Gen3Plus
-    };
+    let info = discovery_radar_info(session.clone(), discovery, nic_addr)?;
     let model_name = discovery.model.as_deref();
 
-    // Determine if this is a dual-range radar based on suffix
-    let is_dual_range = discovery.suffix.is_some();
-
-    let info: RadarInfo = RadarInfo::new(
-        session.clone(),
-        locator_id,
-        Brand::Navico,
-        discovery.serial_number.as_deref(),
-        discovery.suffix.as_deref(),
-        16,
-        NAVICO_SPOKES,
-        NAVICO_SPOKE_LEN,
-        radar_addr,
-        nic_addr,
-        data_addr,
-        report_addr,
-        send_addr,
-        settings::new(session.clone(), model_name),
-        is_dual_range,
-    );
-
-    // Set userName control
-    info.controls.set_string("userName", info.key()).ok();
-
     // Check if this is a new radar
     let Some(mut info) = radars.located(info) else {
         log::debug!("Navico radar {} already known", discovery.name);
         return Ok(());
     };
 
+    // In replay mode, --replay-model overrides whatever the captured beacon said
+    let replay_model = session.read().unwrap().args.replay_model(mayara_core::Brand::Navico);
+
     // Apply model-specific settings if known
-    let model = match model_name {
-        Some(name) => Model::from_name(name),
-        None => Model::Unknown,
+    let model = match (replay_model, model_name) {
+        (Some(model_info), _) => Model::from_name(model_info.family),
+        (None, Some(name)) => Model::from_name(name),
+        (None, None) => Model::Unknown,
     };
 
     if model != Model::Unknown {
         let info2 = info.clone();
         settings::update_when_model_known(&mut info.controls, model, &info2);
         info.set_doppler(model.has_doppler());
+        if let Some(model_info) = replay_model {
+            // Keep the exact model (e.g. HALO24) so capabilities match the capture
+            log::info!("{}: Radar model {} assumed for replay mode", info.key(), model_info.model);
+            info.controls.set_model_name(model_info.model.to_string());
+        }
         radars.update(&info);
     }
 
@@ -448,6 +397,8 @@ pub fn process_discovery(
             info_clone.forward_output(s)
         }));
     }
+    crate::recording::capture::start_capture(&session, &info, subsys);
+    crate::radar::arpa::start_arpa(&session, &info, subsys);
 
     let data_receiver = data::NavicoDataReceiver::new(&session, info.clone());
     let report_receiver = report::NavicoReportReceiver::new(
@@ -472,6 +423,73 @@ pub fn process_discovery(
     Ok(())
 }
 
+/// Build the [`RadarInfo`] for one Navico discovery.
+///
+/// Each range of a dual-range radar gets its own key (suffix A or B) and its
+/// own data, report and send addresses from the beacon.
+fn discovery_radar_info(
+    session: Session,
+    discovery: &RadarDiscovery,
+    nic_addr: IpAddr,
+) -> Result<RadarInfo, io::Error> {
+    // Parse radar's main address
+    let parsed = ParsedAddress::parse(&discovery.address)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let radar_ip = parsed.ip;
+    let radar_addr = parsed.socket_addr();
+
+    // Use the full addresses from beacon if available, otherwise fall back to port-based
+    let data_addr: SocketAddr = if let Some(addr) = &discovery.data_address {
+        parse_socket_addr(addr).unwrap_or_else(|| SocketAddr::new(radar_ip, discovery.data_port))
+    } else {
+        SocketAddr::new(radar_ip, discovery.data_port)
+    };
+
+    let report_addr: SocketAddr = if let Some(addr) = &discovery.report_address {
+        parse_socket_addr(addr).unwrap_or_else(|| SocketAddr::new(radar_ip, discovery.command_port))
+    } else {
+        SocketAddr::new(radar_ip, discovery.command_port)
+    };
+
+    let send_addr: SocketAddr = if let Some(addr) = &discovery.send_address {
+        parse_socket_addr(addr).unwrap_or_else(|| SocketAddr::new(radar_ip, discovery.command_port))
+    } else {
+        SocketAddr::new(radar_ip, discovery.command_port)
+    };
+
+    // Determine locator ID and model
+    let is_br24 = discovery.model.as_deref() == Some("BR24");
+    let locator_id = if is_br24 {
+        LocatorId::GenBR24
+    } else {
+        LocatorId::Gen3Plus
+    };
+    let model_name = discovery.model.as_deref();
+
+    let info: RadarInfo = RadarInfo::new(
+        session.clone(),
+        locator_id,
+        Brand::Navico,
+        discovery.serial_number.as_deref(),
+        discovery.suffix.as_deref(),
+        16,
+        NAVICO_SPOKES,
+        NAVICO_SPOKE_LEN,
+        radar_addr,
+        nic_addr,
+        data_addr,
+        report_addr,
+        send_addr,
+        settings::new(session, model_name),
+        false, // Doppler is set once the model is known
+    );
+
+    // Set userName control
+    info.controls.set_string("userName", info.key()).ok();
+
+    Ok(info)
+}
+
 /// Update controls for a Navico radar when model is known.
 ///
 /// Called from SharedRadars::update_navico_model when a model update arrives.
@@ -480,3 +498,48 @@ pub fn update_controls_for_model(info: &mut RadarInfo, model: Model) {
     settings::update_when_model_known(&mut info.controls, model, &info2);
     info.set_doppler(model.has_doppler());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discovery(suffix: &str, data: &str, report: &str, send: &str) -> RadarDiscovery {
+        RadarDiscovery {
+            brand: mayara_core::Brand::Navico,
+            model: None,
+            name: "1403200123".into(),
+            address: "192.168.1.50:6878".into(),
+            data_port: 0,
+            command_port: 0,
+            spokes_per_revolution: NAVICO_SPOKES_U16,
+            max_spoke_len: NAVICO_SPOKE_LEN_U16,
+            pixel_values: 16,
+            serial_number: None,
+            nic_address: None,
+            suffix: Some(suffix.into()),
+            data_address: Some(data.into()),
+            report_address: Some(report.into()),
+            send_address: Some(send.into()),
+        }
+    }
+
+    #[test]
+    fn test_dual_range_discoveries_are_separate_radars() {
+        let session = Session::new_fake();
+        let nic = IpAddr::from([192, 168, 1, 10]);
+        let a = discovery("A", "236.6.7.8:6678", "236.6.7.9:6679", "236.6.7.10:6680");
+        let b = discovery("B", "236.6.7.13:6657", "236.6.7.15:6659", "236.6.7.14:6658");
+
+        let a = discovery_radar_info(session.clone(), &a, nic).unwrap();
+        let b = discovery_radar_info(session, &b, nic).unwrap();
+        assert_ne!(a.key(), b.key());
+        assert!(a.key().ends_with("-A"));
+        assert!(b.key().ends_with("-B"));
+        assert_eq!(a.spoke_data_addr, "236.6.7.8:6678".parse().unwrap());
+        assert_eq!(b.spoke_data_addr, "236.6.7.13:6657".parse().unwrap());
+        assert_eq!(b.report_addr, "236.6.7.15:6659".parse().unwrap());
+        assert_eq!(b.send_command_addr, "236.6.7.14:6658".parse().unwrap());
+        assert_eq!(a.addr, b.addr);
+        assert!(!a.doppler && !b.doppler);
+    }
+}
@@ -3,18 +3,17 @@ use std::cmp::min;
 use std::io;
 use std::net::SocketAddr;
 use std::time::Duration;
-use tokio::net::UdpSocket;
 use tokio::sync::*;
-use tokio::time::{sleep, sleep_until, Instant};
+use tokio::time::{interval, sleep, sleep_until, Instant, MissedTickBehavior};
 use tokio_graceful_shutdown::SubsystemHandle;
 
-use std::net::SocketAddrV4;
-
 use crate::brand::navico::info::Information;
-use crate::network::create_udp_multicast_listen;
+use crate::network::{
+    create_udp_multicast_listen, ListenSocket, StallDetector, MULTICAST_STALL_TIMEOUT,
+};
 use crate::radar::range::{RangeDetection, RangeDetectionResult};
 use crate::radar::target::MS_TO_KN;
-use crate::radar::{DopplerMode, RadarError, RadarInfo, SharedRadars};
+use crate::radar::{DopplerMode, RadarError, RadarInfo, SharedRadars, ROTATION_CHECK_INTERVAL};
 use crate::settings::{ControlUpdate, ControlValue, DataUpdate};
 use crate::tokio_io::TokioIoProvider;
 use crate::Session;
@@ -28,10 +27,10 @@ use crate::radar::Status;
 
 // Use mayara-core for report parsing and packet types (pure, WASM-compatible)
 use mayara_core::protocol::navico::{
-    parse_report_01, parse_report_02, parse_report_03, parse_report_04,
-    parse_report_06_68, parse_report_06_74, parse_report_08,
-    HaloHeadingPacket, HaloNavigationPacket, HaloSpeedPacket,
-    INFO_ADDR, INFO_PORT, SPEED_ADDR_A, SPEED_PORT_A,
+    antenna_height_meters_to_mm, parse_report_01_detail, parse_report_02, parse_report_03,
+    parse_report_04, parse_report_06_68, parse_report_06_74, parse_report_08,
+    range_decimeters_to_meters, range_meters_to_decimeters, HaloHeadingPacket,
+    HaloNavigationPacket, HaloSpeedPacket, INFO_ADDR, INFO_PORT, SPEED_ADDR_A, SPEED_PORT_A,
 };
 
 pub struct NavicoReportReceiver {
@@ -40,11 +39,12 @@ pub struct NavicoReportReceiver {
     info: RadarInfo,
     key: String,
     report_buf: Vec<u8>,
-    report_socket: Option<UdpSocket>,
+    report_socket: Option<ListenSocket>,
+    report_stall: StallDetector,
     info_buf: Vec<u8>,
-    info_socket: Option<UdpSocket>,
+    info_socket: Option<ListenSocket>,
     speed_buf: Vec<u8>,
-    speed_socket: Option<UdpSocket>,
+    speed_socket: Option<ListenSocket>,
     radars: SharedRadars,
     model: Model,
     /// Unified controller from mayara-core
@@ -102,18 +102,12 @@ impl NavicoReportReceiver {
         );
 
         // Convert server Model to core NavicoModel
-        let core_model = match model {
-            Model::BR24 => NavicoModel::BR24,
-            Model::Gen3 => NavicoModel::Gen3,
-            Model::Gen4 => NavicoModel::Gen4,
-            Model::HALO => NavicoModel::Halo,
-            Model::Unknown => NavicoModel::Unknown,
-        };
+        let core_model = NavicoModel::from(model);
 
         // If we are in replay mode, we don't need a controller
         let controller = if !replay {
             log::debug!("{}: Starting controller (unified)", key);
-            Some(NavicoController::new(
+            let mut controller = NavicoController::new(
                 &key,
                 &info.send_command_addr.ip().to_string(),
                 info.send_command_addr.port(),
@@ -121,18 +115,20 @@ impl NavicoReportReceiver {
                 info.report_addr.port(),
                 &info.nic_addr.to_string(),
                 core_model,
-            ))
+            );
+            controller.set_passive(args.passive);
+            Some(controller)
         } else {
             log::debug!("{}: No controller, replay mode", key);
             None
         };
         let io = TokioIoProvider::new();
 
-        let info_sender = if !replay {
+        let info_sender = if !replay && !args.passive {
             log::debug!("{}: Starting info sender", key);
             Some(Information::new(key.clone(), &info))
         } else {
-            log::debug!("{}: No info sender, replay mode", key);
+            log::debug!("{}: No info sender, replay or passive mode", key);
             None
         };
 
@@ -147,6 +143,7 @@ impl NavicoReportReceiver {
             info,
             report_buf: Vec::with_capacity(1000),
             report_socket: None,
+            report_stall: StallDetector::new(),
             info_buf: Vec::with_capacity(::core::mem::size_of::<HaloHeadingPacket>()),
             info_socket: None,
             speed_buf: Vec::with_capacity(::core::mem::size_of::<HaloSpeedPacket>()),
@@ -169,6 +166,7 @@ impl NavicoReportReceiver {
         match create_udp_multicast_listen(&self.info.report_addr, &self.info.nic_addr) {
             Ok(socket) => {
                 self.report_socket = Some(socket);
+                self.report_stall.reset();
                 log::debug!(
                     "{}: {} via {}: listening for reports",
                     self.key,
@@ -195,7 +193,7 @@ impl NavicoReportReceiver {
         if self.info_socket.is_some() {
             return Ok(()); // Already started
         }
-        let info_addr = SocketAddrV4::new(INFO_ADDR.parse().unwrap(), INFO_PORT);
+        let info_addr = SocketAddr::new(INFO_ADDR.parse().unwrap(), INFO_PORT);
         match create_udp_multicast_listen(&info_addr, &self.info.nic_addr) {
             Ok(socket) => {
                 self.info_socket = Some(socket);
@@ -224,7 +222,7 @@ impl NavicoReportReceiver {
         if self.speed_socket.is_some() {
             return Ok(()); // Already started
         }
-        let speed_addr = SocketAddrV4::new(SPEED_ADDR_A.parse().unwrap(), SPEED_PORT_A);
+        let speed_addr = SocketAddr::new(SPEED_ADDR_A.parse().unwrap(), SPEED_PORT_A);
         match create_udp_multicast_listen(&speed_addr, &self.info.nic_addr) {
             Ok(socket) => {
                 self.speed_socket = Some(socket);
@@ -255,6 +253,8 @@ impl NavicoReportReceiver {
     //
     async fn socket_loop(&mut self, subsys: &SubsystemHandle) -> Result<(), RadarError> {
         log::debug!("{}: listening for reports", self.key);
+        let mut rotation_check = interval(ROTATION_CHECK_INTERVAL);
+        rotation_check.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
         loop {
             if !self.replay {
@@ -273,10 +273,12 @@ impl NavicoReportReceiver {
                     return Err(RadarError::Shutdown);
                 },
 
+                _ = rotation_check.tick() => self.info.check_rotation(),
+
                 _ = sleep_until(timeout) => {
                     let now = Instant::now();
                     if self.range_timeout <= now {
-                        self.process_range(0).await?;
+                        self.process_range_timeout().await?;
                     }
                     if self.report_request_timeout <= now {
                         self.send_report_requests().await?;
@@ -286,9 +288,19 @@ impl NavicoReportReceiver {
                     }
                 },
 
+                _ = self.report_stall.stalled() => {
+                    log::warn!(
+                        "{}: no reports for {:?}, re-joining multicast",
+                        self.key,
+                        MULTICAST_STALL_TIMEOUT
+                    );
+                    return Err(RadarError::Timeout);
+                },
+
                 r = self.report_socket.as_ref().unwrap().recv_buf_from(&mut self.report_buf)  => {
                     match r {
                         Ok((_len, _addr)) => {
+                            self.report_stall.packet_received();
                             if let Err(e) = self.process_report().await {
                                 log::error!("{}: {}", self.key, e);
                             }
@@ -415,7 +427,10 @@ impl NavicoReportReceiver {
 
         match cv.id.as_str() {
             "range" => {
-                controller.set_range(&mut self.io, deci_value);
+                let meters = self
+                    .info
+                    .snap_range(mayara_core::Brand::Navico, value.round() as u32);
+                controller.set_range(&mut self.io, range_meters_to_decimeters(meters));
             }
             "bearingAlignment" => {
                 controller.set_bearing_alignment(&mut self.io, mod_deci_degrees(deci_value));
@@ -448,7 +463,11 @@ impl NavicoReportReceiver {
                 controller.set_local_interference_rejection(&mut self.io, value as u8);
             }
             "scanSpeed" => {
-                controller.set_scan_speed(&mut self.io, value as u8 > 0);
+                let wire = self
+                    .info
+                    .scan_speed_wire(mayara_core::Brand::Navico, value.round() as i32)
+                    .ok_or_else(|| RadarError::MissingValue(cv.id.clone()))?;
+                controller.set_scan_speed(&mut self.io, wire);
             }
             "mode" => {
                 controller.set_mode(&mut self.io, value as u8);
@@ -466,7 +485,7 @@ impl NavicoReportReceiver {
                 controller.set_doppler_speed(&mut self.io, (value as u16) * 16);
             }
             "antennaHeight" => {
-                controller.set_antenna_height(&mut self.io, deci_value as u16);
+                controller.set_antenna_height(&mut self.io, antenna_height_meters_to_mm(value));
             }
             "accentLight" => {
                 controller.set_accent_light(&mut self.io, value as u8);
@@ -634,7 +653,6 @@ impl NavicoReportReceiver {
 
     // If range detection is in progress, go to the next range
     async fn process_range(&mut self, range: i32) -> Result<(), RadarError> {
-        let range = range / 10;
         if self.info.ranges.len() == 0 && self.info.range_detection.is_none() && !self.replay {
             if let Some(status) = self.info.controls.get_status() {
                 if status == Status::Transmit {
@@ -663,32 +681,50 @@ impl NavicoReportReceiver {
         }
 
         if let Some(range_detection) = &mut self.info.range_detection {
-            match range_detection.found_range(range) {
-                RangeDetectionResult::NoRange => {
-                    return Ok(());
-                }
-                RangeDetectionResult::Complete(ranges, saved_range) => {
-                    self.info.ranges = ranges.clone();
-                    self.info
-                        .controls
-                        .set_valid_ranges("range", &ranges)?;
-                    self.info.range_detection = None;
-                    self.range_timeout = Instant::now() + FAR_FUTURE;
+            let result = range_detection.found_range(range);
+            self.process_range_detection_result(result).await?;
+        }
 
-                    self.radars.update(&self.info);
+        Ok(())
+    }
 
-                    self.send_range(saved_range).await?;
-                    if self.transmit_after_range_detection {
-                        self.transmit_after_range_detection = false;
-                        self.send_status(Status::Transmit).await?;
-                    }
-                }
-                RangeDetectionResult::NextRange(r) => {
-                    self.range_timeout = Instant::now() + RANGE_DETECTION_INTERVAL;
+    // No range report since the last range sent during detection
+    async fn process_range_timeout(&mut self) -> Result<(), RadarError> {
+        self.range_timeout = Instant::now() + FAR_FUTURE;
+        if let Some(range_detection) = &mut self.info.range_detection {
+            let result = range_detection.timed_out();
+            self.process_range_detection_result(result).await?;
+        }
+        Ok(())
+    }
 
-                    self.send_range(r).await?;
+    async fn process_range_detection_result(
+        &mut self,
+        result: RangeDetectionResult,
+    ) -> Result<(), RadarError> {
+        match result {
+            RangeDetectionResult::NoRange => {}
+            RangeDetectionResult::Complete(ranges, saved_range) => {
+                self.info.ranges = ranges.clone();
+                self.info
+                    .controls
+                    .set_valid_ranges("range", &ranges)?;
+                self.info.range_detection = None;
+                self.range_timeout = Instant::now() + FAR_FUTURE;
+
+                self.radars.update(&self.info);
+
+                self.send_range(saved_range).await?;
+                if self.transmit_after_range_detection {
+                    self.transmit_after_range_detection = false;
+                    self.send_status(Status::Transmit).await?;
                 }
             }
+            RangeDetectionResult::NextRange(r) => {
+                self.range_timeout = Instant::now() + RANGE_DETECTION_INTERVAL;
+
+                self.send_range(r).await?;
+            }
         }
 
         Ok(())
@@ -704,8 +740,7 @@ impl NavicoReportReceiver {
 
     async fn send_range(&mut self, range: i32) -> Result<(), RadarError> {
         if let Some(controller) = &mut self.controller {
-            // Range is in decimeters (range * 10)
-            controller.set_range(&mut self.io, range * 10);
+            controller.set_range(&mut self.io, range_meters_to_decimeters(range as u32));
         }
         Ok(())
     }
@@ -821,19 +856,28 @@ impl NavicoReportReceiver {
 
     async fn process_report_01(&mut self) -> Result<(), Error> {
         // Use mayara-core parsing
-        let status = parse_report_01(&self.report_buf)
+        let report = parse_report_01_detail(&self.report_buf)
             .map_err(|e| anyhow::anyhow!("{}: Report 01 parse error: {}", self.key, e))?;
 
-        log::debug!("{}: report 01 - status {:?}", self.key, status);
+        log::debug!("{}: report 01 - {:?}", self.key, report);
 
         // Convert mayara_core::protocol::navico::Status to crate::radar::Status
-        let status = match status {
+        let status = match report.status {
             mayara_core::protocol::navico::Status::Off => Status::Off,
             mayara_core::protocol::navico::Status::Standby => Status::Standby,
             mayara_core::protocol::navico::Status::Transmit => Status::Transmit,
             mayara_core::protocol::navico::Status::Preparing => Status::Preparing,
         };
+        let standby = Status::Standby as i32 as f32;
+        if status == Status::Standby
+            && self.info.controls.get("power").and_then(|c| c.value) != Some(standby)
+        {
+            let _ = self.data_tx.send(DataUpdate::Standby);
+        }
         self.set_value("power", status as i32 as f32);
+        // Progress while preparing; zero otherwise
+        self.set_value("preparingStage", report.substage as f32);
+        self.set_value("preparingCounter", report.counter as f32);
         Ok(())
     }
 
@@ -844,7 +888,7 @@ impl NavicoReportReceiver {
 
         log::trace!("{}: report 02 - {:?}", self.key, report);
 
-        let range = report.range;
+        let range = range_decimeters_to_meters(report.range);
         let mode = report.mode as i32;
         let gain_auto = if report.gain_auto { 1u8 } else { 0u8 };
         let gain = report.gain as i32;
@@ -909,14 +953,7 @@ impl NavicoReportReceiver {
 
                     // Update the controller's model
                     if let Some(controller) = &mut self.controller {
-                        let core_model = match model {
-                            Model::BR24 => NavicoModel::BR24,
-                            Model::Gen3 => NavicoModel::Gen3,
-                            Model::Gen4 => NavicoModel::Gen4,
-                            Model::HALO => NavicoModel::Halo,
-                            Model::Unknown => NavicoModel::Unknown,
-                        };
-                        controller.set_model(core_model);
+                        controller.set_model(NavicoModel::from(model));
                     }
 
                     super::settings::update_when_model_known(
@@ -925,6 +962,9 @@ impl NavicoReportReceiver {
                         &info2,
                     );
                     self.info.set_doppler(model.has_doppler());
+                    self.info.antenna_type =
+                        mayara_core::models::get_model(mayara_core::Brand::Navico, model.as_str())
+                            .and_then(|m| m.antenna_type);
 
                     self.radars.update(&self.info);
 
@@ -949,6 +989,7 @@ impl NavicoReportReceiver {
         log::trace!("{}: report 04 - {:?}", self.key, report);
 
         self.set_value("bearingAlignment", report.bearing_alignment as f32);
+        // Millimeters; the control's wire scale (99000 for 99 m) maps to meters
         self.set_value("antennaHeight", report.antenna_height as f32);
         if self.model == Model::HALO {
             self.set_value("accentLight", report.accent_light as f32);
@@ -1074,7 +1115,10 @@ impl NavicoReportReceiver {
             "localInterferenceRejection",
             local_interference_rejection as f32,
         );
-        self.set_value("scanSpeed", scan_speed as f32);
+        let scan_speed_rpm = self
+            .info
+            .scan_speed_rpm(mayara_core::Brand::Navico, scan_speed as u8);
+        self.set_value("scanSpeed", scan_speed_rpm as f32);
         self.set_value_auto(
             "sidelobeSuppression",
             sidelobe_suppression as f32,
@@ -66,6 +66,19 @@ pub fn new(session: Session, model: Option<&str>) -> SharedControls {
         "firmwareVersion".to_string(),
         control_factory::firmware_version_control(),
     );
+    controls.insert(
+        "preparingStage".to_string(),
+        Control::new_numeric("preparingStage", 0., 255.).read_only(true),
+    );
+    controls.insert(
+        "preparingCounter".to_string(),
+        Control::new_numeric("preparingCounter", 0., 65535.).read_only(true),
+    );
+    // Spoke groups lost between frames since the radar last transmitted
+    controls.insert(
+        "frameTears".to_string(),
+        Control::new_numeric("frameTears", 0., u32::MAX as f32).read_only(true),
+    );
     controls.insert(
         "sidelobeSuppression".to_string(),
         control_factory::sidelobe_suppression_control_for_brand(Brand::Navico),
@@ -76,6 +89,10 @@ pub fn new(session: Session, model: Option<&str>) -> SharedControls {
 
 pub fn update_when_model_known(controls: &SharedControls, model: Model, radar_info: &RadarInfo) {
     controls.set_model_name(model.to_string());
+    controls.set_inverted_controls(
+        mayara_core::models::get_model(Brand::Navico, &model.to_string())
+            .map_or(&[], |m| m.inverted_controls),
+    );
 
     let mut control = control_factory::serial_number_control();
     if let Some(serial_number) = radar_info.serial_no.as_ref() {
@@ -166,9 +183,12 @@ pub fn update_when_model_known(controls: &SharedControls, model: Model, radar_in
         );
     }
 
-    controls.insert(
-        "scanSpeed",
-        Control::new_list(
+    // Scan speed in RPM where the model's speeds are known, the wire value otherwise
+    let scan_speed = match mayara_core::models::get_model(Brand::Navico, &model.to_string()) {
+        Some(model_info) if !model_info.scan_speed_options().is_empty() => {
+            control_factory::scan_speed_control_for_model(model_info)
+        }
+        _ => Control::new_list(
             "scanSpeed",
             if model == Model::HALO {
                 &["Normal", "Medium", "Medium Plus", "Fast"]
@@ -176,7 +196,8 @@ pub fn update_when_model_known(controls: &SharedControls, model: Model, radar_in
                 &["Normal", "Medium", "Medium-High"]
             },
         ),
-    );
+    };
+    controls.insert("scanSpeed", scan_speed);
     controls.insert(
         "targetExpansion",
         Control::new_list(
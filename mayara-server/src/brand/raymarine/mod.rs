@@ -1,4 +1,4 @@
-use std::net::{Ipv4Addr, SocketAddrV4};
+use std::net::{IpAddr, SocketAddr};
 use std::io;
 use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
 
@@ -428,18 +428,22 @@ use mayara_core::radar::{ParsedAddress, RadarDiscovery};
 /// then 36-byte beacon with addresses). The core RadarDiscovery provides simplified
 /// info. For full functionality, the existing stateful RaymarineLocatorState should
 /// be used until the core properly handles the two-step process.
+fn endpoint(address: Option<&str>) -> Option<SocketAddr> {
+    let parsed = ParsedAddress::parse(address?).ok()?;
+    (parsed.port > 0).then(|| parsed.socket_addr())
+}
+
 pub fn process_discovery(
     session: Session,
     discovery: &RadarDiscovery,
-    nic_addr: Ipv4Addr,
+    nic_addr: IpAddr,
     radars: &SharedRadars,
     subsys: &SubsystemHandle,
 ) -> Result<(), io::Error> {
     // Parse address from discovery using core's parser
     let parsed = ParsedAddress::parse(&discovery.address)
         .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
-    let radar_ip = Ipv4Addr::from(parsed.ip);
-    let radar_addr = SocketAddrV4::new(radar_ip, if parsed.port > 0 { parsed.port } else { 5800 });
+    let radar_addr = SocketAddr::new(parsed.ip, if parsed.port > 0 { parsed.port } else { 5800 });
 
     // Determine model from discovery
     let model = if let Some(ref model_name) = discovery.model {
@@ -458,10 +462,21 @@ pub fn process_discovery(
     let max_spoke_len = model.max_spoke_len;
     let pixel_values = if model.hd { HD_PIXEL_VALUES } else { NON_HD_PIXEL_VALUES };
 
-    // For simplified discovery, use basic addresses
-    let report_addr: SocketAddrV4 = SocketAddrV4::new(radar_ip, discovery.command_port);
-    let data_addr: SocketAddrV4 = SocketAddrV4::new(radar_ip, discovery.data_port);
-    let send_addr: SocketAddrV4 = SocketAddrV4::new(radar_ip, discovery.command_port);
+    // Endpoints come from the 36-byte beacon; until it has been seen we
+    // cannot listen for reports. The locator passes the radar on again as
+    // an update once they are known.
+    let (Some(report_addr), Some(send_addr)) = (
+        endpoint(discovery.report_address.as_deref()),
+        endpoint(discovery.send_address.as_deref()),
+    ) else {
+        log::debug!(
+            "{}: Raymarine radar endpoints not yet known, waiting for beacon",
+            discovery.name
+        );
+        return Ok(());
+    };
+    // Spoke frames arrive on the report multicast group
+    let data_addr = endpoint(discovery.data_address.as_deref()).unwrap_or(report_addr);
 
     let info: RadarInfo = RadarInfo::new(
         session.clone(),
@@ -497,6 +512,8 @@ pub fn process_discovery(
             info_clone.forward_output(s)
         }));
     }
+    crate::recording::capture::start_capture(&session, &info, subsys);
+    crate::radar::arpa::start_arpa(&session, &info, subsys);
 
     let report_name = info.key();
     let report_receiver = report::RaymarineReportReceiver::new(
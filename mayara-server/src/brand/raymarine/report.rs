@@ -2,16 +2,18 @@ use anyhow::{bail, Error};
 use std::collections::HashMap;
 use std::io;
 use std::time::Duration;
-use tokio::net::UdpSocket;
 use tokio::sync::broadcast;
-use tokio::time::{sleep, sleep_until, Instant};
+use tokio::time::{interval, sleep, sleep_until, Instant, MissedTickBehavior};
 use tokio_graceful_shutdown::SubsystemHandle;
 
 use crate::brand::raymarine::RaymarineModel;
-use crate::network::create_udp_multicast_listen;
+use crate::network::{create_udp_multicast_listen, ListenSocket, StallDetector, MULTICAST_STALL_TIMEOUT};
 use crate::radar::range::Ranges;
 use crate::radar::trail::TrailBuffer;
-use crate::radar::{Legend, RadarError, RadarInfo, SharedRadars, Statistics, BYTE_LOOKUP_LENGTH};
+use crate::radar::{
+    Legend, RadarError, RadarInfo, SharedRadars, Statistics, BYTE_LOOKUP_LENGTH,
+    ROTATION_CHECK_INTERVAL,
+};
 use crate::settings::{ControlUpdate, ControlValue};
 use crate::tokio_io::TokioIoProvider;
 use crate::Session;
@@ -62,9 +64,11 @@ enum ReceiverState {
 
 pub(crate) struct RaymarineReportReceiver {
     replay: bool,
+    passive: bool,
     info: RadarInfo,
     key: String,
-    report_socket: Option<UdpSocket>,
+    report_socket: Option<ListenSocket>,
+    report_stall: StallDetector,
     radars: SharedRadars,
     state: ReceiverState,
     model: Option<RaymarineModel>,
@@ -113,9 +117,11 @@ impl RaymarineReportReceiver {
 
         RaymarineReportReceiver {
             replay,
+            passive: args.passive,
             key,
             info,
             report_socket: None,
+            report_stall: StallDetector::new(),
             radars,
             state: ReceiverState::Initial,
             model: None, // We don't know this yet, it will be set when we receive the first info report
@@ -138,6 +144,7 @@ impl RaymarineReportReceiver {
         match create_udp_multicast_listen(&self.info.report_addr, &self.info.nic_addr) {
             Ok(socket) => {
                 self.report_socket = Some(socket);
+                self.report_stall.reset();
                 log::debug!(
                     "{}: {} via {}: listening for reports",
                     self.key,
@@ -167,6 +174,8 @@ impl RaymarineReportReceiver {
     async fn socket_loop(&mut self, subsys: &SubsystemHandle) -> Result<(), RadarError> {
         log::debug!("{}: listening for reports", self.key);
         let mut buf = Vec::with_capacity(10000);
+        let mut rotation_check = interval(ROTATION_CHECK_INTERVAL);
+        rotation_check.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
         loop {
             let timeout = self.report_request_timeout;
@@ -175,14 +184,26 @@ impl RaymarineReportReceiver {
                     log::info!("{}: shutdown", self.key);
                     return Err(RadarError::Shutdown);
                 },
+                _ = rotation_check.tick() => self.info.check_rotation(),
+
                 _ = sleep_until(timeout) => {
                      self.send_report_requests().await?;
 
                 },
 
+                _ = self.report_stall.stalled() => {
+                    log::warn!(
+                        "{}: no reports for {:?}, re-joining multicast",
+                        self.key,
+                        MULTICAST_STALL_TIMEOUT
+                    );
+                    return Err(RadarError::Timeout);
+                },
+
                 r = self.report_socket.as_ref().unwrap().recv_buf_from(&mut buf)  => {
                     match r {
                         Ok((_len, _addr)) => {
+                            self.report_stall.packet_received();
                             if buf.len() == buf.capacity() {
                                 let old = buf.capacity();
                                 buf.reserve(1024);
@@ -263,6 +284,7 @@ impl RaymarineReportReceiver {
                 let index = if value < ranges.len() as i32 {
                     value as u8
                 } else {
+                    let value = self.info.snap_range(mayara_core::Brand::Raymarine, value as u32) as i32;
                     let mut i = 0u8;
                     for r in ranges.all.iter() {
                         if r.distance() >= value {
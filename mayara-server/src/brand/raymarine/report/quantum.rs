@@ -7,6 +7,7 @@ use mayara_core::protocol::raymarine::{
 
 use crate::brand::raymarine::report::LookupDoppler;
 use mayara_core::controllers::{RaymarineController, RaymarineVariant};
+use mayara_core::{models, Brand};
 use crate::brand::raymarine::{hd_to_pixel_values, settings, RaymarineModel};
 use crate::protos::RadarMessage::RadarMessage;
 use crate::radar::range::{Range, Ranges};
@@ -220,12 +221,14 @@ pub(super) fn process_info_report(receiver: &mut RaymarineReportReceiver, data:
             settings::update_when_model_known(&mut receiver.info.controls, &model, &info2);
             receiver.info.set_pixel_values(hd_to_pixel_values(model.hd));
             receiver.info.set_doppler(model.doppler);
+            receiver.info.antenna_type =
+                models::get_model(Brand::Raymarine, model.name).and_then(|m| m.antenna_type);
             receiver.radars.update(&receiver.info);
 
             // Create the unified controller if not in replay mode
             if !receiver.replay {
                 log::debug!("{}: Starting unified controller (Quantum)", receiver.key);
-                let controller = RaymarineController::new(
+                let mut controller = RaymarineController::new(
                     &receiver.key,
                     &receiver.info.send_command_addr.ip().to_string(),
                     receiver.info.send_command_addr.port(),
@@ -234,6 +237,7 @@ pub(super) fn process_info_report(receiver: &mut RaymarineReportReceiver, data:
                     RaymarineVariant::Quantum,
                     model.doppler,
                 );
+                controller.set_passive(receiver.passive);
                 receiver.controller = Some(controller);
             } else {
                 log::debug!("{}: No controller, replay mode", receiver.key);
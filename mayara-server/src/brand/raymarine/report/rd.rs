@@ -7,6 +7,7 @@ use mayara_core::protocol::raymarine::{
 };
 
 use mayara_core::controllers::{RaymarineController, RaymarineVariant};
+use mayara_core::{models, Brand};
 use crate::brand::raymarine::{hd_to_pixel_values, settings, RaymarineModel};
 use crate::protos::RadarMessage::RadarMessage;
 use crate::radar::range::{Range, Ranges};
@@ -390,6 +391,14 @@ pub(super) fn process_fixed_report(receiver: &mut RaymarineReportReceiver, data:
 
     if receiver.model.is_some() {
         receiver.set_value("operatingHours", report.magnetron_time);
+        let life = receiver
+            .model
+            .as_ref()
+            .and_then(|model| models::get_model(Brand::Raymarine, model.name))
+            .and_then(|model| model.magnetron_life_percent(report.magnetron_time as f64));
+        if let Some(percent) = life {
+            receiver.set_value("magnetronLifePercent", percent as f32);
+        }
         receiver.set_value("magnetronCurrent", report.magnetron_current);
         receiver.set_value("signalStrength", report.signal_strength_value);
         receiver.set_value("displayTiming", report.display_timing);
@@ -452,12 +461,14 @@ pub(super) fn process_info_report(receiver: &mut RaymarineReportReceiver, data:
     receiver.info.set_pixel_values(hd_to_pixel_values(model.hd));
 
     receiver.info.set_doppler(model.doppler);
+    receiver.info.antenna_type =
+        models::get_model(Brand::Raymarine, model.name).and_then(|m| m.antenna_type);
     receiver.radars.update(&receiver.info);
 
     // Create the unified controller if not in replay mode
     if !receiver.replay {
         log::debug!("{}: Starting unified controller (RD)", receiver.key);
-        let controller = RaymarineController::new(
+        let mut controller = RaymarineController::new(
             &receiver.key,
             &receiver.info.send_command_addr.ip().to_string(),
             receiver.info.send_command_addr.port(),
@@ -466,6 +477,7 @@ pub(super) fn process_info_report(receiver: &mut RaymarineReportReceiver, data:
             RaymarineVariant::RD,
             model.doppler,
         );
+        controller.set_passive(receiver.passive);
         receiver.controller = Some(controller);
     } else {
         log::debug!("{}: No controller, replay mode", receiver.key);
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use mayara_core::Brand;
+use mayara_core::{models, Brand};
 
 use crate::{
     brand::raymarine::RaymarineModel,
@@ -113,6 +113,8 @@ pub fn update_when_model_known(
     radar_info: &RadarInfo,
 ) {
     controls.set_model_name(model.name.to_string());
+    let model_info = models::get_model(Brand::Raymarine, model.name);
+    controls.set_inverted_controls(model_info.map_or(&[], |m| m.inverted_controls));
 
     let mut control = control_factory::serial_number_control();
     if let Some(serial_number) = radar_info.serial_no.as_ref() {
@@ -120,6 +122,13 @@ pub fn update_when_model_known(
     }
     controls.insert("serialNumber", control);
 
+    if model_info.is_some_and(|m| m.magnetron_life_hours > 0) {
+        controls.insert(
+            "magnetronLifePercent",
+            control_factory::magnetron_life_percent_control(),
+        );
+    }
+
     // Update the UserName; it had to be present at start so it could be loaded from
     // config. Override it if it is still the 'Raymarine ... ' name.
     if controls.user_name().as_deref() == Some(radar_info.key().as_str()) {
@@ -9,6 +9,10 @@ use std::io::{BufReader, BufWriter, Write};
 use std::path::PathBuf;
 use std::time::SystemTime;
 
+use mayara_core::capabilities::DisplayCenterOffset;
+use mayara_core::guard_zones::GuardZone;
+use mayara_core::radar::RadarDiscovery;
+
 use crate::radar::range::Ranges;
 use crate::radar::RadarInfo;
 
@@ -25,11 +29,26 @@ pub struct Radar {
     // Data that is computed and not immediately known when starting
     pub model_name: Option<String>, // Descriptive model name (4G, HALO)
     pub ranges: Option<Vec<i32>>,   // Detected ranges
+
+    // Display settings, never sent to the radar
+    pub display_center_offset: Option<DisplayCenterOffset>,
+
+    // Controls exposed to clients, e.g. to hide advanced controls from
+    // guests. Edited by hand; empty means all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub control_allowlist: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guard_zones: Vec<GuardZone>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Config {
     pub radars: HashMap<String, Radar>,
+    /// Radars found by the locator, to show them again at the next start
+    /// before their beacons arrive
+    #[serde(default)]
+    pub discovered_radars: Vec<RadarDiscovery>,
 }
 
 #[derive(Debug, Clone)]
@@ -47,9 +66,7 @@ impl Persistence {
         settings_path.push("settings.json");
 
         let mut this = Persistence {
-            config: Config {
-                radars: HashMap::new(),
-            },
+            config: Config::default(),
             timestamp: SystemTime::UNIX_EPOCH,
             path: settings_path,
         };
@@ -172,12 +189,44 @@ impl Persistence {
             radar.id = radar_info.id;
             modified = true;
         }
+        let display_center_offset = radar_info.display_center_offset();
+        if radar.display_center_offset != display_center_offset {
+            radar.display_center_offset = display_center_offset;
+            modified = true;
+        }
+        // GuardZone has no PartialEq, compare as JSON
+        let guard_zones = radar_info.guard_zones.zones();
+        let as_json = |z: &Vec<GuardZone>| serde_json::to_value(z).ok();
+        if as_json(&radar.guard_zones) != as_json(&guard_zones) {
+            radar.guard_zones = guard_zones;
+            modified = true;
+        }
 
         if modified {
             self.save();
         }
     }
 
+    /// Keep a radar's settings when its key changes, as it does for a
+    /// radar without serial number that moves to another address
+    pub fn rekey(&mut self, old_key: &str, new_key: &str) {
+        if old_key == new_key {
+            return;
+        }
+        if let Some(radar) = self.config.radars.remove(old_key) {
+            self.config.radars.insert(new_key.to_string(), radar);
+            self.save();
+        }
+    }
+
+    pub fn store_discovered_radars(&mut self, discoveries: Vec<RadarDiscovery>) {
+        let as_json = |d: &Vec<RadarDiscovery>| serde_json::to_value(d).ok();
+        if as_json(&self.config.discovered_radars) != as_json(&discoveries) {
+            self.config.discovered_radars = discoveries;
+            self.save();
+        }
+    }
+
     pub fn update_info_from_persistence(&self, info: &mut RadarInfo) {
         if let Some(p) = self.config.radars.get(&info.key()) {
             if p.model_name.is_some() {
@@ -191,6 +240,74 @@ impl Persistence {
             }
             info.controls.set_user_name(p.user_name.clone());
             info.id = p.id;
+            info.set_display_center_offset(p.display_center_offset);
+            info.control_allowlist = p.control_allowlist.clone();
+            info.guard_zones.load(&p.guard_zones);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mayara_core::capabilities::RadarStateV5;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_display_center_offset_round_trips_into_state() {
+        let offset = DisplayCenterOffset { x: 0.0, y: -0.5 };
+        assert!(offset.validate().is_ok());
+        assert!(DisplayCenterOffset { x: 0.6, y: 0.6 }.validate().is_err());
+
+        let mut config = Config::default();
+        config.radars.insert(
+            "Navico-1403200123-A".to_string(),
+            Radar {
+                display_center_offset: Some(offset),
+                ..Default::default()
+            },
+        );
+        let json = serde_json::to_string(&config).unwrap();
+        let config: Config = serde_json::from_str(&json).unwrap();
+        let restored = config.radars["Navico-1403200123-A"].display_center_offset;
+        assert_eq!(restored, Some(offset));
+
+        let state = RadarStateV5 {
+            id: "radar-1".to_string(),
+            timestamp: String::new(),
+            status: "standby".to_string(),
+            controls: Default::default(),
+            disabled_controls: vec![],
+            modules: vec![],
+            display_center_offset: restored,
+        };
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["displayCenterOffset"]["x"], 0.0);
+        assert_eq!(json["displayCenterOffset"]["y"], -0.5);
+    }
+
+    #[test]
+    fn test_rekey_keeps_settings_of_moved_radar() {
+        let temp = TempDir::new().unwrap();
+        let mut persistence = Persistence {
+            config: Config::default(),
+            timestamp: SystemTime::UNIX_EPOCH,
+            path: temp.path().join("config.json"),
+        };
+        persistence.config.radars.insert(
+            "Furuno-172.31.3.212:10010".to_string(),
+            Radar {
+                id: 3,
+                user_name: "Mast".to_string(),
+                ..Default::default()
+            },
+        );
+
+        persistence.rekey("Furuno-172.31.3.212:10010", "Furuno-172.31.3.40:10010");
+
+        let radars = &persistence.config.radars;
+        assert!(!radars.contains_key("Furuno-172.31.3.212:10010"));
+        assert_eq!(radars["Furuno-172.31.3.40:10010"].id, 3);
+        assert_eq!(radars["Furuno-172.31.3.40:10010"].user_name, "Mast");
+    }
+}
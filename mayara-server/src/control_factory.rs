@@ -328,6 +328,12 @@ pub fn transmit_hours_control() -> Control {
     build_control(&core_def)
 }
 
+/// Build magnetron life control (read-only, magnetron radars only)
+pub fn magnetron_life_percent_control() -> Control {
+    let core_def = controls::control_magnetron_life_percent();
+    build_control(&core_def)
+}
+
 /// Build serial number control (read-only)
 pub fn serial_number_control() -> Control {
     let core_def = controls::control_serial_number();
@@ -400,6 +406,12 @@ pub fn main_bang_suppression_control() -> Control {
     build_control(&core_def)
 }
 
+/// Build scan speed control with the model's speeds in RPM
+pub fn scan_speed_control_for_model(model: &mayara_core::models::ModelInfo) -> Control {
+    let core_def = controls::control_scan_speed_for_model(model);
+    build_control(&core_def)
+}
+
 // =============================================================================
 // Batch control builders - create all controls for a brand/model from core
 // =============================================================================
@@ -447,7 +459,13 @@ pub fn build_extended_controls_for_model(brand: Brand, model_name: &str) -> Hash
             if *control_id == "noTransmitZones" {
                 continue;
             }
-            if let Some(def) = controls::get_extended_control_for_brand(control_id, brand) {
+            // Scan speed options are RPM values, which differ per model
+            if *control_id == "scanSpeed" {
+                result.insert(
+                    control_id.to_string(),
+                    scan_speed_control_for_model(model_info),
+                );
+            } else if let Some(def) = controls::get_extended_control_for_brand(control_id, brand) {
                 result.insert(control_id.to_string(), build_control(&def));
             }
         }
@@ -516,4 +534,25 @@ mod tests {
         assert_eq!(control.index_to_enum_value(2), Some("transmit".to_string()));
         assert_eq!(control.index_to_enum_value(3), Some("warming".to_string()));
     }
+
+    #[test]
+    fn test_navico_antenna_height_readback_in_meters() {
+        use mayara_core::protocol::navico::antenna_height_meters_to_mm;
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert(
+            "antennaHeight".to_string(),
+            antenna_height_control_for_brand(Brand::Navico),
+        );
+        let controls = crate::settings::SharedControls::new(crate::Session::new_fake(), map);
+
+        // Report 04 carries the millimeters we sent for 15 m
+        let wire = antenna_height_meters_to_mm(15.0);
+        controls.set("antennaHeight", wire as f32, None).unwrap();
+        assert_eq!(controls.get("antennaHeight").unwrap().value, Some(15.));
+
+        // The radar horizon follows the antenna height
+        assert_eq!(controls.get("radarHorizon").unwrap().value, Some(15964.));
+    }
 }
@@ -28,15 +28,15 @@
 //! └────────────────────────────────────────────────────┘
 //! ```
 
-use std::net::{Ipv4Addr, SocketAddrV4};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
 
 use mayara_core::locator::{LocatorEvent, RadarLocator};
-use mayara_core::radar::RadarDiscovery;
+use mayara_core::radar::{ParsedAddress, RadarDiscovery};
 use mayara_core::Brand as CoreBrand;
 use tokio::sync::mpsc;
 use tokio::time::{interval, MissedTickBehavior};
-use tokio_graceful_shutdown::SubsystemHandle;
+use tokio_graceful_shutdown::{NestedSubsystem, SubsystemBuilder, SubsystemHandle};
 
 use crate::tokio_io::TokioIoProvider;
 use crate::Brand;
@@ -48,6 +48,11 @@ pub enum LocatorMessage {
     RadarDiscovered(RadarDiscovery),
     /// An existing radar's info was updated (e.g., model detected)
     RadarUpdated(RadarDiscovery),
+    /// A known radar now answers at another address (e.g. new DHCP lease)
+    RadarAddressChanged {
+        previous_address: String,
+        discovery: RadarDiscovery,
+    },
     /// Locator has shut down
     Shutdown,
 }
@@ -67,6 +72,8 @@ pub struct CoreLocatorAdapter {
     poll_interval: Duration,
     /// Session to update with locator status
     session: crate::Session,
+    /// Events from the warm start, sent on the first poll
+    warm_start_events: Vec<LocatorEvent>,
 }
 
 impl CoreLocatorAdapter {
@@ -83,6 +90,7 @@ impl CoreLocatorAdapter {
             discovery_tx,
             poll_interval,
             session,
+            warm_start_events: Vec::new(),
         }
     }
 
@@ -106,8 +114,26 @@ impl CoreLocatorAdapter {
             log::warn!("No NIC found for Furuno subnet (172.31.x.x) - broadcasts may go to wrong interface");
         }
 
+        if self.session.read().unwrap().args.passive {
+            log::info!("Passive mode: not sending Furuno announces");
+            self.locator.set_passive(true);
+        }
+
         self.locator.start(&mut self.io);
 
+        // Show the radars from the last run until their beacons confirm them
+        let known = self
+            .shared_radars()
+            .map(|radars| radars.discovered_radars())
+            .unwrap_or_default();
+        if !known.is_empty() {
+            log::info!(
+                "Warm start with {} previously discovered radars",
+                known.len()
+            );
+            self.warm_start_events = self.locator.warm_start(&self.io, &known);
+        }
+
         // Update session with locator status
         if let Ok(mut session) = self.session.write() {
             session.locator_status = self.locator.status().clone();
@@ -119,7 +145,9 @@ impl CoreLocatorAdapter {
     ///
     /// Returns list of locator events (new discoveries and updates).
     pub fn poll(&mut self) -> Vec<LocatorEvent> {
-        self.locator.poll(&mut self.io)
+        let mut events = std::mem::take(&mut self.warm_start_events);
+        events.extend(self.locator.poll(&mut self.io));
+        events
     }
 
     /// Send a Furuno announce packet.
@@ -130,6 +158,51 @@ impl CoreLocatorAdapter {
         // This is a design limitation - we might want to change the core API
     }
 
+    /// Re-send all beacon requests now, to find radars whose beacons were missed.
+    pub fn rescan(&mut self) {
+        self.locator.rescan(&mut self.io);
+    }
+
+    /// Publish beacon parse failures in the session's locator status, and
+    /// warn the first time a brand's beacons fail to parse.
+    fn update_parse_errors(&mut self) {
+        let errors = self.locator.parse_errors();
+        match self.session.read() {
+            Ok(session) if session.locator_status.parse_errors != errors => {}
+            _ => return,
+        }
+        let Ok(mut session) = self.session.write() else {
+            return;
+        };
+        for e in errors {
+            if !session
+                .locator_status
+                .parse_errors
+                .iter()
+                .any(|p| p.brand == e.brand)
+            {
+                log::warn!(
+                    "{} beacon received but failed to parse: {}",
+                    e.brand,
+                    e.last_error
+                );
+            }
+        }
+        session.locator_status.parse_errors = errors.to_vec();
+    }
+
+    /// The session's radars, which own the saved config
+    fn shared_radars(&self) -> Option<SharedRadars> {
+        self.session.read().unwrap().radars.clone()
+    }
+
+    /// Save the confirmed radars, for a warm start next time.
+    fn save_discovered_radars(&mut self) {
+        if let Some(radars) = self.shared_radars() {
+            radars.store_discovered_radars(self.locator.confirmed_discoveries());
+        }
+    }
+
     /// Get list of all discovered radars.
     pub fn radars(&self) -> impl Iterator<Item = &RadarDiscovery> {
         self.locator.radars.values().map(|r| &r.discovery)
@@ -154,6 +227,7 @@ impl CoreLocatorAdapter {
         // Set up polling interval
         let mut poll_timer = interval(self.poll_interval);
         poll_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let rescan = self.session.read().unwrap().rescan.clone();
 
         loop {
             tokio::select! {
@@ -161,9 +235,16 @@ impl CoreLocatorAdapter {
                     log::info!("CoreLocatorAdapter: Shutdown requested");
                     break;
                 }
+                _ = rescan.notified() => {
+                    self.rescan();
+                }
                 _ = poll_timer.tick() => {
                     // Poll the core locator
                     let events = self.poll();
+                    if !events.is_empty() {
+                        self.save_discovered_radars();
+                    }
+                    self.update_parse_errors();
 
                     // Send events to the server
                     for event in events {
@@ -182,6 +263,13 @@ impl CoreLocatorAdapter {
                                 );
                                 LocatorMessage::RadarUpdated(discovery)
                             }
+                            LocatorEvent::RadarAddressChanged { previous_address, discovery } => {
+                                log::info!(
+                                    "CoreLocatorAdapter: {} radar '{}' moved from {} to {}",
+                                    discovery.brand, discovery.name, previous_address, discovery.address
+                                );
+                                LocatorMessage::RadarAddressChanged { previous_address, discovery }
+                            }
                         };
 
                         if self.discovery_tx.send(message).await.is_err() {
@@ -250,24 +338,15 @@ pub fn core_brand_to_server_brand(core_brand: CoreBrand) -> Brand {
     }
 }
 
-/// Parse address string to SocketAddrV4
-pub fn parse_address(addr: &str) -> Option<SocketAddrV4> {
-    // Address format: "ip:port" or just "ip"
-    if let Some(colon_pos) = addr.rfind(':') {
-        let ip_str = &addr[..colon_pos];
-        let port_str = &addr[colon_pos + 1..];
-        let ip: Ipv4Addr = ip_str.parse().ok()?;
-        let port: u16 = port_str.parse().ok()?;
-        Some(SocketAddrV4::new(ip, port))
-    } else {
-        let ip: Ipv4Addr = addr.parse().ok()?;
-        Some(SocketAddrV4::new(ip, 0))
-    }
+/// Parse address string to SocketAddr
+pub fn parse_address(addr: &str) -> Option<SocketAddr> {
+    // Address format: "ip:port", "[ipv6]:port" or just "ip"
+    ParsedAddress::parse(addr).ok().map(|a| a.socket_addr())
 }
 
 /// Get the NIC address for a radar using network interface matching
-pub fn get_nic_for_radar(addr: &SocketAddrV4) -> Ipv4Addr {
-    crate::network::find_nic_for_radar(addr.ip()).unwrap_or(Ipv4Addr::UNSPECIFIED)
+pub fn get_nic_for_radar(addr: &SocketAddr) -> IpAddr {
+    crate::network::find_nic_for_radar(&addr.ip()).unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
 }
 
 // =============================================================================
@@ -290,7 +369,9 @@ pub fn dispatch_discovery(
 ) -> Result<(), std::io::Error> {
     // Determine NIC address for this radar
     let radar_addr = parse_address(&discovery.address);
-    let nic_addr = radar_addr.map(|a| get_nic_for_radar(&a)).unwrap_or(Ipv4Addr::UNSPECIFIED);
+    let nic_addr = radar_addr
+        .map(|a| get_nic_for_radar(&a))
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
 
     log::info!(
         "Processing {} discovery: {} at {} via {}",
@@ -313,7 +394,10 @@ pub fn dispatch_discovery(
         CoreBrand::Raymarine => {
             crate::brand::raymarine::process_discovery(session, discovery, nic_addr, radars, subsys)
         }
-        // Garmin not yet implemented with process_discovery
+        #[cfg(feature = "garmin")]
+        CoreBrand::Garmin => {
+            crate::brand::garmin::process_discovery(session, discovery, nic_addr, radars, subsys)
+        }
         #[cfg(not(feature = "furuno"))]
         CoreBrand::Furuno => {
             log::warn!("Furuno support not compiled in");
@@ -329,13 +413,38 @@ pub fn dispatch_discovery(
             log::warn!("Raymarine support not compiled in");
             Ok(())
         }
+        #[cfg(not(feature = "garmin"))]
         CoreBrand::Garmin => {
-            log::warn!("Garmin process_discovery not implemented");
+            log::warn!("Garmin support not compiled in");
             Ok(())
         }
     }
 }
 
+/// Dispatch a discovery in a subsystem of its own.
+///
+/// The radar's receivers are started inside it, so shutting the returned
+/// subsystem down stops them, e.g. when the radar moves to another address.
+pub fn start_radar(
+    session: Session,
+    discovery: RadarDiscovery,
+    radars: SharedRadars,
+    subsys: &SubsystemHandle,
+) -> NestedSubsystem {
+    let name = format!("{} {}", discovery.brand, discovery.address);
+    subsys.start(SubsystemBuilder::new(
+        name,
+        move |s: SubsystemHandle| async move {
+            if let Err(e) = dispatch_discovery(session, &discovery, &radars, &s) {
+                log::error!("Failed to process {} discovery: {}", discovery.brand, e);
+                return Ok(());
+            }
+            s.on_shutdown_requested().await;
+            Ok::<(), std::io::Error>(())
+        },
+    ))
+}
+
 // =============================================================================
 // Interface Detection
 // =============================================================================
@@ -350,7 +459,6 @@ const FURUNO_NETMASK: Ipv4Addr = Ipv4Addr::new(255, 255, 0, 0);
 /// go out on the correct interface.
 fn find_furuno_interface() -> Option<Ipv4Addr> {
     use network_interface::{NetworkInterface, NetworkInterfaceConfig};
-    use std::net::IpAddr;
 
     let interfaces = NetworkInterface::show().ok()?;
 
@@ -403,8 +511,13 @@ mod tests {
         let addr = parse_address("192.168.1.100:10010");
         assert!(addr.is_some());
         let addr = addr.unwrap();
-        assert_eq!(addr.ip(), &Ipv4Addr::new(192, 168, 1, 100));
+        assert_eq!(addr.ip(), IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)));
+        assert_eq!(addr.port(), 10010);
+
+        let addr = parse_address("[fd00::10]:10010").unwrap();
+        assert_eq!(addr.ip(), "fd00::10".parse::<IpAddr>().unwrap());
         assert_eq!(addr.port(), 10010);
+        assert_eq!(parse_address("fd00::10").unwrap().port(), 0);
     }
 
     #[test]
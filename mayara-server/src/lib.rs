@@ -97,7 +97,12 @@
 //! - `-p, --port` - HTTP server port (default: 6502)
 //! - `-v` - Increase verbosity (use multiple times)
 //! - `--replay` - Replay mode for testing without radar hardware
+//! - `--replay-model` - Radar model to assume during replay
+//! - `--replay-motion` - Scripted own-ship motion during replay
+//! - `--replay-file` - PCAP capture to play back, with `--replay-speed` and `--replay-loop`
+//! - `--record` - Capture raw radar packets to PCAPNG files
 //! - `--interface` - Limit discovery to specific network interface
+//! - `--targets` - Target analysis; `arpa` tracks targets, refreshed every `--arpa-refresh-ms`
 
 extern crate tokio;
 
@@ -109,8 +114,9 @@ use serde::{Serialize, Serializer};
 use std::{
     collections::{HashMap, HashSet},
     net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
 };
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::{broadcast, mpsc, Notify};
 use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
 
 pub mod brand;
@@ -166,6 +172,16 @@ pub struct Cli {
     #[arg(short, long, default_value_t, value_enum)]
     pub targets: TargetMode,
 
+    /// How often ARPA targets are refreshed and sent to clients, in
+    /// milliseconds. Each target is still searched for once per rotation.
+    #[arg(long, value_name = "MS", default_value_t = 500, value_parser = clap::value_parser!(u64).range(50..))]
+    pub arpa_refresh_ms: u64,
+
+    /// Check guard zones on only every Nth spoke, to save CPU on low-power
+    /// hosts. Every zone is still checked at least once per rotation.
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub guard_zone_subsample: u32,
+
     /// Set navigation service address, either
     /// - Nothing: all interfaces will search via MDNS
     /// - An interface name: only that interface will seach for via MDNS
@@ -185,6 +201,37 @@ pub struct Cli {
     #[arg(short, long, default_value_t = false)]
     pub replay: bool,
 
+    /// Radar model to assume in replay mode (e.g. `DRS6A-NXT` or `HALO24`)
+    /// instead of the brand default. Must be a model in the mayara-core
+    /// model database.
+    #[arg(long, value_parser = parse_replay_model, requires = "replay")]
+    pub replay_model: Option<String>,
+
+    /// Own-ship motion script for replay mode, one JSON record per line
+    /// (time, position, heading, COG and SOG). Replaces the navigation data
+    /// source so ARPA sees a moving own ship.
+    #[arg(long, value_name = "FILE", requires = "replay")]
+    pub replay_motion: Option<PathBuf>,
+
+    /// PCAP or PCAPNG capture (optionally gzipped) to play back in replay
+    /// mode, with its original timing. Works for radars of every brand; the
+    /// packets are fed to mayara's sockets and never sent onto the network.
+    #[arg(long, value_name = "FILE", requires = "replay")]
+    pub replay_file: Option<PathBuf>,
+
+    /// Playback speed of --replay-file, from 0.5 (half speed) to 10
+    #[arg(long, default_value_t = 1.0, value_parser = parse_replay_speed, requires = "replay_file")]
+    pub replay_speed: f64,
+
+    /// Restart --replay-file from the beginning when it ends
+    #[arg(long, default_value_t = false, requires = "replay_file")]
+    pub replay_loop: bool,
+
+    /// Capture the raw packets of every radar into rotating PCAPNG files
+    /// in this directory, for replay and protocol debugging
+    #[arg(long, value_name = "DIR")]
+    pub record: Option<PathBuf>,
+
     /// Fake error mode, see below
     #[arg(long, default_value_t = false)]
     pub fake_errors: bool,
@@ -197,6 +244,73 @@ pub struct Cli {
     #[arg(long, default_value_t = false)]
     pub stationary: bool,
 
+    /// Range-dependent gain (STC) slope applied to displayed spokes, e.g. 1.0
+    /// doubles the intensity at the end of the spoke. ARPA, guard zones and
+    /// trails still see the raw values.
+    #[arg(long)]
+    pub stc: Option<f32>,
+
+    /// Sweep fade (afterglow): fraction of each displayed return kept per
+    /// revolution, e.g. 0.5 halves it every sweep. ARPA, guard zones and
+    /// trails still see the raw values.
+    #[arg(long, value_name = "DECAY")]
+    pub sweep_fade: Option<f32>,
+
+    /// Bits per pixel of displayed spokes (1-8), for constrained links. The
+    /// client legend gets the reduced levels; ARPA, guard zones and trails
+    /// still see the full depth.
+    #[arg(long, value_name = "BITS", value_parser = clap::value_parser!(u8).range(1..=8))]
+    pub spoke_bits: Option<u8>,
+
+    /// Rotate displayed spokes clockwise by this many degrees, e.g. to match
+    /// a chart overlay. Local only: unlike bearingAlignment nothing is sent
+    /// to the radar, and ARPA, guard zones and trails see unrotated spokes.
+    #[arg(long, value_name = "DEGREES", allow_negative_numbers = true)]
+    pub display_rotation: Option<f64>,
+
+    /// Bearing of emitted spoke 0 in degrees clockwise from the bow, for
+    /// renderers that expect spoke 0 elsewhere (e.g. 90 for east). Applied
+    /// after --display-rotation.
+    #[arg(long, value_name = "DEGREES", allow_negative_numbers = true)]
+    pub spoke_zero_bearing: Option<f64>,
+
+    /// Number emitted spokes counter-clockwise instead of clockwise. With
+    /// `--spoke-zero-bearing 90` this is the mathematical convention.
+    #[arg(long, default_value_t = false)]
+    pub spokes_counter_clockwise: bool,
+
+    /// Fill spoke groups lost between Navico frames ("tears") by blending
+    /// the spokes either side of the gap. Display only: ARPA, guard zones
+    /// and trails never see the synthesized spokes.
+    #[arg(long, default_value_t = false)]
+    pub interpolate_tears: bool,
+
+    /// Spoke data address of Furuno radars that do not announce one in
+    /// their beacon, e.g. a FAR series radar configured for 239.255.0.3.
+    /// A bare IP uses the Furuno data port. Defaults to the DRS multicast
+    /// address.
+    #[arg(long, value_name = "ADDRESS")]
+    pub furuno_data_address: Option<String>,
+
+    /// Value of the variable byte (index 9) in the Furuno login message to
+    /// try first, for firmware that rejects the default 1. The other known
+    /// values are still tried after it.
+    #[arg(long, value_name = "BYTE")]
+    pub furuno_login_byte: Option<u8>,
+
+    /// Passive mode: only listen to radars, never send to them. No
+    /// announces, report requests or commands are sent, so an MFD can stay
+    /// in control of a radar on a shared network. Control writes that
+    /// would go to the radar are rejected with an error.
+    #[arg(long, default_value_t = false)]
+    pub passive: bool,
+
+    /// Maximum control changes per second sent to each radar, protecting
+    /// the radar link from runaway clients; 0 disables the limit. Power
+    /// (standby/transmit) is never limited.
+    #[arg(long, value_name = "WRITES", default_value_t = mayara_core::engine::DEFAULT_CONTROL_RATE_LIMIT)]
+    pub control_rate_limit: u32,
+
     /// Multi-radar mode keeps locators running even when one radar is found
     #[arg(long, default_value_t = false)]
     pub multiple_radar: bool,
@@ -209,6 +323,46 @@ pub struct Cli {
     pub legacy_locator: bool,
 }
 
+/// Validate a `--replay-model` name against the mayara-core model database
+fn parse_replay_model(name: &str) -> Result<String, String> {
+    mayara_core::models::find_model(name)
+        .map(|m| m.model.to_string())
+        .ok_or_else(|| format!("Unknown radar model '{}'", name))
+}
+
+/// Validate a `--replay-speed` multiplier
+fn parse_replay_speed(speed: &str) -> Result<f64, String> {
+    use recording::replay::{MAX_REPLAY_SPEED, MIN_REPLAY_SPEED};
+
+    let speed: f64 = speed.parse().map_err(|e| format!("{}", e))?;
+    if (MIN_REPLAY_SPEED..=MAX_REPLAY_SPEED).contains(&speed) {
+        Ok(speed)
+    } else {
+        Err(format!(
+            "Replay speed must be between {} and {}",
+            MIN_REPLAY_SPEED, MAX_REPLAY_SPEED
+        ))
+    }
+}
+
+impl Cli {
+    /// The model to assume for a replayed radar of the given brand, if any.
+    ///
+    /// Returns `None` outside replay mode, or when the `--replay-model` given
+    /// belongs to a different brand.
+    pub fn replay_model(
+        &self,
+        brand: mayara_core::Brand,
+    ) -> Option<&'static mayara_core::models::ModelInfo> {
+        if !self.replay {
+            return None;
+        }
+        self.replay_model
+            .as_deref()
+            .and_then(|name| mayara_core::models::get_model(brand, name))
+    }
+}
+
 #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Brand {
     Furuno,
@@ -328,6 +482,8 @@ pub struct SessionInner {
     pub radars: Option<SharedRadars>,
     /// Locator status from core (updated by CoreLocatorAdapter)
     pub locator_status: mayara_core::LocatorStatus,
+    /// Wakes the locator to re-send its beacon requests
+    pub rescan: Arc<Notify>,
 }
 
 #[derive(Clone)]
@@ -364,6 +520,7 @@ impl Session {
                 tx_interface_request,
                 radars: None,
                 locator_status: mayara_core::LocatorStatus::default(),
+                rescan: Arc::new(Notify::new()),
             })),
         };
         selfref
@@ -405,6 +562,23 @@ impl Session {
             log::error!("--legacy-locator flag is no longer supported, legacy code has been commented out");
             log::warn!("Falling back to unified core locator");
         }
+
+        // Switch the sockets to the replay before the locator creates any
+        let args = session.args();
+        if let Some(path) = args.replay_file.as_deref() {
+            match recording::replay::PacketReplayer::new(path, args.replay_speed, args.replay_loop)
+            {
+                Ok(replayer) => {
+                    subsystem.start(SubsystemBuilder::new("Replay", |subsys| {
+                        replayer.run(subsys)
+                    }));
+                }
+                Err(e) => {
+                    log::error!("Cannot replay {}: {}", path.display(), e);
+                }
+            }
+        }
+
         log::info!("Using unified core locator");
         subsystem.start(SubsystemBuilder::new("Locator", |subsys| {
             locator.run_with_core_locator(subsys)
@@ -430,3 +604,59 @@ impl std::fmt::Debug for Session {
         write!(f, "Session {{ }}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mayara_core::capabilities::builder::build_capabilities_from_model;
+    use mayara_core::models;
+
+    #[test]
+    fn test_replay_model_changes_manifest() {
+        let default = models::get_model(mayara_core::Brand::Furuno, "DRS4D-NXT").unwrap();
+        let args = Cli::parse_from(["mayara", "--replay", "--replay-model", "DRS25A-NXT"]);
+        let model = args.replay_model(mayara_core::Brand::Furuno).unwrap();
+        assert_eq!(model.model, "DRS25A-NXT");
+        assert!(args.replay_model(mayara_core::Brand::Navico).is_none());
+
+        let default_caps = build_capabilities_from_model(default, "radar-1", vec![]);
+        let replay_caps = build_capabilities_from_model(model, "radar-1", vec![]);
+        assert_eq!(replay_caps.model, "DRS25A-NXT");
+        assert!(replay_caps.characteristics.max_range > default_caps.characteristics.max_range);
+    }
+
+    #[test]
+    fn test_replay_speed_validated() {
+        let args = Cli::parse_from(["mayara", "--replay", "--replay-file", "x.pcap"]);
+        assert_eq!((args.replay_speed, args.replay_loop), (1.0, false));
+        let args = Cli::parse_from([
+            "mayara",
+            "--replay",
+            "--replay-file",
+            "x.pcap",
+            "--replay-speed",
+            "2.5",
+            "--replay-loop",
+        ]);
+        assert_eq!((args.replay_speed, args.replay_loop), (2.5, true));
+        for speed in ["0.25", "11", "fast"] {
+            assert!(Cli::try_parse_from([
+                "mayara",
+                "--replay",
+                "--replay-file",
+                "x.pcap",
+                "--replay-speed",
+                speed,
+            ])
+            .is_err());
+        }
+        assert!(Cli::try_parse_from(["mayara", "--replay-file", "x.pcap"]).is_err());
+    }
+
+    #[test]
+    fn test_replay_model_validated() {
+        assert!(Cli::try_parse_from(["mayara", "--replay", "--replay-model", "DRS99"]).is_err());
+        assert!(Cli::try_parse_from(["mayara", "--replay-model", "HALO24"]).is_err());
+        assert!(Cli::try_parse_from(["mayara", "--replay", "--replay-model", "HALO24"]).is_ok());
+    }
+}
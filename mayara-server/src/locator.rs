@@ -13,6 +13,7 @@
 // use std::io;
 // use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
 // use std::time::Duration;
+use std::collections::HashMap;
 
 // use miette::Result;
 // use network_interface::{NetworkInterface, NetworkInterfaceConfig};
@@ -46,6 +47,7 @@ pub enum LocatorId {
     Gen3Plus,
     Furuno,
     Raymarine,
+    Garmin,
     Playback,
 }
 
@@ -57,6 +59,7 @@ impl LocatorId {
             Gen3Plus => "Navico 3G/4G/HALO",
             Furuno => "Furuno DRSxxxx",
             Raymarine => "Raymarine",
+            Garmin => "Garmin xHD",
             Playback => "Playback",
         }
     }
@@ -343,7 +346,7 @@ impl Locator {
         self,
         subsys: SubsystemHandle,
     ) -> Result<(), RadarError> {
-        use crate::core_locator::{create_locator_subsystem, dispatch_discovery, LocatorMessage};
+        use crate::core_locator::{create_locator_subsystem, start_radar, LocatorMessage};
         use tokio_graceful_shutdown::SubsystemBuilder;
 
         log::info!("Starting locator with CoreLocatorAdapter");
@@ -351,6 +354,8 @@ impl Locator {
         let (discovery_tx, mut discovery_rx) = mpsc::channel(32);
         let radars = self.radars.clone();
         let session = self.session.clone();
+        // The subsystem running each radar's receivers, by discovery address
+        let mut radar_subsystems = HashMap::new();
 
         // Spawn the core locator subsystem
         let session_for_locator = session.clone();
@@ -376,18 +381,10 @@ impl Locator {
                             );
 
                             // Dispatch to brand-specific processor
-                            if let Err(e) = dispatch_discovery(
-                                session.clone(),
-                                &discovery,
-                                &radars,
-                                &subsys,
-                            ) {
-                                log::error!(
-                                    "Failed to process {} discovery: {}",
-                                    discovery.brand,
-                                    e
-                                );
-                            }
+                            let address = discovery.address.clone();
+                            let radar_subsys =
+                                start_radar(session.clone(), discovery, radars.clone(), &subsys);
+                            radar_subsystems.insert(address, radar_subsys);
                         }
                         Some(LocatorMessage::RadarUpdated(discovery)) => {
                             log::info!(
@@ -397,8 +394,42 @@ impl Locator {
                                 discovery.model
                             );
 
-                            // Update existing radar with new model info
-                            radars.update_from_discovery(&discovery);
+                            // Update existing radar with new model info. A radar
+                            // that could not be started from its first beacon
+                            // (e.g. Raymarine without endpoints) starts now.
+                            if !radars.update_from_discovery(&discovery) {
+                                let address = discovery.address.clone();
+                                let radar_subsys =
+                                    start_radar(session.clone(), discovery, radars.clone(), &subsys);
+                                if let Some(previous) = radar_subsystems.insert(address, radar_subsys) {
+                                    previous.initiate_shutdown();
+                                }
+                            }
+                        }
+                        Some(LocatorMessage::RadarAddressChanged { previous_address, discovery }) => {
+                            log::info!(
+                                "Core locator moved {} radar: {} from {} to {}",
+                                discovery.brand,
+                                discovery.name,
+                                previous_address,
+                                discovery.address
+                            );
+
+                            // Stop the receivers listening at the old address
+                            // before the radar is found again at the new one,
+                            // where it keeps its ID and settings.
+                            if let Some(previous) = radar_subsystems.remove(&previous_address) {
+                                previous.initiate_shutdown();
+                                if let Err(e) = previous.join().await {
+                                    log::warn!("{}: receivers did not stop cleanly: {}", previous_address, e);
+                                }
+                            }
+                            radars.readdress(&previous_address, &discovery);
+
+                            let address = discovery.address.clone();
+                            let radar_subsys =
+                                start_radar(session.clone(), discovery, radars.clone(), &subsys);
+                            radar_subsystems.insert(address, radar_subsys);
                         }
                         Some(LocatorMessage::Shutdown) => {
                             log::info!("Core locator shutdown");
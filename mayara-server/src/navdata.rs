@@ -1,5 +1,6 @@
 use atomic_float::AtomicF64;
 use futures_util::future::select_ok;
+use mayara_core::arpa::{OwnShipMotionScript, KN_TO_MS};
 use mdns_sd::{Error, IfKind, ServiceDaemon, ServiceEvent};
 use nmea_parser::*;
 use serde_json::Value;
@@ -10,7 +11,7 @@ use std::{
     net::SocketAddr,
     pin::Pin,
     sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::{io::AsyncBufReadExt, net::UdpSocket, time::sleep};
 use tokio::{io::AsyncWriteExt, net::TcpStream};
@@ -197,6 +198,27 @@ impl NavigationData {
         let mut rx_ip_change = rx_ip_change;
         let navigation_address = self.session.read().unwrap().args.navigation_address.clone();
 
+        let replay_motion = self.session.read().unwrap().args.replay_motion.clone();
+        if let Some(path) = replay_motion {
+            match std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| OwnShipMotionScript::parse(&text).map_err(|e| e.to_string()))
+            {
+                Ok(script) => {
+                    log::info!(
+                        "Replaying own-ship motion from {} ({} records)",
+                        path.display(),
+                        script.records().len()
+                    );
+                    run_motion_script(script, &subsys).await;
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Cannot load motion script {}: {}", path.display(), e);
+                }
+            }
+        }
+
         loop {
             match self
                 .find_service(&subsys, &mut rx_ip_change, &navigation_address)
@@ -636,6 +658,30 @@ fn parse_signalk(s: &str) -> Result<(), RadarError> {
     )));
 }
 
+/// Feed own-ship navigation data from a motion script until shutdown
+async fn run_motion_script(script: OwnShipMotionScript, subsys: &SubsystemHandle) {
+    let start = Instant::now();
+    let mut interval = tokio::time::interval(Duration::from_millis(100));
+
+    loop {
+        tokio::select! { biased;
+            _ = subsys.on_shutdown_requested() => {
+                log::debug!("Motion script shutdown");
+                return;
+            },
+            _ = interval.tick() => {
+                if let Some(own) = script.own_ship_at(start.elapsed().as_millis() as u64) {
+                    // Same units as Signal K: radians and m/s
+                    set_position(Some(own.latitude), Some(own.longitude));
+                    set_heading_true(Some(own.heading.to_radians()));
+                    set_cog(Some(own.course.to_radians()));
+                    set_sog(Some(own.speed * KN_TO_MS));
+                }
+            }
+        }
+    }
+}
+
 async fn connect_to_socket(address: SocketAddr) -> Result<TcpStream, RadarError> {
     let stream = TcpStream::connect(address)
         .await
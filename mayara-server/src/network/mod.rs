@@ -1,13 +1,17 @@
 use serde::Deserialize;
 use socket2::{Domain, Protocol, Type};
 use std::fmt;
-use std::net::SocketAddrV4;
+use std::net::{Ipv6Addr, SocketAddrV4, SocketAddrV6};
 use std::sync::atomic::AtomicBool;
 use std::{
     io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
 };
+use std::time::Duration;
 use tokio::net::UdpSocket;
+use tokio::time::Instant;
+
+use crate::recording::packet_bus::{self, ReplayListener};
 
 #[cfg(target_os = "linux")]
 pub(crate) mod linux;
@@ -97,7 +101,15 @@ impl fmt::Debug for LittleEndianSocketAddrV4 {
 
 // this will be common for all our sockets
 pub fn new_socket() -> io::Result<socket2::Socket> {
-    let socket = socket2::Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    new_socket_for(&SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0))
+}
+
+/// A socket of the address family (IPv4 or IPv6) of `addr`
+pub fn new_socket_for(addr: &SocketAddr) -> io::Result<socket2::Socket> {
+    let socket = socket2::Socket::new(Domain::for_address(*addr), Type::DGRAM, Some(Protocol::UDP))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
 
     // we're going to use read timeouts so that we don't hang waiting for packets
     socket.set_nonblocking(true)?;
@@ -106,24 +118,92 @@ pub fn new_socket() -> io::Result<socket2::Socket> {
     Ok(socket)
 }
 
+/// The unspecified address of the same family as `addr`
+fn unspecified(addr: &SocketAddr) -> IpAddr {
+    match addr {
+        SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    }
+}
+
+/// Whether `ip` is an IPv6 link-local unicast or multicast address, which
+/// needs a scope id
+pub(crate) fn is_ipv6_link_local(ip: &IpAddr) -> bool {
+    matches!(ip, IpAddr::V6(v6) if (v6.segments()[0] & 0xffc0) == 0xfe80
+        || (v6.segments()[0] & 0xff0f) == 0xff02)
+}
+
+/// The socket address for `ip`, scoped to interface `scope_id` when
+/// `ip` is an IPv6 link-local address
+pub(crate) fn scoped_socket_addr(ip: IpAddr, port: u16, scope_id: u32) -> SocketAddr {
+    match ip {
+        IpAddr::V6(v6) if is_ipv6_link_local(&ip) => {
+            SocketAddr::V6(SocketAddrV6::new(v6, port, 0, scope_id))
+        }
+        _ => SocketAddr::new(ip, port),
+    }
+}
+
+/// `addr` scoped to interface `scope_id`, unless it already has a scope id
+fn with_scope_id(addr: &SocketAddr, scope_id: u32) -> SocketAddr {
+    match addr {
+        SocketAddr::V6(v6) if v6.scope_id() != 0 => *addr,
+        _ => scoped_socket_addr(addr.ip(), addr.port(), scope_id),
+    }
+}
+
+/// Index of the interface that has `nic_addr`, 0 (any) if none has it
+pub fn interface_index(nic_addr: &IpAddr) -> u32 {
+    use network_interface::{NetworkInterface, NetworkInterfaceConfig};
+
+    NetworkInterface::show()
+        .ok()
+        .and_then(|interfaces| {
+            interfaces
+                .into_iter()
+                .find(|itf| itf.addr.iter().any(|a| a.ip() == *nic_addr))
+        })
+        .map(|itf| itf.index)
+        .unwrap_or(0)
+}
+
+/// Join the multicast group `addr` on the interface with `nic_addr`
+fn join_multicast(socket: &socket2::Socket, addr: &SocketAddr, nic_addr: &IpAddr) -> io::Result<()> {
+    match (addr.ip(), nic_addr) {
+        (IpAddr::V4(group), IpAddr::V4(nic_addr)) => socket.join_multicast_v4(&group, nic_addr),
+        (IpAddr::V4(group), IpAddr::V6(_)) => {
+            socket.join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)
+        }
+        (IpAddr::V6(group), nic_addr) => {
+            let index = if nic_addr.is_unspecified() {
+                0
+            } else {
+                interface_index(nic_addr)
+            };
+            socket.join_multicast_v6(&group, index)
+        }
+    }
+}
+
 /// On Windows, unlike all Unix variants, it is improper to bind to the multicast address
 ///
 /// see https://msdn.microsoft.com/en-us/library/windows/desktop/ms737550(v=vs.85).aspx
 #[cfg(windows)]
 fn bind_to_multicast(
     socket: &socket2::Socket,
-    addr: &SocketAddrV4,
-    nic_addr: &Ipv4Addr,
+    addr: &SocketAddr,
+    nic_addr: &IpAddr,
 ) -> io::Result<()> {
+    let any = unspecified(addr);
     let nic_addr = if G_REPLAY.load(std::sync::atomic::Ordering::Relaxed) {
-        &Ipv4Addr::UNSPECIFIED
+        &any
     } else {
         nic_addr
     };
 
-    socket.join_multicast_v4(addr.ip(), nic_addr)?;
+    join_multicast(socket, addr, nic_addr)?;
 
-    let socketaddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), addr.port());
+    let socketaddr = SocketAddr::new(unspecified(addr), addr.port());
     socket.bind(&socket2::SockAddr::from(socketaddr))?;
     log::trace!("Binding multicast socket to {}", socketaddr);
 
@@ -134,20 +214,23 @@ fn bind_to_multicast(
 #[cfg(unix)]
 fn bind_to_multicast(
     socket: &socket2::Socket,
-    addr: &SocketAddrV4,
-    nic_addr: &Ipv4Addr,
+    addr: &SocketAddr,
+    nic_addr: &IpAddr,
 ) -> io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let any = unspecified(addr);
     #[cfg(target_os = "macos")]
     let nic_addr = if G_REPLAY.load(std::sync::atomic::Ordering::Relaxed) {
-        &Ipv4Addr::UNSPECIFIED
+        &any
     } else {
         nic_addr
     };
 
     // Linux is special, if we don't disable IP_MULTICAST_ALL the kernel forgets on
     // which device the multicast packet arrived and sends it to all sockets.
+    // IPv6 groups are always joined on a single interface index.
     #[cfg(target_os = "linux")]
-    {
+    if addr.is_ipv4() {
         use std::{io, mem, os::unix::io::AsRawFd};
 
         unsafe {
@@ -169,14 +252,13 @@ fn bind_to_multicast(
     // This differs from WASM which must use 0.0.0.0 due to Node.js dgram limitations.
     // The IP_MULTICAST_ALL=0 above ensures we only receive packets for groups we've
     // joined on this specific interface.
-    let socketaddr = SocketAddr::new(IpAddr::V4(*addr.ip()), addr.port());
-    socket.bind(&socket2::SockAddr::from(socketaddr))?;
+    socket.bind(&socket2::SockAddr::from(*addr))?;
 
-    socket.join_multicast_v4(addr.ip(), nic_addr)?;
+    join_multicast(socket, addr, nic_addr)?;
 
     log::trace!(
         "Binding multicast socket to {} for multicast group {} nic {}",
-        socketaddr,
+        addr,
         addr.ip(),
         nic_addr
     );
@@ -190,13 +272,13 @@ fn bind_to_multicast(
 #[cfg(windows)]
 fn bind_to_broadcast(
     socket: &socket2::Socket,
-    addr: &SocketAddrV4,
-    nic_addr: &Ipv4Addr,
+    addr: &SocketAddr,
+    nic_addr: &IpAddr,
 ) -> io::Result<()> {
     let _ = socket.set_broadcast(true);
     let _ = addr; // Not used on Windows
 
-    let socketaddr = SocketAddr::new(IpAddr::V4(*nic_addr), addr.port());
+    let socketaddr = SocketAddr::new(*nic_addr, addr.port());
 
     socket.bind(&socket2::SockAddr::from(socketaddr))?;
     log::trace!("Binding broadcast socket to {}", socketaddr);
@@ -207,8 +289,8 @@ fn bind_to_broadcast(
 #[cfg(unix)]
 fn bind_to_broadcast(
     socket: &socket2::Socket,
-    addr: &SocketAddrV4,
-    nic_addr: &Ipv4Addr,
+    addr: &SocketAddr,
+    nic_addr: &IpAddr,
 ) -> io::Result<()> {
     let _ = socket.set_broadcast(true);
     let _ = nic_addr; // Not used on Linux
@@ -218,65 +300,187 @@ fn bind_to_broadcast(
     Ok(())
 }
 
-pub fn create_udp_multicast_listen(
-    addr: &SocketAddrV4,
-    nic_addr: &Ipv4Addr,
-) -> io::Result<UdpSocket> {
-    let socket: socket2::Socket = new_socket()?;
+/// A socket receiving the datagrams sent to one (multicast) address
+///
+/// Every datagram received is offered to the packet capture. While a capture
+/// is replayed the datagrams come from the replay instead of the network,
+/// see [`crate::recording::packet_bus`].
+#[derive(Debug)]
+pub struct ListenSocket {
+    source: ListenSource,
+    addr: SocketAddr,
+}
+
+#[derive(Debug)]
+enum ListenSource {
+    Network(UdpSocket),
+    Replay(tokio::sync::Mutex<ReplayListener>),
+}
+
+impl ListenSocket {
+    fn new(socket: socket2::Socket, addr: &SocketAddr) -> io::Result<Self> {
+        Ok(ListenSocket {
+            source: ListenSource::Network(UdpSocket::from_std(socket.into())?),
+            addr: *addr,
+        })
+    }
+
+    /// A socket fed by the replay, if one runs
+    fn replayed(addr: &SocketAddr) -> Option<Self> {
+        ReplayListener::new(*addr).map(|listener| ListenSocket {
+            source: ListenSource::Replay(tokio::sync::Mutex::new(listener)),
+            addr: *addr,
+        })
+    }
+
+    /// The address this socket listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Receive a datagram, appended to `buf` like [`UdpSocket::recv_buf_from`]
+    pub async fn recv_buf_from(&self, buf: &mut Vec<u8>) -> io::Result<(usize, SocketAddr)> {
+        let start = buf.len();
+        let (len, src) = match &self.source {
+            ListenSource::Network(socket) => socket.recv_buf_from(buf).await?,
+            ListenSource::Replay(listener) => {
+                let packet = listener.lock().await.recv().await;
+                buf.extend_from_slice(&packet.payload);
+                (packet.payload.len(), SocketAddr::V4(packet.src))
+            }
+        };
+        packet_bus::capture(src, self.addr, &buf[start..start + len]);
+        Ok((len, src))
+    }
+}
+
+pub fn create_udp_multicast_listen(addr: &SocketAddr, nic_addr: &IpAddr) -> io::Result<ListenSocket> {
+    if let Some(socket) = ListenSocket::replayed(addr) {
+        return Ok(socket);
+    }
+    let socket: socket2::Socket = new_socket_for(addr)?;
 
     socket.set_reuse_address(true)?;
 
     bind_to_multicast(&socket, addr, nic_addr)?;
 
-    let socket = UdpSocket::from_std(socket.into())?;
-    Ok(socket)
+    ListenSocket::new(socket, addr)
 }
 
 pub fn create_udp_listen(
-    addr: &SocketAddrV4,
-    nic_addr: &Ipv4Addr,
+    addr: &SocketAddr,
+    nic_addr: &IpAddr,
     no_broadcast: bool,
-) -> io::Result<UdpSocket> {
-    let socket: socket2::Socket = new_socket()?;
+) -> io::Result<ListenSocket> {
+    if let Some(socket) = ListenSocket::replayed(addr) {
+        return Ok(socket);
+    }
+    let socket: socket2::Socket = new_socket_for(addr)?;
 
     if addr.ip().is_multicast() {
         bind_to_multicast(&socket, addr, nic_addr)?;
-    } else if !no_broadcast {
+    } else if !no_broadcast && addr.is_ipv4() {
+        // IPv6 has no broadcast, radars use multicast instead
         bind_to_broadcast(&socket, addr, nic_addr)?;
     } else {
-        let socketaddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), addr.port());
+        let socketaddr = SocketAddr::new(unspecified(addr), addr.port());
 
         socket.bind(&socket2::SockAddr::from(socketaddr))?;
         log::trace!("Binding socket to {}", socketaddr);
     }
 
-    let socket = UdpSocket::from_std(socket.into())?;
-    Ok(socket)
+    ListenSocket::new(socket, addr)
 }
 
-pub fn create_multicast_send(addr: &SocketAddrV4, nic_addr: &Ipv4Addr) -> io::Result<UdpSocket> {
-    let socket: socket2::Socket = new_socket()?;
+pub fn create_multicast_send(addr: &SocketAddr, nic_addr: &IpAddr) -> io::Result<UdpSocket> {
+    let socket: socket2::Socket = new_socket_for(addr)?;
 
-    let socketaddr = SocketAddr::new(IpAddr::V4(*addr.ip()), addr.port());
-    let socketaddr_nic = SocketAddr::new(IpAddr::V4(*nic_addr), addr.port());
+    let mut scope_id = 0;
+    if addr.is_ipv6() {
+        scope_id = interface_index(nic_addr);
+        socket.set_multicast_if_v6(scope_id)?;
+    }
+    // Link-local addresses, where radars live, only bind and route with
+    // the scope id of their interface
+    let socketaddr_nic = scoped_socket_addr(*nic_addr, addr.port(), scope_id);
     socket.bind(&socket2::SockAddr::from(socketaddr_nic))?;
-    socket.connect(&socket2::SockAddr::from(socketaddr))?;
+    socket.connect(&socket2::SockAddr::from(with_scope_id(addr, scope_id)))?;
 
     let socket = UdpSocket::from_std(socket.into())?;
     Ok(socket)
 }
 
+/// Time without packets after which an active multicast socket is recreated
+pub const MULTICAST_STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Detects multicast sockets that silently stopped receiving.
+///
+/// After the NIC goes down and up (e.g. a switch is power cycled) the
+/// kernel drops the group membership and the socket never sees another
+/// packet. The detector is armed by the first packet, so sockets that
+/// never received anything (radar in standby) are left alone; when it
+/// fires the receiver returns [`crate::radar::RadarError::Timeout`] and
+/// its run loop recreates the socket, joining the group again.
+#[derive(Debug, Default)]
+pub struct StallDetector {
+    last_packet: Option<Instant>,
+}
+
+impl StallDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that a packet arrived on the socket
+    pub fn packet_received(&mut self) {
+        self.last_packet = Some(Instant::now());
+    }
+
+    /// Disarm, e.g. after the socket has been recreated
+    pub fn reset(&mut self) {
+        self.last_packet = None;
+    }
+
+    /// When the socket counts as stalled, or `None` while disarmed
+    pub fn deadline(&self) -> Option<Instant> {
+        self.last_packet.map(|t| t + MULTICAST_STALL_TIMEOUT)
+    }
+
+    /// Wait until the socket stalls; never completes while disarmed
+    pub async fn stalled(&self) {
+        match self.deadline() {
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            None => std::future::pending().await,
+        }
+    }
+}
+
 pub fn match_ipv4(addr: &Ipv4Addr, bcast: &Ipv4Addr, netmask: &Ipv4Addr) -> bool {
     let r = addr & netmask;
     let b = bcast & netmask;
     r == b
 }
 
+/// Whether `addr` and `other` are on the same subnet, for IPv4 and IPv6
+pub fn match_ip(addr: &IpAddr, other: &IpAddr, netmask: &IpAddr) -> bool {
+    match (addr, other, netmask) {
+        (IpAddr::V4(addr), IpAddr::V4(other), IpAddr::V4(netmask)) => {
+            match_ipv4(addr, other, netmask)
+        }
+        (IpAddr::V6(addr), IpAddr::V6(other), IpAddr::V6(netmask)) => {
+            let netmask = u128::from(*netmask);
+            u128::from(*addr) & netmask == u128::from(*other) & netmask
+        }
+        _ => false,
+    }
+}
+
 /// Find the NIC address that can reach a given radar IP.
 ///
-/// Returns the first interface IP that matches the radar's subnet.
-/// Falls back to the first non-loopback interface if no match is found.
-pub fn find_nic_for_radar(radar_ip: &Ipv4Addr) -> Option<Ipv4Addr> {
+/// Returns the first interface IP of the same address family that matches
+/// the radar's subnet. Falls back to the first non-loopback interface of
+/// that family if no match is found.
+pub fn find_nic_for_radar(radar_ip: &IpAddr) -> Option<IpAddr> {
     use network_interface::{NetworkInterface, NetworkInterfaceConfig};
 
     let interfaces = NetworkInterface::show().ok()?;
@@ -284,8 +488,8 @@ pub fn find_nic_for_radar(radar_ip: &Ipv4Addr) -> Option<Ipv4Addr> {
     // First pass: find an interface on the same subnet
     for itf in &interfaces {
         for addr in &itf.addr {
-            if let (IpAddr::V4(nic_ip), Some(IpAddr::V4(netmask))) = (addr.ip(), addr.netmask()) {
-                if !nic_ip.is_loopback() && match_ipv4(&nic_ip, radar_ip, &netmask) {
+            if let (nic_ip, Some(netmask)) = (addr.ip(), addr.netmask()) {
+                if !nic_ip.is_loopback() && match_ip(&nic_ip, radar_ip, &netmask) {
                     log::debug!(
                         "Found NIC {} ({}) for radar {}",
                         itf.name, nic_ip, radar_ip
@@ -299,14 +503,13 @@ pub fn find_nic_for_radar(radar_ip: &Ipv4Addr) -> Option<Ipv4Addr> {
     // Second pass: return first non-loopback interface
     for itf in &interfaces {
         for addr in &itf.addr {
-            if let IpAddr::V4(nic_ip) = addr.ip() {
-                if !nic_ip.is_loopback() {
-                    log::debug!(
-                        "Fallback NIC {} ({}) for radar {} (no subnet match)",
-                        itf.name, nic_ip, radar_ip
-                    );
-                    return Some(nic_ip);
-                }
+            let nic_ip = addr.ip();
+            if !nic_ip.is_loopback() && nic_ip.is_ipv4() == radar_ip.is_ipv4() {
+                log::debug!(
+                    "Fallback NIC {} ({}) for radar {} (no subnet match)",
+                    itf.name, nic_ip, radar_ip
+                );
+                return Some(nic_ip);
             }
         }
     }
@@ -314,6 +517,67 @@ pub fn find_nic_for_radar(radar_ip: &Ipv4Addr) -> Option<Ipv4Addr> {
     None
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_socket_addr() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        assert_eq!(
+            scoped_socket_addr(ip("fe80::1"), 10010, 3).to_string(),
+            "[fe80::1%3]:10010"
+        );
+        assert_eq!(
+            scoped_socket_addr(ip("fd00::1"), 10010, 3).to_string(),
+            "[fd00::1]:10010"
+        );
+        assert_eq!(
+            scoped_socket_addr(ip("172.31.3.1"), 10010, 3).to_string(),
+            "172.31.3.1:10010"
+        );
+        assert_eq!(
+            with_scope_id(&"[ff02::1]:6878".parse().unwrap(), 3).to_string(),
+            "[ff02::1%3]:6878"
+        );
+        assert_eq!(
+            with_scope_id(&"[fe80::1%2]:10010".parse().unwrap(), 3).to_string(),
+            "[fe80::1%2]:10010"
+        );
+    }
+
+    #[test]
+    fn test_stall_detector_armed_by_first_packet() {
+        let mut detector = StallDetector::new();
+        assert!(detector.deadline().is_none());
+
+        let before = Instant::now();
+        detector.packet_received();
+        let deadline = detector.deadline().unwrap();
+        assert!(deadline >= before + MULTICAST_STALL_TIMEOUT);
+        assert!(deadline <= Instant::now() + MULTICAST_STALL_TIMEOUT);
+
+        detector.reset();
+        assert!(detector.deadline().is_none());
+    }
+
+    #[test]
+    fn test_match_ip() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        assert!(match_ip(&ip("172.31.3.10"), &ip("172.31.1.4"), &ip("255.255.0.0")));
+        assert!(!match_ip(&ip("192.168.1.10"), &ip("172.31.1.4"), &ip("255.255.0.0")));
+
+        let mask = ip("ffff:ffff:ffff:ffff::");
+        assert!(match_ip(&ip("fd00:1::10"), &ip("fd00:1::4"), &mask));
+        assert!(!match_ip(&ip("fd00:2::10"), &ip("fd00:1::4"), &mask));
+
+        // Different address families never match
+        assert!(!match_ip(&ip("172.31.3.10"), &ip("::ffff:172.31.3.10"), &mask));
+    }
+}
+
 // deprecated_marked_for_delete: All platform-specific re-exports below are dead code
 // Only used by legacy locator which has been removed. Will be deleted in a future cleanup.
 
@@ -0,0 +1,679 @@
+//! Server side ARPA target tracking
+//!
+//! Runs the mayara-core ARPA pipeline (`HistoryBuffer` and `refresh_target`)
+//! for one radar. The data receivers hand over every spoke before it is
+//! drawn into trails via `RadarInfo::spoke_tx`; the tracked targets are
+//! published as `RadarMessage.targets`, so they reach both the websocket
+//! clients and the `--output` forwarder.
+//!
+//! Radars with Doppler acquire moving targets automatically, with IDs from
+//! `RESERVED_TARGET_ID_START` up. Targets acquired through the REST API
+//! arrive as [`TargetRequest`]s on the data update channel and get IDs
+//! below that. Spokes without a radar position (no GNSS fix) are ignored,
+//! as targets are tracked in geographic coordinates.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use mayara_core::arpa::{
+    bearing_distance_to, meters_per_degree_longitude, refresh_target, ArpaSettings, ContourError,
+    DopplerState, ExtendedPosition, HistoryBuffer, Legend, Pass, Polar, PolarConverter,
+    RefreshConfig, RefreshState, TargetState, TargetStatus, KN_TO_MS, MAX_DETECTION_SPEED_KN,
+    METERS_PER_DEGREE_LATITUDE, RESERVED_TARGET_ID_START,
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc;
+use tokio::time::{interval, MissedTickBehavior};
+use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
+
+use crate::protos::RadarMessage::radar_message::{Spoke, Target};
+use crate::protos::RadarMessage::RadarMessage;
+use crate::settings::DataUpdate;
+use crate::{Session, TargetMode};
+
+use super::{RadarError, RadarInfo};
+
+// Used until a full rotation has been seen
+const DEFAULT_ROTATION_MS: u64 = 2500;
+
+// Doppler returns closer than this to the radar or the end of the spoke
+// are not acquired, they are mostly clutter or cut off.
+const ACQUIRE_MARGIN: usize = 20;
+
+/// A request from the REST API, sent as [`DataUpdate::Target`]
+///
+/// The ARPA subsystem replies with the target ID or an error message, or
+/// with the image for [`TargetRequest::Sweep`].
+#[derive(Clone, Debug)]
+pub enum TargetRequest {
+    /// Acquire a target at a true bearing (degrees) and distance (meters),
+    /// with the given ID or the first free manual ID
+    Acquire {
+        bearing: f64,
+        distance: f64,
+        id: Option<u32>,
+        reply: mpsc::Sender<Result<u32, String>>,
+    },
+    /// Stop tracking a target
+    Cancel {
+        id: u32,
+        reply: mpsc::Sender<Result<u32, String>>,
+    },
+    /// Render the last revolution as a `size` x `size` RGBA image, see
+    /// [`HistoryBuffer::render_rgba`]
+    Sweep {
+        size: usize,
+        reply: mpsc::Sender<Vec<u8>>,
+    },
+}
+
+// How long a REST request waits for the ARPA subsystem to reply
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Start the ARPA subsystem for a radar when `--targets arpa` is selected
+pub fn start_arpa(session: &Session, info: &RadarInfo, subsys: &SubsystemHandle) {
+    let refresh_ms = {
+        let session = session.read().unwrap();
+        if session.args.targets != TargetMode::Arpa {
+            return;
+        }
+        session.args.arpa_refresh_ms
+    };
+
+    let arpa = ArpaSubsystem::new(info.clone(), Duration::from_millis(refresh_ms));
+    subsys.start(SubsystemBuilder::new(info.key() + " arpa", |s| arpa.run(s)));
+}
+
+/// Send a target request to the radar's ARPA subsystem and wait for its
+/// reply, the target ID or an error message
+pub async fn target_request(
+    info: &RadarInfo,
+    request: impl FnOnce(mpsc::Sender<Result<u32, String>>) -> TargetRequest,
+) -> Result<u32, String> {
+    let (reply_tx, mut reply_rx) = mpsc::channel(1);
+    info.controls
+        .get_data_update_tx()
+        .send(DataUpdate::Target(request(reply_tx)))
+        .map_err(|_| "ARPA is not running".to_string())?;
+    match tokio::time::timeout(REQUEST_TIMEOUT, reply_rx.recv()).await {
+        Ok(Some(result)) => result,
+        Ok(None) | Err(_) => Err("ARPA did not reply".to_string()),
+    }
+}
+
+/// Ask the radar's ARPA subsystem for a `size` x `size` RGBA image of the
+/// last revolution
+pub async fn sweep_request(info: &RadarInfo, size: usize) -> Result<Vec<u8>, String> {
+    let (reply, mut reply_rx) = mpsc::channel(1);
+    info.controls
+        .get_data_update_tx()
+        .send(DataUpdate::Target(TargetRequest::Sweep { size, reply }))
+        .map_err(|_| "ARPA is not running".to_string())?;
+    match tokio::time::timeout(REQUEST_TIMEOUT, reply_rx.recv()).await {
+        Ok(Some(image)) => Ok(image),
+        Ok(None) | Err(_) => Err("ARPA did not reply".to_string()),
+    }
+}
+
+pub struct ArpaSubsystem {
+    key: String,
+    info: RadarInfo,
+    spoke_rx: broadcast::Receiver<Spoke>,
+    data_update_rx: broadcast::Receiver<DataUpdate>,
+    refresh_interval: Duration,
+    settings: ArpaSettings,
+    legend: Legend,
+    colors: Vec<[u8; 4]>, // RGBA of each pixel value, for sweep images
+    history: HistoryBuffer,
+    targets: HashMap<usize, TargetState>,
+
+    // Taken from the most recent spoke
+    spoke_len: usize,
+    pixels_per_meter: f64,
+    own_position: Option<(f64, f64)>,
+    angle: Option<usize>,
+
+    rotation_start: Option<u64>,
+    rotation_ms: u64,
+    scanned_angle: Option<usize>, // Doppler acquisition has looked up to here
+    published_targets: bool,
+}
+
+impl ArpaSubsystem {
+    pub fn new(info: RadarInfo, refresh_interval: Duration) -> ArpaSubsystem {
+        let spokes = info.spokes_per_revolution as usize;
+        let legend = core_legend(&info.legend);
+        let colors = info.legend.colors();
+        ArpaSubsystem {
+            key: info.key(),
+            spoke_rx: info.spoke_tx.subscribe(),
+            data_update_rx: info.controls.data_update_subscribe(),
+            info,
+            refresh_interval,
+            settings: ArpaSettings::default(),
+            legend,
+            colors,
+            history: HistoryBuffer::new(spokes),
+            targets: HashMap::new(),
+            spoke_len: 0,
+            pixels_per_meter: 0.,
+            own_position: None,
+            angle: None,
+            rotation_start: None,
+            rotation_ms: DEFAULT_ROTATION_MS,
+            scanned_angle: None,
+            published_targets: false,
+        }
+    }
+
+    pub async fn run(mut self, subsys: SubsystemHandle) -> Result<(), RadarError> {
+        log::debug!("{}: ARPA started", self.key);
+
+        let mut refresh = interval(self.refresh_interval);
+        refresh.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = subsys.on_shutdown_requested() => {
+                    return Ok(());
+                },
+                r = self.spoke_rx.recv() => {
+                    match r {
+                        Ok(spoke) => self.process_spoke(&spoke),
+                        Err(RecvError::Lagged(n)) => {
+                            log::debug!("{}: ARPA skipped {} spokes", self.key, n);
+                        }
+                        Err(RecvError::Closed) => return Ok(()),
+                    }
+                },
+                r = self.data_update_rx.recv() => {
+                    match r {
+                        Ok(DataUpdate::Legend(legend)) => {
+                            self.legend = core_legend(&legend);
+                            self.colors = legend.colors();
+                        }
+                        Ok(DataUpdate::ControlValue(_, cv)) if cv.id == "clearTargets" => {
+                            self.targets.clear();
+                            self.publish();
+                        }
+                        Ok(DataUpdate::Target(request)) => {
+                            self.handle_target_request(request).await;
+                        }
+                        Ok(_) | Err(RecvError::Lagged(_)) => {}
+                        Err(RecvError::Closed) => return Ok(()),
+                    }
+                },
+                _ = refresh.tick() => {
+                    self.refresh();
+                    self.publish();
+                },
+            }
+        }
+    }
+
+    async fn handle_target_request(&mut self, request: TargetRequest) {
+        let (result, reply) = match request {
+            TargetRequest::Acquire {
+                bearing,
+                distance,
+                id,
+                reply,
+            } => (self.acquire_target(bearing, distance, id, now()), reply),
+            TargetRequest::Cancel { id, reply } => {
+                let result = match self.targets.remove(&(id as usize)) {
+                    Some(_) => Ok(id),
+                    None => Err(format!("target {} not found", id)),
+                };
+                (result, reply)
+            }
+            TargetRequest::Sweep { size, reply } => {
+                let image = self.history.render_rgba(&self.legend, &self.colors, size);
+                let _ = reply.send(image).await;
+                return;
+            }
+        };
+        if result.is_ok() {
+            self.publish();
+        }
+        let _ = reply.send(result).await;
+    }
+
+    /// Acquire a target by hand, it is tracked from the next refresh on
+    ///
+    /// IDs given by the client must be in `1..RESERVED_TARGET_ID_START`.
+    fn acquire_target(
+        &mut self,
+        bearing: f64,
+        distance: f64,
+        id: Option<u32>,
+        time: u64,
+    ) -> Result<u32, String> {
+        let Some((own_lat, own_lon)) = self.own_position else {
+            return Err("radar position unknown".to_string());
+        };
+        if self.targets.len() >= self.settings.max_targets as usize {
+            return Err("max targets reached".to_string());
+        }
+        let id = match id {
+            Some(id) if id == 0 || id >= RESERVED_TARGET_ID_START => {
+                return Err(format!(
+                    "target id must be 1-{}",
+                    RESERVED_TARGET_ID_START - 1
+                ));
+            }
+            Some(id) if self.targets.contains_key(&(id as usize)) => {
+                return Err(format!("target id {} already in use", id));
+            }
+            Some(id) => id as usize,
+            None => self
+                .next_free_id(1..RESERVED_TARGET_ID_START as usize)
+                .ok_or_else(|| "no free target id".to_string())?,
+        };
+
+        let (sin, cos) = bearing.to_radians().sin_cos();
+        let lat = own_lat + distance * cos / METERS_PER_DEGREE_LATITUDE;
+        let lon = own_lon + distance * sin / meters_per_degree_longitude(own_lat);
+        let position = ExtendedPosition::new(lat, lon, 0., 0., time, 0., 0.);
+        let target = TargetState::new(
+            id,
+            position,
+            own_lat,
+            own_lon,
+            self.info.spokes_per_revolution as usize,
+            TargetStatus::Acquire0,
+            self.info.doppler,
+        );
+        log::debug!("{}: ARPA acquired target {}", self.key, id);
+        self.targets.insert(id, target);
+        Ok(id as u32)
+    }
+
+    /// First ID in `ids` that no tracked target uses
+    fn next_free_id(&self, mut ids: Range<usize>) -> Option<usize> {
+        ids.find(|id| !self.targets.contains_key(id))
+    }
+
+    fn process_spoke(&mut self, spoke: &Spoke) {
+        let (Some(lat), Some(lon)) = (spoke.lat, spoke.lon) else {
+            log::trace!("{}: ARPA needs the radar position", self.key);
+            return;
+        };
+        if spoke.range == 0 || spoke.data.is_empty() {
+            return;
+        }
+        let time = spoke.time.unwrap_or_else(now);
+
+        let pixels_per_meter = spoke.data.len() as f64 / spoke.range as f64;
+        if (pixels_per_meter - self.pixels_per_meter).abs() > f64::EPSILON * pixels_per_meter {
+            // Targets keep their geographic position, only the history is
+            // no longer valid at the new range.
+            log::debug!(
+                "{}: ARPA range {} m, {} pixels",
+                self.key,
+                spoke.range,
+                spoke.data.len()
+            );
+            self.history.reset();
+            self.scanned_angle = None;
+            self.pixels_per_meter = pixels_per_meter;
+        }
+        self.spoke_len = spoke.data.len();
+
+        let spokes = self.info.spokes_per_revolution as usize;
+        let angle = spoke.bearing.unwrap_or(spoke.angle) as usize % spokes;
+        if let Some(prev) = self.angle {
+            if angle < prev {
+                if let Some(start) = self.rotation_start {
+                    if time > start {
+                        self.rotation_ms = time - start;
+                    }
+                }
+                self.rotation_start = Some(time);
+            }
+        }
+        self.angle = Some(angle);
+
+        let (lat, lon) = (lat as f64 * 1e-16, lon as f64 * 1e-16);
+        self.own_position = Some((lat, lon));
+        self.history
+            .update_spoke(angle, &spoke.data, time, lat, lon, &self.legend);
+    }
+
+    fn refresh(&mut self) {
+        let Some((own_lat, own_lon)) = self.own_position else {
+            return;
+        };
+
+        let config = RefreshConfig {
+            spokes_per_revolution: self.info.spokes_per_revolution as i32,
+            spoke_len: self.spoke_len as i32,
+            pixels_per_meter: self.pixels_per_meter,
+            rotation_period_ms: self.rotation_ms,
+            have_doppler: self.info.doppler,
+            dead_reckoning: self.settings.dead_reckoning,
+            contour_limits: self.settings.contour_limits,
+            contour_merge: self.settings.contour_merge,
+        };
+        let search_radius =
+            (MAX_DETECTION_SPEED_KN * KN_TO_MS * self.rotation_ms as f64 * self.pixels_per_meter
+                / 1000.) as i32;
+
+        // A target is only searched for once the radar has swept past it
+        // again, earlier calls return `WaitForRefresh`.
+        let history = &mut self.history;
+        self.targets.retain(|_, target| {
+            target.refreshed = RefreshState::NotFound;
+            !matches!(
+                refresh_target(
+                    target,
+                    history,
+                    own_lat,
+                    own_lon,
+                    &config,
+                    search_radius,
+                    Pass::Third
+                ),
+                Err(ContourError::Lost)
+            )
+        });
+
+        if self.info.doppler {
+            self.acquire_doppler_targets(own_lat, own_lon);
+        }
+    }
+
+    /// Acquire Doppler returns in the spokes received since the last scan
+    ///
+    /// The last spokes are left for the next scan, as a blob there may not
+    /// be complete yet.
+    fn acquire_doppler_targets(&mut self, own_lat: f64, own_lon: f64) {
+        let Some(angle) = self.angle else {
+            return;
+        };
+        let spokes = self.info.spokes_per_revolution as usize;
+        let converter = PolarConverter::new(spokes as i32, self.pixels_per_meter);
+        let end = (angle + spokes - converter.scan_margin() as usize) % spokes;
+        let start = self.scanned_angle.unwrap_or(end);
+        self.scanned_angle = Some(end);
+
+        let doppler = DopplerState::AnyDoppler;
+        for a in scan_window(start, end, spokes) {
+            for r in ACQUIRE_MARGIN..self.spoke_len.saturating_sub(ACQUIRE_MARGIN) {
+                if self.targets.len() >= self.settings.max_targets as usize {
+                    return;
+                }
+                if !self.history.multi_pix(&doppler, a as i32, r as i32) {
+                    continue;
+                }
+                let time = self.history.spokes[a].time;
+                let Ok((contour, _)) = self
+                    .history
+                    .get_contour(&doppler, Polar::new(a as i32, r as i32, time))
+                else {
+                    continue;
+                };
+                // A ship split into several nearby echoes is one target
+                let contour = self.history.merge_nearby_contours(
+                    &doppler,
+                    contour,
+                    &self.settings.contour_merge,
+                );
+                let pos = contour.position;
+                // Don't acquire the same blob again on the next spokes
+                self.history
+                    .reset_pixels(&contour, &pos, self.pixels_per_meter);
+
+                let (dlat, dlon) = converter.polar_to_geo_offset(&contour.position, own_lat);
+                let position =
+                    ExtendedPosition::new(own_lat + dlat, own_lon + dlon, 0., 0., time, 0., 0.);
+                let Some(id) = self.next_free_id(RESERVED_TARGET_ID_START as usize..usize::MAX)
+                else {
+                    return;
+                };
+                let mut target = TargetState::new(
+                    id,
+                    position,
+                    own_lat,
+                    own_lon,
+                    spokes,
+                    TargetStatus::Acquire0,
+                    true,
+                );
+                target.doppler = doppler;
+                target.automatic = true;
+                log::debug!("{}: ARPA acquired Doppler target {}", self.key, id);
+                self.targets.insert(id, target);
+            }
+        }
+    }
+
+    /// Send the targets to the clients. Nothing is sent when there are no
+    /// targets, except once to tell that the last target is gone.
+    fn publish(&mut self) {
+        if self.targets.is_empty() && !self.published_targets {
+            return;
+        }
+        self.published_targets = !self.targets.is_empty();
+
+        let (own_lat, own_lon) = self.own_position.unwrap_or_default();
+        let mut targets: Vec<&TargetState> = self.targets.values().collect();
+        targets.sort_by_key(|t| t.id);
+
+        let mut message = RadarMessage::new();
+        message.radar = self.info.id as u32;
+        for target in targets {
+            message
+                .targets
+                .push(to_protobuf_target(target, own_lat, own_lon));
+        }
+        self.info.broadcast_radar_message(message);
+    }
+}
+
+fn core_legend(legend: &super::Legend) -> Legend {
+    Legend {
+        strong_return: legend.strong_return,
+        doppler_approaching: legend.doppler_approaching,
+        doppler_receding: legend.doppler_receding,
+        border: legend.border,
+    }
+}
+
+fn to_protobuf_target(target: &TargetState, own_lat: f64, own_lon: f64) -> Target {
+    let position = &target.position;
+    let (bearing, distance) = bearing_distance_to(own_lat, own_lon, position.lat, position.lon);
+
+    let mut t = Target::new();
+    t.id = target.id as u32;
+    t.acquiring = target.status != TargetStatus::Active;
+    t.lat = (position.lat * 1e16) as i64;
+    t.lon = (position.lon * 1e16) as i64;
+    t.bearing = bearing;
+    t.distance = distance;
+    t.speed = position.speed_kn * KN_TO_MS;
+    t.course = target.course;
+    t.dead_reckoning = target.dead_reckoning;
+    t.confidence = target.confidence();
+    t
+}
+
+/// Spoke angles from `start` up to, but not including, `end`, wrapping
+/// at `spokes`
+fn scan_window(start: usize, end: usize, spokes: usize) -> impl Iterator<Item = usize> {
+    let count = (end + spokes - start) % spokes;
+    (0..count).map(move |i| (start + i) % spokes)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_window() {
+        assert_eq!(
+            scan_window(10, 14, 2048).collect::<Vec<_>>(),
+            [10, 11, 12, 13]
+        );
+        assert_eq!(
+            scan_window(2046, 2, 2048).collect::<Vec<_>>(),
+            [2046, 2047, 0, 1]
+        );
+        assert_eq!(scan_window(5, 5, 2048).count(), 0);
+    }
+
+    #[cfg(feature = "navico")]
+    fn subsystem() -> ArpaSubsystem {
+        use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+        let session = crate::Session::new_fake();
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+        let info = RadarInfo::new(
+            session.clone(),
+            crate::locator::LocatorId::Gen3Plus,
+            crate::Brand::Navico,
+            Some("1234"),
+            None,
+            16,
+            2048,
+            1024,
+            addr,
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            addr,
+            addr,
+            addr,
+            crate::brand::navico::settings::new(session, Some("HALO")),
+            true,
+        );
+        ArpaSubsystem::new(info, Duration::from_secs(1))
+    }
+
+    #[cfg(feature = "navico")]
+    #[test]
+    fn test_manual_ids_below_reserved_range() {
+        let mut arpa = subsystem();
+        assert!(arpa.acquire_target(90., 1000., None, 0).is_err());
+
+        arpa.own_position = Some((52.0, 4.0));
+        assert_eq!(arpa.acquire_target(90., 1000., None, 0), Ok(1));
+        assert_eq!(arpa.acquire_target(180., 500., Some(7), 0), Ok(7));
+        assert!(arpa.acquire_target(180., 500., Some(7), 0).is_err());
+        assert!(arpa
+            .acquire_target(180., 500., Some(RESERVED_TARGET_ID_START), 0)
+            .is_err());
+        assert_eq!(
+            arpa.next_free_id(RESERVED_TARGET_ID_START as usize..usize::MAX),
+            Some(RESERVED_TARGET_ID_START as usize)
+        );
+
+        let target = &arpa.targets[&1];
+        let (bearing, distance) =
+            bearing_distance_to(52.0, 4.0, target.position.lat, target.position.lon);
+        assert!((bearing - 90.).abs() < 0.01);
+        assert!((distance - 1000.).abs() < 0.1);
+    }
+
+    #[cfg(feature = "navico")]
+    #[test]
+    fn test_confidence_rises_while_tracked() {
+        let mut arpa = subsystem();
+        arpa.info.doppler = false;
+        let strong = arpa.legend.strong_return;
+        let rotation = |arpa: &mut ArpaSubsystem, n: u64, echo: bool| {
+            for angle in 0..2048u32 {
+                let mut spoke = Spoke::new();
+                spoke.angle = angle;
+                spoke.range = 1000;
+                spoke.time = Some(n * 2500 + angle as u64 * 2500 / 2048);
+                spoke.lat = Some(520_000_000_000_000_000);
+                spoke.lon = Some(40_000_000_000_000_000);
+                spoke.data = vec![0; 512];
+                if echo && (1020..1030).contains(&angle) {
+                    spoke.data[250..260].fill(strong);
+                }
+                arpa.process_spoke(&spoke);
+            }
+        };
+
+        rotation(&mut arpa, 0, true);
+        let id = arpa.acquire_target(180.2, 496., None, 2500).unwrap() as usize;
+        assert!(arpa.targets[&id].confidence() < 0.3);
+
+        // Rising over the scans, give or take a pixel of residual
+        let mut confidence = Vec::new();
+        for n in 1..=12 {
+            rotation(&mut arpa, n, true);
+            arpa.refresh();
+            confidence.push(arpa.targets[&id].confidence());
+        }
+        assert!(
+            confidence.windows(4).all(|w| w[3] > w[0]),
+            "{:?}",
+            confidence
+        );
+        let settled = confidence[11];
+        assert!(settled > 0.7, "settled confidence {}", settled);
+        let t = to_protobuf_target(&arpa.targets[&id], 52.0, 4.0);
+        assert_eq!(t.confidence, settled);
+
+        // A scan without its echo lowers it again
+        rotation(&mut arpa, 13, false);
+        arpa.refresh();
+        assert!(arpa.targets[&id].confidence() < settled);
+    }
+
+    #[cfg(feature = "navico")]
+    #[tokio::test]
+    async fn test_sweep_request_renders_history() {
+        let mut arpa = subsystem();
+        let strong = arpa.legend.strong_return;
+        for angle in 0..2048u32 {
+            let mut spoke = Spoke::new();
+            spoke.angle = angle;
+            spoke.range = 1000;
+            spoke.time = Some(angle as u64);
+            spoke.lat = Some(520_000_000_000_000_000);
+            spoke.lon = Some(40_000_000_000_000_000);
+            spoke.data = vec![0; 512];
+            spoke.data[240..272].fill(strong);
+            arpa.process_spoke(&spoke);
+        }
+
+        let (reply, mut reply_rx) = mpsc::channel(1);
+        arpa.handle_target_request(TargetRequest::Sweep { size: 64, reply })
+            .await;
+        let image = reply_rx.recv().await.unwrap();
+        assert_eq!(image.len(), 64 * 64 * 4);
+        let pixel = |x: usize, y: usize| &image[(y * 64 + x) * 4..(y * 64 + x + 1) * 4];
+
+        // The ring is half way out, 16 pixels from the center
+        assert_eq!(pixel(32, 16), arpa.colors[strong as usize]);
+        assert_eq!(pixel(48, 32), arpa.colors[strong as usize]);
+        assert_eq!(pixel(32, 32), [0; 4]);
+        assert_eq!(pixel(32, 2), [0; 4]);
+    }
+
+    #[test]
+    fn test_protobuf_target() {
+        let position = ExtendedPosition::new(52.001, 4.0, 0., 0., 1000, 10., 0.);
+        let mut target =
+            TargetState::new(7, position, 52.0, 4.0, 2048, TargetStatus::Active, false);
+        target.course = 90.;
+
+        let t = to_protobuf_target(&target, 52.0, 4.0);
+        assert_eq!(t.id, 7);
+        assert!(!t.acquiring);
+        assert_eq!(t.lat, 520_010_000_000_000_000);
+        assert!(t.bearing < 0.01 || t.bearing > 359.99);
+        assert!((t.distance - 111.).abs() < 1.);
+        assert!((t.speed - 5.144).abs() < 0.001);
+        assert_eq!(t.course, 90.);
+    }
+}
@@ -0,0 +1,397 @@
+//! Guard zone alarms
+//!
+//! Every spoke is checked against the radar's guard zones by the mayara-core
+//! `GuardZoneProcessor`. The processor clears a zone after a few spokes
+//! without returns, so a small target would alarm again on every revolution.
+//! Here a zone stays in alarm until it has been swept `CLEAR_REVOLUTIONS`
+//! times without an intrusion. Alarms and clears are logged and sent as a
+//! `GuardZoneEvent` to all subscribers, i.e. the control websocket clients,
+//! where it is told apart from control values by `"type": "guardZone"`.
+//!
+//! The zones are persisted with the radar's settings.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use mayara_core::arpa::Legend as CoreLegend;
+use mayara_core::guard_zones::{
+    GuardZone, GuardZoneProcessor, GuardZoneStatus, ZoneAlertState, RESERVED_ZONE_ID_START,
+};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::protos::RadarMessage::radar_message::Spoke;
+use crate::settings::ControlError;
+
+use super::Legend;
+
+/// Revolutions without an intrusion before a zone alarm is cleared
+pub const CLEAR_REVOLUTIONS: u32 = 3;
+
+/// A zone alarm was raised or cleared
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename = "guardZone", rename_all = "camelCase")]
+pub struct GuardZoneEvent {
+    pub zone_id: u32,
+    pub state: ZoneAlertState,
+    pub timestamp: u64, // millis since UNIX epoch
+    /// Bearing from the bow in degrees of the return that raised the alarm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bearing: Option<f64>,
+    /// Distance in meters of the return that raised the alarm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct ZoneAlarm {
+    hit: bool,              // Intrusion seen in this revolution
+    clear_revolutions: u32, // Revolutions without intrusion
+}
+
+#[derive(Debug)]
+struct Monitor {
+    processor: GuardZoneProcessor,
+    alarms: HashMap<u32, ZoneAlarm>,
+    prev_angle: Option<u32>,
+}
+
+/// The guard zones of a radar, shared by all clones of its `RadarInfo`
+#[derive(Clone, Debug)]
+pub struct GuardZones {
+    key: String,
+    monitor: Arc<Mutex<Monitor>>,
+    event_tx: broadcast::Sender<GuardZoneEvent>,
+}
+
+impl GuardZones {
+    /// `subsample` checks only every Nth spoke of a zone, see
+    /// [`GuardZoneProcessor::set_subsample`]
+    pub(crate) fn new(key: &str, subsample: u32) -> GuardZones {
+        let (event_tx, _) = broadcast::channel(32);
+        let mut processor = GuardZoneProcessor::new();
+        processor.set_subsample(subsample);
+        GuardZones {
+            key: key.to_string(),
+            monitor: Arc::new(Mutex::new(Monitor {
+                processor,
+                alarms: HashMap::new(),
+                prev_angle: None,
+            })),
+            event_tx,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<GuardZoneEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// All zones, sorted by ID
+    pub fn zones(&self) -> Vec<GuardZone> {
+        let monitor = self.monitor.lock().unwrap();
+        let mut zones: Vec<GuardZone> =
+            monitor.processor.get_zones().into_iter().cloned().collect();
+        zones.sort_by_key(|z| z.id);
+        zones
+    }
+
+    /// All zones with their alarm state, sorted by ID
+    pub fn status(&self) -> Vec<GuardZoneStatus> {
+        let monitor = self.monitor.lock().unwrap();
+        let mut status: Vec<GuardZoneStatus> = monitor
+            .processor
+            .get_zones()
+            .into_iter()
+            .map(|zone| monitor.status(zone))
+            .collect();
+        status.sort_by_key(|s| s.zone.id);
+        status
+    }
+
+    pub fn get(&self, zone_id: u32) -> Option<GuardZoneStatus> {
+        let monitor = self.monitor.lock().unwrap();
+        monitor
+            .processor
+            .get_zone(zone_id)
+            .map(|zone| monitor.status(zone))
+    }
+
+    /// Add or replace a zone
+    ///
+    /// IDs from `RESERVED_ZONE_ID_START` are kept for internal zones, and a
+    /// zone starting beyond `max_range` (meters, if known) can never alarm.
+    /// Zones reaching past it are only checked up to it.
+    pub fn set(&self, zone: GuardZone, max_range: Option<i32>) -> Result<(), ControlError> {
+        if zone.id >= RESERVED_ZONE_ID_START {
+            return Err(ControlError::Invalid(
+                "guardZone".to_string(),
+                format!(
+                    "id {} is reserved, use 0-{}",
+                    zone.id,
+                    RESERVED_ZONE_ID_START - 1
+                ),
+            ));
+        }
+        let (inner, _) = zone.shape.radii();
+        if let Some(max_range) = max_range {
+            if inner >= max_range as f64 {
+                return Err(ControlError::Invalid(
+                    "guardZone".to_string(),
+                    format!(
+                        "{} starts beyond the radar's {} m range",
+                        zone.id, max_range
+                    ),
+                ));
+            }
+        }
+
+        let mut monitor = self.monitor.lock().unwrap();
+        if max_range.is_some() {
+            monitor.processor.set_max_range(max_range.map(f64::from));
+        }
+        let (id, enabled) = (zone.id, zone.enabled);
+        monitor.processor.add_zone(zone);
+        if !enabled {
+            monitor.processor.set_zone_enabled(id, false);
+            self.clear_alarm(&mut monitor, id, now());
+        }
+        Ok(())
+    }
+
+    pub fn remove(&self, zone_id: u32) -> bool {
+        let mut monitor = self.monitor.lock().unwrap();
+        self.clear_alarm(&mut monitor, zone_id, now());
+        monitor.processor.remove_zone(zone_id)
+    }
+
+    /// Restore the persisted zones
+    pub(crate) fn load(&self, zones: &[GuardZone]) {
+        let mut monitor = self.monitor.lock().unwrap();
+        for zone in zones {
+            monitor.processor.add_zone(zone.clone());
+        }
+    }
+
+    /// Check a spoke, before trails are drawn into it
+    pub(crate) fn check_spoke(&self, spoke: &Spoke, spokes_per_revolution: u32, legend: &Legend) {
+        let mut monitor = self.monitor.lock().unwrap();
+        if monitor.processor.zone_count() == 0 || spoke.range == 0 {
+            return;
+        }
+        let time = spoke.time.unwrap_or_else(now);
+        let degrees_per_spoke = 360. / spokes_per_revolution as f64;
+
+        let processor = &mut monitor.processor;
+        processor.set_range_scale(spoke.range as f64);
+        processor.set_legend(CoreLegend {
+            strong_return: legend.strong_return,
+            doppler_approaching: legend.doppler_approaching,
+            doppler_receding: legend.doppler_receding,
+            border: legend.border,
+        });
+        if let Some(bearing) = spoke.bearing {
+            let heading = (bearing + spokes_per_revolution - spoke.angle) % spokes_per_revolution;
+            processor.set_heading(Some(heading as f64 * degrees_per_spoke));
+        }
+
+        let alerts =
+            processor.check_spoke(&spoke.data, spoke.angle as f64 * degrees_per_spoke, time);
+        for alert in alerts {
+            if monitor.alarms.contains_key(&alert.zone_id) {
+                continue;
+            }
+            log::warn!(
+                "{}: guard zone {} alarm, return at {:.0}° {:.0} m",
+                self.key,
+                alert.zone_id,
+                alert.bearing,
+                alert.distance
+            );
+            monitor.alarms.insert(alert.zone_id, ZoneAlarm::default());
+            self.send(GuardZoneEvent {
+                zone_id: alert.zone_id,
+                state: ZoneAlertState::Alarm,
+                timestamp: time,
+                bearing: Some(alert.bearing),
+                distance: Some(alert.distance),
+            });
+        }
+
+        // The processor reports an intrusion until the zone has been clear
+        // for a few spokes.
+        let Monitor {
+            processor, alarms, ..
+        } = &mut *monitor;
+        for (zone_id, alarm) in alarms.iter_mut() {
+            if processor.get_alert_state(*zone_id) == ZoneAlertState::Alarm {
+                alarm.hit = true;
+            }
+        }
+
+        if monitor.prev_angle.is_some_and(|prev| spoke.angle < prev) {
+            self.end_revolution(&mut monitor, time);
+        }
+        monitor.prev_angle = Some(spoke.angle);
+    }
+
+    fn end_revolution(&self, monitor: &mut Monitor, time: u64) {
+        let mut cleared = Vec::new();
+        for (zone_id, alarm) in monitor.alarms.iter_mut() {
+            if alarm.hit {
+                alarm.clear_revolutions = 0;
+            } else {
+                alarm.clear_revolutions += 1;
+                if alarm.clear_revolutions >= CLEAR_REVOLUTIONS {
+                    cleared.push(*zone_id);
+                }
+            }
+            alarm.hit = false;
+        }
+        for zone_id in cleared {
+            self.clear_alarm(monitor, zone_id, time);
+        }
+    }
+
+    fn clear_alarm(&self, monitor: &mut Monitor, zone_id: u32, time: u64) {
+        if monitor.alarms.remove(&zone_id).is_some() {
+            log::info!("{}: guard zone {} clear", self.key, zone_id);
+            self.send(GuardZoneEvent {
+                zone_id,
+                state: ZoneAlertState::Clear,
+                timestamp: time,
+                bearing: None,
+                distance: None,
+            });
+        }
+    }
+
+    fn send(&self, event: GuardZoneEvent) {
+        // No receivers just means that no client is connected
+        let _ = self.event_tx.send(event);
+    }
+}
+
+impl Monitor {
+    fn status(&self, zone: &GuardZone) -> GuardZoneStatus {
+        GuardZoneStatus {
+            zone: zone.clone(),
+            state: if self.alarms.contains_key(&zone.id) {
+                ZoneAlertState::Alarm
+            } else {
+                ZoneAlertState::Clear
+            },
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPOKES: u32 = 360;
+
+    fn spoke(angle: u32, echo: bool) -> Spoke {
+        let mut spoke = Spoke::new();
+        spoke.angle = angle;
+        spoke.range = 1000;
+        spoke.time = Some(angle as u64);
+        spoke.data = vec![0; 100];
+        if echo {
+            spoke.data[50] = 200;
+        }
+        spoke
+    }
+
+    fn legend() -> Legend {
+        Legend {
+            pixels: Vec::new(),
+            border: 0,
+            doppler_approaching: 0,
+            doppler_receding: 0,
+            history_start: 0,
+            strong_return: 0,
+        }
+    }
+
+    // One revolution, with an echo at 500 m on spokes 10 and 11
+    fn revolution(zones: &GuardZones, echo: bool) {
+        let legend = legend();
+        for angle in 0..SPOKES {
+            zones.check_spoke(
+                &spoke(angle, echo && (10..12).contains(&angle)),
+                SPOKES,
+                &legend,
+            );
+        }
+    }
+
+    #[test]
+    fn test_alarm_hysteresis() {
+        let zones = GuardZones::new("test", 1);
+        let mut rx = zones.subscribe();
+        zones
+            .set(GuardZone::new_arc(1, 0., 90., 400., 600.), Some(1000))
+            .unwrap();
+
+        revolution(&zones, true);
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.zone_id, 1);
+        assert_eq!(event.state, ZoneAlertState::Alarm);
+        assert_eq!(event.distance, Some(500.));
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "guardZone");
+        assert_eq!(json["zoneId"], 1);
+
+        // The processor clears within the revolution, no new alarm follows
+        for _ in 0..3 {
+            revolution(&zones, true);
+        }
+        assert!(rx.try_recv().is_err());
+        assert_eq!(zones.get(1).unwrap().state, ZoneAlertState::Alarm);
+
+        for _ in 0..CLEAR_REVOLUTIONS {
+            assert_eq!(zones.get(1).unwrap().state, ZoneAlertState::Alarm);
+            revolution(&zones, false);
+        }
+        // The last revolution only ends when the next one starts
+        zones.check_spoke(&spoke(0, false), SPOKES, &legend());
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.state, ZoneAlertState::Clear);
+        assert_eq!(zones.get(1).unwrap().state, ZoneAlertState::Clear);
+    }
+
+    #[test]
+    fn test_zone_validation() {
+        let zones = GuardZones::new("test", 1);
+        assert!(zones
+            .set(GuardZone::new_ring(RESERVED_ZONE_ID_START, 0., 100.), None)
+            .is_err());
+        assert!(zones
+            .set(GuardZone::new_ring(1, 2000., 3000.), Some(1000))
+            .is_err());
+        assert!(zones
+            .set(GuardZone::new_ring(2, 500., 3000.), Some(1000))
+            .is_ok());
+        assert_eq!(zones.zones().len(), 1);
+        assert_eq!(
+            zones.monitor.lock().unwrap().processor.max_range(),
+            Some(1000.)
+        );
+        assert!(zones.remove(2));
+        assert!(!zones.remove(2));
+    }
+
+    #[test]
+    fn test_subsample_reaches_processor() {
+        let zones = GuardZones::new("test", 4);
+        assert_eq!(zones.monitor.lock().unwrap().processor.subsample(), 4);
+    }
+}
@@ -9,12 +9,21 @@ use std::time::{Duration, Instant};
 use std::{
     collections::HashMap,
     fmt::{self, Display, Write},
-    net::{Ipv4Addr, SocketAddrV4},
-    sync::{Arc, RwLock},
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex, RwLock},
 };
 use thiserror::Error;
 use tokio_graceful_shutdown::SubsystemHandle;
 
+use mayara_core::capabilities::{is_control_allowed, AntennaType, DisplayCenterOffset, ModuleInfo};
+use mayara_core::engine::RadarEventKind;
+use mayara_core::spoke::{
+    DisplayRotation, RotationAlarm, RotationMonitor, SpokeConvention, SpokeQuantizer, StcCurve,
+    SweepFade,
+};
+
+pub(crate) mod arpa;
+pub mod guard_zone;
 pub(crate) mod range;
 pub(crate) mod spoke;
 pub(crate) mod target;
@@ -22,11 +31,14 @@ pub(crate) mod trail;
 
 use crate::config::Persistence;
 use crate::locator::LocatorId;
-use crate::protos::RadarMessage::RadarMessage;
+use crate::protos::RadarMessage::{radar_message::Spoke, RadarMessage};
 use crate::settings::{ControlError, ControlUpdate, ControlValue, SharedControls};
 use crate::{Brand, Session, TargetMode};
+use guard_zone::GuardZones;
 use range::{RangeDetection, Ranges};
 
+pub use arpa::{sweep_request, target_request, TargetRequest};
+
 pub(crate) const NAUTICAL_MILE: i32 = 1852; // 1 nautical mile in meters
 pub(crate) const NAUTICAL_MILE_F64: f64 = 1852.; // 1 nautical mile in meters
 
@@ -35,6 +47,12 @@ pub(crate) type SpokeBearing = u16;
 
 pub(crate) const BYTE_LOOKUP_LENGTH: usize = (u8::MAX as usize) + 1;
 
+// Unprocessed spokes buffered for slow consumers, about half a rotation
+const SPOKE_CHANNEL_SIZE: usize = 1024;
+
+/// How often report loops call [`RadarInfo::check_rotation`]
+pub(crate) const ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Error, Debug)]
 pub enum RadarError {
     #[error("I/O operation failed")]
@@ -136,6 +154,19 @@ pub struct Legend {
     pub strong_return: u8,
 }
 
+impl Legend {
+    /// RGBA color of each pixel value
+    pub fn colors(&self) -> Vec<[u8; 4]> {
+        self.pixels
+            .iter()
+            .map(|lookup| {
+                let color = &lookup.color;
+                [color.r, color.g, color.b, color.a]
+            })
+            .collect()
+    }
+}
+
 impl Serialize for Legend {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -184,20 +215,28 @@ pub struct RadarInfo {
     pub(crate) pixel_values: u8,          // How many values per pixel, 0..220 or so
     pub spokes_per_revolution: u16,       // How many spokes per rotation
     pub max_spoke_len: u16,               // Fixed for some radars, variable for others
-    pub(crate) addr: SocketAddrV4,        // The IP address of the radar
-    pub(crate) nic_addr: Ipv4Addr,        // IPv4 address of NIC via which radar can be reached
-    pub(crate) spoke_data_addr: SocketAddrV4, // Where the radar will send data spokes
-    pub(crate) report_addr: SocketAddrV4, // Where the radar will send reports
-    pub(crate) send_command_addr: SocketAddrV4, // Where displays will send commands to the radar
+    pub(crate) addr: SocketAddr,          // The IP address of the radar
+    pub(crate) nic_addr: IpAddr,          // IPv4 or IPv6 address of NIC via which radar can be reached
+    pub(crate) spoke_data_addr: SocketAddr, // Where the radar will send data spokes
+    pub(crate) report_addr: SocketAddr,   // Where the radar will send reports
+    pub(crate) send_command_addr: SocketAddr, // Where displays will send commands to the radar
     pub legend: Legend,                   // What pixel values mean
     pub controls: SharedControls,         // Which controls there are, not complete in beginning
     pub ranges: Ranges,                   // Ranges for this radar, empty in beginning
     pub(crate) range_detection: Option<RangeDetection>, // if Some, then ranges are flexible, detected and persisted
     pub(crate) doppler: bool,                           // Does it support Doppler?
+    pub modules: Vec<ModuleInfo>,                       // Hardware module inventory, if reported
+    pub antenna_type: Option<AntennaType>,              // Radome or open array, if the model tells
+    display_center_offset: Arc<RwLock<Option<DisplayCenterOffset>>>, // Own ship off center, display only
+    pub control_allowlist: Vec<String>,                 // Controls exposed to clients, empty = all
     rotation_timestamp: Instant,
+    rotation_monitor: Arc<Mutex<RotationMonitor>>, // Detects a stalled antenna
+    sweep_fade: Arc<Mutex<SweepFade>>,             // Afterglow of displayed spokes
+    pub guard_zones: GuardZones,                   // Zones and their alarms, persisted
 
     // Channels
     pub message_tx: tokio::sync::broadcast::Sender<Vec<u8>>, // Serialized RadarMessage
+    pub(crate) spoke_tx: tokio::sync::broadcast::Sender<Spoke>, // Unprocessed spokes, for ARPA
 }
 
 impl RadarInfo {
@@ -210,36 +249,40 @@ impl RadarInfo {
         pixel_values: u8, // How many values per pixel, 0..220 or so
         spokes_per_revolution: usize,
         max_spoke_len: usize,
-        addr: SocketAddrV4,
-        nic_addr: Ipv4Addr,
-        spoke_data_addr: SocketAddrV4,
-        report_addr: SocketAddrV4,
-        send_command_addr: SocketAddrV4,
+        addr: SocketAddr,
+        nic_addr: IpAddr,
+        spoke_data_addr: SocketAddr,
+        report_addr: SocketAddr,
+        send_command_addr: SocketAddr,
         controls: SharedControls,
         doppler: bool,
     ) -> Self {
         let (message_tx, _message_rx) = tokio::sync::broadcast::channel(32);
+        let (spoke_tx, _spoke_rx) = tokio::sync::broadcast::channel(SPOKE_CHANNEL_SIZE);
 
         let legend = default_legend(session.clone(), false, pixel_values);
 
-        let info = RadarInfo {
-            session,
-            key: {
-                let mut key = brand.to_string();
+        let key = {
+            let mut key = brand.to_string();
 
-                if let Some(serial_no) = serial_no {
-                    key.push_str("-");
-                    key.push_str(serial_no);
-                } else {
-                    write!(key, "-{}", &addr).unwrap();
-                }
+            if let Some(serial_no) = serial_no {
+                key.push_str("-");
+                key.push_str(serial_no);
+            } else {
+                write!(key, "-{}", &addr).unwrap();
+            }
 
-                if let Some(which) = which {
-                    key.push_str("-");
-                    key.push_str(which);
-                }
-                key
-            },
+            if let Some(which) = which {
+                key.push_str("-");
+                key.push_str(which);
+            }
+            key
+        };
+        let guard_zones = GuardZones::new(&key, session.read().unwrap().args.guard_zone_subsample);
+
+        let info = RadarInfo {
+            session,
+            key,
             id: usize::MAX,
             locator_id,
             brand,
@@ -255,11 +298,19 @@ impl RadarInfo {
             send_command_addr,
             legend: legend,
             message_tx,
+            spoke_tx,
             ranges: Ranges::empty(),
             range_detection: None,
             controls,
             doppler,
+            modules: Vec::new(),
+            antenna_type: None,
+            display_center_offset: Arc::new(RwLock::new(None)),
+            control_allowlist: Vec::new(),
             rotation_timestamp: Instant::now() - Duration::from_secs(2),
+            rotation_monitor: Arc::new(Mutex::new(RotationMonitor::default())),
+            sweep_fade: Arc::new(Mutex::new(SweepFade::default())),
+            guard_zones,
         };
 
         log::debug!("Created RadarInfo {:?}", info);
@@ -274,6 +325,66 @@ impl RadarInfo {
         self.controls.control_update_subscribe()
     }
 
+    /// Snap a requested range to the ranges detected on this radar
+    ///
+    /// Before any ranges are detected this falls back to the model's
+    /// approximate range table, see [`mayara_core::models::snap_range`];
+    /// unchanged until the model is known.
+    pub fn snap_range(&self, brand: mayara_core::Brand, range_meters: u32) -> u32 {
+        if !self.ranges.is_empty() {
+            let requested = i32::try_from(range_meters).unwrap_or(i32::MAX);
+            let index = self.ranges.distance_to_index(requested);
+            return self.ranges.get_distance(index as usize) as u32;
+        }
+        match self.controls.model_name() {
+            Some(model) => mayara_core::models::snap_range(brand, &model, range_meters),
+            None => range_meters,
+        }
+    }
+
+    /// Own ship offset from the display center, `None` when centered
+    pub fn display_center_offset(&self) -> Option<DisplayCenterOffset> {
+        *self.display_center_offset.read().unwrap()
+    }
+
+    /// Set the display center offset for all clones of this `RadarInfo`
+    pub fn set_display_center_offset(&self, offset: Option<DisplayCenterOffset>) {
+        *self.display_center_offset.write().unwrap() = offset;
+    }
+
+    /// The model's entry in the mayara-core model database, once the model is known
+    pub fn core_model(&self, brand: mayara_core::Brand) -> Option<&'static mayara_core::models::ModelInfo> {
+        self.controls
+            .model_name()
+            .and_then(|model| mayara_core::models::get_model(brand, &model))
+    }
+
+    /// Wire value for a `scanSpeed` control value in RPM
+    ///
+    /// Returns `None` if the model does not have that speed. For models
+    /// whose speeds are not known the control holds the wire value itself.
+    pub fn scan_speed_wire(&self, brand: mayara_core::Brand, rpm: i32) -> Option<u8> {
+        match self.core_model(brand) {
+            Some(model) if !model.scan_speed_options().is_empty() => {
+                u16::try_from(rpm).ok().and_then(|rpm| model.scan_speed_wire(rpm))
+            }
+            _ => u8::try_from(rpm).ok(),
+        }
+    }
+
+    /// `scanSpeed` control value in RPM for a wire value reported by the radar
+    pub fn scan_speed_rpm(&self, brand: mayara_core::Brand, wire: u8) -> i32 {
+        self.core_model(brand)
+            .and_then(|model| model.scan_speed_rpm(wire))
+            .map(i32::from)
+            .unwrap_or(wire as i32)
+    }
+
+    /// Is `control_id` exposed to clients by the control allowlist?
+    pub fn allows_control(&self, control_id: &str) -> bool {
+        is_control_allowed(&self.control_allowlist, control_id)
+    }
+
     pub fn key(&self) -> String {
         self.key.to_owned()
     }
@@ -294,6 +405,20 @@ impl RadarInfo {
         self.pixel_values = pixel_values;
     }
 
+    /// Legend for clients: as `legend`, unless displayed spokes are
+    /// quantized to fewer bits (`--spoke-bits`)
+    pub fn display_legend(&self) -> Legend {
+        let bits = self.session.read().unwrap().args.spoke_bits;
+        match bits {
+            Some(bits) => default_legend(
+                self.session.clone(),
+                self.doppler,
+                SpokeQuantizer::new(bits).output_pixel_values(self.pixel_values),
+            ),
+            None => self.legend.clone(),
+        }
+    }
+
     pub fn set_rotation_length(&mut self, millis: u32) -> u32 {
         let diff = millis as f64;
         let rpm = format!("{:.0}", (600_000. / diff));
@@ -343,7 +468,69 @@ impl RadarInfo {
         Ok(())
     }
 
-    pub(crate) fn broadcast_radar_message(&self, message: RadarMessage) {
+    pub(crate) fn broadcast_radar_message(&self, mut message: RadarMessage) {
+        self.monitor_rotation(&message);
+
+        // STC is display-only: the data receivers have already fed the raw
+        // spokes to ARPA and trails before the message gets here.
+        let (stc, fade, bits, rotation, convention) = {
+            let session = self.session.read().unwrap();
+            let args = &session.args;
+            let convention = SpokeConvention {
+                zero_bearing: args.spoke_zero_bearing.unwrap_or(0.0),
+                clockwise: !args.spokes_counter_clockwise,
+            };
+            (
+                args.stc,
+                args.sweep_fade,
+                args.spoke_bits,
+                args.display_rotation,
+                convention,
+            )
+        };
+        let max_value = self.pixel_values.saturating_sub(1);
+        if let Some(slope) = stc {
+            let stc = StcCurve::new(slope);
+            for spoke in message.spokes.iter_mut() {
+                stc.apply(&mut spoke.data, max_value);
+            }
+        }
+        if let Some(decay) = fade {
+            let mut sweep_fade = self.sweep_fade.lock().unwrap();
+            if sweep_fade.decay() != decay {
+                sweep_fade.set_decay(decay);
+            }
+            for spoke in message.spokes.iter_mut() {
+                sweep_fade.set_range(spoke.range);
+                sweep_fade.apply(spoke.angle as usize, &mut spoke.data, max_value);
+            }
+        }
+        if let Some(bits) = bits {
+            let quantizer = SpokeQuantizer::new(bits);
+            for spoke in message.spokes.iter_mut() {
+                quantizer.apply(&mut spoke.data, self.pixel_values);
+            }
+        }
+        if let Some(degrees) = rotation {
+            let rotation = DisplayRotation::new(degrees);
+            let spokes = self.spokes_per_revolution as u32;
+            for spoke in message.spokes.iter_mut() {
+                spoke.angle = rotation.rotate(spoke.angle, spokes);
+                if let Some(bearing) = spoke.bearing {
+                    spoke.bearing = Some(rotation.rotate(bearing, spokes));
+                }
+            }
+        }
+        if convention.is_enabled() {
+            let spokes = self.spokes_per_revolution as u32;
+            for spoke in message.spokes.iter_mut() {
+                spoke.angle = convention.map(spoke.angle, spokes);
+                if let Some(bearing) = spoke.bearing {
+                    spoke.bearing = Some(convention.map(bearing, spokes));
+                }
+            }
+        }
+
         let mut bytes = Vec::new();
         message
             .write_to_vec(&mut bytes)
@@ -362,6 +549,43 @@ impl RadarInfo {
         }
     }
 
+    /// Raise an alarm when spokes keep arriving but their angle doesn't
+    /// advance, i.e. the radar transmits with a stuck antenna.
+    fn monitor_rotation(&self, message: &RadarMessage) {
+        let now = rotation_clock_ms();
+        let mut monitor = self.rotation_monitor.lock().unwrap();
+        let alarm = monitor.alarm();
+        for spoke in message.spokes.iter() {
+            if let Some(RotationAlarm::AntennaStalled) = monitor.on_spoke(spoke.angle, now) {
+                log::warn!(
+                    "{}: antennaStalled: spoke angle stuck at {} while transmitting",
+                    self.key,
+                    spoke.angle
+                );
+            }
+        }
+        match (alarm, monitor.alarm()) {
+            (Some(RotationAlarm::AntennaStalled), None) => {
+                log::info!("{}: antenna rotating again", self.key)
+            }
+            (Some(RotationAlarm::NoData), None) => log::info!("{}: spokes arriving again", self.key),
+            _ => {}
+        }
+    }
+
+    /// Raise an alarm when the radar transmits but no spokes arrive at all.
+    ///
+    /// Call this every [`ROTATION_CHECK_INTERVAL`] from the radar's report
+    /// loop, the spoke stream can't notice its own absence.
+    pub(crate) fn check_rotation(&self) {
+        let transmitting = self.controls.get("power").and_then(|c| c.value)
+            == Some(Status::Transmit as i32 as f32);
+        let mut monitor = self.rotation_monitor.lock().unwrap();
+        if let Some(RotationAlarm::NoData) = monitor.check(rotation_clock_ms(), transmitting) {
+            log::warn!("{}: noData: transmitting but no spokes received", self.key);
+        }
+    }
+
     ///
     ///  forward_output is activated in all starts of radars when cli args.output
     ///  is true:
@@ -438,6 +662,7 @@ impl SharedRadars {
             radars: Arc::new(RwLock::new(Radars {
                 info: HashMap::new(),
                 persistent_data: Persistence::new(),
+                moved: Vec::new(),
             })),
         }
     }
@@ -465,6 +690,16 @@ impl SharedRadars {
 
         let is_new = radars.info.get(&key).is_none();
         if is_new {
+            // A radar that moved keeps its ID and settings, also when its
+            // key holds the address
+            let ip = new_info.addr.ip();
+            if let Some(i) = radars.moved.iter().position(|(moved_ip, old)| {
+                *moved_ip == ip && old.brand == new_info.brand && old.which == new_info.which
+            }) {
+                let (_, old) = radars.moved.remove(i);
+                radars.persistent_data.rekey(&old.key, &key);
+            }
+
             // Set any previously detected model and ranges
             radars
                 .persistent_data
@@ -481,6 +716,7 @@ impl SharedRadars {
                 new_info.id,
                 new_info.controls.user_name().unwrap_or_else(|| new_info.key.clone())
             );
+            new_info.controls.record_event(RadarEventKind::Discovered);
             radars.info.insert(key, new_info.clone());
             Some(new_info)
         } else {
@@ -553,12 +789,57 @@ impl SharedRadars {
         radars.info.get(key).cloned()
     }
 
+    /// Radars confirmed in an earlier run, for a warm start of the locator
+    pub(crate) fn discovered_radars(&self) -> Vec<mayara_core::radar::RadarDiscovery> {
+        let radars = self.radars.read().unwrap();
+        radars.persistent_data.config.discovered_radars.clone()
+    }
+
+    /// Save the radars confirmed by the locator, for a warm start next time
+    pub(crate) fn store_discovered_radars(&self, discoveries: Vec<mayara_core::radar::RadarDiscovery>) {
+        let mut radars = self.radars.write().unwrap();
+        radars.persistent_data.store_discovered_radars(discoveries);
+    }
+
     pub fn remove(&self, key: &str) {
         let mut radars = self.radars.write().unwrap();
 
         radars.info.remove(key);
     }
 
+    /// A radar now answers at the address in `discovery`: forget it at
+    /// `previous_address` until [`Self::located`] finds it at the new one
+    pub(crate) fn readdress(
+        &self,
+        previous_address: &str,
+        discovery: &mayara_core::radar::RadarDiscovery,
+    ) {
+        use mayara_core::radar::ParsedAddress;
+
+        let (Ok(previous), Ok(current)) = (
+            ParsedAddress::parse(previous_address),
+            ParsedAddress::parse(&discovery.address),
+        ) else {
+            return;
+        };
+        let brand = crate::core_locator::core_brand_to_server_brand(discovery.brand);
+
+        let mut radars = self.radars.write().unwrap();
+        let keys: Vec<String> = radars
+            .info
+            .values()
+            .filter(|info| info.brand == brand && info.addr.ip() == previous.ip)
+            .map(|info| info.key.clone())
+            .collect();
+        for key in keys {
+            if let Some(info) = radars.info.remove(&key) {
+                log::info!("{}: moved to {}", key, current.ip);
+                radars.persistent_data.store(&info);
+                radars.moved.push((current.ip, info));
+            }
+        }
+    }
+
     ///
     /// Update radar info in radars container
     ///
@@ -583,7 +864,7 @@ impl SharedRadars {
 
     // deprecated_marked_for_delete: Only used by legacy locator (reply_with_interface_state)
     #[allow(dead_code)]
-    pub(crate) fn is_active_radar(&self, brand: &Brand, ip: &Ipv4Addr) -> bool {
+    pub(crate) fn is_active_radar(&self, brand: &Brand, ip: &IpAddr) -> bool {
         let radars = self.radars.read().unwrap();
         for (_, info) in radars.info.iter() {
             log::trace!(
@@ -622,11 +903,13 @@ impl SharedRadars {
     /// Update radar info from a core RadarDiscovery (e.g., when model info arrives).
     ///
     /// This finds the existing radar by address and updates its model/serial.
-    pub fn update_from_discovery(&self, discovery: &mayara_core::radar::RadarDiscovery) {
+    ///
+    /// Returns false if no radar matches the discovery.
+    pub fn update_from_discovery(&self, discovery: &mayara_core::radar::RadarDiscovery) -> bool {
         use mayara_core::Brand as CoreBrand;
 
-        // Extract IP from discovery address (which may be "ip:port" or just "ip")
-        let discovery_ip = discovery.address.split(':').next().unwrap_or(&discovery.address);
+        // Extract IP from discovery address (which may be "ip:port", "[ipv6]:port" or just "ip")
+        let discovery_ip = mayara_core::radar::ParsedAddress::host(&discovery.address);
 
         // Find radar by matching address
         let matching_key = {
@@ -679,11 +962,13 @@ impl SharedRadars {
                     }
                 }
             }
+            true
         } else {
-            log::warn!(
+            log::debug!(
                 "update_from_discovery: No radar found for address {}",
                 discovery_ip
             );
+            false
         }
     }
 
@@ -714,6 +999,14 @@ impl SharedRadars {
     }
 }
 
+/// Host clock for the [`RotationMonitor`]
+fn rotation_clock_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 /// Convert model name string to RadarModel enum (for Furuno)
 #[cfg(feature = "furuno")]
 fn model_name_to_radar_model(name: &str) -> crate::brand::furuno::RadarModel {
@@ -739,6 +1032,7 @@ fn model_name_to_radar_model(name: &str) -> crate::brand::furuno::RadarModel {
 struct Radars {
     pub info: HashMap<String, RadarInfo>,
     pub persistent_data: Persistence,
+    pub moved: Vec<(IpAddr, RadarInfo)>, // Radars moved to the IP, not yet found there
 }
 
 pub struct Statistics {
@@ -982,4 +1276,72 @@ mod tests {
         let json = serde_json::to_string_pretty(&legend).unwrap();
         println!("{}", json);
     }
+
+    #[cfg(feature = "navico")]
+    fn halo_info() -> super::RadarInfo {
+        use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+        let session = crate::Session::new_fake();
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+        super::RadarInfo::new(
+            session.clone(),
+            crate::locator::LocatorId::Gen3Plus,
+            crate::Brand::Navico,
+            Some("1234"),
+            None,
+            16,
+            2048,
+            1024,
+            addr,
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            addr,
+            addr,
+            addr,
+            crate::brand::navico::settings::new(session, Some("HALO")),
+            false,
+        )
+    }
+
+    #[cfg(feature = "navico")]
+    #[test]
+    fn scan_speed_rpm_wire_conversion() {
+        let info = halo_info();
+
+        assert_eq!(info.scan_speed_wire(mayara_core::Brand::Navico, 36), Some(2));
+        assert_eq!(info.scan_speed_wire(mayara_core::Brand::Navico, 25), None);
+        assert_eq!(info.scan_speed_rpm(mayara_core::Brand::Navico, 3), 48);
+    }
+
+    #[cfg(feature = "navico")]
+    #[test]
+    fn display_center_offset_survives_receiver_update() {
+        let receiver_info = halo_info();
+        // The web handler works on its own clone from `SharedRadars`
+        let web_info = receiver_info.clone();
+        let offset = mayara_core::capabilities::DisplayCenterOffset { x: 0.0, y: -0.5 };
+        web_info.set_display_center_offset(Some(offset));
+
+        // The receiver's copy, which it writes back with `SharedRadars::update`,
+        // sees the new offset
+        assert_eq!(receiver_info.display_center_offset(), Some(offset));
+        assert_eq!(receiver_info.clone().display_center_offset(), Some(offset));
+
+        receiver_info.set_display_center_offset(None);
+        assert_eq!(web_info.display_center_offset(), None);
+    }
+
+    #[cfg(feature = "navico")]
+    #[test]
+    fn snap_range_prefers_detected_ranges() {
+        let mut info = halo_info();
+        let fallback = info.snap_range(mayara_core::Brand::Navico, 1852);
+
+        info.ranges = super::Ranges::new_by_distance(&[926, 1852, 3704]);
+        assert_eq!(info.snap_range(mayara_core::Brand::Navico, 1852), 1852);
+        assert_eq!(info.snap_range(mayara_core::Brand::Navico, 1000), 1852);
+        assert_eq!(info.snap_range(mayara_core::Brand::Navico, 10000), 3704);
+
+        info.ranges = super::Ranges::empty();
+        assert_eq!(info.snap_range(mayara_core::Brand::Navico, 1852), fallback);
+    }
 }
@@ -265,6 +265,9 @@ impl Display for Ranges {
     }
 }
 
+/// How often an unanswered range command is resent before moving on
+pub const RANGE_DETECTION_RESENDS: u8 = 2;
+
 pub enum RangeDetectionResult {
     NoRange,
     Complete(Ranges, i32),
@@ -280,6 +283,7 @@ pub(crate) struct RangeDetection {
     ranges: Ranges,
     ranges_to_try: Ranges,
     index_to_try: usize,
+    awaiting: Option<(i32, u8)>, // Range sent but not yet answered, and how often it was resent
 }
 
 impl RangeDetection {
@@ -306,6 +310,7 @@ impl RangeDetection {
             ranges: Ranges::empty(),
             ranges_to_try: Ranges::new(ranges_to_try),
             index_to_try: 0,
+            awaiting: None,
         }
     }
 
@@ -356,13 +361,79 @@ impl RangeDetection {
 
             log::trace!("{}: ranges to try: {}", self.key, self.ranges_to_try);
 
-            if let Some(range) = self.advance_to_next_index() {
-                return RangeDetectionResult::NextRange(range.distance());
-            } else {
-                self.ranges = Ranges::new(self.ranges.all.clone()); // Sort by distance
-                log::info!("{}: Found supported ranges {}", self.key, self.ranges);
-                return RangeDetectionResult::Complete(self.ranges.clone(), self.saved_range);
+            self.try_next_range()
+        }
+    }
+
+    ///
+    /// No range report arrived in time for the last range sent.
+    /// The command may have been lost, so resend it a few times before
+    /// giving up on that range and moving on to the next one.
+    ///
+    pub fn timed_out(&mut self) -> RangeDetectionResult {
+        match self.awaiting {
+            None => RangeDetectionResult::NoRange,
+            Some((range, resends)) if resends < RANGE_DETECTION_RESENDS => {
+                log::debug!("{}: No report for range {} m, resending", self.key, range);
+                self.awaiting = Some((range, resends + 1));
+                RangeDetectionResult::NextRange(range)
+            }
+            Some((range, _)) => {
+                log::warn!(
+                    "{}: Range {} m not acknowledged after {} resends, skipping it",
+                    self.key,
+                    range,
+                    RANGE_DETECTION_RESENDS
+                );
+                self.try_next_range()
             }
         }
     }
+
+    fn try_next_range(&mut self) -> RangeDetectionResult {
+        if let Some(range) = self.advance_to_next_index() {
+            let range = range.distance();
+            self.awaiting = Some((range, 0));
+            RangeDetectionResult::NextRange(range)
+        } else {
+            self.awaiting = None;
+            self.ranges = Ranges::new(self.ranges.all.clone()); // Sort by distance
+            log::info!("{}: Found supported ranges {}", self.key, self.ranges);
+            RangeDetectionResult::Complete(self.ranges.clone(), self.saved_range)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_range_command_is_resent() {
+        let mut detection =
+            RangeDetection::new_for_brand("test".to_string(), Brand::Navico, 50, 2000);
+        let next_range = |result: RangeDetectionResult| match result {
+            RangeDetectionResult::NextRange(r) => r,
+            _ => panic!("expected a range to try"),
+        };
+
+        // The radar reports its current range, detection asks for another one
+        let sent = next_range(detection.found_range(50));
+
+        // The command is lost: no report, so the same range is sent again
+        for _ in 0..RANGE_DETECTION_RESENDS {
+            assert_eq!(next_range(detection.timed_out()), sent);
+        }
+
+        // The resend arrives and the radar reports the range
+        let next = next_range(detection.found_range(sent));
+        assert_ne!(next, sent);
+        assert!(detection.ranges.all.iter().any(|r| r.distance() == sent));
+
+        // A range that is never acknowledged is skipped after the resends
+        for _ in 0..RANGE_DETECTION_RESENDS {
+            assert_eq!(next_range(detection.timed_out()), next);
+        }
+        assert_ne!(next_range(detection.timed_out()), next);
+    }
 }
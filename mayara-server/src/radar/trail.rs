@@ -1,5 +1,6 @@
 use cartesian::PolarToCartesianLookup;
 use ndarray::{s, Array2};
+use tokio::sync::broadcast;
 
 mod cartesian;
 use crate::protos::RadarMessage::radar_message::Spoke;
@@ -9,6 +10,7 @@ use crate::radar::{GeoPosition, Legend, SpokeBearing, BLOB_HISTORY_COLORS};
 use crate::settings::{ControlError, ControlValue, SharedControls};
 use crate::{Session, TargetMode};
 
+use super::guard_zone::GuardZones;
 use super::target::TargetBuffer;
 use super::{RadarError, RadarInfo};
 
@@ -41,6 +43,8 @@ pub struct TrailBuffer {
     previous_range: u32,
     pixels_per_meter: f64,
     have_heading: bool,
+    spoke_tx: broadcast::Sender<Spoke>,
+    guard_zones: GuardZones,
 }
 
 impl TrailBuffer {
@@ -91,6 +95,8 @@ impl TrailBuffer {
             previous_range: 0,
             pixels_per_meter: 0.0,
             have_heading: false,
+            spoke_tx: info.spoke_tx.clone(),
+            guard_zones: info.guard_zones.clone(),
         }
     }
 
@@ -201,6 +207,12 @@ impl TrailBuffer {
     }
 
     pub fn update_trails(&mut self, spoke: &mut Spoke, legend: &Legend) {
+        // Guard zones and ARPA need the spoke before trails are drawn into it
+        self.guard_zones
+            .check_spoke(spoke, self.spokes_per_revolution as u32, legend);
+        if self.spoke_tx.receiver_count() > 0 {
+            let _ = self.spoke_tx.send(spoke.clone());
+        }
         if self.session.read().unwrap().args.targets == TargetMode::None {
             return;
         }
@@ -0,0 +1,442 @@
+//! Raw packet capture - writes the radar traffic mayara receives to PCAPNG.
+//!
+//! With `--record <dir>` every active radar gets a capture subsystem that
+//! takes every datagram mayara receives from the radar off the
+//! [`packet_bus`] - beacons on the locator's sockets as well as reports and
+//! spokes on the receivers' sockets, multicast and unicast - and writes it,
+//! wrapped in synthesized Ethernet, IPv4 and UDP headers, to
+//! `<dir>/<radar>-<time>.pcapng`. The interface block names the NIC and its
+//! address so the file can be replayed onto the right network. Files are
+//! rotated when they reach [`MAX_CAPTURE_FILE_BYTES`].
+//!
+//! TCP traffic (e.g. the Furuno command connection) is not captured.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4};
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
+
+use super::packet_bus;
+use super::replay::CapturedPacket;
+use crate::radar::{RadarError, RadarInfo};
+use crate::Session;
+
+/// Capture files are rotated when they grow beyond this size
+pub const MAX_CAPTURE_FILE_BYTES: u64 = 100 * 1024 * 1024;
+
+const BLOCK_SECTION_HEADER: u32 = 0x0A0D0D0A;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 0x00000001;
+const BLOCK_ENHANCED_PACKET: u32 = 0x00000006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+const LINKTYPE_ETHERNET: u16 = 1;
+const SNAPLEN: u32 = 65535;
+
+const OPT_END: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const OPT_IF_NAME: u16 = 2;
+const OPT_IF_IPV4ADDR: u16 = 4;
+const OPT_SHB_USERAPPL: u16 = 4;
+
+const ETHERNET_HEADER_LEN: usize = 14;
+const IPV4_HEADER_LEN: usize = 20;
+const UDP_HEADER_LEN: usize = 8;
+
+/// The network interface the capture was made on
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureNic {
+    /// Interface name, e.g. `eth0`
+    pub name: Option<String>,
+    pub addr: Ipv4Addr,
+    pub netmask: Option<Ipv4Addr>,
+}
+
+impl CaptureNic {
+    /// Look up the name and netmask of the interface with address `addr`
+    pub fn for_addr(addr: Ipv4Addr) -> Self {
+        use network_interface::{NetworkInterface, NetworkInterfaceConfig};
+
+        let mut nic = CaptureNic {
+            name: None,
+            addr,
+            netmask: None,
+        };
+        for itf in NetworkInterface::show().unwrap_or_default() {
+            for a in &itf.addr {
+                if a.ip() == IpAddr::V4(addr) {
+                    nic.name = Some(itf.name.clone());
+                    if let Some(IpAddr::V4(netmask)) = a.netmask() {
+                        nic.netmask = Some(netmask);
+                    }
+                }
+            }
+        }
+        nic
+    }
+}
+
+/// Writes UDP packets as Ethernet frames to a PCAPNG stream
+pub struct PcapngWriter<W: Write> {
+    writer: W,
+    bytes_written: u64,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// Start a capture: writes the section header and one interface block
+    pub fn new(writer: W, nic: &CaptureNic, comment: &str) -> io::Result<Self> {
+        let mut pcap = PcapngWriter {
+            writer,
+            bytes_written: 0,
+        };
+
+        let mut options = Vec::new();
+        push_option(&mut options, OPT_SHB_USERAPPL, b"mayara");
+        push_option(&mut options, OPT_COMMENT, comment.as_bytes());
+        push_option(&mut options, OPT_END, &[]);
+        let mut body = Vec::new();
+        body.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes()); // major version
+        body.extend_from_slice(&0u16.to_le_bytes()); // minor version
+        body.extend_from_slice(&(-1i64).to_le_bytes()); // section length unknown
+        body.extend_from_slice(&options);
+        pcap.write_block(BLOCK_SECTION_HEADER, &body)?;
+
+        let mut options = Vec::new();
+        if let Some(name) = &nic.name {
+            push_option(&mut options, OPT_IF_NAME, name.as_bytes());
+        }
+        let mut addr = nic.addr.octets().to_vec();
+        addr.extend_from_slice(&nic.netmask.unwrap_or(Ipv4Addr::BROADCAST).octets());
+        push_option(&mut options, OPT_IF_IPV4ADDR, &addr);
+        push_option(&mut options, OPT_END, &[]);
+        let mut body = Vec::new();
+        body.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes()); // reserved
+        body.extend_from_slice(&SNAPLEN.to_le_bytes());
+        body.extend_from_slice(&options);
+        pcap.write_block(BLOCK_INTERFACE_DESCRIPTION, &body)?;
+
+        Ok(pcap)
+    }
+
+    /// Append a UDP packet received at `timestamp_us` (microseconds since epoch)
+    pub fn write_packet(
+        &mut self,
+        timestamp_us: u64,
+        src: SocketAddrV4,
+        dst: SocketAddrV4,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        let frame = udp_frame(src, dst, payload);
+
+        let mut body = Vec::with_capacity(20 + frame.len() + 3);
+        body.extend_from_slice(&0u32.to_le_bytes()); // interface id
+        body.extend_from_slice(&((timestamp_us >> 32) as u32).to_le_bytes());
+        body.extend_from_slice(&(timestamp_us as u32).to_le_bytes());
+        body.extend_from_slice(&(frame.len() as u32).to_le_bytes()); // captured
+        body.extend_from_slice(&(frame.len() as u32).to_le_bytes()); // original
+        body.extend_from_slice(&frame);
+        pad32(&mut body);
+        self.write_block(BLOCK_ENHANCED_PACKET, &body)
+    }
+
+    /// Total number of bytes written so far
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Stop writing and return the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_block(&mut self, block_type: u32, body: &[u8]) -> io::Result<()> {
+        let len = (12 + body.len()) as u32;
+        self.writer.write_all(&block_type.to_le_bytes())?;
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.writer.write_all(body)?;
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.bytes_written += len as u64;
+        Ok(())
+    }
+}
+
+fn push_option(options: &mut Vec<u8>, code: u16, value: &[u8]) {
+    options.extend_from_slice(&code.to_le_bytes());
+    options.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    options.extend_from_slice(value);
+    pad32(options);
+}
+
+fn pad32(buf: &mut Vec<u8>) {
+    while buf.len() % 4 != 0 {
+        buf.push(0);
+    }
+}
+
+/// Wrap a UDP payload in Ethernet, IPv4 and UDP headers
+fn udp_frame(src: SocketAddrV4, dst: SocketAddrV4, payload: &[u8]) -> Vec<u8> {
+    let mut frame =
+        Vec::with_capacity(ETHERNET_HEADER_LEN + IPV4_HEADER_LEN + UDP_HEADER_LEN + payload.len());
+
+    // Multicast MAC for multicast groups, locally administered MACs otherwise
+    let mac = |ip: &Ipv4Addr| {
+        let o = ip.octets();
+        if ip.is_multicast() {
+            [0x01, 0x00, 0x5e, o[1] & 0x7f, o[2], o[3]]
+        } else {
+            [0x02, 0x00, o[0], o[1], o[2], o[3]]
+        }
+    };
+    frame.extend_from_slice(&mac(dst.ip()));
+    frame.extend_from_slice(&mac(src.ip()));
+    frame.extend_from_slice(&0x0800u16.to_be_bytes()); // IPv4
+
+    let ip_len = (IPV4_HEADER_LEN + UDP_HEADER_LEN + payload.len()) as u16;
+    let mut ip = [0u8; IPV4_HEADER_LEN];
+    ip[0] = 0x45; // version 4, 5 words
+    ip[2..4].copy_from_slice(&ip_len.to_be_bytes());
+    ip[6] = 0x40; // don't fragment
+    ip[8] = 1; // TTL
+    ip[9] = 17; // UDP
+    ip[12..16].copy_from_slice(&src.ip().octets());
+    ip[16..20].copy_from_slice(&dst.ip().octets());
+    let checksum = ipv4_checksum(&ip);
+    ip[10..12].copy_from_slice(&checksum.to_be_bytes());
+    frame.extend_from_slice(&ip);
+
+    frame.extend_from_slice(&src.port().to_be_bytes());
+    frame.extend_from_slice(&dst.port().to_be_bytes());
+    frame.extend_from_slice(&((UDP_HEADER_LEN + payload.len()) as u16).to_be_bytes());
+    frame.extend_from_slice(&0u16.to_be_bytes()); // no UDP checksum
+    frame.extend_from_slice(payload);
+    frame
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header
+        .chunks(2)
+        .map(|w| u16::from_be_bytes([w[0], w[1]]) as u32)
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Start capturing the traffic of `info` if `--record` was given
+pub fn start_capture(session: &Session, info: &RadarInfo, subsys: &SubsystemHandle) {
+    let Some(dir) = session.read().unwrap().args.record.clone() else {
+        return;
+    };
+    let capture = RadarCapture::new(dir, info);
+    subsys.start(SubsystemBuilder::new(info.key() + " capture", |s| {
+        capture.run(s)
+    }));
+}
+
+/// Capture subsystem for one radar
+///
+/// Picks the radar's datagrams off the [`packet_bus`] and hands them to a
+/// blocking task that writes the files, so file I/O never stalls the runtime.
+pub struct RadarCapture {
+    key: String,
+    radar_ip: IpAddr,
+    files: CaptureFiles,
+}
+
+impl RadarCapture {
+    pub fn new(dir: PathBuf, info: &RadarInfo) -> Self {
+        RadarCapture {
+            key: info.key(),
+            radar_ip: info.addr.ip(),
+            files: CaptureFiles {
+                dir,
+                key: info.key(),
+                nic_addr: info.nic_addr,
+                writer: None,
+            },
+        }
+    }
+
+    pub async fn run(self, subsys: SubsystemHandle) -> Result<(), RadarError> {
+        let mut packets = packet_bus::subscribe_captured();
+        let (tx, rx) = mpsc::channel::<CapturedPacket>();
+        let files = self.files;
+        let writer = tokio::task::spawn_blocking(move || files.write_all(rx));
+
+        loop {
+            tokio::select! {
+                _ = subsys.on_shutdown_requested() => break,
+                r = packets.recv() => match r {
+                    Ok(packet) => {
+                        // A send error means the writer stopped, see below
+                        if IpAddr::V4(*packet.src.ip()) == self.radar_ip && tx.send(packet).is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(n)) => {
+                        log::warn!("{}: capture lost {} packets", self.key, n);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        drop(tx);
+
+        match writer.await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => {
+                log::error!("{}: capture stopped: {}", self.key, e);
+                Err(RadarError::Io(e))
+            }
+            Err(e) => Err(RadarError::Io(io::Error::other(e))),
+        }
+    }
+}
+
+/// The rotating capture files of one radar
+struct CaptureFiles {
+    dir: PathBuf,
+    key: String,
+    nic_addr: IpAddr,
+    writer: Option<PcapngWriter<BufWriter<File>>>,
+}
+
+impl CaptureFiles {
+    fn file_name(&self, time: chrono::DateTime<chrono::Utc>) -> String {
+        let key: String = self
+            .key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}-{}.pcapng", key, time.format("%Y%m%d-%H%M%S"))
+    }
+
+    fn open_file(&mut self) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(self.file_name(chrono::Utc::now()));
+        let file = BufWriter::new(File::create(&path)?);
+        self.writer = Some(PcapngWriter::new(
+            file,
+            &CaptureNic::for_addr(match self.nic_addr {
+                IpAddr::V4(nic_addr) => nic_addr,
+                IpAddr::V6(_) => Ipv4Addr::UNSPECIFIED,
+            }),
+            &self.key,
+        )?);
+        log::info!(
+            "{}: capturing radar traffic to {}",
+            self.key,
+            path.display()
+        );
+        Ok(())
+    }
+
+    fn write(&mut self, packet: &CapturedPacket) -> io::Result<()> {
+        let rotate = match &self.writer {
+            Some(writer) => writer.bytes_written() >= MAX_CAPTURE_FILE_BYTES,
+            None => true,
+        };
+        if rotate {
+            if let Some(mut writer) = self.writer.take() {
+                writer.flush()?;
+            }
+            self.open_file()?;
+        }
+        self.writer.as_mut().unwrap().write_packet(
+            packet.timestamp_us,
+            packet.src,
+            packet.dst,
+            &packet.payload,
+        )
+    }
+
+    /// Write packets until the sender is dropped; runs on a blocking thread
+    fn write_all(mut self, rx: mpsc::Receiver<CapturedPacket>) -> io::Result<()> {
+        for packet in rx {
+            self.write(&packet)?;
+        }
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_pcapng_blocks() {
+        let nic = CaptureNic {
+            name: Some("eth1".to_string()),
+            addr: Ipv4Addr::new(172, 31, 3, 1),
+            netmask: Some(Ipv4Addr::new(255, 255, 0, 0)),
+        };
+        let mut pcap = PcapngWriter::new(Vec::new(), &nic, "test").unwrap();
+        let src = SocketAddrV4::new(Ipv4Addr::new(172, 31, 3, 212), 10024);
+        let dst = SocketAddrV4::new(Ipv4Addr::new(239, 255, 0, 2), 10024);
+        pcap.write_packet(1_700_000_000_123_456, src, dst, &[1, 2, 3])
+            .unwrap();
+        let written = pcap.bytes_written();
+        let data = pcap.into_inner();
+        assert_eq!(written as usize, data.len());
+
+        // Walk the blocks: every length is a multiple of 4 and repeated at the end
+        let mut blocks = Vec::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            let len = u32_at(&data, offset + 4) as usize;
+            assert_eq!(len % 4, 0);
+            assert_eq!(u32_at(&data, offset + len - 4) as usize, len);
+            blocks.push((u32_at(&data, offset), offset));
+            offset += len;
+        }
+        let types: Vec<u32> = blocks.iter().map(|b| b.0).collect();
+        assert_eq!(
+            types,
+            [
+                BLOCK_SECTION_HEADER,
+                BLOCK_INTERFACE_DESCRIPTION,
+                BLOCK_ENHANCED_PACKET
+            ]
+        );
+
+        // Interface block names the NIC
+        let idb = &data[blocks[1].1..blocks[2].1];
+        assert!(idb.windows(4).any(|w| w == b"eth1"));
+        assert!(idb.windows(8).any(|w| w == [172, 31, 3, 1, 255, 255, 0, 0]));
+
+        // Packet: timestamp and a valid Ethernet/IPv4/UDP frame
+        let epb = &data[blocks[2].1..];
+        let ts = ((u32_at(epb, 12) as u64) << 32) | u32_at(epb, 16) as u64;
+        assert_eq!(ts, 1_700_000_000_123_456);
+        let frame_len = u32_at(epb, 20) as usize;
+        assert_eq!(frame_len, 14 + 20 + 8 + 3);
+        let frame = &epb[28..28 + frame_len];
+        assert_eq!(&frame[0..6], &[0x01, 0x00, 0x5e, 0x7f, 0x00, 0x02]);
+        assert_eq!(ipv4_checksum(&frame[14..34]), 0);
+        assert_eq!(&frame[26..30], &[172, 31, 3, 212]);
+        assert_eq!(&frame[30..34], &[239, 255, 0, 2]);
+        assert_eq!(&frame[42..], &[1, 2, 3]);
+    }
+}
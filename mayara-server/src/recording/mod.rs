@@ -4,6 +4,8 @@
 //! - Record radar data to `.mrr` files (MaYaRa Radar Recording)
 //! - Play back recordings as virtual radars
 //! - Manage recording files (list, upload, download, delete)
+//! - Capture raw radar packets to PCAPNG files (`--record`, see [`capture`])
+//! - Replay PCAP/PCAPNG captures into the locator and receivers (`--replay-file`, see [`replay`])
 //!
 //! ## File Format
 //!
@@ -27,10 +29,13 @@
 //! └──────────────────────────┘
 //! ```
 
+pub mod capture;
 pub mod file_format;
 pub mod manager;
+pub mod packet_bus;
 pub mod player;
 pub mod recorder;
+pub mod replay;
 
 pub use file_format::{MrrHeader, MrrFooter, MrrIndexEntry, MrrReader, MrrWriter};
 pub use manager::{RecordingInfo, RecordingManager, recordings_dir};
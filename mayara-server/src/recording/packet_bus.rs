@@ -0,0 +1,178 @@
+//! In-process bus for the raw datagrams mayara receives.
+//!
+//! Every datagram received on the locator's sockets (`TokioIoProvider`) and
+//! on the receivers' [`ListenSocket`](crate::network::ListenSocket)s is
+//! offered to [`capture`]: beacons, reports and spokes, multicast as well as
+//! unicast. It is only kept while a packet capture (`--record`, see
+//! [`super::capture`]) is subscribed.
+//!
+//! When a capture is replayed (`--replay-file`, see [`super::replay`]) the
+//! same sockets take their datagrams from [`replay`] through a
+//! [`ReplayListener`] instead of from the network, so nothing replayed is
+//! ever sent onto the network.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+use super::replay::CapturedPacket;
+
+/// Datagrams queued per subscriber before the oldest are dropped
+const CAPTURE_QUEUE_LEN: usize = 4096;
+
+static CAPTURED: OnceLock<broadcast::Sender<CapturedPacket>> = OnceLock::new();
+static REPLAYED: OnceLock<broadcast::Sender<Arc<CapturedPacket>>> = OnceLock::new();
+
+/// Receive every datagram mayara receives from now on
+pub fn subscribe_captured() -> broadcast::Receiver<CapturedPacket> {
+    CAPTURED
+        .get_or_init(|| broadcast::channel(CAPTURE_QUEUE_LEN).0)
+        .subscribe()
+}
+
+/// Offer a datagram received from `src` on the socket listening on `dst`
+/// to the packet capture
+///
+/// Capture files hold IPv4 frames only, IPv6 datagrams are skipped.
+pub fn capture(src: SocketAddr, dst: SocketAddr, payload: &[u8]) {
+    let Some(tx) = CAPTURED.get() else {
+        return;
+    };
+    if tx.receiver_count() == 0 {
+        return;
+    }
+    let (SocketAddr::V4(src), SocketAddr::V4(dst)) = (src, dst) else {
+        return;
+    };
+    let timestamp_us = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0);
+    let _ = tx.send(CapturedPacket {
+        timestamp_us,
+        src,
+        dst,
+        payload: payload.to_vec(),
+    });
+}
+
+/// Switch to replay: sockets created from now on receive the datagrams
+/// passed to [`replay`] instead of datagrams from the network
+pub fn start_replay() {
+    REPLAYED.get_or_init(|| broadcast::channel(CAPTURE_QUEUE_LEN).0);
+}
+
+/// Whether sockets receive replayed datagrams, see [`start_replay`]
+pub fn is_replaying() -> bool {
+    REPLAYED.get().is_some()
+}
+
+/// Deliver a replayed datagram to the sockets listening on its destination
+pub fn replay(packet: Arc<CapturedPacket>) {
+    if let Some(tx) = REPLAYED.get() {
+        let _ = tx.send(packet);
+    }
+}
+
+/// The replayed datagrams for one socket
+#[derive(Debug)]
+pub struct ReplayListener {
+    addr: SocketAddr,
+    rx: broadcast::Receiver<Arc<CapturedPacket>>,
+}
+
+impl ReplayListener {
+    /// Listen for replayed datagrams to `addr`; an unspecified IP address
+    /// takes all datagrams to its port. `None` unless replaying.
+    pub fn new(addr: SocketAddr) -> Option<Self> {
+        REPLAYED.get().map(|tx| Self::subscribe(addr, tx))
+    }
+
+    fn subscribe(addr: SocketAddr, tx: &broadcast::Sender<Arc<CapturedPacket>>) -> Self {
+        ReplayListener {
+            addr,
+            rx: tx.subscribe(),
+        }
+    }
+
+    fn accepts(&self, packet: &CapturedPacket) -> bool {
+        packet.dst.port() == self.addr.port()
+            && (self.addr.ip().is_unspecified() || self.addr.ip() == IpAddr::V4(*packet.dst.ip()))
+    }
+
+    /// The next datagram for this socket, if one was replayed
+    pub fn try_recv(&mut self) -> Option<Arc<CapturedPacket>> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(packet) if self.accepts(&packet) => return Some(packet),
+                Ok(_) | Err(TryRecvError::Lagged(_)) => {}
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Wait for the next datagram for this socket; never returns once the
+    /// replay is over, like a socket the radar stopped sending to
+    pub async fn recv(&mut self) -> Arc<CapturedPacket> {
+        loop {
+            match self.rx.recv().await {
+                Ok(packet) if self.accepts(&packet) => return packet,
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => std::future::pending().await,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_captured_packets_reach_subscribers() {
+        let src: SocketAddr = "172.31.3.212:10024".parse().unwrap();
+        let dst: SocketAddr = "239.255.0.2:10024".parse().unwrap();
+        let mut rx = subscribe_captured();
+
+        capture(src, dst, &[1, 2, 3]);
+        capture("[fe80::1]:10024".parse().unwrap(), dst, &[4]);
+
+        // Other tests may capture concurrently, pick out our packet
+        let packet = std::iter::from_fn(|| rx.try_recv().ok())
+            .find(|p| SocketAddr::V4(p.src) == src)
+            .unwrap();
+        assert_eq!(SocketAddr::V4(packet.dst), dst);
+        assert_eq!(packet.payload, [1, 2, 3]);
+        assert!(std::iter::from_fn(|| rx.try_recv().ok()).all(|p| p.payload != [4]));
+    }
+
+    #[test]
+    fn test_replayed_packets_reach_listeners_by_destination() {
+        // A bus of its own, start_replay() would switch all sockets to replay
+        let (tx, _) = broadcast::channel(16);
+        let group: SocketAddr = "239.255.0.2:10024".parse().unwrap();
+        let mut data = ReplayListener::subscribe(group, &tx);
+        let mut any = ReplayListener::subscribe("0.0.0.0:10010".parse().unwrap(), &tx);
+
+        let packet = |dst: &str, payload: &[u8]| {
+            Arc::new(CapturedPacket {
+                timestamp_us: 0,
+                src: "172.31.3.212:10010".parse().unwrap(),
+                dst: dst.parse().unwrap(),
+                payload: payload.to_vec(),
+            })
+        };
+        tx.send(packet("239.255.0.2:10024", b"spoke")).unwrap();
+        tx.send(packet("239.255.0.3:10024", b"other group"))
+            .unwrap();
+        tx.send(packet("172.31.255.255:10010", b"beacon")).unwrap();
+
+        assert_eq!(data.try_recv().unwrap().payload, b"spoke");
+        assert!(data.try_recv().is_none());
+        assert_eq!(any.try_recv().unwrap().payload, b"beacon");
+        assert!(any.try_recv().is_none());
+    }
+}
@@ -4,7 +4,7 @@ use log::{debug, error, info, warn};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
-use std::net::{Ipv4Addr, SocketAddrV4};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
@@ -262,7 +262,7 @@ pub async fn load_recording(
     }
 
     // Create fake addresses for playback radar
-    let fake_addr = SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 0);
+    let fake_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0);
 
     // Create RadarInfo for the virtual radar
     let info = RadarInfo::new(
@@ -275,7 +275,7 @@ pub async fn load_recording(
         header.spokes_per_rev as usize,
         header.max_spoke_len as usize,
         fake_addr,
-        Ipv4Addr::LOCALHOST,
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
         fake_addr,
         fake_addr,
         fake_addr,
@@ -0,0 +1,548 @@
+//! Packet replay - plays a PCAP or PCAPNG capture back into mayara.
+//!
+//! With `--replay --replay-file <FILE>` the UDP packets in the capture are
+//! delivered, with their original timing, optionally faster or slower
+//! (`--replay-speed`) and looped (`--replay-loop`), over the in-process
+//! [`packet_bus`] to the sockets listening on their original (multicast or
+//! broadcast) destinations. Beacons reach the locator and reports and spokes
+//! reach the receivers exactly as from a live radar of any brand, at the
+//! radar's original address. Nothing is sent onto the network.
+//!
+//! Captures may be gzipped and may hold fragmented IPv4 datagrams.
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::net::{Ipv4Addr, SocketAddrV4};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::{sleep_until, Instant};
+use tokio_graceful_shutdown::SubsystemHandle;
+
+use super::packet_bus;
+use crate::radar::RadarError;
+
+/// Slowest supported replay speed
+pub const MIN_REPLAY_SPEED: f64 = 0.5;
+/// Fastest supported replay speed
+pub const MAX_REPLAY_SPEED: f64 = 10.0;
+
+const PCAP_MAGIC_US: u32 = 0xa1b2c3d4;
+const PCAP_MAGIC_NS: u32 = 0xa1b23c4d;
+const PCAPNG_SECTION_HEADER: u32 = 0x0A0D0D0A;
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 0x00000001;
+const PCAPNG_SIMPLE_PACKET: u32 = 0x00000003;
+const PCAPNG_ENHANCED_PACKET: u32 = 0x00000006;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+const OPT_IF_TSRESOL: u16 = 9;
+
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+
+/// A UDP datagram from a capture
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedPacket {
+    /// Capture time in microseconds since the epoch
+    pub timestamp_us: u64,
+    pub src: SocketAddrV4,
+    pub dst: SocketAddrV4,
+    pub payload: Vec<u8>,
+}
+
+/// Read all UDP/IPv4 packets from a PCAP or PCAPNG file, gzipped or not
+pub fn read_capture_file(path: &Path) -> io::Result<Vec<CapturedPacket>> {
+    let mut data = std::fs::read(path)?;
+    if data.starts_with(&[0x1f, 0x8b]) {
+        let mut unzipped = Vec::new();
+        flate2::read::GzDecoder::new(data.as_slice()).read_to_end(&mut unzipped)?;
+        data = unzipped;
+    }
+    read_capture(&data)
+}
+
+/// Read all UDP/IPv4 packets from a PCAP or PCAPNG capture in memory
+pub fn read_capture(data: &[u8]) -> io::Result<Vec<CapturedPacket>> {
+    let mut reader = FrameReader::new(data)?;
+    let mut fragments = Fragments::default();
+    let mut packets = Vec::new();
+    while let Some((timestamp_us, link_type, frame)) = reader.next_frame()? {
+        if let Some(ip) = ip_payload(link_type, frame) {
+            if let Some(packet) = fragments.udp_packet(timestamp_us, ip) {
+                packets.push(packet);
+            }
+        }
+    }
+    Ok(packets)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    link_type: u32,
+    /// Timestamp units per second
+    units_per_sec: u64,
+}
+
+enum Format {
+    Pcap(Interface),
+    Pcapng(Vec<Interface>),
+}
+
+/// Walks the link-layer frames of a capture
+struct FrameReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    big_endian: bool,
+    format: Format,
+}
+
+impl<'a> FrameReader<'a> {
+    fn new(data: &'a [u8]) -> io::Result<Self> {
+        if data.len() < 24 {
+            return Err(invalid("capture too short"));
+        }
+        let magic_le = u32::from_le_bytes(data[0..4].try_into().unwrap());
+        let magic_be = u32::from_be_bytes(data[0..4].try_into().unwrap());
+        if magic_le == PCAPNG_SECTION_HEADER {
+            let big_endian =
+                u32::from_be_bytes(data[8..12].try_into().unwrap()) == PCAPNG_BYTE_ORDER_MAGIC;
+            return Ok(FrameReader {
+                data,
+                offset: 0,
+                big_endian,
+                format: Format::Pcapng(Vec::new()),
+            });
+        }
+        let (big_endian, magic) = match (magic_le, magic_be) {
+            (PCAP_MAGIC_US | PCAP_MAGIC_NS, _) => (false, magic_le),
+            (_, PCAP_MAGIC_US | PCAP_MAGIC_NS) => (true, magic_be),
+            _ => return Err(invalid("not a PCAP or PCAPNG file")),
+        };
+        let link_type = data[20..24].try_into().unwrap();
+        let link_type = if big_endian {
+            u32::from_be_bytes(link_type)
+        } else {
+            u32::from_le_bytes(link_type)
+        };
+        Ok(FrameReader {
+            data,
+            offset: 24,
+            big_endian,
+            format: Format::Pcap(Interface {
+                // The upper bits may hold FCS information
+                link_type: link_type & 0x0fff_ffff,
+                units_per_sec: if magic == PCAP_MAGIC_NS {
+                    1_000_000_000
+                } else {
+                    1_000_000
+                },
+            }),
+        })
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        let b = self.data[offset..offset + 2].try_into().unwrap();
+        if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        }
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        let b = self.data[offset..offset + 4].try_into().unwrap();
+        if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        }
+    }
+
+    fn timestamp_us(ticks: u64, interface: &Interface) -> u64 {
+        (ticks as u128 * 1_000_000 / interface.units_per_sec.max(1) as u128) as u64
+    }
+
+    /// The next frame as (timestamp in µs, link type, frame bytes)
+    fn next_frame(&mut self) -> io::Result<Option<(u64, u32, &'a [u8])>> {
+        match &self.format {
+            Format::Pcap(interface) => {
+                let interface = *interface;
+                if self.offset + 16 > self.data.len() {
+                    return Ok(None);
+                }
+                let seconds = self.u32_at(self.offset) as u64;
+                let fraction = self.u32_at(self.offset + 4) as u64;
+                let len = self.u32_at(self.offset + 8) as usize;
+                let start = self.offset + 16;
+                if start + len > self.data.len() {
+                    return Ok(None); // Truncated last packet
+                }
+                self.offset = start + len;
+                let ticks = seconds * interface.units_per_sec + fraction;
+                Ok(Some((
+                    Self::timestamp_us(ticks, &interface),
+                    interface.link_type,
+                    &self.data[start..start + len],
+                )))
+            }
+            Format::Pcapng(_) => self.next_pcapng_frame(),
+        }
+    }
+
+    fn next_pcapng_frame(&mut self) -> io::Result<Option<(u64, u32, &'a [u8])>> {
+        loop {
+            if self.offset + 12 > self.data.len() {
+                return Ok(None);
+            }
+            let block_type = self.u32_at(self.offset);
+            if block_type == PCAPNG_SECTION_HEADER {
+                // A new section may switch byte order and restarts interfaces
+                self.big_endian = u32::from_be_bytes(
+                    self.data[self.offset + 8..self.offset + 12]
+                        .try_into()
+                        .unwrap(),
+                ) == PCAPNG_BYTE_ORDER_MAGIC;
+                self.format = Format::Pcapng(Vec::new());
+            }
+            let len = self.u32_at(self.offset + 4) as usize;
+            if len < 12 || self.offset + len > self.data.len() {
+                return Ok(None); // Truncated last block
+            }
+            let body = self.offset + 8;
+            let end = self.offset + len - 4;
+            self.offset += len;
+
+            let Format::Pcapng(interfaces) = &self.format else {
+                unreachable!()
+            };
+            match block_type {
+                PCAPNG_INTERFACE_DESCRIPTION if end >= body + 8 => {
+                    let interface = Interface {
+                        link_type: self.u16_at(body) as u32,
+                        units_per_sec: self.tsresol(body + 8, end),
+                    };
+                    if let Format::Pcapng(interfaces) = &mut self.format {
+                        interfaces.push(interface);
+                    }
+                }
+                PCAPNG_ENHANCED_PACKET if end >= body + 20 => {
+                    let Some(interface) = interfaces.get(self.u32_at(body) as usize).copied()
+                    else {
+                        return Err(invalid("packet for unknown interface"));
+                    };
+                    let ticks =
+                        ((self.u32_at(body + 4) as u64) << 32) | self.u32_at(body + 8) as u64;
+                    let caplen = self.u32_at(body + 12) as usize;
+                    let start = body + 20;
+                    let frame = &self.data[start..(start + caplen).min(end)];
+                    return Ok(Some((
+                        Self::timestamp_us(ticks, &interface),
+                        interface.link_type,
+                        frame,
+                    )));
+                }
+                PCAPNG_SIMPLE_PACKET => {
+                    // No timestamp: cannot be replayed with its timing
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Timestamp resolution from the interface options (default µs)
+    fn tsresol(&self, mut offset: usize, end: usize) -> u64 {
+        while offset + 4 <= end {
+            let code = self.u16_at(offset);
+            let len = self.u16_at(offset + 2) as usize;
+            if code == 0 {
+                break;
+            }
+            if code == OPT_IF_TSRESOL && len >= 1 && offset + 5 <= end {
+                let resol = self.data[offset + 4];
+                let exp = (resol & 0x7f) as u32;
+                return if resol & 0x80 != 0 {
+                    1u64.checked_shl(exp).unwrap_or(1_000_000)
+                } else {
+                    10u64.checked_pow(exp).unwrap_or(1_000_000)
+                };
+            }
+            offset += 4 + len.div_ceil(4) * 4;
+        }
+        1_000_000
+    }
+}
+
+/// The IPv4 packet inside a link-layer frame
+fn ip_payload(link_type: u32, frame: &[u8]) -> Option<&[u8]> {
+    let ethertype_at = |offset: usize| -> Option<u16> {
+        frame
+            .get(offset..offset + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+    let ip = match link_type {
+        LINKTYPE_ETHERNET => {
+            let mut offset = 12;
+            while ethertype_at(offset)? == ETHERTYPE_VLAN {
+                offset += 4;
+            }
+            (ethertype_at(offset)? == ETHERTYPE_IPV4).then(|| &frame[offset + 2..])?
+        }
+        LINKTYPE_LINUX_SLL => (ethertype_at(14)? == ETHERTYPE_IPV4).then(|| &frame[16..])?,
+        LINKTYPE_LINUX_SLL2 => (ethertype_at(0)? == ETHERTYPE_IPV4).then(|| &frame[20..])?,
+        LINKTYPE_NULL => frame.get(4..)?,
+        LINKTYPE_RAW | LINKTYPE_IPV4 => frame,
+        _ => return None,
+    };
+    (ip.first()? >> 4 == 4).then_some(ip)
+}
+
+/// Fragments are matched on source, destination and IP identification
+type FragmentKey = (Ipv4Addr, Ipv4Addr, u16);
+/// Offset, more fragments flag and data of one fragment
+type Fragment = (usize, bool, Vec<u8>);
+
+/// Reassembles fragmented IPv4 datagrams
+#[derive(Default)]
+struct Fragments {
+    pending: HashMap<FragmentKey, Vec<Fragment>>,
+}
+
+impl Fragments {
+    fn udp_packet(&mut self, timestamp_us: u64, ip: &[u8]) -> Option<CapturedPacket> {
+        let header_len = ((ip.first()? & 0x0f) as usize) * 4;
+        if header_len < 20 || ip.len() < header_len || ip[9] != 17 {
+            return None;
+        }
+        let total_len = (u16::from_be_bytes([ip[2], ip[3]]) as usize).clamp(header_len, ip.len());
+        let src = Ipv4Addr::new(ip[12], ip[13], ip[14], ip[15]);
+        let dst = Ipv4Addr::new(ip[16], ip[17], ip[18], ip[19]);
+        let id = u16::from_be_bytes([ip[4], ip[5]]);
+        let flags_offset = u16::from_be_bytes([ip[6], ip[7]]);
+        let more = flags_offset & 0x2000 != 0;
+        let offset = (flags_offset & 0x1fff) as usize * 8;
+        let data = &ip[header_len..total_len];
+
+        let datagram = if !more && offset == 0 {
+            data.to_vec()
+        } else {
+            let key = (src, dst, id);
+            let parts = self.pending.entry(key).or_default();
+            parts.push((offset, more, data.to_vec()));
+            parts.sort_by_key(|p| p.0);
+            // Complete when the parts are contiguous up to one without more
+            let mut next = 0;
+            let mut complete = false;
+            for (offset, more, data) in parts.iter() {
+                if *offset != next {
+                    break;
+                }
+                next += data.len();
+                if !more {
+                    complete = true;
+                    break;
+                }
+            }
+            if !complete {
+                return None;
+            }
+            self.pending
+                .remove(&key)?
+                .into_iter()
+                .flat_map(|p| p.2)
+                .collect()
+        };
+        udp(timestamp_us, src, dst, &datagram)
+    }
+}
+
+fn udp(timestamp_us: u64, src: Ipv4Addr, dst: Ipv4Addr, datagram: &[u8]) -> Option<CapturedPacket> {
+    if datagram.len() < 8 {
+        return None;
+    }
+    let src_port = u16::from_be_bytes([datagram[0], datagram[1]]);
+    let dst_port = u16::from_be_bytes([datagram[2], datagram[3]]);
+    let len = (u16::from_be_bytes([datagram[4], datagram[5]]) as usize).clamp(8, datagram.len());
+    Some(CapturedPacket {
+        timestamp_us,
+        src: SocketAddrV4::new(src, src_port),
+        dst: SocketAddrV4::new(dst, dst_port),
+        payload: datagram[8..len].to_vec(),
+    })
+}
+
+/// When each packet of a capture is due, relative to the start of replay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayClock {
+    speed: f64,
+}
+
+impl ReplayClock {
+    /// `speed` is clamped to [`MIN_REPLAY_SPEED`]..=[`MAX_REPLAY_SPEED`]
+    pub fn new(speed: f64) -> Self {
+        ReplayClock {
+            speed: speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED),
+        }
+    }
+
+    /// Delay after the start of a pass at which a packet captured at
+    /// `timestamp_us` is sent, given the capture started at `first_us`
+    pub fn due(&self, first_us: u64, timestamp_us: u64) -> Duration {
+        let elapsed = timestamp_us.saturating_sub(first_us) as f64 / self.speed;
+        Duration::from_micros(elapsed as u64)
+    }
+}
+
+/// Replay subsystem: delivers the packets of a capture to mayara's sockets
+pub struct PacketReplayer {
+    name: String,
+    packets: Vec<Arc<CapturedPacket>>,
+    clock: ReplayClock,
+    looping: bool,
+}
+
+impl PacketReplayer {
+    /// Read the capture and switch mayara's sockets to replay, see
+    /// [`packet_bus::start_replay`]
+    pub fn new(path: &Path, speed: f64, looping: bool) -> io::Result<Self> {
+        let packets = read_capture_file(path)?.into_iter().map(Arc::new).collect();
+        packet_bus::start_replay();
+        Ok(PacketReplayer {
+            name: path.display().to_string(),
+            packets,
+            clock: ReplayClock::new(speed),
+            looping,
+        })
+    }
+
+    pub async fn run(self, subsys: SubsystemHandle) -> Result<(), RadarError> {
+        let Some(first_us) = self.packets.first().map(|p| p.timestamp_us) else {
+            log::warn!("{}: no UDP packets to replay", self.name);
+            return Ok(());
+        };
+        log::info!(
+            "{}: replaying {} packets at {}x{}",
+            self.name,
+            self.packets.len(),
+            self.clock.speed,
+            if self.looping { ", looping" } else { "" }
+        );
+
+        loop {
+            let start = Instant::now();
+            for packet in &self.packets {
+                let due = start + self.clock.due(first_us, packet.timestamp_us);
+                tokio::select! {
+                    _ = subsys.on_shutdown_requested() => return Ok(()),
+                    _ = sleep_until(due) => {}
+                }
+                packet_bus::replay(packet.clone());
+            }
+            if !self.looping {
+                log::info!("{}: replay finished", self.name);
+                return Ok(());
+            }
+            log::debug!("{}: restarting replay", self.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::capture::{CaptureNic, PcapngWriter};
+
+    fn addr(s: &str) -> SocketAddrV4 {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_read_own_pcapng() {
+        let nic = CaptureNic {
+            name: Some("eth0".to_string()),
+            addr: Ipv4Addr::new(10, 0, 0, 1),
+            netmask: None,
+        };
+        let mut pcap = PcapngWriter::new(Vec::new(), &nic, "test").unwrap();
+        let radar = addr("172.31.3.212:10010");
+        pcap.write_packet(1_000_000, radar, addr("172.31.255.255:10010"), b"beacon")
+            .unwrap();
+        pcap.write_packet(1_250_000, radar, addr("239.255.0.2:10024"), &[7; 1000])
+            .unwrap();
+        let data = pcap.into_inner();
+
+        let packets = read_capture(&data).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].src, radar);
+        assert_eq!(packets[0].dst, addr("172.31.255.255:10010"));
+        assert_eq!(packets[0].payload, b"beacon");
+        assert_eq!(packets[1].timestamp_us, 1_250_000);
+        assert_eq!(packets[1].payload, vec![7; 1000]);
+    }
+
+    /// Classic big-endian nanosecond pcap with a datagram in two fragments
+    #[test]
+    fn test_read_fragmented_pcap() {
+        let payload: Vec<u8> = (0..40u8).collect();
+        let mut udp = vec![0x17, 0x70, 0x17, 0x71, 0, 48, 0, 0];
+        udp.extend_from_slice(&payload);
+
+        let ip_fragment = |offset: usize, more: bool, data: &[u8]| {
+            let mut ip = vec![0x45, 0, 0, 0, 0x12, 0x34, 0, 0, 1, 17, 0, 0];
+            ip[2..4].copy_from_slice(&((20 + data.len()) as u16).to_be_bytes());
+            let flags = (offset / 8) as u16 | if more { 0x2000 } else { 0 };
+            ip[6..8].copy_from_slice(&flags.to_be_bytes());
+            ip.extend_from_slice(&[192, 168, 1, 10, 236, 6, 7, 8]);
+            ip.extend_from_slice(data);
+            ip
+        };
+
+        let mut file = Vec::new();
+        file.extend_from_slice(&PCAP_MAGIC_NS.to_be_bytes());
+        file.extend_from_slice(&[0, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff]);
+        file.extend_from_slice(&LINKTYPE_RAW.to_be_bytes());
+        for (ns, ip) in [
+            (500_000_000u32, ip_fragment(0, true, &udp[..24])),
+            (500_001_000u32, ip_fragment(24, false, &udp[24..])),
+        ] {
+            file.extend_from_slice(&7u32.to_be_bytes());
+            file.extend_from_slice(&ns.to_be_bytes());
+            file.extend_from_slice(&(ip.len() as u32).to_be_bytes());
+            file.extend_from_slice(&(ip.len() as u32).to_be_bytes());
+            file.extend_from_slice(&ip);
+        }
+
+        let packets = read_capture(&file).unwrap();
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].timestamp_us, 7_500_001);
+        assert_eq!(packets[0].src, addr("192.168.1.10:6000"));
+        assert_eq!(packets[0].dst, addr("236.6.7.8:6001"));
+        assert_eq!(packets[0].payload, payload);
+    }
+
+    #[test]
+    fn test_replay_clock() {
+        let clock = ReplayClock::new(2.0);
+        assert_eq!(clock.due(1_000_000, 3_000_000), Duration::from_secs(1));
+        assert_eq!(clock.due(1_000_000, 1_000_000), Duration::ZERO);
+        // Out of range speeds are clamped
+        assert_eq!(
+            ReplayClock::new(100.0).due(0, 10_000_000),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            ReplayClock::new(0.1).due(0, 1_000_000),
+            Duration::from_secs(2)
+        );
+    }
+}
@@ -7,10 +7,13 @@ use std::{
 use thiserror::Error;
 
 use mayara_core::capabilities::ControlDefinition as CoreControlDefinition;
+use mayara_core::engine::{ControlRateLimiter, EventLog, RadarEvent, RadarEventKind};
+use mayara_core::radar::radar_horizon_m;
+use mayara_core::state::{ChangeSource, CLIENT_WRITE_WINDOW_MS};
 
 use crate::{
     control_factory,
-    radar::{range::Ranges, DopplerMode, Legend, RadarError, Status},
+    radar::{arpa::TargetRequest, range::Ranges, DopplerMode, Legend, RadarError, Status},
     Session, TargetMode,
 };
 
@@ -48,6 +51,18 @@ pub struct Controls {
     control_update_tx: tokio::sync::broadcast::Sender<ControlUpdate>,
     #[serde(skip)]
     data_update_tx: tokio::sync::broadcast::Sender<DataUpdate>,
+    #[serde(skip)]
+    rate_limiter: ControlRateLimiter,
+    /// Control ID -> value and time (ms) of a client write the radar has
+    /// not read back yet
+    #[serde(skip)]
+    client_writes: HashMap<String, (String, u64)>,
+    /// Recent lifecycle events, for troubleshooting
+    #[serde(skip)]
+    events: EventLog,
+    /// Controls whose wire value runs the other way on this model
+    #[serde(skip)]
+    inverted_controls: &'static [&'static str],
 }
 
 impl Controls {
@@ -55,6 +70,7 @@ impl Controls {
         let v = Control {
             item: ControlDefinition {
                 is_read_only: self.session.read().unwrap().args.replay || value.item.is_read_only,
+                wire_inverted: self.inverted_controls.contains(&id),
                 ..value.item
             },
             ..value
@@ -81,6 +97,17 @@ impl Controls {
             });
         }
 
+        // Radars that know their antenna height also tell how far the
+        // radar horizon is, i.e. why low targets further out don't show
+        if string_controls.contains_key("antennaHeight") {
+            string_controls.insert(
+                "radarHorizon".to_string(),
+                Control::new_numeric("radarHorizon", 0., 100000.)
+                    .read_only(true)
+                    .set_destination(ControlDestination::Internal),
+            );
+        }
+
         // Add controls that are not radar dependent
         string_controls.insert(
             "userName".to_string(),
@@ -134,6 +161,7 @@ impl Controls {
         let (all_clients_tx, _) = tokio::sync::broadcast::channel(32);
         let (control_update_tx, _) = tokio::sync::broadcast::channel(32);
         let (data_update_tx, _) = tokio::sync::broadcast::channel(10);
+        let rate_limiter = ControlRateLimiter::new(session.read().unwrap().args.control_rate_limit);
 
         Controls {
             session: session.clone(),
@@ -141,6 +169,10 @@ impl Controls {
             all_clients_tx,
             control_update_tx,
             data_update_tx,
+            rate_limiter,
+            client_writes: HashMap::new(),
+            events: EventLog::default(),
+            inverted_controls: &[],
         }
     }
 }
@@ -221,6 +253,7 @@ impl SharedControls {
                     ControlValue::from(&c, None),
                     control_value
                 );
+                self.note_client_write(&control_value);
                 match c.item().destination {
                     ControlDestination::Internal => self
                         // set_string will also set numeric values
@@ -231,7 +264,8 @@ impl SharedControls {
                         self.send_to_data_handler(&reply_tx, control_value.clone())
                     }
                     ControlDestination::Command => {
-                        self.send_to_command_handler(control_value.clone(), reply_tx.clone())
+                        let control_value = self.to_wire_direction(&c, control_value.clone());
+                        self.send_to_command_handler(control_value, reply_tx.clone())
                     }
                 }
             }
@@ -289,6 +323,24 @@ impl SharedControls {
         control_value: ControlValue,
         reply_tx: tokio::sync::mpsc::Sender<ControlValue>,
     ) -> Result<(), RadarError> {
+        if self
+            .controls
+            .read()
+            .unwrap()
+            .session
+            .read()
+            .unwrap()
+            .args
+            .passive
+        {
+            return Err(RadarError::ControlError(ControlError::Passive(
+                control_value.id,
+            )));
+        }
+        // Standby must always get through, whatever else a client sends
+        if control_value.id != "power" {
+            self.check_rate_limit(&control_value.id)?;
+        }
         let control_update = ControlUpdate {
             control_value,
             reply_tx,
@@ -299,13 +351,104 @@ impl SharedControls {
             .map_err(|_| RadarError::Shutdown)
     }
 
-    fn send_to_all_clients(&self, control: &Control) {
+    /// Complement the value of a control whose wire value runs the other
+    /// way, so the brand command handlers can send it as they always do
+    fn to_wire_direction(
+        &self,
+        control: &Control,
+        mut control_value: ControlValue,
+    ) -> ControlValue {
+        if let Ok(value) = control_value.value.parse::<f32>() {
+            let wire_value = control.map_inverted(value);
+            if wire_value != value {
+                control_value.value = wire_value.to_string();
+            }
+        }
+        control_value
+    }
+
+    /// Count a write to the radar against `--control-rate-limit`
+    fn check_rate_limit(&self, id: &str) -> Result<(), RadarError> {
+        let mut locked = self.controls.write().unwrap();
+        if locked.rate_limiter.check(now_ms()).is_err() {
+            // Log once per window, not once per rejected write
+            if locked.rate_limiter.rejected_in_window() == 1 {
+                log::warn!("Control writes rate limited, rejecting {}", id);
+                locked.events.record(
+                    now_ms(),
+                    RadarEventKind::Error {
+                        message: "control writes rate limited".to_string(),
+                    },
+                );
+            }
+            return Err(RadarError::ControlError(ControlError::RateLimited(
+                id.to_string(),
+                locked.rate_limiter.max_writes(),
+            )));
+        }
+        Ok(())
+    }
+
+    /// Keep the read-only radar horizon in step with the antenna height
+    fn update_radar_horizon(&self, control: &Control) -> Result<(), ControlError> {
+        if let (true, Some(meters)) = (control.item().id == "antennaHeight", control.value) {
+            if self.get("radarHorizon").is_some() {
+                let horizon = radar_horizon_m(meters as f64, None).round();
+                self.set("radarHorizon", horizon as f32, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remember a client write, so its read back is tagged
+    /// [`ChangeSource::Client`]
+    fn note_client_write(&self, control_value: &ControlValue) {
+        let mut locked = self.controls.write().unwrap();
+        locked.client_writes.insert(
+            control_value.id.clone(),
+            (control_value.value.clone(), now_ms()),
+        );
+    }
+
+    /// Who caused the value just read back for `id`
+    ///
+    /// A read back of the value a client wrote within
+    /// [`CLIENT_WRITE_WINDOW_MS`] answers that write, whether or not the
+    /// value `changed`; any other change came from the radar.
+    fn readback_source(&self, id: &str, changed: bool) -> ChangeSource {
+        let mut locked = self.controls.write().unwrap();
+        let Some((value, written)) = locked.client_writes.get(id) else {
+            return ChangeSource::Radar;
+        };
+        if now_ms().saturating_sub(*written) > CLIENT_WRITE_WINDOW_MS {
+            locked.client_writes.remove(id);
+            return ChangeSource::Radar;
+        }
+        if !locked
+            .controls
+            .get(id)
+            .is_some_and(|c| c.holds_value(value))
+        {
+            return ChangeSource::Radar;
+        }
+        locked.client_writes.remove(id);
+        if changed {
+            ChangeSource::Client
+        } else {
+            ChangeSource::Radar
+        }
+    }
+
+    fn send_to_all_clients(&self, control: &Control, source: ChangeSource) {
+        self.record_control_event(control);
+
         let control_value = crate::settings::ControlValue {
             id: control.item().id.clone(),
             value: control.value(),
             auto: control.auto,
             enabled: control.enabled,
             error: None,
+            last_change_source: Some(source),
         };
 
         let locked = self.controls.read().unwrap();
@@ -350,12 +493,62 @@ impl SharedControls {
             self.send_reply_to_client(reply_tx, &control, Some(e.to_string()))
                 .await?;
             log::warn!("User tried to set invalid {}: {}", cv.id, e);
+            self.record_event(RadarEventKind::Error {
+                message: format!("{}: {}", cv.id, e),
+            });
             Ok(())
         } else {
             Err(RadarError::CannotSetControlType(cv.id.clone()))
         }
     }
 
+    /// Add an event to the radar's event log
+    pub fn record_event(&self, kind: RadarEventKind) {
+        let mut locked = self.controls.write().unwrap();
+        locked.events.record(now_ms(), kind);
+    }
+
+    /// Recent events for the radar, oldest first
+    pub fn events(&self) -> Vec<RadarEvent> {
+        let locked = self.controls.read().unwrap();
+        locked.events.events().cloned().collect()
+    }
+
+    /// Log transmit changes and the model becoming known
+    ///
+    /// Power is sent to clients on every report, so only a change from the
+    /// last logged transmit state is recorded.
+    fn record_control_event(&self, control: &Control) {
+        let kind = match control.item().id.as_str() {
+            "power" if control.value == Some(Status::Transmit as i32 as f32) => {
+                RadarEventKind::Transmit
+            }
+            "power" if control.value == Some(Status::Standby as i32 as f32) => {
+                RadarEventKind::Standby
+            }
+            "modelName" => match &control.description {
+                Some(model) if !model.is_empty() => RadarEventKind::ModelLearned {
+                    model: model.clone(),
+                },
+                _ => return,
+            },
+            _ => return,
+        };
+        let mut locked = self.controls.write().unwrap();
+        if matches!(kind, RadarEventKind::Transmit | RadarEventKind::Standby) {
+            let last = locked
+                .events
+                .events()
+                .rev()
+                .map(|e| &e.kind)
+                .find(|k| matches!(k, RadarEventKind::Transmit | RadarEventKind::Standby));
+            if last == Some(&kind) {
+                return;
+            }
+        }
+        locked.events.record(now_ms(), kind);
+    }
+
     // ******* GET & SET METHODS
 
     pub fn insert(&self, id: &str, value: Control) {
@@ -418,8 +611,10 @@ impl SharedControls {
         }?;
 
         // If the control changed, control.set returned Some(control)
+        let source = self.readback_source(id, control.is_some());
         if let Some(control) = control {
-            self.send_to_all_clients(&control);
+            self.send_to_all_clients(&control, source);
+            self.update_radar_horizon(&control)?;
             Ok(Some(()))
         } else {
             Ok(None)
@@ -443,7 +638,7 @@ impl SharedControls {
 
         // If the control changed, control.set returned Some(control)
         if let Some(control) = control {
-            self.send_to_all_clients(&control);
+            self.send_to_all_clients(&control, ChangeSource::Radar);
             Ok(Some(()))
         } else {
             Ok(None)
@@ -463,8 +658,11 @@ impl SharedControls {
         let control = {
             let mut locked = self.controls.write().unwrap();
             if let Some(control) = locked.controls.get_mut(id) {
+                // Keep the value the radar's auto algorithm chose (reported
+                // separately) for as long as the control stays in auto
+                let auto_value = control.auto_value.filter(|_| auto == Some(true));
                 Ok(control
-                    .set(value, None, auto, None)?
+                    .set(value, auto_value, auto, None)?
                     .map(|_| control.clone()))
             } else {
                 Err(ControlError::NotSupported(id.to_string()))
@@ -472,8 +670,10 @@ impl SharedControls {
         }?;
 
         // If the control changed, control.set returned Some(control)
+        let source = self.readback_source(id, control.is_some());
         if let Some(control) = control {
-            self.send_to_all_clients(&control);
+            self.send_to_all_clients(&control, source);
+            self.update_radar_horizon(&control)?;
             Ok(Some(()))
         } else {
             Ok(None)
@@ -522,8 +722,9 @@ impl SharedControls {
         }?;
 
         // If the control changed, control.set returned Some(control)
+        let source = self.readback_source(id, control.is_some());
         if let Some(control) = control {
-            self.send_to_all_clients(&control);
+            self.send_to_all_clients(&control, source);
             Ok(Some(()))
         } else {
             Ok(None)
@@ -553,8 +754,9 @@ impl SharedControls {
             }
         }?;
 
+        let source = self.readback_source(id, control.is_some());
         if let Some(control) = control {
-            self.send_to_all_clients(&control);
+            self.send_to_all_clients(&control, source);
             Ok(control.description.clone())
         } else {
             Ok(None)
@@ -572,6 +774,16 @@ impl SharedControls {
             .and_then(|c| c.description)
     }
 
+    /// Mark the controls whose wire value runs the other way on the model,
+    /// including those inserted later
+    pub fn set_inverted_controls(&self, inverted: &'static [&'static str]) {
+        let mut locked = self.controls.write().unwrap();
+        locked.inverted_controls = inverted;
+        for (id, control) in locked.controls.iter_mut() {
+            control.item.wire_inverted = inverted.contains(&id.as_str());
+        }
+    }
+
     pub fn set_model_name(&self, name: String) {
         let mut locked = self.controls.write().unwrap();
         let control = locked.controls.get_mut("modelName").unwrap();
@@ -638,6 +850,17 @@ pub enum DataUpdate {
     Legend(Legend),
     Ranges(Ranges),
     ControlValue(tokio::sync::mpsc::Sender<ControlValue>, ControlValue),
+    Target(TargetRequest),
+    /// The radar went to standby
+    Standby,
+}
+
+/// Host clock in milliseconds
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
 
 // This is what we send back and forth to clients
@@ -653,6 +876,9 @@ pub struct ControlValue {
     pub enabled: Option<bool>,
     #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Whether a client write or the radar itself caused the change
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub last_change_source: Option<ChangeSource>,
 }
 
 impl ControlValue {
@@ -663,6 +889,7 @@ impl ControlValue {
             auto: None,
             enabled: None,
             error: None,
+            last_change_source: None,
         }
     }
 
@@ -673,6 +900,7 @@ impl ControlValue {
             auto: control.auto,
             enabled: control.enabled,
             error,
+            last_change_source: None,
         }
     }
 }
@@ -849,6 +1077,7 @@ impl Control {
             step_value: None,
             wire_scale_factor: max_value,
             wire_offset: None,
+            wire_inverted: false,
             unit: None,
             descriptions: None,
             valid_values: None,
@@ -879,6 +1108,7 @@ impl Control {
             step_value: None,
             wire_scale_factor: max_value,
             wire_offset: None,
+            wire_inverted: false,
             unit: None,
             descriptions: None,
             valid_values: None,
@@ -902,6 +1132,7 @@ impl Control {
             step_value: None,
             wire_scale_factor: Some(description_count),
             wire_offset: None,
+            wire_inverted: false,
             unit: None,
             descriptions: Some(
                 descriptions
@@ -930,6 +1161,7 @@ impl Control {
             step_value: None,
             wire_scale_factor: Some(((descriptions.len() as i32) - 1) as f32),
             wire_offset: None,
+            wire_inverted: false,
             unit: None,
             descriptions: Some(descriptions),
             valid_values: None,
@@ -952,6 +1184,7 @@ impl Control {
             step_value: None,
             wire_scale_factor: None,
             wire_offset: None,
+            wire_inverted: false,
             unit: None,
             descriptions: None,
             valid_values: None,
@@ -975,6 +1208,7 @@ impl Control {
             step_value: None,
             wire_scale_factor: None,
             wire_offset: None,
+            wire_inverted: false,
             unit: None,
             descriptions: None,
             valid_values: None,
@@ -1021,13 +1255,35 @@ impl Control {
             return self.description.clone().unwrap_or_else(|| "".to_string());
         }
 
-        if self.auto.unwrap_or(false) && self.auto_value.is_some() {
-            return self.auto_value.unwrap().to_string();
+        self.applied_value()
+            .unwrap_or(self.item.default_value.unwrap_or(0.))
+            .to_string()
+    }
+
+    /// Whether the control holds `value`, as a client would write it
+    fn holds_value(&self, value: &str) -> bool {
+        if self.item.data_type == ControlDataType::String {
+            return self.description.as_deref() == Some(value);
+        }
+        let value = value
+            .parse::<f32>()
+            .ok()
+            .or_else(|| self.enum_value_to_index(value).map(|i| i as f32));
+        match (value, self.value) {
+            (Some(written), Some(current)) => {
+                (written - current).abs() < self.item.step_value.unwrap_or(1.0) / 2.0
+            }
+            _ => false,
         }
+    }
 
+    /// The value the radar is currently applying: in auto mode the value
+    /// the radar's auto algorithm chose, if it reports one, else the set value.
+    pub fn applied_value(&self) -> Option<f32> {
+        if self.auto.unwrap_or(false) && self.auto_value.is_some() {
+            return self.auto_value;
+        }
         self.value
-            .unwrap_or(self.item.default_value.unwrap_or(0.))
-            .to_string()
     }
 
     pub fn set_auto(&mut self, auto: bool) {
@@ -1086,6 +1342,8 @@ impl Control {
                 log::trace!("{} map value to scaled {}", self.item.id, value);
             }
         }
+        value = self.map_inverted(value);
+        auto_value = auto_value.map(|v| self.map_inverted(v));
 
         // RANGE MAPPING
         if let (Some(min_value), Some(max_value)) = (self.item.min_value, self.item.max_value) {
@@ -1189,6 +1447,19 @@ impl Control {
         }
         Ok(None)
     }
+
+    /// Complement a value within the control's range if its wire value runs
+    /// the other way; converts in both directions
+    pub fn map_inverted(&self, value: f32) -> f32 {
+        match (
+            self.item.wire_inverted,
+            self.item.min_value,
+            self.item.max_value,
+        ) {
+            (true, Some(min_value), Some(max_value)) => min_value + max_value - value,
+            _ => value,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -1252,6 +1523,8 @@ pub struct ControlDefinition {
     wire_scale_factor: Option<f32>,
     #[serde(skip)]
     wire_offset: Option<f32>,
+    #[serde(skip)]
+    wire_inverted: bool, // The wire value runs the other way (0 = most)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) unit: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1288,6 +1561,10 @@ pub enum ControlError {
     NoHeading(String, &'static str),
     #[error("Control {0} value '{1}' requires a GNSS position")]
     NoPosition(String, &'static str),
+    #[error("Control {0} rate limited to {1} changes per second")]
+    RateLimited(String, u32),
+    #[error("Control {0} cannot be set in passive mode")]
+    Passive(String),
 }
 
 #[cfg(test)]
@@ -1325,6 +1602,136 @@ mod test {
         }
     }
 
+    #[test]
+    fn control_writes_rate_limited_except_power() {
+        let session = crate::Session::new_fake();
+        let limit = session.read().unwrap().args.control_rate_limit;
+        let controls = SharedControls::new(session, HashMap::new());
+        let _commands = controls.control_update_subscribe();
+        let (reply_tx, _reply_rx) = tokio::sync::mpsc::channel(1);
+        let send = |id: &str| {
+            controls
+                .send_to_command_handler(ControlValue::new(id, "1".to_string()), reply_tx.clone())
+        };
+
+        for _ in 0..limit {
+            assert!(send("gain").is_ok());
+        }
+        assert!(matches!(
+            send("gain"),
+            Err(RadarError::ControlError(ControlError::RateLimited(..)))
+        ));
+        // Standby must get through a control storm
+        assert!(send("power").is_ok());
+    }
+
+    #[test]
+    fn control_writes_rejected_in_passive_mode() {
+        let session = crate::Session::new_fake();
+        session.write().unwrap().args.passive = true;
+        let controls = SharedControls::new(session, HashMap::new());
+        let _commands = controls.control_update_subscribe();
+        let (reply_tx, _reply_rx) = tokio::sync::mpsc::channel(1);
+
+        for id in ["gain", "power"] {
+            assert!(matches!(
+                controls.send_to_command_handler(
+                    ControlValue::new(id, "1".to_string()),
+                    reply_tx.clone()
+                ),
+                Err(RadarError::ControlError(ControlError::Passive(..)))
+            ));
+        }
+    }
+
+    #[test]
+    fn readback_tags_client_writes() {
+        let session = crate::Session::new_fake();
+        let mut map = HashMap::new();
+        map.insert("gain".to_string(), Control::new_numeric("gain", 0., 100.));
+        let controls = SharedControls::new(session, map);
+        let mut rx = controls.all_clients_rx();
+        let mut source = |value: f32| {
+            controls.set("gain", value, None).unwrap();
+            rx.try_recv().ok().map(|cv| cv.last_change_source.unwrap())
+        };
+
+        assert_eq!(source(50.), Some(ChangeSource::Radar));
+
+        // Only the read back of the written value answers the write
+        controls.note_client_write(&ControlValue::new("gain", "60".to_string()));
+        assert_eq!(source(55.), Some(ChangeSource::Radar));
+        assert_eq!(source(60.), Some(ChangeSource::Client));
+        assert_eq!(source(65.), Some(ChangeSource::Radar));
+
+        // Writing the value the radar already has is answered by a read
+        // back that changes nothing; a later change is external
+        controls.note_client_write(&ControlValue::new("gain", "65".to_string()));
+        assert_eq!(source(65.), None);
+        assert_eq!(source(70.), Some(ChangeSource::Radar));
+    }
+
+    #[test]
+    fn inverted_controls_complement_the_wire_value() {
+        let session = crate::Session::new_fake();
+        let mut map = HashMap::new();
+        map.insert("rain".to_string(), Control::new_numeric("rain", 0., 100.));
+        let controls = SharedControls::new(session, map);
+        controls.set_inverted_controls(&["gain"]);
+        // Inserted once the model is known, after the flags were set
+        controls.insert(
+            "gain",
+            Control::new_numeric("gain", 0., 100.).wire_scale_factor(255., false),
+        );
+
+        controls.set("gain", 204., None).unwrap();
+        controls.set("rain", 80., None).unwrap();
+        assert_eq!(controls.get("gain").unwrap().value, Some(20.));
+        assert_eq!(controls.get("rain").unwrap().value, Some(80.));
+
+        // A client write goes to the radar complemented
+        let wire = |id: &str, value: &str| {
+            let control = controls.get(id).unwrap();
+            controls
+                .to_wire_direction(&control, ControlValue::new(id, value.to_string()))
+                .value
+        };
+        assert_eq!(wire("gain", "20"), "80");
+        assert_eq!(wire("rain", "20"), "20");
+    }
+
+    #[test]
+    fn control_changes_recorded_in_event_log() {
+        let session = crate::Session::new_fake();
+        let controls = SharedControls::new(session, HashMap::new());
+
+        controls
+            .set_string("modelName", "HALO24".to_string())
+            .unwrap();
+        controls
+            .set("power", Status::Transmit as i32 as f32, None)
+            .unwrap();
+        controls
+            .set("power", Status::Standby as i32 as f32, None)
+            .unwrap();
+        // Power is sent on every report, but only logged when it changes
+        controls
+            .set("power", Status::Standby as i32 as f32, None)
+            .unwrap();
+
+        let kinds: Vec<_> = controls.events().into_iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                RadarEventKind::ModelLearned {
+                    model: "HALO24".to_string()
+                },
+                RadarEventKind::Transmit,
+                RadarEventKind::Standby,
+            ]
+        );
+    }
+
     #[test]
     fn control_range_values() {
         let session = crate::Session::new_fake();
@@ -1339,4 +1746,28 @@ mod test {
         assert!(controls.set("targetTrails", -1., None).is_err());
         assert!(controls.set("targetTrails", 0.3, None).is_ok());
     }
+
+    #[test]
+    fn auto_surfaces_applied_value() {
+        let session = crate::Session::new_fake();
+        let mut map = HashMap::new();
+        map.insert(
+            "sea".to_string(),
+            Control::new_auto("sea", 0., 100., HAS_AUTO_NOT_ADJUSTABLE),
+        );
+        let controls = SharedControls::new(session, map);
+
+        // Manual: the set value
+        controls.set_value_auto("sea", false, 30.).unwrap();
+        controls.set_value_with_many_auto("sea", 30., 45.).unwrap();
+        assert_eq!(controls.get("sea").unwrap().applied_value(), Some(30.));
+
+        // Auto: the value the radar chose, also after a report without it
+        controls.set_value_auto("sea", true, 30.).unwrap();
+        controls.set_value_with_many_auto("sea", 30., 45.).unwrap();
+        assert_eq!(controls.get("sea").unwrap().applied_value(), Some(45.));
+        assert_eq!(controls.get("sea").unwrap().value(), "45");
+        controls.set_value_auto("sea", true, 30.).unwrap();
+        assert_eq!(controls.get("sea").unwrap().applied_value(), Some(45.));
+    }
 }
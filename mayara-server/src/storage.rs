@@ -11,7 +11,7 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
 use std::io::{BufReader, BufWriter, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
 use crate::config::get_project_dirs;
@@ -226,17 +226,25 @@ pub struct AppDataRadars {
     pub radars: Option<std::collections::HashMap<String, InstallationSettings>>,
 }
 
-/// Load installation settings for a radar directly from disk.
+/// Path of the installation settings file.
 /// Uses same path as WASM SignalK plugin: @mayara/signalk-radar/1.0.0
-/// This is used by report receivers to restore write-only settings on startup.
-pub fn load_installation_settings(radar_id: &str) -> Option<InstallationSettings> {
+fn installation_settings_path() -> PathBuf {
     let project_dirs = get_project_dirs();
     let mut path = project_dirs.data_dir().to_owned();
     path.push("applicationData");
     path.push("@mayara");
     path.push("signalk-radar");
     path.push("1.0.0.json");
+    path
+}
+
+/// Load installation settings for a radar directly from disk.
+/// This is used by report receivers to restore write-only settings on startup.
+pub fn load_installation_settings(radar_id: &str) -> Option<InstallationSettings> {
+    load_installation_settings_from(&installation_settings_path(), radar_id)
+}
 
+fn load_installation_settings_from(path: &Path, radar_id: &str) -> Option<InstallationSettings> {
     info!("Loading installation settings for {} from {}", radar_id, path.display());
 
     if !path.exists() {
@@ -244,7 +252,7 @@ pub fn load_installation_settings(radar_id: &str) -> Option<InstallationSettings
         return None;
     }
 
-    match std::fs::File::open(&path) {
+    match std::fs::File::open(path) {
         Ok(file) => {
             let reader = std::io::BufReader::new(file);
             match serde_json::from_reader::<_, AppDataRadars>(reader) {
@@ -271,6 +279,66 @@ pub fn load_installation_settings(radar_id: &str) -> Option<InstallationSettings
     }
 }
 
+/// Store one installation setting (e.g. "bearingAlignment") for a radar,
+/// keeping all other settings in the file.
+/// Returns true if the stored value changed.
+pub fn store_installation_setting(
+    radar_id: &str,
+    setting: &str,
+    value: Value,
+) -> Result<bool, String> {
+    store_installation_setting_at(&installation_settings_path(), radar_id, setting, value)
+}
+
+fn store_installation_setting_at(
+    path: &Path,
+    radar_id: &str,
+    setting: &str,
+    value: Value,
+) -> Result<bool, String> {
+    let mut data = match fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str::<Value>(&text)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+        Err(_) => Value::Object(Default::default()),
+    };
+
+    let radar = data
+        .as_object_mut()
+        .ok_or_else(|| format!("{} is not a JSON object", path.display()))?
+        .entry("radars")
+        .or_insert_with(|| Value::Object(Default::default()))
+        .as_object_mut()
+        .ok_or_else(|| format!("{}: radars is not a JSON object", path.display()))?
+        .entry(radar_id)
+        .or_insert_with(|| Value::Object(Default::default()))
+        .as_object_mut()
+        .ok_or_else(|| {
+            format!(
+                "{}: radar {} is not a JSON object",
+                path.display(),
+                radar_id
+            )
+        })?;
+    if radar.get(setting) == Some(&value) {
+        return Ok(false);
+    }
+    radar.insert(setting.to_string(), value);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?;
+    fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    info!(
+        "Stored installation setting {}.{} in {}",
+        radar_id,
+        setting,
+        path.display()
+    );
+    Ok(true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -342,4 +410,30 @@ mod tests {
         assert!(keys.contains(&"key1".to_string()));
         assert!(keys.contains(&"key2".to_string()));
     }
+
+    #[test]
+    fn test_store_installation_setting_updates_loaded_value() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("1.0.0.json");
+        fs::write(
+            &path,
+            json!({"radars": {"Furuno-1": {"bearingAlignment": 2, "antennaHeight": 5}}})
+                .to_string(),
+        )
+        .unwrap();
+
+        // Radar reports a different alignment than the stored config
+        assert_eq!(
+            store_installation_setting_at(&path, "Furuno-1", "bearingAlignment", json!(-7)),
+            Ok(true)
+        );
+        let settings = load_installation_settings_from(&path, "Furuno-1").unwrap();
+        assert_eq!(settings.bearing_alignment, Some(-7));
+        assert_eq!(settings.antenna_height, Some(5));
+
+        assert_eq!(
+            store_installation_setting_at(&path, "Furuno-1", "bearingAlignment", json!(-7)),
+            Ok(false)
+        );
+    }
 }
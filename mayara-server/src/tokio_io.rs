@@ -8,27 +8,28 @@
 
 use std::collections::HashMap;
 use std::io::ErrorKind;
-use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::time::Instant;
 
 use mayara_core::io::{IoError, IoProvider, TcpSocketHandle, UdpSocketHandle};
+use mayara_core::radar::ParsedAddress;
 use socket2::{Domain, Protocol, Socket, Type};
 use tokio::net::UdpSocket;
 
-/// Find the interface name for a given IPv4 address.
+use crate::network::{is_ipv6_link_local, scoped_socket_addr};
+use crate::recording::packet_bus::{self, ReplayListener};
+
+/// Find the interface name for a given IPv4 or IPv6 address.
 #[cfg(target_os = "linux")]
-fn find_interface_name_for_ip(ip: &Ipv4Addr) -> Option<String> {
+fn find_interface_name_for_ip(ip: &IpAddr) -> Option<String> {
     use network_interface::{NetworkInterface, NetworkInterfaceConfig};
-    use std::net::IpAddr;
 
     let interfaces = NetworkInterface::show().ok()?;
 
     for itf in &interfaces {
         for addr in &itf.addr {
-            if let IpAddr::V4(nic_ip) = addr.ip() {
-                if &nic_ip == ip {
-                    return Some(itf.name.clone());
-                }
+            if &addr.ip() == ip {
+                return Some(itf.name.clone());
             }
         }
     }
@@ -36,19 +37,147 @@ fn find_interface_name_for_ip(ip: &Ipv4Addr) -> Option<String> {
     None
 }
 
+/// Parse an IPv4 or IPv6 address passed in by mayara-core
+fn parse_ip(addr: &str, what: &str) -> Result<IpAddr, IoError> {
+    addr.parse()
+        .map_err(|e| IoError::new(-1, format!("Invalid {} '{}': {}", what, addr, e)))
+}
+
+/// Parse an IP address that may carry an IPv6 scope id ("fe80::1%3"),
+/// returned as 0 if it has none
+fn parse_scoped_ip(addr: &str, what: &str) -> Result<(IpAddr, u32), IoError> {
+    ParsedAddress::parse(addr)
+        .map(|a| (a.ip, a.scope_id))
+        .map_err(|e| IoError::new(-1, format!("Invalid {} '{}': {}", what, addr, e)))
+}
+
+/// The unspecified address of the same family as `ip`
+fn unspecified(ip: &IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    }
+}
+
+/// Create a non-blocking UDP socket for IPv4 or IPv6 that allows address reuse
+fn new_udp_socket(ipv6: bool) -> Result<Socket, IoError> {
+    let domain = if ipv6 { Domain::IPV6 } else { Domain::IPV4 };
+    let socket = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))
+        .map_err(|e| IoError::new(-1, format!("Failed to create socket: {}", e)))?;
+
+    if ipv6 {
+        // Keep IPv4 traffic on IPv4 sockets, so both can use the same port
+        socket
+            .set_only_v6(true)
+            .map_err(|e| IoError::new(-1, format!("Failed to set IPv6 only: {}", e)))?;
+    }
+    socket
+        .set_nonblocking(true)
+        .map_err(|e| IoError::new(-1, format!("Failed to set non-blocking: {}", e)))?;
+    socket
+        .set_reuse_address(true)
+        .map_err(|e| IoError::new(-1, format!("Failed to set reuse address: {}", e)))?;
+
+    #[cfg(unix)]
+    {
+        let _ = socket.set_reuse_port(true);
+    }
+
+    Ok(socket)
+}
+
+/// Hand a configured socket2 socket over to tokio
+fn into_tokio(socket: Socket) -> Result<UdpSocket, IoError> {
+    let std_socket: std::net::UdpSocket = socket.into();
+    UdpSocket::from_std(std_socket)
+        .map_err(|e| IoError::new(-1, format!("Failed to convert to tokio socket: {}", e)))
+}
+
 /// Internal state for a UDP socket
 struct UdpSocketState {
     socket: UdpSocket,
+    /// Interface index for IPv6 link-local destinations, 0 if not bound
+    /// to an interface
+    scope_id: u32,
+    /// Last multicast group joined, the destination of received datagrams
+    group: Option<IpAddr>,
+    /// Replayed datagrams to the bound port, instead of the network, while
+    /// a capture is replayed
+    replay: Option<ReplayListener>,
+}
+
+impl UdpSocketState {
+    /// The address datagrams received on this socket were sent to
+    fn listen_addr(&self) -> Option<SocketAddr> {
+        let local_addr = self.socket.local_addr().ok()?;
+        Some(SocketAddr::new(
+            self.group.unwrap_or(local_addr.ip()),
+            local_addr.port(),
+        ))
+    }
+
+    /// Recreate the socket for the address family of `ip`, keeping its port,
+    /// when it was created for the other family.
+    ///
+    /// Sockets start out as IPv4; this is how they move to IPv6 when
+    /// mayara-core hands them an IPv6 group or destination.
+    fn match_family(&mut self, ip: &IpAddr) -> Result<(), IoError> {
+        let local_addr = self.socket.local_addr().ok();
+        if local_addr.map(|a| a.is_ipv6()) == Some(ip.is_ipv6()) {
+            return Ok(());
+        }
+        let port = local_addr.map(|a| a.port()).unwrap_or(0);
+
+        let socket = new_udp_socket(ip.is_ipv6())?;
+        if port != 0 {
+            let bind_addr = SocketAddr::new(unspecified(ip), port);
+            socket
+                .bind(&bind_addr.into())
+                .map_err(|e| IoError::new(-1, format!("Failed to bind to {}: {}", bind_addr, e)))?;
+        }
+        if ip.is_ipv4() && self.socket.broadcast().unwrap_or(false) {
+            let _ = socket.set_broadcast(true);
+        }
+        log::debug!("UDP socket on port {} recreated for {}", port, ip);
+
+        self.socket = into_tokio(socket)?;
+        Ok(())
+    }
 }
 
+/// Default number of bytes read from a TCP socket per `tcp_recv_line` call
+pub const DEFAULT_TCP_READ_SIZE: usize = 1024;
+
+/// Default maximum length of a buffered TCP line before it is discarded
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 16 * 1024;
+
 /// Internal state for a TCP socket
 struct TcpSocketState {
     socket: Option<tokio::net::TcpStream>,
     connecting: bool,
+    read_buf: Vec<u8>,
     line_buffer: String,
     line_buffered: bool,
 }
 
+/// Append received data to a line buffer, discarding the buffer if it grows
+/// beyond `max_line_length` without containing a newline.
+///
+/// Returns true if data was discarded.
+fn append_line_data(line_buffer: &mut String, data: &str, max_line_length: usize) -> bool {
+    line_buffer.push_str(data);
+    if line_buffer.len() <= max_line_length || line_buffer.contains('\n') {
+        return false;
+    }
+    log::warn!(
+        "tcp_recv_line: discarding {} bytes without newline (max line length {})",
+        line_buffer.len(),
+        max_line_length
+    );
+    line_buffer.clear();
+    true
+}
+
 /// Tokio implementation of IoProvider for the native server.
 ///
 /// Wraps tokio sockets in a poll-based interface that matches the
@@ -76,6 +205,10 @@ pub struct TokioIoProvider {
     tcp_sockets: HashMap<i32, TcpSocketState>,
     /// Start time for current_time_ms calculation
     start_time: Instant,
+    /// Bytes read from a TCP socket per `tcp_recv_line` call
+    tcp_read_size: usize,
+    /// Longest TCP line kept while waiting for a newline
+    max_line_length: usize,
 }
 
 impl TokioIoProvider {
@@ -86,9 +219,28 @@ impl TokioIoProvider {
             udp_sockets: HashMap::new(),
             tcp_sockets: HashMap::new(),
             start_time: Instant::now(),
+            tcp_read_size: DEFAULT_TCP_READ_SIZE,
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
         }
     }
 
+    /// Set the number of bytes read per `tcp_recv_line` call (minimum 1).
+    ///
+    /// Applies to TCP sockets created afterwards.
+    pub fn with_tcp_read_size(mut self, size: usize) -> Self {
+        self.tcp_read_size = size.max(1);
+        self
+    }
+
+    /// Set the maximum line length for line-buffered TCP sockets.
+    ///
+    /// A radar that sends more than this without a newline has its
+    /// buffered data discarded with a warning.
+    pub fn with_max_line_length(mut self, length: usize) -> Self {
+        self.max_line_length = length;
+        self
+    }
+
     fn alloc_handle(&mut self) -> i32 {
         let handle = self.next_handle;
         self.next_handle += 1;
@@ -108,37 +260,18 @@ impl IoProvider for TokioIoProvider {
     // -------------------------------------------------------------------------
 
     fn udp_create(&mut self) -> Result<UdpSocketHandle, IoError> {
-        // Create a socket using socket2 for more control
-        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))
-            .map_err(|e| IoError::new(-1, format!("Failed to create socket: {}", e)))?;
-
-        // Set non-blocking mode
-        socket
-            .set_nonblocking(true)
-            .map_err(|e| IoError::new(-1, format!("Failed to set non-blocking: {}", e)))?;
-
-        // Allow address reuse
-        socket
-            .set_reuse_address(true)
-            .map_err(|e| IoError::new(-1, format!("Failed to set reuse address: {}", e)))?;
-
-        #[cfg(unix)]
-        {
-            socket
-                .set_reuse_port(true)
-                .map_err(|e| IoError::new(-1, format!("Failed to set reuse port: {}", e)))?;
-        }
-
-        // Convert to tokio socket
-        let std_socket: std::net::UdpSocket = socket.into();
-        let tokio_socket = UdpSocket::from_std(std_socket)
-            .map_err(|e| IoError::new(-1, format!("Failed to convert to tokio socket: {}", e)))?;
+        // IPv4 until the socket is used with an IPv6 address
+        let socket = new_udp_socket(false)?;
+        let tokio_socket = into_tokio(socket)?;
 
         let handle = self.alloc_handle();
         self.udp_sockets.insert(
             handle,
             UdpSocketState {
                 socket: tokio_socket,
+                scope_id: 0,
+                group: None,
+                replay: None,
             },
         );
         Ok(UdpSocketHandle(handle))
@@ -152,6 +285,11 @@ impl IoProvider for TokioIoProvider {
             .get_mut(&socket.0)
             .ok_or_else(|| IoError::new(-1, "Invalid socket handle"))?;
 
+        if port != 0 {
+            let any = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port);
+            state.replay = ReplayListener::new(any);
+        }
+
         // Get the raw socket and rebind
         // Note: tokio sockets don't support rebinding, so we need to recreate
         let local_addr = state.socket.local_addr().ok();
@@ -165,32 +303,18 @@ impl IoProvider for TokioIoProvider {
 
         // Need to recreate the socket with the new bind
         // This is a limitation - socket2 must bind before converting to tokio
-        let new_socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))
-            .map_err(|e| IoError::new(-1, format!("Failed to create socket: {}", e)))?;
-
-        new_socket
-            .set_nonblocking(true)
-            .map_err(|e| IoError::new(-1, format!("Failed to set non-blocking: {}", e)))?;
-        new_socket
-            .set_reuse_address(true)
-            .map_err(|e| IoError::new(-1, format!("Failed to set reuse address: {}", e)))?;
-
-        #[cfg(unix)]
-        {
-            let _ = new_socket.set_reuse_port(true);
-        }
+        let new_socket = new_udp_socket(local_addr.is_some_and(|a| a.is_ipv6()))?;
 
         // Bind to all interfaces
-        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port);
+        let bind_addr = match local_addr {
+            Some(addr) => SocketAddr::new(unspecified(&addr.ip()), port),
+            None => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port),
+        };
         new_socket
             .bind(&bind_addr.into())
             .map_err(|e| IoError::new(-1, format!("Failed to bind to port {}: {}", port, e)))?;
 
-        let std_socket: std::net::UdpSocket = new_socket.into();
-        let tokio_socket = UdpSocket::from_std(std_socket)
-            .map_err(|e| IoError::new(-1, format!("Failed to convert to tokio socket: {}", e)))?;
-
-        state.socket = tokio_socket;
+        state.socket = into_tokio(new_socket)?;
         Ok(())
     }
 
@@ -214,25 +338,49 @@ impl IoProvider for TokioIoProvider {
     ) -> Result<(), IoError> {
         let state = self
             .udp_sockets
-            .get(&socket.0)
+            .get_mut(&socket.0)
             .ok_or_else(|| IoError::new(-1, "Invalid socket handle"))?;
 
-        let multicast_addr: Ipv4Addr = group
-            .parse()
-            .map_err(|e| IoError::new(-1, format!("Invalid multicast address '{}': {}", group, e)))?;
-
-        let interface_addr: Ipv4Addr = if interface.is_empty() {
-            Ipv4Addr::UNSPECIFIED
+        let multicast_addr = parse_ip(group, "multicast address")?;
+        let interface_addr = if interface.is_empty() {
+            unspecified(&multicast_addr)
         } else {
-            interface.parse().map_err(|e| {
-                IoError::new(-1, format!("Invalid interface address '{}': {}", interface, e))
-            })?
+            parse_ip(interface, "interface address")?
         };
 
-        state
-            .socket
-            .join_multicast_v4(multicast_addr, interface_addr)
-            .map_err(|e| IoError::new(-1, format!("Failed to join multicast {}: {}", group, e)))
+        if state.replay.is_some() {
+            // Replayed datagrams do not come from the network
+            state.group = Some(multicast_addr);
+            return Ok(());
+        }
+
+        state.match_family(&multicast_addr)?;
+        let result = match (multicast_addr, interface_addr) {
+            (IpAddr::V4(group), IpAddr::V4(interface)) => {
+                state.socket.join_multicast_v4(group, interface)
+            }
+            (IpAddr::V6(group), interface) => {
+                let index = if interface.is_unspecified() {
+                    0
+                } else {
+                    crate::network::interface_index(&interface)
+                };
+                state.socket.join_multicast_v6(&group, index)
+            }
+            (IpAddr::V4(_), IpAddr::V6(_)) => {
+                return Err(IoError::new(
+                    -1,
+                    format!(
+                        "Cannot join IPv4 multicast {} on IPv6 interface {}",
+                        group, interface
+                    ),
+                ));
+            }
+        };
+        result
+            .map_err(|e| IoError::new(-1, format!("Failed to join multicast {}: {}", group, e)))?;
+        state.group = Some(multicast_addr);
+        Ok(())
     }
 
     fn udp_send_to(
@@ -244,13 +392,21 @@ impl IoProvider for TokioIoProvider {
     ) -> Result<usize, IoError> {
         let state = self
             .udp_sockets
-            .get(&socket.0)
+            .get_mut(&socket.0)
             .ok_or_else(|| IoError::new(-1, "Invalid socket handle"))?;
 
-        let ip: Ipv4Addr = addr
-            .parse()
-            .map_err(|e| IoError::new(-1, format!("Invalid address '{}': {}", addr, e)))?;
-        let target = SocketAddr::V4(SocketAddrV4::new(ip, port));
+        let (ip, scope_id) = parse_scoped_ip(addr, "address")?;
+        if packet_bus::is_replaying() {
+            // Nothing goes onto the network while a capture is replayed
+            return Ok(data.len());
+        }
+        state.match_family(&ip)?;
+        let scope_id = if scope_id != 0 {
+            scope_id
+        } else {
+            state.scope_id
+        };
+        let target = scoped_socket_addr(ip, port, scope_id);
 
         // Use try_send_to for non-blocking send
         state
@@ -264,11 +420,25 @@ impl IoProvider for TokioIoProvider {
         socket: &UdpSocketHandle,
         buf: &mut [u8],
     ) -> Option<(usize, String, u16)> {
-        let state = self.udp_sockets.get(&socket.0)?;
-
-        // Use try_recv_from for non-blocking receive
-        match state.socket.try_recv_from(buf) {
+        let state = self.udp_sockets.get_mut(&socket.0)?;
+
+        let received = match state.replay.as_mut() {
+            Some(listener) => listener
+                .try_recv()
+                .map(|packet| {
+                    let len = packet.payload.len().min(buf.len());
+                    buf[..len].copy_from_slice(&packet.payload[..len]);
+                    (len, SocketAddr::V4(packet.src))
+                })
+                .ok_or_else(|| std::io::Error::from(ErrorKind::WouldBlock)),
+            // Use try_recv_from for non-blocking receive
+            None => state.socket.try_recv_from(buf),
+        };
+        match received {
             Ok((len, addr)) => {
+                if let Some(listen_addr) = state.listen_addr() {
+                    packet_bus::capture(addr, listen_addr, &buf[..len]);
+                }
                 let ip = match addr {
                     SocketAddr::V4(v4) => v4.ip().to_string(),
                     SocketAddr::V6(v6) => v6.ip().to_string(),
@@ -304,44 +474,37 @@ impl IoProvider for TokioIoProvider {
             .unwrap_or(0);
 
         // Parse the interface IP address
-        let interface_ip: Ipv4Addr = interface
-            .parse()
-            .map_err(|e| IoError::new(-1, format!("Invalid interface address '{}': {}", interface, e)))?;
+        let interface_ip = parse_ip(interface, "interface address")?;
 
         // Recreate the socket bound to the specific interface
-        let new_socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))
-            .map_err(|e| IoError::new(-1, format!("Failed to create socket: {}", e)))?;
-
-        new_socket
-            .set_nonblocking(true)
-            .map_err(|e| IoError::new(-1, format!("Failed to set non-blocking: {}", e)))?;
-        new_socket
-            .set_reuse_address(true)
-            .map_err(|e| IoError::new(-1, format!("Failed to set reuse address: {}", e)))?;
-
-        #[cfg(unix)]
-        {
-            let _ = new_socket.set_reuse_port(true);
+        let new_socket = new_udp_socket(interface_ip.is_ipv6())?;
+
+        // Re-enable broadcast mode (was set on original socket), IPv6 has
+        // no broadcast
+        if interface_ip.is_ipv4() {
+            new_socket
+                .set_broadcast(true)
+                .map_err(|e| IoError::new(-1, format!("Failed to set broadcast: {}", e)))?;
         }
 
-        // Re-enable broadcast mode (was set on original socket)
-        new_socket
-            .set_broadcast(true)
-            .map_err(|e| IoError::new(-1, format!("Failed to set broadcast: {}", e)))?;
-
         // IMPORTANT: Bind to 0.0.0.0:port to receive broadcast responses
         // (binding to interface_ip:port would prevent receiving broadcasts)
         // We use IP_MULTICAST_IF to control OUTGOING packets only
-        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, current_port);
+        let bind_addr = SocketAddr::new(unspecified(&interface_ip), current_port);
         new_socket
             .bind(&bind_addr.into())
             .map_err(|e| IoError::new(-1, format!("Failed to bind to {}: {}", bind_addr, e)))?;
 
         // Set the outgoing interface for multicast/broadcast packets
         // This ensures broadcasts go out on the correct NIC without affecting receive
-        new_socket
-            .set_multicast_if_v4(&interface_ip)
-            .map_err(|e| IoError::new(-1, format!("Failed to set multicast interface: {}", e)))?;
+        let scope_id = match interface_ip {
+            IpAddr::V4(v4) => new_socket.set_multicast_if_v4(&v4).map(|_| 0),
+            IpAddr::V6(_) => {
+                let index = crate::network::interface_index(&interface_ip);
+                new_socket.set_multicast_if_v6(index).map(|_| index)
+            }
+        }
+        .map_err(|e| IoError::new(-1, format!("Failed to set multicast interface: {}", e)))?;
 
         // On Linux, also bind to the device to ensure proper routing
         #[cfg(target_os = "linux")]
@@ -369,11 +532,8 @@ impl IoProvider for TokioIoProvider {
 
         log::debug!("UDP socket configured for interface {} port {}", interface_ip, current_port);
 
-        let std_socket: std::net::UdpSocket = new_socket.into();
-        let tokio_socket = UdpSocket::from_std(std_socket)
-            .map_err(|e| IoError::new(-1, format!("Failed to convert to tokio socket: {}", e)))?;
-
-        state.socket = tokio_socket;
+        state.socket = into_tokio(new_socket)?;
+        state.scope_id = scope_id;
         Ok(())
     }
 
@@ -388,6 +548,7 @@ impl IoProvider for TokioIoProvider {
             TcpSocketState {
                 socket: None,
                 connecting: false,
+                read_buf: vec![0u8; self.tcp_read_size],
                 line_buffer: String::new(),
                 line_buffered: false,
             },
@@ -406,10 +567,15 @@ impl IoProvider for TokioIoProvider {
             .get_mut(&socket.0)
             .ok_or_else(|| IoError::new(-1, "Invalid socket handle"))?;
 
-        let ip: Ipv4Addr = addr
-            .parse()
-            .map_err(|e| IoError::new(-1, format!("Invalid address '{}': {}", addr, e)))?;
-        let target = SocketAddr::V4(SocketAddrV4::new(ip, port));
+        let (ip, scope_id) = parse_scoped_ip(addr, "address")?;
+        let scope_id = if scope_id != 0 || !is_ipv6_link_local(&ip) {
+            scope_id
+        } else {
+            crate::network::find_nic_for_radar(&ip)
+                .map(|nic| crate::network::interface_index(&nic))
+                .unwrap_or(0)
+        };
+        let target = scoped_socket_addr(ip, port, scope_id);
 
         // Start async connect - we'll poll for completion
         state.connecting = true;
@@ -476,27 +642,30 @@ impl IoProvider for TokioIoProvider {
     }
 
     fn tcp_recv_line(&mut self, socket: &TcpSocketHandle, buf: &mut [u8]) -> Option<usize> {
+        let max_line_length = self.max_line_length;
         let state = self.tcp_sockets.get_mut(&socket.0)?;
-        let stream = state.socket.as_ref()?;
 
-        // Read into internal buffer
-        let mut temp_buf = [0u8; 1024];
-        match stream.try_read(&mut temp_buf) {
-            Ok(0) => {
-                log::debug!("tcp_recv_line: EOF");
-                return None;
-            }
-            Ok(n) => {
-                let data = String::from_utf8_lossy(&temp_buf[..n]);
-                log::debug!("tcp_recv_line: read {} bytes: {:?}", n, data);
-                state.line_buffer.push_str(&data);
-            }
-            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
-                // No data available yet - this is normal for non-blocking I/O
-            }
-            Err(e) => {
-                log::debug!("tcp_recv_line: error: {}", e);
-                return None;
+        // Read into internal buffer. Lines already buffered are still
+        // returned after EOF.
+        if let Some(stream) = state.socket.as_ref() {
+            match stream.try_read(&mut state.read_buf) {
+                Ok(0) => {
+                    log::debug!("tcp_recv_line: EOF");
+                    // Mark closed so tcp_is_connected() reports the drop
+                    state.socket = None;
+                }
+                Ok(n) => {
+                    let data = String::from_utf8_lossy(&state.read_buf[..n]);
+                    log::debug!("tcp_recv_line: read {} bytes: {:?}", n, data);
+                    append_line_data(&mut state.line_buffer, &data, max_line_length);
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    // No data available yet - this is normal for non-blocking I/O
+                }
+                Err(e) => {
+                    log::debug!("tcp_recv_line: error: {}", e);
+                    state.socket = None;
+                }
             }
         }
 
@@ -520,14 +689,17 @@ impl IoProvider for TokioIoProvider {
     }
 
     fn tcp_recv_raw(&mut self, socket: &TcpSocketHandle, buf: &mut [u8]) -> Option<usize> {
-        let state = self.tcp_sockets.get(&socket.0)?;
+        let state = self.tcp_sockets.get_mut(&socket.0)?;
         let stream = state.socket.as_ref()?;
 
         match stream.try_read(buf) {
-            Ok(0) => None, // EOF
-            Ok(n) => Some(n),
+            Ok(n) if n > 0 => Some(n),
             Err(ref e) if e.kind() == ErrorKind::WouldBlock => None,
-            Err(_) => None,
+            // EOF or error: mark closed so tcp_is_connected() reports the drop
+            _ => {
+                state.socket = None;
+                None
+            }
         }
     }
 
@@ -579,4 +751,61 @@ mod tests {
         let h2 = io.alloc_handle();
         assert_ne!(h1, h2);
     }
+
+    #[test]
+    fn test_line_buffer_is_bounded() {
+        let max = 4096;
+        let chunk = "x".repeat(DEFAULT_TCP_READ_SIZE);
+        let mut line_buffer = String::new();
+        let mut discarded = 0;
+
+        for _ in 0..100 {
+            if append_line_data(&mut line_buffer, &chunk, max) {
+                discarded += 1;
+            }
+            assert!(line_buffer.len() <= max);
+        }
+        assert!(discarded > 0);
+
+        // Normal lines are unaffected
+        line_buffer.clear();
+        assert!(!append_line_data(&mut line_buffer, "$N96,1\r\n", max));
+        assert_eq!(line_buffer, "$N96,1\r\n");
+    }
+
+    #[test]
+    fn test_tcp_read_size_is_configurable() {
+        let mut io = TokioIoProvider::new().with_tcp_read_size(64);
+        let socket = io.tcp_create().unwrap();
+        assert_eq!(io.tcp_sockets[&socket.0].read_buf.len(), 64);
+    }
+
+    #[test]
+    fn test_parse_scoped_ip() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        assert_eq!(
+            parse_scoped_ip("fe80::1%3", "address").unwrap(),
+            (ip("fe80::1"), 3)
+        );
+        assert_eq!(
+            parse_scoped_ip("172.31.3.1", "address").unwrap(),
+            (ip("172.31.3.1"), 0)
+        );
+        assert!(parse_scoped_ip("fe80::1%eth0", "address").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_udp_socket_follows_address_family() {
+        let mut io = TokioIoProvider::new();
+        let socket = io.udp_create().unwrap();
+        let state = io.udp_sockets.get_mut(&socket.0).unwrap();
+        assert!(state.socket.local_addr().unwrap().is_ipv4());
+
+        state.match_family(&"fd00::1".parse().unwrap()).unwrap();
+        assert!(state.socket.local_addr().unwrap().is_ipv6());
+
+        state.match_family(&"172.31.3.1".parse().unwrap()).unwrap();
+        assert!(state.socket.local_addr().unwrap().is_ipv4());
+    }
 }
@@ -1,6 +1,8 @@
 // Various common functions
 
+use flate2::{write::ZlibEncoder, Compression, Crc};
 use std::fmt;
+use std::io::{self, Write};
 
 pub fn c_string(bytes: &[u8]) -> Option<&str> {
     let bytes_without_null = match bytes.iter().position(|&b| b == 0) {
@@ -105,3 +107,64 @@ impl fmt::Display for PrintableSpoke<'_> {
         Ok(())
     }
 }
+
+/// Encode an RGBA image, rows top to bottom and 4 bytes per pixel, as PNG
+pub fn encode_png(rgba: &[u8], width: usize, height: usize) -> io::Result<Vec<u8>> {
+    fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        let mut crc = Crc::new();
+        crc.update(kind);
+        crc.update(data);
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        png.extend_from_slice(&crc.sum().to_be_bytes());
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8 bit RGBA, not interlaced
+
+    // Every row starts with its filter type, 0 is none
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    for row in rgba.chunks_exact(width * 4).take(height) {
+        encoder.write_all(&[0])?;
+        encoder.write_all(row)?;
+    }
+    let data = encoder.finish()?;
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &data);
+    chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::ZlibDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_encode_png() {
+        let rgba = [255, 0, 0, 255, 0, 0, 0, 0, 0, 255, 0, 128, 1, 2, 3, 4];
+        let png = encode_png(&rgba, 2, 2).unwrap();
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 2]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+
+        // IHDR is 13 bytes, so IDAT starts after 8 + 12 + 13 bytes
+        let len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let mut rows = Vec::new();
+        ZlibDecoder::new(&png[41..41 + len])
+            .read_to_end(&mut rows)
+            .unwrap();
+        assert_eq!(rows.len(), 2 * (1 + 2 * 4));
+        assert_eq!(&rows[..9], &[0, 255, 0, 0, 255, 0, 0, 0, 0]);
+        assert_eq!(&rows[9..], &[0, 0, 255, 0, 128, 1, 2, 3, 4]);
+    }
+}
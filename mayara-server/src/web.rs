@@ -33,17 +33,18 @@ mod axum_fix;
 use axum_fix::{Message, WebSocket, WebSocketUpgrade};
 
 use mayara_server::{
-    radar::{Legend, RadarError, RadarInfo},
+    radar::{sweep_request, target_request, Legend, RadarError, RadarInfo, TargetRequest},
     recording::{
         RecordingManager, RecordingInfo, RecordingStatus, ActiveRecording, start_recording, build_initial_state,
         ActivePlayback, PlaybackSettings, PlaybackStatus, load_recording, unregister_playback_radar,
     },
     storage::{AppDataKey, SharedStorage, create_shared_storage},
-    ProtoAssets, Session,
+    util::encode_png,
+    ProtoAssets, Session, TargetMode,
 };
 
 // ARPA types from mayara-core for v6 API
-use mayara_core::arpa::{ArpaSettings, ArpaTarget};
+use mayara_core::arpa::{ArpaSettings, ArpaTarget, TargetFilter};
 
 // Guard zone types from mayara-core
 use mayara_core::guard_zones::{GuardZone, GuardZoneStatus};
@@ -58,19 +59,26 @@ use mayara_core::dual_range::{DualRangeConfig, DualRangeState as CoreDualRangeSt
 use mayara_core::engine::RadarEngine;
 
 // Capability types from mayara-core for v5 API
-use mayara_core::capabilities::{builder::build_capabilities_from_model_with_key, RadarStateV5, SupportedFeature};
+use mayara_core::capabilities::{
+    builder::{build_capabilities_from_model_with_key, build_capability_summary},
+    is_control_allowed, CapabilitySummary, ControlError, DisplayCenterOffset, RadarStateV5,
+    SupportedFeature,
+};
 use mayara_core::models;
 
 // Standalone Radar API v2 paths (matches SignalK Radar API v2 structure)
 const RADARS_URI: &str = "/v2/api/radars";
 const RADAR_CAPABILITIES_URI: &str = "/v2/api/radars/{radar_id}/capabilities";
 const RADAR_STATE_URI: &str = "/v2/api/radars/{radar_id}/state";
+const RADAR_EVENTS_URI: &str = "/v2/api/radars/{radar_id}/events";
 const SPOKES_URI: &str = "/v2/api/radars/{radar_id}/spokes";
 const CONTROL_URI: &str = "/v2/api/radars/{radar_id}/control";
 const CONTROL_VALUE_URI: &str = "/v2/api/radars/{radar_id}/controls/{control_id}";
+const DISPLAY_CENTER_OFFSET_URI: &str = "/v2/api/radars/{radar_id}/displayCenterOffset";
 const TARGETS_URI: &str = "/v2/api/radars/{radar_id}/targets";
 const TARGET_URI: &str = "/v2/api/radars/{radar_id}/targets/{target_id}";
 const ARPA_SETTINGS_URI: &str = "/v2/api/radars/{radar_id}/arpa/settings";
+const ARPA_SWEEP_URI: &str = "/v2/api/radars/{radar_id}/arpa/sweep";
 // Guard zones
 const GUARD_ZONES_URI: &str = "/v2/api/radars/{radar_id}/guardZones";
 const GUARD_ZONE_URI: &str = "/v2/api/radars/{radar_id}/guardZones/{zone_id}";
@@ -84,6 +92,7 @@ const DUAL_RANGE_SPOKES_URI: &str = "/v2/api/radars/{radar_id}/dualRange/spokes"
 
 // Non-radar endpoints
 const INTERFACES_URI: &str = "/v2/api/interfaces";
+const RESCAN_URI: &str = "/v2/api/interfaces/rescan";
 
 // SignalK applicationData API (for settings persistence)
 const APP_DATA_URI: &str = "/signalk/v1/applicationData/global/{appid}/{version}/{*key}";
@@ -231,12 +240,15 @@ impl Web {
             .route(RADARS_URI, get(get_radars))
             .route(RADAR_CAPABILITIES_URI, get(get_radar_capabilities))
             .route(RADAR_STATE_URI, get(get_radar_state))
+            .route(RADAR_EVENTS_URI, get(get_radar_events))
             .route(SPOKES_URI, get(spokes_handler))
             .route(CONTROL_URI, get(control_handler))
             .route(CONTROL_VALUE_URI, put(set_control_value))
+            .route(DISPLAY_CENTER_OFFSET_URI, put(set_display_center_offset))
             .route(TARGETS_URI, get(get_targets).post(acquire_target))
             .route(TARGET_URI, delete(cancel_target))
             .route(ARPA_SETTINGS_URI, get(get_arpa_settings).put(set_arpa_settings))
+            .route(ARPA_SWEEP_URI, get(get_arpa_sweep))
             // Guard zones
             .route(GUARD_ZONES_URI, get(get_guard_zones).post(create_guard_zone))
             .route(GUARD_ZONE_URI, get(get_guard_zone).put(update_guard_zone).delete(delete_guard_zone))
@@ -249,6 +261,7 @@ impl Web {
             .route(DUAL_RANGE_SPOKES_URI, get(dual_range_spokes_handler))
             // Other endpoints
             .route(INTERFACES_URI, get(get_interfaces))
+            .route(RESCAN_URI, post(rescan_interfaces))
             // SignalK applicationData API
             .route(APP_DATA_URI, get(get_app_data).put(put_app_data).delete(delete_app_data))
             // Recordings API - File management
@@ -330,6 +343,8 @@ struct RadarApi {
     stream_url: String,
     control_url: String,
     legend: Legend,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    capabilities: Option<CapabilitySummary>,
 }
 
 impl RadarApi {
@@ -354,6 +369,7 @@ impl RadarApi {
             stream_url,
             control_url,
             legend,
+            capabilities: None,
         }
     }
 }
@@ -386,6 +402,7 @@ async fn get_radars(
     debug!("target host = '{}'", host);
 
     let mut api: HashMap<String, RadarApi> = HashMap::new();
+    let mut radar_models = Vec::new();
     for info in state
         .session
         .read()
@@ -396,7 +413,7 @@ async fn get_radars(
         .get_active()
         .clone()
     {
-        let legend = &info.legend;
+        let legend = info.display_legend();
         let id = format!("radar-{}", info.id);
         let stream_url = format!("ws://{}/v2/api/radars/{}/spokes", host, id);
         let control_url = format!("ws://{}/v2/api/radars/{}/control", host, id);
@@ -410,14 +427,59 @@ async fn get_radars(
             info.max_spoke_len,
             stream_url,
             control_url,
-            legend.clone(),
+            legend,
         );
 
+        let model_info = info
+            .controls
+            .model_name()
+            .as_deref()
+            .and_then(|m| models::get_model(to_core_brand(info.brand), m))
+            .unwrap_or(&models::UNKNOWN_MODEL)
+            .clone();
+        radar_models.push((id.to_owned(), model_info, info.control_allowlist.clone()));
         api.insert(id.to_owned(), v);
     }
+
+    // Include a compact capability summary so clients can render without
+    // fetching the full manifest first. Built on a blocking thread for the
+    // same stack reasons as get_radar_capabilities.
+    let summaries = tokio::task::spawn_blocking(move || {
+        radar_models
+            .into_iter()
+            .map(|(id, model_info, control_allowlist)| {
+                let features = server_supported_features(&model_info);
+                let mut summary = build_capability_summary(&model_info, features);
+                summary.control_ids.retain(|c| is_control_allowed(&control_allowlist, c));
+                (id, summary)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .expect("spawn_blocking task failed");
+    for (id, summary) in summaries {
+        if let Some(radar) = api.get_mut(&id) {
+            radar.capabilities = Some(summary);
+        }
+    }
     Json(api).into_response()
 }
 
+/// Optional API features the standalone server implements for a radar
+fn server_supported_features(model_info: &models::ModelInfo) -> Vec<SupportedFeature> {
+    let mut supported_features = vec![
+        SupportedFeature::Arpa,
+        SupportedFeature::GuardZones,
+        SupportedFeature::Trails,
+    ];
+
+    // Add DualRange if the radar supports it
+    if model_info.has_dual_range {
+        supported_features.push(SupportedFeature::DualRange);
+    }
+    supported_features
+}
+
 /// Parameters for radar-specific endpoints
 #[derive(Deserialize)]
 struct RadarIdParam {
@@ -461,17 +523,15 @@ async fn get_radar_capabilities(
                     .and_then(|m| models::get_model(core_brand, m))
                     .unwrap_or(&models::UNKNOWN_MODEL);
 
-                // Declare supported features for standalone server
-                let mut supported_features = vec![
-                    SupportedFeature::Arpa,
-                    SupportedFeature::GuardZones,
-                    SupportedFeature::Trails,
-                ];
+                let supported_features = server_supported_features(model_info);
 
-                // Add DualRange if the radar supports it
-                if model_info.has_dual_range {
-                    supported_features.push(SupportedFeature::DualRange);
-                }
+                // Ranges the radar itself reported (detected or persisted)
+                let reported_ranges: Vec<u32> = info
+                    .ranges
+                    .all
+                    .iter()
+                    .map(|r| r.distance() as u32)
+                    .collect();
 
                 Some((
                     model_info.clone(),
@@ -480,6 +540,8 @@ async fn get_radar_capabilities(
                     supported_features,
                     info.spokes_per_revolution,
                     info.max_spoke_len,
+                    reported_ranges,
+                    info.control_allowlist.clone(),
                 ))
             }
             None => None,
@@ -487,19 +549,36 @@ async fn get_radar_capabilities(
     }; // session lock released here
 
     match build_args {
-        Some((model_info, radar_id, radar_key, supported_features, spokes_per_revolution, max_spoke_len)) => {
+        Some((model_info, radar_id, radar_key, supported_features, spokes_per_revolution, max_spoke_len, reported_ranges, control_allowlist)) => {
             // Use spawn_blocking to run capability building on a thread with larger stack
             // This avoids stack overflow in debug builds where ControlDefinition structs
             // (328 bytes each) can overflow the default 2MB async task stack
             let capabilities = tokio::task::spawn_blocking(move || {
-                build_capabilities_from_model_with_key(
+                let mut capabilities = build_capabilities_from_model_with_key(
                     &model_info,
                     &radar_id,
                     Some(&radar_key), // Persistent key for installation settings storage
                     supported_features,
                     spokes_per_revolution,
                     max_spoke_len,
-                )
+                );
+                // The radar may support ranges beyond what the model table declares
+                for range in reported_ranges.iter().copied() {
+                    if capabilities.characteristics.include_reported_range(range) {
+                        log::warn!(
+                            "{}: radar reports range {} m outside model {} limits {}..{} m, widening",
+                            radar_id,
+                            range,
+                            model_info.model,
+                            model_info.min_range,
+                            model_info.max_range
+                        );
+                    }
+                }
+                // Once known, present the radar's own step set (nm or metric)
+                capabilities.set_reported_ranges(&reported_ranges);
+                capabilities.apply_control_allowlist(&control_allowlist);
+                capabilities
             })
             .await
             .expect("spawn_blocking task failed");
@@ -510,6 +589,23 @@ async fn get_radar_capabilities(
     }
 }
 
+/// GET /v2/api/radars/{radar_id}/events
+/// Returns the radar's recent lifecycle events, oldest first
+#[debug_handler]
+async fn get_radar_events(
+    State(state): State<Web>,
+    Path(params): Path<RadarIdParam>,
+) -> Response {
+    debug!("Events request for radar {}", params.radar_id);
+
+    let session = state.session.read().unwrap();
+    let radars = session.radars.as_ref().unwrap();
+    match radars.get_by_id(&params.radar_id) {
+        Some(info) => Json(info.controls.events()).into_response(),
+        None => RadarError::NoSuchRadar(params.radar_id.to_string()).into_response(),
+    }
+}
+
 /// GET /v2/api/radars/{radar_id}/state
 /// Returns the current state of a radar (v5 API format)
 #[debug_handler]
@@ -546,7 +642,8 @@ async fn get_radar_state(
                 // Controls with auto mode (compound controls)
                 if control.auto.is_some() {
                     let mode = if control.auto.unwrap_or(false) { "auto" } else { "manual" };
-                    let value = control.value.unwrap_or(0.0);
+                    // In auto mode, show what the radar's auto algorithm chose
+                    let value = control.applied_value().unwrap_or(0.0);
                     // Return integer for most controls, but preserve decimals for bearing alignment
                     if control_id == "bearingAlignment" {
                         return serde_json::json!({"mode": mode, "value": value});
@@ -582,9 +679,31 @@ async fn get_radar_state(
                 if control_id == "userName" || control_id == "modelName" {
                     continue;
                 }
+                // Hidden by the control allowlist
+                if !info.allows_control(&control_id) {
+                    continue;
+                }
+                // Part of the timedTransmit compound control
+                if control_id == "timedStandby" {
+                    continue;
+                }
+                if control_id == "timedTransmit" {
+                    let standby = info.controls.get("timedStandby").and_then(|c| c.value);
+                    controls.insert(control_id.clone(), serde_json::json!({
+                        "enabled": control.enabled.unwrap_or(false),
+                        "transmitPeriod": control.value.unwrap_or(0.0) as i32,
+                        "standbyPeriod": standby.unwrap_or(0.0) as i32,
+                    }));
+                    continue;
+                }
                 controls.insert(control_id.clone(), format_control_value(&control_id, &control));
             }
 
+            // Read-only installation info
+            if let Some(antenna_type) = info.antenna_type {
+                controls.insert("antennaType".to_string(), serde_json::json!(antenna_type));
+            }
+
             // Determine status string for top-level field
             let status = controls
                 .get("power")
@@ -598,6 +717,8 @@ async fn get_radar_state(
                 status,
                 controls,
                 disabled_controls: vec![],
+                modules: info.modules.clone(),
+                display_center_offset: info.display_center_offset(),
             };
 
             Json(state_v5).into_response()
@@ -624,6 +745,14 @@ async fn get_interfaces(
     Json(status).into_response()
 }
 
+/// Ask the locator to re-send its beacon requests now
+#[debug_handler]
+async fn rescan_interfaces(State(state): State<Web>) -> Response {
+    debug!("Rescan request");
+    state.session.read().unwrap().rescan.notify_one();
+    StatusCode::ACCEPTED.into_response()
+}
+
 #[debug_handler]
 async fn spokes_handler(
     State(state): State<Web>,
@@ -723,7 +852,8 @@ async fn control_handler(
 
             // finalize the upgrade process by returning upgrade callback.
             // we can customize the callback by sending additional info such as address.
-            ws.on_upgrade(move |socket| control_stream(socket, radar, shutdown_rx))
+            let engine = state.engine.clone();
+            ws.on_upgrade(move |socket| control_stream(socket, radar, engine, shutdown_rx))
         }
         None => RadarError::NoSuchRadar(params.radar_id.to_string()).into_response(),
     }
@@ -734,9 +864,11 @@ async fn control_handler(
 async fn control_stream(
     mut socket: WebSocket,
     radar: RadarInfo,
+    engine: SharedEngine,
     mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
 ) {
     let mut broadcast_control_rx = radar.all_clients_rx();
+    let mut guard_zone_rx = radar.guard_zones.subscribe();
     let (reply_tx, mut reply_rx) = tokio::sync::mpsc::channel(60);
 
     if radar
@@ -748,6 +880,19 @@ async fn control_stream(
         return;
     }
 
+    // The engine's radars only run the feature processors (ARPA, trails,
+    // dual range); their control state is in the controls sent above, so
+    // only their capabilities are sent here.
+    let snapshots = engine.read().unwrap().on_client_subscribed();
+    for mut snapshot in snapshots {
+        snapshot.controls.clear();
+        let message = serde_json::to_string(&snapshot).unwrap();
+        if let Err(e) = socket.send(Message::Text(message.into())).await {
+            log::error!("send to websocket client: {e}");
+            return;
+        }
+    }
+
     debug!("Started /control websocket");
 
     loop {
@@ -762,6 +907,11 @@ async fn control_stream(
             r = reply_rx.recv() => {
                 match r {
                     Some(message) => {
+                        // Errors are replies to this client's own writes,
+                        // including those to hidden controls
+                        if message.error.is_none() && !radar.allows_control(&message.id) {
+                            continue;
+                        }
                         let message = serde_json::to_string(&message).unwrap();
                         log::trace!("Sending {:?}", message);
                         let ws_message = Message::Text(message.into());
@@ -781,6 +931,9 @@ async fn control_stream(
             r = broadcast_control_rx.recv() => {
                 match r {
                     Ok(message) => {
+                        if !radar.allows_control(&message.id) {
+                            continue;
+                        }
                         let message: String = serde_json::to_string(&message).unwrap();
                         log::debug!("Sending {:?}", message);
                         let ws_message = Message::Text(message.into());
@@ -798,14 +951,38 @@ async fn control_stream(
                     }
                 }
             },
+            r = guard_zone_rx.recv() => {
+                match r {
+                    Ok(event) => {
+                        let message: String = serde_json::to_string(&event).unwrap();
+                        log::debug!("Sending {:?}", message);
+                        let ws_message = Message::Text(message.into());
+
+                        if let Err(e) = socket.send(ws_message).await {
+                            log::error!("send to websocket client: {e}");
+                            break;
+                        }
+                    },
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {},
+                    Err(e) => {
+                        log::error!("Error on guard zone channel: {e}");
+                        break;
+                    }
+                }
+            },
             // receive control values from the client
             r = socket.recv() => {
                 match r {
                     Some(Ok(message)) => {
                         match message {
                             Message::Text(message) => {
-                                if let Ok(control_value) = serde_json::from_str(&message) {
+                                if let Ok(control_value) = serde_json::from_str::<mayara_server::settings::ControlValue>(&message) {
                                     log::debug!("Received ControlValue {:?}", control_value);
+                                    if !radar.allows_control(&control_value.id) {
+                                        let e = RadarError::CannotSetControlType(control_value.id.clone());
+                                        let _ = radar.controls.send_error_to_client(reply_tx.clone(), &control_value, &e).await;
+                                        continue;
+                                    }
                                     let _ = radar.controls.process_client_request(control_value, reply_tx.clone()).await;
                                 } else {
                                     log::error!("Unknown JSON string '{}'", message);
@@ -872,6 +1049,10 @@ async fn set_control_value(
 
         match radars.get_by_id(&params.radar_id) {
             Some(radar) => {
+                if !radar.allows_control(&params.control_id) {
+                    let e = ControlError::ControlNotFound(params.control_id.clone());
+                    return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+                }
                 // Look up the control by name
                 let control = match radar.controls.get_by_name(&params.control_id) {
                     Some(c) => c,
@@ -895,6 +1076,7 @@ async fn set_control_value(
                 };
 
                 // Parse the value - handle compound controls {mode, value} and simple values
+                let mut enabled = None;
                 let (value_str, auto) = match &request.value {
                     serde_json::Value::String(s) => {
                         // Try to normalize enum values using core definition
@@ -920,16 +1102,36 @@ async fn set_control_value(
                             };
                             // Pass enabled state via 'auto' field (repurposed), mode as value
                             (mode_val.to_string(), Some(enabled))
+                        } else if params.control_id == "timedTransmit" {
+                            // {"enabled": bool, "transmitPeriod": N, "standbyPeriod": M} in
+                            // minutes, missing fields keep their current value
+                            let period = |key: &str, current: Option<f32>| {
+                                obj.get(key)
+                                    .and_then(|v| v.as_f64())
+                                    .map(|v| v as f32)
+                                    .or(current)
+                                    .unwrap_or(0.0)
+                            };
+                            let standby = radar.controls.get("timedStandby").and_then(|c| c.value);
+                            enabled = obj.get("enabled").and_then(|v| v.as_bool()).or(control.enabled);
+                            let value = format!(
+                                "{},{}",
+                                period("transmitPeriod", control.value),
+                                period("standbyPeriod", standby)
+                            );
+                            (value, None)
                         } else {
                             // Standard compound control: {"mode": "auto"|"manual", "value": N}
                             let mode = obj.get("mode").and_then(|v| v.as_str()).unwrap_or("manual");
                             let auto = Some(mode == "auto");
+                            // A mode switch without a value keeps the value in use
                             let value = obj.get("value")
                                 .map(|v| match v {
                                     serde_json::Value::Number(n) => n.to_string(),
                                     serde_json::Value::String(s) => s.clone(),
                                     _ => v.to_string(),
                                 })
+                                .or_else(|| control.applied_value().map(|v| v.to_string()))
                                 .unwrap_or_default();
                             (value, auto)
                         }
@@ -939,6 +1141,7 @@ async fn set_control_value(
 
                 let mut control_value = ControlValue::new(control.id(), value_str);
                 control_value.auto = auto;
+                control_value.enabled = enabled;
                 (radar.controls.clone(), control_value)
             }
             None => {
@@ -1005,6 +1208,9 @@ struct TargetListResponse {
 struct AcquireTargetRequest {
     bearing: f64,
     distance: f64,
+    /// Optional caller-chosen target ID; must be below the reserved range
+    #[serde(default)]
+    id: Option<u32>,
 }
 
 /// Response for POST /radars/{id}/targets
@@ -1016,16 +1222,47 @@ struct AcquireTargetResponse {
     error: Option<String>,
 }
 
+/// PUT /radars/{radar_id}/displayCenterOffset - Move own ship off the display center
+///
+/// Display only, the radar is not touched. The offset is persisted with the
+/// radar's settings and included in the state. `null` re-centers.
+#[debug_handler]
+async fn set_display_center_offset(
+    State(state): State<Web>,
+    Path(params): Path<RadarIdParam>,
+    Json(offset): Json<Option<DisplayCenterOffset>>,
+) -> Response {
+    debug!("PUT display center offset for radar {}: {:?}", params.radar_id, offset);
+
+    if let Some(Err(e)) = offset.map(|o| o.validate()) {
+        return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+    }
+
+    let session = state.session.read().unwrap();
+    let radars = session.radars.as_ref().unwrap();
+    match radars.get_by_id(&params.radar_id) {
+        Some(info) => {
+            info.set_display_center_offset(offset);
+            radars.update(&info);
+            StatusCode::OK.into_response()
+        }
+        None => RadarError::NoSuchRadar(params.radar_id.to_string()).into_response(),
+    }
+}
+
 /// GET /radars/{radar_id}/targets - List all tracked ARPA targets
+///
+/// `?minTcpa=0` hides receding targets (negative TCPA); they stay tracked.
 #[debug_handler]
 async fn get_targets(
     State(state): State<Web>,
     Path(params): Path<RadarIdParam>,
+    axum::extract::Query(filter): axum::extract::Query<TargetFilter>,
 ) -> Response {
-    debug!("GET targets for radar {}", params.radar_id);
+    debug!("GET targets for radar {} ({:?})", params.radar_id, filter);
 
     let engine = state.engine.read().unwrap();
-    let targets = engine.get_targets(&params.radar_id);
+    let targets = engine.get_targets_filtered(&params.radar_id, &filter);
 
     let response = TargetListResponse {
         radar_id: params.radar_id,
@@ -1036,6 +1273,16 @@ async fn get_targets(
     Json(response).into_response()
 }
 
+/// The radar whose server side ARPA tracker (`--targets arpa`) handles
+/// target acquisition; other target modes use the engine's tracker
+fn arpa_radar(state: &Web, radar_id: &str) -> Option<RadarInfo> {
+    let session = state.session.read().unwrap();
+    if session.args.targets != TargetMode::Arpa {
+        return None;
+    }
+    session.radars.as_ref()?.get_by_id(radar_id)
+}
+
 /// POST /radars/{radar_id}/targets - Manual target acquisition
 #[debug_handler]
 async fn acquire_target(
@@ -1074,6 +1321,43 @@ async fn acquire_target(
             .into_response();
     }
 
+    if let Some(info) = arpa_radar(&state, &params.radar_id) {
+        let result = target_request(&info, |reply| TargetRequest::Acquire {
+            bearing: request.bearing,
+            distance: request.distance,
+            id: request.id,
+            reply,
+        })
+        .await;
+        return match result {
+            Ok(target_id) => {
+                debug!("Acquired target {} on radar {}", target_id, params.radar_id);
+                Json(AcquireTargetResponse {
+                    success: true,
+                    target_id: Some(target_id),
+                    error: None,
+                })
+                .into_response()
+            }
+            Err(e) => {
+                let status = if e == "max targets reached" {
+                    StatusCode::TOO_MANY_REQUESTS
+                } else {
+                    StatusCode::BAD_REQUEST
+                };
+                (
+                    status,
+                    Json(AcquireTargetResponse {
+                        success: false,
+                        target_id: None,
+                        error: Some(e),
+                    }),
+                )
+                    .into_response()
+            }
+        };
+    }
+
     // Ensure radar exists in engine
     state.ensure_radar_in_engine(&params.radar_id);
 
@@ -1084,6 +1368,31 @@ async fn acquire_target(
         .as_millis() as u64;
 
     let mut engine = state.engine.write().unwrap();
+    if let Some(id) = request.id {
+        return match engine.acquire_target_with_id(
+            &params.radar_id,
+            id,
+            request.bearing,
+            request.distance,
+            timestamp,
+        ) {
+            Ok(target_id) => Json(AcquireTargetResponse {
+                success: true,
+                target_id: Some(target_id),
+                error: None,
+            })
+            .into_response(),
+            Err(e) => (
+                StatusCode::BAD_REQUEST,
+                Json(AcquireTargetResponse {
+                    success: false,
+                    target_id: None,
+                    error: Some(e.to_string()),
+                }),
+            )
+                .into_response(),
+        };
+    }
     match engine.acquire_target(&params.radar_id, request.bearing, request.distance, timestamp) {
         Some(target_id) => {
             debug!("Acquired target {} on radar {}", target_id, params.radar_id);
@@ -1117,6 +1426,17 @@ async fn cancel_target(
         params.target_id, params.radar_id
     );
 
+    if let Some(info) = arpa_radar(&state, &params.radar_id) {
+        let id = params.target_id;
+        return match target_request(&info, |reply| TargetRequest::Cancel { id, reply }).await {
+            Ok(_) => {
+                debug!("Cancelled target {} on radar {}", id, params.radar_id);
+                StatusCode::NO_CONTENT.into_response()
+            }
+            Err(e) => (StatusCode::NOT_FOUND, e).into_response(),
+        };
+    }
+
     let mut engine = state.engine.write().unwrap();
     if engine.cancel_target(&params.radar_id, params.target_id) {
         debug!("Cancelled target {} on radar {}", params.target_id, params.radar_id);
@@ -1161,6 +1481,52 @@ async fn set_arpa_settings(
     StatusCode::OK.into_response()
 }
 
+/// Query parameters for the ARPA sweep image
+#[derive(Debug, Deserialize)]
+struct SweepQuery {
+    /// Width and height of the image in pixels
+    size: Option<usize>,
+}
+
+const DEFAULT_SWEEP_SIZE: usize = 512;
+const MAX_SWEEP_SIZE: usize = 4096;
+
+/// GET /radars/{radar_id}/arpa/sweep - PNG image of the last revolution
+/// as seen by the server side ARPA tracker (`--targets arpa`)
+#[debug_handler]
+async fn get_arpa_sweep(
+    State(state): State<Web>,
+    Path(params): Path<RadarIdParam>,
+    axum::extract::Query(query): axum::extract::Query<SweepQuery>,
+) -> Response {
+    debug!("GET ARPA sweep for radar {}", params.radar_id);
+
+    let size = query.size.unwrap_or(DEFAULT_SWEEP_SIZE);
+    if size == 0 || size > MAX_SWEEP_SIZE {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("size must be 1-{}", MAX_SWEEP_SIZE),
+        )
+            .into_response();
+    }
+
+    let Some(info) = arpa_radar(&state, &params.radar_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            "no ARPA tracker for this radar, start with --targets arpa",
+        )
+            .into_response();
+    };
+    let image = match sweep_request(&info, size).await {
+        Ok(image) => image,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, e).into_response(),
+    };
+    match encode_png(&image, size, size) {
+        Ok(png) => ([(header::CONTENT_TYPE, "image/png")], png).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
 // =============================================================================
 // SignalK applicationData API Handlers
 // =============================================================================
@@ -1261,15 +1627,37 @@ async fn get_guard_zones(
 ) -> Response {
     debug!("GET guard zones for radar {}", params.radar_id);
 
-    let engine = state.engine.read().unwrap();
-    let zones = engine.get_guard_zones(&params.radar_id);
-
-    let response = GuardZoneListResponse {
-        radar_id: params.radar_id,
-        zones,
-    };
+    let session = state.session.read().unwrap();
+    let radars = session.radars.as_ref().unwrap();
+    match radars.get_by_id(&params.radar_id) {
+        Some(info) => {
+            let response = GuardZoneListResponse {
+                radar_id: params.radar_id,
+                zones: info.guard_zones.status(),
+            };
+            Json(response).into_response()
+        }
+        None => RadarError::NoSuchRadar(params.radar_id.to_string()).into_response(),
+    }
+}
 
-    Json(response).into_response()
+/// Add or replace a zone on the radar and persist it
+fn set_guard_zone(state: &Web, radar_id: &str, zone: GuardZone) -> Result<(), Response> {
+    let session = state.session.read().unwrap();
+    let radars = session.radars.as_ref().unwrap();
+    let info = radars
+        .get_by_id(radar_id)
+        .ok_or_else(|| RadarError::NoSuchRadar(radar_id.to_string()).into_response())?;
+    // The detected model's range, the ranges seen so far until it is known
+    let max_range = info
+        .core_model(to_core_brand(info.brand))
+        .map(|model| model.max_range as i32)
+        .or_else(|| info.ranges.all.iter().map(|r| r.distance()).max());
+    if let Err(e) = info.guard_zones.set(zone, max_range) {
+        return Err((StatusCode::BAD_REQUEST, e.to_string()).into_response());
+    }
+    radars.update(&info);
+    Ok(())
 }
 
 /// POST /radars/{radar_id}/guardZones - Create a new guard zone
@@ -1281,11 +1669,9 @@ async fn create_guard_zone(
 ) -> Response {
     debug!("POST create guard zone {} for radar {}", zone.id, params.radar_id);
 
-    // Ensure radar exists in engine
-    state.ensure_radar_in_engine(&params.radar_id);
-
-    let mut engine = state.engine.write().unwrap();
-    engine.set_guard_zone(&params.radar_id, zone.clone());
+    if let Err(response) = set_guard_zone(&state, &params.radar_id, zone.clone()) {
+        return response;
+    }
     debug!("Created guard zone {} on radar {}", zone.id, params.radar_id);
 
     (StatusCode::CREATED, Json(zone)).into_response()
@@ -1299,8 +1685,12 @@ async fn get_guard_zone(
 ) -> Response {
     debug!("GET guard zone {} for radar {}", params.zone_id, params.radar_id);
 
-    let engine = state.engine.read().unwrap();
-    if let Some(status) = engine.get_guard_zone(&params.radar_id, params.zone_id) {
+    let session = state.session.read().unwrap();
+    let radars = session.radars.as_ref().unwrap();
+    if let Some(status) = radars
+        .get_by_id(&params.radar_id)
+        .and_then(|info| info.guard_zones.get(params.zone_id))
+    {
         return Json(status).into_response();
     }
 
@@ -1316,15 +1706,13 @@ async fn update_guard_zone(
 ) -> Response {
     debug!("PUT update guard zone {} for radar {}", params.zone_id, params.radar_id);
 
-    // Ensure radar exists in engine
-    state.ensure_radar_in_engine(&params.radar_id);
-
     // Ensure zone ID matches path
     let mut zone = zone;
     zone.id = params.zone_id;
 
-    let mut engine = state.engine.write().unwrap();
-    engine.set_guard_zone(&params.radar_id, zone);
+    if let Err(response) = set_guard_zone(&state, &params.radar_id, zone) {
+        return response;
+    }
     debug!("Updated guard zone {} on radar {}", params.zone_id, params.radar_id);
 
     StatusCode::OK.into_response()
@@ -1338,10 +1726,14 @@ async fn delete_guard_zone(
 ) -> Response {
     debug!("DELETE guard zone {} for radar {}", params.zone_id, params.radar_id);
 
-    let mut engine = state.engine.write().unwrap();
-    if engine.remove_guard_zone(&params.radar_id, params.zone_id) {
-        debug!("Deleted guard zone {} on radar {}", params.zone_id, params.radar_id);
-        return StatusCode::NO_CONTENT.into_response();
+    let session = state.session.read().unwrap();
+    let radars = session.radars.as_ref().unwrap();
+    if let Some(info) = radars.get_by_id(&params.radar_id) {
+        if info.guard_zones.remove(params.zone_id) {
+            radars.update(&info);
+            debug!("Deleted guard zone {} on radar {}", params.zone_id, params.radar_id);
+            return StatusCode::NO_CONTENT.into_response();
+        }
     }
 
     (StatusCode::NOT_FOUND, "Zone not found").into_response()
@@ -1490,7 +1882,7 @@ async fn get_dual_range(
     debug!("GET dual-range for radar {}", params.radar_id);
 
     // Check if radar exists and supports dual-range (get model info from session)
-    let model_info = {
+    let (model_info, detected_ranges) = {
         let session = state.session.read().unwrap();
         let radars = session.radars.as_ref().unwrap();
 
@@ -1511,7 +1903,7 @@ async fn get_dual_range(
                         .into_response();
                 }
 
-                model_info.clone()
+                (model_info.clone(), detected_ranges(&info))
             }
             None => return RadarError::NoSuchRadar(params.radar_id.to_string()).into_response(),
         }
@@ -1527,9 +1919,14 @@ async fn get_dual_range(
             ..Default::default()
         });
 
-    // Filter ranges for secondary display
-    let available_ranges: Vec<u32> = model_info
-        .range_table
+    // Filter ranges for secondary display, preferring the radar's own
+    // ranges over the model's approximate table
+    let ranges = if detected_ranges.is_empty() {
+        model_info.range_table
+    } else {
+        detected_ranges.as_slice()
+    };
+    let available_ranges: Vec<u32> = ranges
         .iter()
         .filter(|&&r| r <= model_info.max_dual_range)
         .copied()
@@ -1557,7 +1954,7 @@ async fn set_dual_range(
     );
 
     // Check if radar exists and supports dual-range (get model info from session)
-    let (model_name, model_info) = {
+    let (model_name, detected_ranges) = {
         let session = state.session.read().unwrap();
         let radars = session.radars.as_ref().unwrap();
 
@@ -1578,7 +1975,7 @@ async fn set_dual_range(
                         .into_response();
                 }
 
-                (model_name_opt, model.clone())
+                (model_name_opt, detected_ranges(&info))
             }
             None => return RadarError::NoSuchRadar(params.radar_id.to_string()).into_response(),
         }
@@ -1591,16 +1988,15 @@ async fn set_dual_range(
 
     // Apply config to engine
     let mut engine = state.engine.write().unwrap();
-    if !engine.set_dual_range(&params.radar_id, &config) {
-        return (
-            StatusCode::BAD_REQUEST,
-            format!(
-                "Secondary range {} exceeds maximum {}",
-                config.secondary_range,
-                model_info.max_dual_range
-            ),
-        )
-            .into_response();
+    if !detected_ranges.is_empty() {
+        engine.set_dual_range_ranges(&params.radar_id, &detected_ranges);
+    }
+    if let Err(e) = engine.set_dual_range(&params.radar_id, &config) {
+        let body = serde_json::json!({
+            "error": e.to_string(),
+            "validRanges": engine.get_dual_range_available_ranges(&params.radar_id),
+        });
+        return (StatusCode::BAD_REQUEST, Json(body)).into_response();
     }
 
     debug!(
@@ -1611,6 +2007,16 @@ async fn set_dual_range(
     StatusCode::OK.into_response()
 }
 
+/// Ranges (meters) the radar reported, empty until they are detected
+fn detected_ranges(info: &RadarInfo) -> Vec<u32> {
+    info.ranges
+        .all
+        .iter()
+        .filter(|r| r.distance() > 0)
+        .map(|r| r.distance() as u32)
+        .collect()
+}
+
 /// WebSocket handler for secondary range spokes
 #[debug_handler]
 async fn dual_range_spokes_handler(